@@ -0,0 +1,40 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use grib2::reader::PrrReader;
+
+/// 1kmメッシュ解析雨量のGRIB2ファイル（8,601,600格子）
+const PRR_PATH: &str = "../resources/prr.bin";
+
+fn values(c: &mut Criterion) {
+    let mut group = c.benchmark_group("value_iter");
+    group.sample_size(10);
+    group.bench_function("values", |b| {
+        let mut reader = PrrReader::new(PRR_PATH).unwrap();
+        b.iter(|| {
+            let count = reader
+                .values()
+                .unwrap()
+                .map(|value| value.unwrap())
+                .filter(|value| value.value.is_some())
+                .count();
+            black_box(count)
+        })
+    });
+    group.bench_function("values_raw", |b| {
+        let mut reader = PrrReader::new(PRR_PATH).unwrap();
+        b.iter(|| {
+            let count = reader
+                .values_raw()
+                .unwrap()
+                .map(|value| value.unwrap())
+                .filter(|value| value.level() != 0)
+                .count();
+            black_box(count)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, values);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+use std::thread;
+
+use grib2::reader::{PrrReader, ReaderResult};
+
+/// 解析雨量ファイルの集計結果
+struct Summary {
+    /// 欠測値でない格子の数
+    count: usize,
+    /// 物理値の最大値
+    max: Option<u16>,
+}
+
+/// 解析雨量ファイルを読み込み、欠測値でない格子の数と物理値の最大値を集計する。
+fn summarize(mut reader: PrrReader) -> ReaderResult<Summary> {
+    let mut summary = Summary {
+        count: 0,
+        max: None,
+    };
+    for value in reader.values()? {
+        if let Some(v) = value?.value {
+            summary.count += 1;
+            summary.max = summary.max.max(Some(v));
+        }
+    }
+
+    Ok(summary)
+}
+
+/// コマンドライン引数で指定した解析雨量ファイルを、ファイル単位でスレッドに分けて集計する。
+///
+/// 引数を省略した場合は`resources/prr.bin`を集計する。
+fn main() {
+    let mut paths: Vec<PathBuf> = std::env::args().skip(1).map(PathBuf::from).collect();
+    if paths.is_empty() {
+        paths.push(PathBuf::from("resources/prr.bin"));
+    }
+
+    thread::scope(|scope| {
+        let handles = paths
+            .iter()
+            .map(|path| {
+                // リーダーはSendであるため、メインスレッドで構築してワーカースレッドに渡せる
+                let reader = PrrReader::new(path);
+                scope.spawn(move || reader.and_then(summarize))
+            })
+            .collect::<Vec<_>>();
+        for (path, handle) in paths.iter().zip(handles) {
+            match handle.join().unwrap() {
+                Ok(summary) => println!(
+                    "{}: count={}, max={}",
+                    path.display(),
+                    summary.count,
+                    summary
+                        .max
+                        .map_or_else(|| String::from("-"), |max| max.to_string())
+                ),
+                Err(e) => eprintln!("{}: {}", path.display(), e),
+            }
+        }
+    });
+}
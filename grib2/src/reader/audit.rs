@@ -0,0 +1,340 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use super::product::{detect_product, open_product, ProductKind};
+use super::sections::{LeadingSections, Section3_0};
+use super::{FileReader, MessageIter, PrrReader, ReaderError, ReaderResult};
+
+/// GRIB2ファイルの妥当性の監査結果
+#[derive(Debug, Clone)]
+pub struct AuditResult {
+    /// ファイルのパス
+    path: PathBuf,
+    /// 読み込めたGRIB報の数
+    number_of_messages: usize,
+    /// 第3節:格子系定義節（読み込めなかった場合は`None`）
+    section3: Option<Section3_0>,
+    /// 監査中に発生したエラーのコレクション
+    errors: Vec<ReaderError>,
+}
+
+impl AuditResult {
+    /// ファイルのパスを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// ファイルのパス
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 読み込めたGRIB報の数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 読み込めたGRIB報の数
+    pub fn number_of_messages(&self) -> usize {
+        self.number_of_messages
+    }
+
+    /// 第3節:格子系定義節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第3節:格子系定義節（読み込めなかった場合は`None`）
+    pub fn section3(&self) -> Option<&Section3_0> {
+        self.section3.as_ref()
+    }
+
+    /// 監査中に発生したエラーを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 監査中に発生したエラーのスライス
+    pub fn errors(&self) -> &[ReaderError] {
+        &self.errors
+    }
+
+    /// ファイルを問題なく読み込めたか確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// エラーが発生しなかった場合は`true`
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// GRIB2ファイルの妥当性の検査項目の結果
+#[derive(Debug, Clone)]
+pub enum ValidationCheck {
+    /// 検査に合格
+    Passed,
+    /// 検査に不合格
+    Failed(ReaderError),
+    /// 前提となる検査に不合格のため未検査
+    Skipped,
+}
+
+impl ValidationCheck {
+    /// 検査に合格したか確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// 検査に合格した場合は`true`
+    pub fn is_passed(&self) -> bool {
+        matches!(self, Self::Passed)
+    }
+
+    /// 検査結果から検査項目の結果を構築する。
+    pub(crate) fn from_result(result: ReaderResult<()>) -> Self {
+        match result {
+            Ok(_) => Self::Passed,
+            Err(e) => Self::Failed(e),
+        }
+    }
+}
+
+/// GRIB2ファイルの妥当性の検査結果
+///
+/// 検査項目ごとに合否を保持する。
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    /// ファイルのパス
+    pub(crate) path: PathBuf,
+    /// 第0節から第7節までの節の長さと、第0節に記録されたGRIB報全体のバイト数の整合
+    pub(crate) section_lengths: ValidationCheck,
+    /// 第8節の終端のマーカー
+    pub(crate) end_marker: ValidationCheck,
+    /// 第3節の資料点数と第5節の全資料点の数の一致
+    pub(crate) number_of_points: ValidationCheck,
+    /// ランレングス圧縮符号列を展開した格子の数と第3節の資料点数の一致
+    pub(crate) expanded_points: ValidationCheck,
+}
+
+impl ValidationReport {
+    /// ファイルのパスを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// ファイルのパス
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 第0節から第7節までの節の長さと、GRIB報全体のバイト数の整合の検査結果を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 節の長さの整合の検査結果
+    pub fn section_lengths(&self) -> &ValidationCheck {
+        &self.section_lengths
+    }
+
+    /// 第8節の終端のマーカーの検査結果を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第8節の終端のマーカーの検査結果
+    pub fn end_marker(&self) -> &ValidationCheck {
+        &self.end_marker
+    }
+
+    /// 第3節の資料点数と第5節の全資料点の数の一致の検査結果を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 資料点数の一致の検査結果
+    pub fn number_of_points(&self) -> &ValidationCheck {
+        &self.number_of_points
+    }
+
+    /// ランレングス圧縮符号列を展開した格子の数の検査結果を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 展開した格子の数の検査結果
+    pub fn expanded_points(&self) -> &ValidationCheck {
+        &self.expanded_points
+    }
+
+    /// 全ての検査項目に合格したか確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// 全ての検査項目に合格した場合は`true`
+    pub fn is_valid(&self) -> bool {
+        [
+            &self.section_lengths,
+            &self.end_marker,
+            &self.number_of_points,
+            &self.expanded_points,
+        ]
+        .iter()
+        .all(|check| check.is_passed())
+    }
+}
+
+/// ディレクトリ内の全てのファイルのプロダクトを判別して読み込み、妥当性を監査する。
+///
+/// 結果はファイルのパスの昇順に並べる。読み込めないファイルもエラーを付けて結果に含めるため、
+/// 壊れたファイルが混在していても全てのファイルを監査する。ディレクトリを読み込めなかった場合は、
+/// ディレクトリのパスとエラーを記録した結果のみを返す。
+/// feature`rayon`が有効な場合は、ファイル単位で並列に監査する。
+///
+/// # 引数
+///
+/// * `dir` - 監査するディレクトリのパス
+///
+/// # 戻り値
+///
+/// ファイルごとの監査結果
+pub fn audit_directory<P: AsRef<Path>>(dir: P) -> Vec<AuditResult> {
+    let dir = dir.as_ref();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return vec![AuditResult {
+                path: dir.to_path_buf(),
+                number_of_messages: 0,
+                section3: None,
+                errors: vec![ReaderError::NotFound(e.to_string().into())],
+            }]
+        }
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    #[cfg(feature = "rayon")]
+    let results = {
+        use rayon::prelude::*;
+        paths.par_iter().map(|path| audit_file(path)).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let results = paths.iter().map(|path| audit_file(path)).collect();
+
+    results
+}
+
+/// ファイルのプロダクトを判別して読み込み、妥当性を監査する。
+///
+/// ファイルに連結されたGRIB報を数えた後、プロダクトを判別してリーダーを構築し、格子系定義を
+/// 記録する。解析雨量の場合は、リーダーを構築せずに読み込めた節まで読み込んで資料点数を検査する。
+/// プロダクトを判別できなかった場合は、第3節まで読み込めれば格子系定義を記録する。
+fn audit_file(path: &Path) -> AuditResult {
+    let mut result = AuditResult {
+        path: path.to_path_buf(),
+        number_of_messages: 0,
+        section3: None,
+        errors: vec![],
+    };
+    let messages = match MessageIter::open(path) {
+        Ok(messages) => messages,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+    for message in messages {
+        match message {
+            Ok(_) => result.number_of_messages += 1,
+            Err(e) => result.errors.push(e),
+        }
+    }
+    // 解析雨量は読み込めた節まで読み込むため、プロダクトを判別した後にリーダーを構築しない
+    let product = detect_product(path).and_then(|kind| match kind {
+        ProductKind::Prr => Ok(None),
+        kind => open_product(path, kind).map(Some),
+    });
+    match product {
+        Ok(None) => {
+            let (message, errors) = PrrReader::new_partial(path);
+            result.section3 = message.and_then(|message| message.section3().copied());
+            result.errors.extend(errors);
+        }
+        Ok(Some(product)) => result.section3 = Some(*product.section3()),
+        Err(e) => {
+            result.section3 = read_section3(path);
+            result.errors.push(e);
+        }
+    }
+
+    result
+}
+
+/// ファイルの第0節から第3節までを読み込み、第3節を返す。
+///
+/// # 戻り値
+///
+/// 第3節:格子系定義節（読み込めなかった場合は`None`）
+fn read_section3(path: &Path) -> Option<Section3_0> {
+    let file = File::open(path).ok()?;
+    let sections = LeadingSections::from_reader(&mut FileReader::new(file)).ok()?;
+
+    Some(sections.section3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::audit_directory;
+    use crate::reader::sections::{
+        section0_to_section3_bytes, small_lswj_bytes, small_prr_bytes, TempPath,
+    };
+
+    #[test]
+    fn audit_directory_with_broken_files_ok() {
+        let temp_dir = TempPath::dir();
+        let dir = temp_dir.path();
+        // 第3節まで正常で、第4節以降が存在しないファイル
+        std::fs::write(dir.join("a.bin"), section0_to_section3_bytes(1_000)).unwrap();
+        // GRIB報ではないファイル
+        std::fs::write(dir.join("b.bin"), b"not a grib2 file").unwrap();
+        // 空のファイル
+        std::fs::write(dir.join("c.bin"), []).unwrap();
+        // サブディレクトリは監査しない
+        std::fs::create_dir_all(dir.join("d")).unwrap();
+
+        let results = audit_directory(dir);
+        assert_eq!(3, results.len());
+        assert!(results.iter().all(|result| !result.is_valid()));
+        assert!(results
+            .iter()
+            .all(|result| result.number_of_messages() == 0));
+        assert_eq!(dir.join("a.bin"), results[0].path());
+        assert_eq!(
+            2_560,
+            results[0].section3().unwrap().number_of_along_lat_points()
+        );
+        assert!(results[1].section3().is_none());
+        assert!(results[2].section3().is_none());
+    }
+
+    #[test]
+    fn audit_directory_with_other_products_ok() {
+        // 解析雨量と土砂災害警戒判定メッシュ情報のファイル
+        let temp_dir = TempPath::dir();
+        let dir = temp_dir.path();
+        std::fs::write(dir.join("a.bin"), small_prr_bytes(36_004_167, [1, 2, 3, 0])).unwrap();
+        std::fs::write(dir.join("b.bin"), small_lswj_bytes(&[0, 60])).unwrap();
+
+        let results = audit_directory(dir);
+        assert_eq!(2, results.len());
+        for result in &results {
+            assert!(result.is_valid(), "{:?}", result.errors());
+            assert_eq!(1, result.number_of_messages());
+            assert_eq!(2, result.section3().unwrap().number_of_along_lat_points());
+        }
+    }
+
+    #[test]
+    fn audit_directory_not_found() {
+        let dir = TempPath::dir().path().join("not_found");
+        let results = audit_directory(&dir);
+        assert_eq!(1, results.len());
+        assert_eq!(dir, results[0].path());
+        assert!(!results[0].is_valid());
+    }
+}
@@ -0,0 +1,484 @@
+use std::io::{Seek, Write};
+
+use super::sections::decimal_scale;
+use super::{Grid, ReaderError, ReaderResult};
+
+/// タイルの1辺の画素数
+const TILE_SIZE: usize = 256;
+/// 1タイルのバイト数（32ビット浮動小数点数）
+const TILE_BYTES: usize = TILE_SIZE * TILE_SIZE * 4;
+/// TIFFヘッダのバイト数
+const HEADER_BYTES: u64 = 8;
+
+/// TIFFのフィールド型:ASCII
+const TYPE_ASCII: u16 = 2;
+/// TIFFのフィールド型:SHORT
+const TYPE_SHORT: u16 = 3;
+/// TIFFのフィールド型:LONG
+const TYPE_LONG: u16 = 4;
+/// TIFFのフィールド型:DOUBLE
+const TYPE_DOUBLE: u16 = 12;
+
+/// TIFFタグ
+const TAG_NEW_SUBFILE_TYPE: u16 = 254;
+const TAG_IMAGE_WIDTH: u16 = 256;
+const TAG_IMAGE_LENGTH: u16 = 257;
+const TAG_BITS_PER_SAMPLE: u16 = 258;
+const TAG_COMPRESSION: u16 = 259;
+const TAG_PHOTOMETRIC_INTERPRETATION: u16 = 262;
+const TAG_SAMPLES_PER_PIXEL: u16 = 277;
+const TAG_PLANAR_CONFIGURATION: u16 = 284;
+const TAG_TILE_WIDTH: u16 = 322;
+const TAG_TILE_LENGTH: u16 = 323;
+const TAG_TILE_OFFSETS: u16 = 324;
+const TAG_TILE_BYTE_COUNTS: u16 = 325;
+const TAG_SAMPLE_FORMAT: u16 = 339;
+const TAG_MODEL_PIXEL_SCALE: u16 = 33550;
+const TAG_MODEL_TIEPOINT: u16 = 33922;
+const TAG_GEO_KEY_DIRECTORY: u16 = 34735;
+const TAG_GDAL_NODATA: u16 = 42113;
+
+/// COG（Cloud Optimized GeoTIFF）の地理参照
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CogGeoreference {
+    /// 最も西の格子の中心の経度（度）
+    pub west: f64,
+    /// 最も北の格子の中心の緯度（度）
+    pub north: f64,
+    /// 経度方向の格子の間隔（度）
+    pub lon_inc: f64,
+    /// 緯度方向の格子の間隔（度）
+    pub lat_inc: f64,
+    /// 地理座標系のEPSGコード
+    pub epsg: u16,
+}
+
+/// 格子をCOG（Cloud Optimized GeoTIFF）形式で出力する。
+///
+/// 物理値は32ビット浮動小数点数で、欠測値はNaNで出力する。画像は256×256画素のタイルに分割し、
+/// 縦横とも1タイルに収まるまで、縦横を1/2にしたオーバービューを生成する。オーバービューの画素は
+/// 欠測値を除いた2×2画素の平均値とする。全ての画像のIFDをファイルの先頭に、タイルを解像度が
+/// 低い画像から順に配置する。タイルは圧縮しない。
+///
+/// # 引数
+///
+/// * `writer` - 出力先（先頭に位置していること）
+/// * `grid` - 格子
+/// * `decimal_scale_factor` - データ代表値の尺度因子
+/// * `georeference` - 地理参照
+pub(crate) fn write_cog<W, V>(
+    writer: &mut W,
+    grid: &Grid<V>,
+    decimal_scale_factor: u8,
+    georeference: &CogGeoreference,
+) -> ReaderResult<()>
+where
+    W: Write + Seek,
+    V: Copy + Into<f64>,
+{
+    let to_error = |e: std::io::Error| {
+        ReaderError::Unexpected(format!("COG形式の出力に失敗しました: {}", e).into())
+    };
+    // 本体の画像と、解像度が高い順のオーバービュー
+    let mut rasters = vec![Raster::from_grid(grid, decimal_scale_factor)];
+    while let Some(last) = rasters.last() {
+        if last.width <= TILE_SIZE && last.height <= TILE_SIZE {
+            break;
+        }
+        rasters.push(last.downsample());
+    }
+
+    // IFDの位置と、IFDに収まらない値を記録する領域の大きさを決定
+    let mut ifds: Vec<Vec<Entry>> = rasters
+        .iter()
+        .enumerate()
+        .map(|(i, raster)| raster_entries(raster, i == 0, georeference))
+        .collect();
+    let ifds_bytes: u64 = ifds.iter().map(|entries| ifd_bytes(entries)).sum();
+    let values_bytes: u64 = ifds
+        .iter()
+        .flat_map(|entries| entries.iter())
+        .map(|entry| entry.external_bytes())
+        .sum();
+
+    // タイルは解像度が低い画像から順に配置
+    let mut offset = HEADER_BYTES + ifds_bytes + values_bytes;
+    for (raster, entries) in rasters.iter().zip(ifds.iter_mut()).rev() {
+        let tile_offsets = (0..raster.number_of_tiles())
+            .map(|i| to_u32(offset + (i * TILE_BYTES) as u64))
+            .collect::<ReaderResult<Vec<_>>>()?;
+        offset += (raster.number_of_tiles() * TILE_BYTES) as u64;
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.tag == TAG_TILE_OFFSETS)
+            .unwrap();
+        *entry = Entry::longs(TAG_TILE_OFFSETS, &tile_offsets);
+    }
+    to_u32(offset)?;
+
+    // TIFFヘッダ（リトルエンディアン）
+    let mut bytes = Vec::with_capacity((HEADER_BYTES + ifds_bytes + values_bytes) as usize);
+    bytes.extend_from_slice(b"II");
+    bytes.extend_from_slice(&42u16.to_le_bytes());
+    bytes.extend_from_slice(&(HEADER_BYTES as u32).to_le_bytes());
+    // IFD
+    let mut ifd_offset = HEADER_BYTES;
+    let mut value_offset = HEADER_BYTES + ifds_bytes;
+    let mut values = Vec::with_capacity(values_bytes as usize);
+    for (i, entries) in ifds.iter().enumerate() {
+        let next_ifd_offset = if i + 1 < ifds.len() {
+            ifd_offset + ifd_bytes(entries)
+        } else {
+            0
+        };
+        bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for entry in entries {
+            bytes.extend_from_slice(&entry.tag.to_le_bytes());
+            bytes.extend_from_slice(&entry.field_type.to_le_bytes());
+            bytes.extend_from_slice(&entry.count.to_le_bytes());
+            if entry.value.len() <= 4 {
+                let mut value = [0u8; 4];
+                value[..entry.value.len()].copy_from_slice(&entry.value);
+                bytes.extend_from_slice(&value);
+            } else {
+                bytes.extend_from_slice(&(value_offset as u32).to_le_bytes());
+                values.extend_from_slice(&entry.value);
+                values.resize(values.len() + entry.value.len() % 2, 0);
+                value_offset += entry.external_bytes();
+            }
+        }
+        bytes.extend_from_slice(&(next_ifd_offset as u32).to_le_bytes());
+        ifd_offset = next_ifd_offset;
+    }
+    bytes.extend_from_slice(&values);
+    writer.write_all(&bytes).map_err(to_error)?;
+
+    // タイル
+    for raster in rasters.iter().rev() {
+        for tile in 0..raster.number_of_tiles() {
+            writer
+                .write_all(&raster.tile_bytes(tile))
+                .map_err(to_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 本体の画像またはオーバービューの画素
+struct Raster {
+    /// 幅（画素）
+    width: usize,
+    /// 高さ（画素）
+    height: usize,
+    /// 行優先で格納した画素値（欠測値はNaN）
+    pixels: Vec<f32>,
+}
+
+impl Raster {
+    /// 格子から尺度因子を適用した物理値の画素を構築する。
+    fn from_grid<V>(grid: &Grid<V>, decimal_scale_factor: u8) -> Self
+    where
+        V: Copy + Into<f64>,
+    {
+        let scale = decimal_scale(decimal_scale_factor);
+        let (width, height) = (grid.ncols() as usize, grid.nrows() as usize);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in 0..grid.nrows() {
+            for col in 0..grid.ncols() {
+                pixels.push(match grid.get(row, col).flatten() {
+                    Some(value) => (value.into() / scale) as f32,
+                    None => f32::NAN,
+                });
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// 縦横を1/2にした画像を、欠測値を除いた2×2画素の平均値で構築する。
+    fn downsample(&self) -> Self {
+        let (width, height) = (self.width.div_ceil(2), self.height.div_ceil(2));
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col in 0..width {
+                let (mut sum, mut count) = (0f64, 0);
+                for r in (row * 2)..(row * 2 + 2).min(self.height) {
+                    for c in (col * 2)..(col * 2 + 2).min(self.width) {
+                        let value = self.pixels[r * self.width + c];
+                        if !value.is_nan() {
+                            sum += value as f64;
+                            count += 1;
+                        }
+                    }
+                }
+                pixels.push(if count == 0 {
+                    f32::NAN
+                } else {
+                    (sum / count as f64) as f32
+                });
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// 横方向のタイルの数を返す。
+    fn tiles_across(&self) -> usize {
+        self.width.div_ceil(TILE_SIZE)
+    }
+
+    /// タイルの数を返す。
+    fn number_of_tiles(&self) -> usize {
+        self.tiles_across() * self.height.div_ceil(TILE_SIZE)
+    }
+
+    /// 行優先で数えたタイルのバイト列を返す。画像の外の画素はNaNで埋める。
+    fn tile_bytes(&self, tile: usize) -> Vec<u8> {
+        let (top, left) = (
+            tile / self.tiles_across() * TILE_SIZE,
+            tile % self.tiles_across() * TILE_SIZE,
+        );
+        let mut bytes = Vec::with_capacity(TILE_BYTES);
+        for row in top..top + TILE_SIZE {
+            for col in left..left + TILE_SIZE {
+                let value = if row < self.height && col < self.width {
+                    self.pixels[row * self.width + col]
+                } else {
+                    f32::NAN
+                };
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+}
+
+/// IFDのエントリ
+struct Entry {
+    /// タグ
+    tag: u16,
+    /// フィールド型
+    field_type: u16,
+    /// 値の数
+    count: u32,
+    /// リトルエンディアンで格納した値
+    value: Vec<u8>,
+}
+
+impl Entry {
+    fn shorts(tag: u16, values: &[u16]) -> Self {
+        Self {
+            tag,
+            field_type: TYPE_SHORT,
+            count: values.len() as u32,
+            value: values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        }
+    }
+
+    fn longs(tag: u16, values: &[u32]) -> Self {
+        Self {
+            tag,
+            field_type: TYPE_LONG,
+            count: values.len() as u32,
+            value: values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        }
+    }
+
+    fn doubles(tag: u16, values: &[f64]) -> Self {
+        Self {
+            tag,
+            field_type: TYPE_DOUBLE,
+            count: values.len() as u32,
+            value: values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        }
+    }
+
+    fn ascii(tag: u16, value: &str) -> Self {
+        let mut value = value.as_bytes().to_vec();
+        value.push(0);
+        Self {
+            tag,
+            field_type: TYPE_ASCII,
+            count: value.len() as u32,
+            value,
+        }
+    }
+
+    /// IFDに収まらず、IFDの外に記録する値のバイト数（ワード境界に揃える）を返す。
+    fn external_bytes(&self) -> u64 {
+        match self.value.len() {
+            len if len <= 4 => 0,
+            len => (len + len % 2) as u64,
+        }
+    }
+}
+
+/// 画像のIFDのエントリを、タグの昇順で返す。
+///
+/// タイルの位置は仮の値で、配置を決定した後に置き換える。
+fn raster_entries(raster: &Raster, is_main: bool, georeference: &CogGeoreference) -> Vec<Entry> {
+    let number_of_tiles = raster.number_of_tiles();
+    let mut entries = vec![
+        Entry::longs(TAG_NEW_SUBFILE_TYPE, &[if is_main { 0 } else { 1 }]),
+        Entry::longs(TAG_IMAGE_WIDTH, &[raster.width as u32]),
+        Entry::longs(TAG_IMAGE_LENGTH, &[raster.height as u32]),
+        Entry::shorts(TAG_BITS_PER_SAMPLE, &[32]),
+        // 圧縮なし
+        Entry::shorts(TAG_COMPRESSION, &[1]),
+        // BlackIsZero
+        Entry::shorts(TAG_PHOTOMETRIC_INTERPRETATION, &[1]),
+        Entry::shorts(TAG_SAMPLES_PER_PIXEL, &[1]),
+        Entry::shorts(TAG_PLANAR_CONFIGURATION, &[1]),
+        Entry::shorts(TAG_TILE_WIDTH, &[TILE_SIZE as u16]),
+        Entry::shorts(TAG_TILE_LENGTH, &[TILE_SIZE as u16]),
+        Entry::longs(TAG_TILE_OFFSETS, &vec![0; number_of_tiles]),
+        Entry::longs(
+            TAG_TILE_BYTE_COUNTS,
+            &vec![TILE_BYTES as u32; number_of_tiles],
+        ),
+        // IEEE浮動小数点数
+        Entry::shorts(TAG_SAMPLE_FORMAT, &[3]),
+    ];
+    if is_main {
+        let g = georeference;
+        entries.push(Entry::doubles(
+            TAG_MODEL_PIXEL_SCALE,
+            &[g.lon_inc, g.lat_inc, 0.0],
+        ));
+        // 左上の画素の左上隅の座標
+        entries.push(Entry::doubles(
+            TAG_MODEL_TIEPOINT,
+            &[
+                0.0,
+                0.0,
+                0.0,
+                g.west - g.lon_inc / 2.0,
+                g.north + g.lat_inc / 2.0,
+                0.0,
+            ],
+        ));
+        entries.push(Entry::shorts(
+            TAG_GEO_KEY_DIRECTORY,
+            &[
+                // バージョン、キーの改訂番号、マイナー改訂番号、キーの数
+                1, 1, 0, 3, // GTModelTypeGeoKey: 地理座標系
+                1024, 0, 1, 2, // GTRasterTypeGeoKey: RasterPixelIsArea
+                1025, 0, 1, 1, // GeographicTypeGeoKey
+                2048, 0, 1, g.epsg,
+            ],
+        ));
+    }
+    entries.push(Entry::ascii(TAG_GDAL_NODATA, "nan"));
+
+    entries
+}
+
+/// IFDのバイト数を返す。
+fn ifd_bytes(entries: &[Entry]) -> u64 {
+    2 + 12 * entries.len() as u64 + 4
+}
+
+/// TIFFのオフセットを32ビットに変換する。
+fn to_u32(offset: u64) -> ReaderResult<u32> {
+    u32::try_from(offset).map_err(|_| {
+        ReaderError::Unexpected(
+            format!(
+                "COG形式で出力するファイルの大きさ({}バイト)が、TIFF形式の上限を超えました。",
+                offset
+            )
+            .into(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{write_cog, CogGeoreference, TILE_BYTES};
+    use crate::reader::Grid;
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// IFDのタグの値（IFDに収まる値またはIFDの外の値の位置）を返す。
+    fn tag_value(bytes: &[u8], ifd: usize, tag: u16) -> Option<u32> {
+        let n = u16_at(bytes, ifd) as usize;
+        (0..n)
+            .map(|i| ifd + 2 + 12 * i)
+            .find(|&entry| u16_at(bytes, entry) == tag)
+            .map(|entry| u32_at(bytes, entry + 8))
+    }
+
+    #[test]
+    fn write_cog_ok() {
+        // 600行×520列の格子は、オーバービュー300×260と150×130を生成
+        let (nrows, ncols) = (600_u32, 520_u32);
+        let cells = (0..nrows * ncols)
+            .map(|i| if i % 7 == 0 { None } else { Some(25_u16) })
+            .collect();
+        let grid = Grid::from_cells(nrows, ncols, cells);
+        let georeference = CogGeoreference {
+            west: 118.00625,
+            north: 47.99583,
+            lon_inc: 0.0125,
+            lat_inc: 0.008333,
+            epsg: 4019,
+        };
+        let mut cursor = Cursor::new(vec![]);
+        write_cog(&mut cursor, &grid, 1, &georeference).unwrap();
+        let bytes = cursor.into_inner();
+
+        assert_eq!(b"II", &bytes[..2]);
+        assert_eq!(42, u16_at(&bytes, 2));
+        // IFDをたどり、画像の大きさとタイルの位置を確認
+        let mut ifd = u32_at(&bytes, 4) as usize;
+        let mut sizes = vec![];
+        let mut first_tiles = vec![];
+        while ifd != 0 {
+            sizes.push((
+                tag_value(&bytes, ifd, 256).unwrap(),
+                tag_value(&bytes, ifd, 257).unwrap(),
+            ));
+            let number_of_tiles = u32_at(&bytes, ifd + 2 + 12 * 10 + 4) as usize;
+            let offsets = tag_value(&bytes, ifd, 324).unwrap() as usize;
+            let first_tile = if number_of_tiles == 1 {
+                offsets
+            } else {
+                u32_at(&bytes, offsets) as usize
+            };
+            first_tiles.push(first_tile);
+            let n = u16_at(&bytes, ifd) as usize;
+            ifd = u32_at(&bytes, ifd + 2 + 12 * n) as usize;
+        }
+        assert_eq!(vec![(520, 600), (260, 300), (130, 150)], sizes);
+        // IFDはタイルより前に、タイルは解像度が低い画像から順に配置
+        assert!(first_tiles.iter().all(|&tile| tile > 8));
+        assert!(first_tiles[2] < first_tiles[1] && first_tiles[1] < first_tiles[0]);
+        assert_eq!(first_tiles[0] + 9 * TILE_BYTES, bytes.len());
+        // 地理参照は本体の画像にのみ記録
+        let first_ifd = u32_at(&bytes, 4) as usize;
+        assert!(tag_value(&bytes, first_ifd, 34735).is_some());
+        assert!(tag_value(&bytes, first_ifd + 2 + 12 * 17 + 4, 34735).is_none());
+        // 本体の画像の最初の画素は欠測値、2番目の画素は尺度因子を適用した物理値
+        let first = f32::from_le_bytes(bytes[first_tiles[0]..][..4].try_into().unwrap());
+        let second = f32::from_le_bytes(bytes[first_tiles[0] + 4..][..4].try_into().unwrap());
+        assert!(first.is_nan());
+        assert_eq!(2.5, second);
+    }
+}
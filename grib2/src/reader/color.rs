@@ -0,0 +1,86 @@
+use super::{ReaderError, ReaderResult};
+
+/// 透明を示すRGBA
+pub(crate) const TRANSPARENT: [u8; 4] = [0, 0, 0, 0];
+
+/// カラーマップ
+///
+/// レベル値をRGBAの色に対応付ける。各色は、その色を適用するレベル値の下限と組み合わせて登録し、
+/// レベル値には、レベル値以下で最大の下限の色を適用する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorMap {
+    /// 色を適用するレベル値の下限とRGBAの組み合わせ（下限の昇順）
+    entries: Vec<(u16, [u8; 4])>,
+}
+
+impl ColorMap {
+    /// カラーマップを構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `entries` - 色を適用するレベル値の下限とRGBAの組み合わせ（下限の昇順）
+    ///
+    /// # 戻り値
+    ///
+    /// カラーマップ
+    pub fn new(entries: Vec<(u16, [u8; 4])>) -> ReaderResult<Self> {
+        if entries.is_empty() {
+            return Err(ReaderError::Unexpected(
+                "カラーマップに色が登録されていません。".into(),
+            ));
+        }
+        if let Some(i) = entries.windows(2).position(|w| w[1].0 <= w[0].0) {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "カラーマップの{}番目の下限({})が、前の下限({})以下です。",
+                    i + 2,
+                    entries[i + 1].0,
+                    entries[i].0
+                )
+                .into(),
+            ));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// レベル値に対応する色を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `level` - レベル値
+    ///
+    /// # 戻り値
+    ///
+    /// RGBA。レベル値が最初の下限より小さい場合は透明
+    pub fn color(&self, level: u16) -> [u8; 4] {
+        match self.entries.partition_point(|(lower, _)| *lower <= level) {
+            0 => TRANSPARENT,
+            n => self.entries[n - 1].1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorMap, TRANSPARENT};
+
+    #[test]
+    fn color_ok() {
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let colormap = ColorMap::new(vec![(1, red), (3, blue)]).unwrap();
+        assert_eq!(TRANSPARENT, colormap.color(0));
+        assert_eq!(red, colormap.color(1));
+        assert_eq!(red, colormap.color(2));
+        assert_eq!(blue, colormap.color(3));
+        assert_eq!(blue, colormap.color(u16::MAX));
+    }
+
+    #[test]
+    fn new_with_unordered_entries_err() {
+        assert!(ColorMap::new(vec![]).is_err());
+        assert!(ColorMap::new(vec![(3, [0; 4]), (3, [0; 4])]).is_err());
+        assert!(ColorMap::new(vec![(3, [0; 4]), (1, [0; 4])]).is_err());
+    }
+}
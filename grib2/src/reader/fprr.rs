@@ -3,10 +3,13 @@ use std::io::{Seek, SeekFrom};
 use std::path::Path;
 
 use super::sections::{
-    FromReader, Section0, Section1, Section2, Section3_0, Section4_50009, Section5_200u16,
-    Section6, Section7_200, Section8,
+    impl_leading_section_getters, peek_section_number, validate_section_limits,
+    write_leading_sections_debug_info, FromReader, LeadingSections, MessageBoundary, Section0,
+    Section1, Section2, Section3_0, Section4_50009, Section5_200u16, Section6, Section7_200,
+    Section8,
 };
-use super::{FileReader, ForecastHour6, Grib2ValueIter, ReaderError, ReaderResult};
+use super::value_iter::RunLength;
+use super::{FileReader, ForecastHour6, Grib2ValueIter, ReadLimits, ReaderError, ReaderResult};
 
 /// 1kmメッシュ降水短時間予報リーダー
 pub struct FprrReader<P>
@@ -30,11 +33,60 @@ pub struct FPprSections {
 }
 
 impl FPprSections {
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    /// 時間ステップの第4節から第7節を読み込む。
+    ///
+    /// 時間ステップの前に第3節が繰り返し記録されている場合は、その格子の定義が最初の第3節と
+    /// 一致することを確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 時間ステップの先頭に位置するGRIB2ファイルリーダー
+    /// * `boundary` - GRIB報の境界
+    /// * `section3` - 最初の第3節
+    /// * `limits` - 読み込みの上限
+    /// * `hour` - 時間ステップ
+    ///
+    /// # 戻り値
+    ///
+    /// 時間ステップの第4節から第7節
+    fn from_reader(
+        reader: &mut FileReader,
+        boundary: &MessageBoundary,
+        section3: &Section3_0,
+        limits: &ReadLimits,
+        hour: ForecastHour6,
+    ) -> ReaderResult<Self> {
+        if peek_section_number(reader)? == 3 {
+            validate_section_limits(reader, 3, limits)?;
+            let repeated = Section3_0::from_reader(reader)?;
+            boundary.validate(reader, "第3節")?;
+            let differences = section3.grid_differences(&repeated);
+            if !differences.is_empty() {
+                return Err(ReaderError::Unexpected(
+                    format!(
+                        "{}の第3節の{}が、最初の第3節と一致しません。",
+                        hour,
+                        differences.join("、")
+                    )
+                    .into(),
+                ));
+            }
+        }
+        validate_section_limits(reader, 4, limits)?;
         let section4 = Section4_50009::from_reader(reader)?;
+        boundary.validate(reader, "第4節")?;
+        validate_section_limits(reader, 5, limits)?;
         let section5 = Section5_200u16::from_reader(reader)?;
+        boundary.validate(reader, "第5節")?;
+        if limits.validate_levels() {
+            section5.validate_levels()?;
+        }
+        validate_section_limits(reader, 6, limits)?;
         let section6 = Section6::from_reader(reader)?;
+        boundary.validate(reader, "第6節")?;
+        validate_section_limits(reader, 7, limits)?;
         let section7 = Section7_200::from_reader(reader)?;
+        boundary.validate(reader, "第7節")?;
 
         Ok(FPprSections {
             section4,
@@ -49,7 +101,7 @@ impl FPprSections {
 macro_rules! impl_forecast_values_iter {
     ($([$fn:ident, $hour:ident]),*) => {
         $(
-            pub fn $fn(&mut self) -> ReaderResult<Grib2ValueIter<'_, u16>> {
+            pub fn $fn(&mut self) -> ReaderResult<Grib2ValueIter<'static, u16>> {
                 self.forecast_value_iter(ForecastHour6::$hour)
             }
         )*
@@ -60,21 +112,54 @@ impl<P> FprrReader<P>
 where
     P: AsRef<Path>,
 {
+    /// ファイルパスを受け取り、1kmメッシュ降水短時間予報リーダーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// 1kmメッシュ降水短時間予報リーダー
     pub fn new(path: P) -> ReaderResult<Self> {
+        Self::new_with_limits(path, ReadLimits::default())
+    }
+
+    /// ファイルパスと読み込みの上限を受け取り、1kmメッシュ降水短時間予報リーダーを返す。
+    ///
+    /// 第2節以降の節は、読み込む前に節の長さなどが上限以下であることを確認し、上限を超えている
+    /// 場合はメモリを確保せずにエラーを返す。第4節は、メソモデル予想値の結合比率の計算領域数も
+    /// 確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    /// * `limits` - 読み込みの上限
+    ///
+    /// # 戻り値
+    ///
+    /// 1kmメッシュ降水短時間予報リーダー
+    pub fn new_with_limits(path: P, limits: ReadLimits) -> ReaderResult<Self> {
         let file =
-            File::open(path.as_ref()).map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            File::open(path.as_ref()).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
-        let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2::from_reader(&mut reader)?;
-        let section3 = Section3_0::from_reader(&mut reader)?;
-        let hour1 = FPprSections::from_reader(&mut reader)?;
-        let hour2 = FPprSections::from_reader(&mut reader)?;
-        let hour3 = FPprSections::from_reader(&mut reader)?;
-        let hour4 = FPprSections::from_reader(&mut reader)?;
-        let hour5 = FPprSections::from_reader(&mut reader)?;
-        let hour6 = FPprSections::from_reader(&mut reader)?;
+        let LeadingSections {
+            section0,
+            section1,
+            section2,
+            section3,
+            boundary,
+        } = LeadingSections::from_reader_with_limits(&mut reader, &limits)?;
+        let mut read_forecast =
+            |hour| FPprSections::from_reader(&mut reader, &boundary, &section3, &limits, hour);
+        let hour1 = read_forecast(ForecastHour6::Hour1)?;
+        let hour2 = read_forecast(ForecastHour6::Hour2)?;
+        let hour3 = read_forecast(ForecastHour6::Hour3)?;
+        let hour4 = read_forecast(ForecastHour6::Hour4)?;
+        let hour5 = read_forecast(ForecastHour6::Hour5)?;
+        let hour6 = read_forecast(ForecastHour6::Hour6)?;
         let section8 = Section8::from_reader(&mut reader)?;
+        boundary.validate_end(&mut reader)?;
 
         Ok(Self {
             path,
@@ -87,64 +172,21 @@ where
         })
     }
 
-    /// 第0節:指示節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第0節:指示節
-    pub fn section0(&self) -> &Section0 {
-        &self.section0
-    }
-
-    /// 第1節:識別節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第1節:識別節
-    pub fn section1(&self) -> &Section1 {
-        &self.section1
-    }
-
-    /// 第2節:地域使用節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第2節:地域使用節
-    pub fn section2(&self) -> &Section2 {
-        &self.section2
-    }
-
-    /// 第3節:格子系定義節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第3節:格子系定義節
-    pub fn section3(&self) -> &Section3_0 {
-        &self.section3
-    }
+    impl_leading_section_getters!();
 
     // 時間別の降水短時間予報を返す。
     pub fn forecast(&self, hour: ForecastHour6) -> &FPprSections {
         &self.forecasts[hour as usize - 1]
     }
 
-    /// 第8節:気象要素値節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第8節:気象要素値節
-    pub fn section8(&self) -> &Section8 {
-        &self.section8
-    }
-
     /// 時間別の降水短時間予想値を返すイテレーターを返す。
     pub fn forecast_value_iter(
         &mut self,
         hour: ForecastHour6,
-    ) -> ReaderResult<Grib2ValueIter<'_, u16>> {
+    ) -> ReaderResult<Grib2ValueIter<'static, u16>> {
         let forecast = &self.forecasts[hour as usize - 1];
         let file = File::open(self.path.as_ref())
-            .map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            .map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
         reader
             .seek(SeekFrom::Start(
@@ -155,7 +197,7 @@ where
             })?;
 
         Ok(Grib2ValueIter::new(
-            reader,
+            RunLength::reader(reader),
             forecast.section7.run_length_bytes(),
             self.section3.number_of_data_points(),
             self.section3.lat_of_first_grid_point(),
@@ -163,10 +205,13 @@ where
             self.section3.lon_of_last_grid_point(),
             self.section3.j_direction_increment(),
             self.section3.i_direction_increment(),
+            self.section3.scanning_mode(),
             forecast.section5.bits_per_value() as u16,
             forecast.section5.max_level_value(),
+            forecast.section5.decimal_scale_factor(),
             forecast.section5.level_values(),
-        ))
+        )
+        .with_bitmap(forecast.section6.shared_bitmap()))
     }
 
     // 時間別の降水短時間予想値を返すイテレーターを返すメソッド
@@ -184,14 +229,13 @@ where
     where
         W: std::io::Write,
     {
-        self.section0.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section1.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section2.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section3.debug_info(writer)?;
-        writeln!(writer)?;
+        write_leading_sections_debug_info(
+            writer,
+            &self.section0,
+            &self.section1,
+            &self.section2,
+            &self.section3,
+        )?;
         for i in 0..6usize {
             writeln!(writer, "{}時間後予想値:", i + 1)?;
             self.forecasts[i].debug_info(writer)?;
@@ -257,3 +301,76 @@ impl FPprSections {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FprrReader;
+    use crate::reader::sections::{small_prr_bytes, TempPath};
+    use crate::reader::{ReadLimits, ReaderError};
+
+    /// 2行2列の格子の降水短時間予報のGRIB2ファイルを一時ファイルとして作成する。
+    ///
+    /// 引数`repeated`に指定した時間ステップ（0から5）の前には、最初の格子点の緯度を指定した
+    /// 第3節を繰り返し記録する。
+    fn small_fprr_file(repeated: &[(usize, u32)]) -> TempPath {
+        // 第0節から第3節と第5節から第7節は解析雨量と同じ構成
+        let prr = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let section4 = prr.len() - 4 - 9 - 6 - 23 - 82;
+        let mut bytes = prr[..16 + 21 + 72].to_vec();
+        for step in 0..6 {
+            if let Some(&(_, lat)) = repeated.iter().find(|(s, _)| *s == step) {
+                bytes.extend_from_slice(&small_prr_bytes(lat, [1, 2, 3, 0])[16 + 21..16 + 21 + 72]);
+            }
+            // 第4節（テンプレート4.50009、計算領域数0）
+            let start = bytes.len();
+            bytes.extend_from_slice(&prr[section4..section4 + 82]);
+            bytes[start + 3] = 85;
+            bytes[start + 7..start + 9].copy_from_slice(&50009u16.to_be_bytes());
+            bytes.extend_from_slice(&[0x00; 3]);
+            // 第5節から第7節
+            bytes.extend_from_slice(&prr[section4 + 82..prr.len() - 4]);
+        }
+        // 第8節
+        bytes.extend_from_slice(b"7777");
+        let total_length = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_length.to_be_bytes());
+
+        TempPath::file(&bytes)
+    }
+
+    #[test]
+    fn new_with_repeated_same_grid_ok() {
+        let path = small_fprr_file(&[(0, 36_004_167), (3, 36_004_167)]);
+        let mut reader = FprrReader::new(&path).unwrap();
+        assert_eq!(4, reader.forecast_hour6_value_iter().unwrap().count());
+    }
+
+    #[test]
+    fn new_with_shifted_grid_err() {
+        // 3時間後予報の格子を1格子分北にずらしたファイル
+        let path = small_fprr_file(&[(2, 36_004_167 + 8_333)]);
+        match FprrReader::new(&path) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.starts_with("3時間後予報の第3節の最初の格子点の緯度"));
+                assert!(!message.contains("経度"));
+            }
+            _ => panic!("格子の定義が一致しないエラーにならなければなりません。"),
+        }
+    }
+
+    #[test]
+    fn new_with_limits_rejects_too_many_calculation_areas() {
+        // 最初の時間ステップの第4節のメソモデル予想値の結合比率の計算領域数を1に変更
+        let mut bytes = std::fs::read(small_fprr_file(&[])).unwrap();
+        let section4 = 16 + 21 + 72;
+        bytes[section4 + 82..section4 + 84].copy_from_slice(&1u16.to_be_bytes());
+        let path = TempPath::file(&bytes);
+        let limits = ReadLimits::default().with_max_calculation_areas(0);
+        match FprrReader::new_with_limits(&path, limits) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("計算領域数(1)が上限(0)"))
+            }
+            _ => panic!("計算領域数が上限を超えるエラーを想定しています。"),
+        }
+    }
+}
@@ -3,11 +3,13 @@ use std::io::{Seek, SeekFrom};
 use std::path::Path;
 
 use super::sections::{
-    FromReader, PswSections, Section0, Section1, Section2, Section3_0, Section8,
+    impl_leading_section_getters, write_leading_sections_debug_info, FromReader, LeadingSections,
+    MessageBoundary, PswSections, Section0, Section1, Section2, Section3_0, Section8,
 };
+use super::value_iter::RunLength;
 use super::{
-    vec_to_fixed_array, FileReader, ForecastHour6, Grib2ValueIter, PswTank, ReaderError,
-    ReaderResult,
+    vec_to_fixed_array, FileReader, ForecastHour6, Grib2ValueIter, PswTank, ReadLimits,
+    ReaderError, ReaderResult,
 };
 
 /// 土壌雨量指数6時間予想値(1km メッシュ)リーダー
@@ -30,10 +32,14 @@ pub struct Forecast {
 }
 
 impl Forecast {
-    pub(crate) fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
-        let swi = PswSections::from_reader(reader)?;
-        let first_tank = PswSections::from_reader(reader)?;
-        let second_tank = PswSections::from_reader(reader)?;
+    pub(crate) fn from_reader(
+        reader: &mut FileReader,
+        boundary: &MessageBoundary,
+        limits: &ReadLimits,
+    ) -> ReaderResult<Self> {
+        let swi = PswSections::from_reader(reader, boundary, limits)?;
+        let first_tank = PswSections::from_reader(reader, boundary, limits)?;
+        let second_tank = PswSections::from_reader(reader, boundary, limits)?;
 
         Ok(Self {
             tanks: [swi, first_tank, second_tank],
@@ -74,19 +80,40 @@ where
     ///
     /// 土壌雨量指数6時間予想値リーダー
     pub fn new(path: P) -> ReaderResult<Self> {
+        Self::new_with_limits(path, ReadLimits::default())
+    }
+
+    /// ファイルパスと読み込みの上限を受け取り、土壌雨量指数6時間予想値リーダーを返す。
+    ///
+    /// 第2節以降の節は、読み込む前に節の長さなどが上限以下であることを確認し、上限を超えている
+    /// 場合はメモリを確保せずにエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    /// * `limits` - 読み込みの上限
+    ///
+    /// # 戻り値
+    ///
+    /// 土壌雨量指数6時間予想値リーダー
+    pub fn new_with_limits(path: P, limits: ReadLimits) -> ReaderResult<Self> {
         let file =
-            File::open(path.as_ref()).map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            File::open(path.as_ref()).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
-        let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2::from_reader(&mut reader)?;
-        let section3 = Section3_0::from_reader(&mut reader)?;
+        let LeadingSections {
+            section0,
+            section1,
+            section2,
+            section3,
+            boundary,
+        } = LeadingSections::from_reader_with_limits(&mut reader, &limits)?;
         let mut forecasts = vec![];
         for _ in 0..6 {
-            forecasts.push(Forecast::from_reader(&mut reader)?);
+            forecasts.push(Forecast::from_reader(&mut reader, &boundary, &limits)?);
         }
         let forecasts = vec_to_fixed_array(forecasts)?;
         let section8 = Section8::from_reader(&mut reader)?;
+        boundary.validate_end(&mut reader)?;
 
         Ok(Self {
             path,
@@ -99,66 +126,23 @@ where
         })
     }
 
-    /// 第0節:指示節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第0節:指示節
-    pub fn section0(&self) -> &Section0 {
-        &self.section0
-    }
-
-    /// 第1節:識別節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第1節:識別節
-    pub fn section1(&self) -> &Section1 {
-        &self.section1
-    }
-
-    /// 第2節:地域使用節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第2節:地域使用節
-    pub fn section2(&self) -> &Section2 {
-        &self.section2
-    }
-
-    /// 第3節:格子系定義節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第3節:格子系定義節
-    pub fn section3(&self) -> &Section3_0 {
-        &self.section3
-    }
+    impl_leading_section_getters!();
 
     /// 時間別の土壌雨量指数予想値の第4節から第7節までを返す。
     pub fn forecast(&self, hour: ForecastHour6) -> &Forecast {
         &self.forecasts[hour as usize - 1]
     }
 
-    /// 第8節:気象要素値節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第8節:気象要素値節
-    pub fn section8(&self) -> &Section8 {
-        &self.section8
-    }
-
     /// 予想時間別、土壌雨量指数タンク別の土壌雨量指数予想値を返すイテレーターを返す。
     pub fn forecast_value_iter(
         &mut self,
         hour: ForecastHour6,
         tank: PswTank,
-    ) -> ReaderResult<Grib2ValueIter<'_, u16>> {
+    ) -> ReaderResult<Grib2ValueIter<'static, u16>> {
         let forecast = self.forecast(hour);
         let tank = forecast.tank(tank);
         let file = File::open(self.path.as_ref())
-            .map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            .map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
         reader
             .seek(SeekFrom::Start(tank.section7().run_length_position() as u64))
@@ -167,7 +151,7 @@ where
             })?;
 
         Ok(Grib2ValueIter::new(
-            reader,
+            RunLength::reader(reader),
             tank.section7().run_length_bytes(),
             self.section3.number_of_data_points(),
             self.section3.lat_of_first_grid_point(),
@@ -175,10 +159,13 @@ where
             self.section3.lon_of_last_grid_point(),
             self.section3.j_direction_increment(),
             self.section3.i_direction_increment(),
+            self.section3.scanning_mode(),
             tank.section5().bits_per_value() as u16,
             tank.section5().max_level_value(),
+            tank.section5().decimal_scale_factor(),
             tank.section5().level_values(),
-        ))
+        )
+        .with_bitmap(tank.section6().shared_bitmap()))
     }
 
     /// 全ての節を出力する。
@@ -186,14 +173,13 @@ where
     where
         W: std::io::Write,
     {
-        self.section0.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section1.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section2.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section3.debug_info(writer)?;
-        writeln!(writer)?;
+        write_leading_sections_debug_info(
+            writer,
+            &self.section0,
+            &self.section1,
+            &self.section2,
+            &self.section3,
+        )?;
         for i in 0..6usize {
             writeln!(writer, "{}時間後予想値:", i + 1)?;
             self.forecasts[i].debug_info(writer)?;
@@ -0,0 +1,744 @@
+use macros::Getter;
+use num_format::{Locale, ToFormattedString};
+
+use super::sections::Section3_0;
+use super::value_iter::Grib2ValueIter;
+use super::{ReaderError, ReaderResult};
+
+/// 走査モード:i方向が負（東から西）であることを示すビット
+pub(crate) const SCANNING_MODE_NEGATIVE_I: u8 = 0x80;
+/// 走査モード:j方向が正（南から北）であることを示すビット
+pub(crate) const SCANNING_MODE_POSITIVE_J: u8 = 0x40;
+/// 走査モード:j方向に隣接する格子点が連続することを示すビット
+pub(crate) const SCANNING_MODE_CONSECUTIVE_J: u8 = 0x20;
+/// 走査モード:隣接する行（列）の走査方向が反対（牛耕式）であることを示すビット
+pub(crate) const SCANNING_MODE_BOUSTROPHEDON: u8 = 0x10;
+
+/// 格子
+///
+/// 北を上、西を左とした行×列の2次元構造で、各格子の物理値を保持する。
+/// 1行目は最も北の格子、1列目は最も西の格子である。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<V = u16> {
+    /// 行数（経線に沿った格子点数）
+    nrows: u32,
+    /// 列数（緯線に沿った格子点数）
+    ncols: u32,
+    /// 行優先で格納した物理値（欠測値は`None`）
+    cells: Vec<Option<V>>,
+}
+
+impl<V> Grid<V>
+where
+    V: Copy,
+{
+    /// GRIB2値を走査するイテレーターから格子を構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `iter` - GRIB2値を走査するイテレーター
+    /// * `nrows` - 行数（経線に沿った格子点数）
+    /// * `ncols` - 列数（緯線に沿った格子点数）
+    /// * `number_of_points` - 第3節に記録されている資料点数
+    /// * `scanning_mode` - 第3節に記録されている走査モード
+    ///
+    /// # 戻り値
+    ///
+    /// 格子
+    pub(crate) fn from_values(
+        iter: Grib2ValueIter<'_, V>,
+        nrows: u32,
+        ncols: u32,
+        number_of_points: u32,
+        scanning_mode: u8,
+    ) -> ReaderResult<Self> {
+        let expected = nrows as usize * ncols as usize;
+        if number_of_points as usize != expected {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "第3節に記録されている資料点数({})が行数と列数の積({})と一致しません。",
+                    number_of_points.to_formatted_string(&Locale::ja),
+                    expected.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        let mut cells = vec![None; expected];
+        let mut count = 0_usize;
+        for value in iter {
+            let value = value?;
+            // 格子点数を超えた値は格納せずに数えて、エラーに展開した格子の数を含める
+            if count < expected {
+                let (row, col) =
+                    cell_position(count, nrows as usize, ncols as usize, scanning_mode);
+                cells[row * ncols as usize + col] = value.value;
+            }
+            count += 1;
+        }
+        if count != expected {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "展開した格子の数({})が行数と列数の積({})と一致しません。",
+                    count.to_formatted_string(&Locale::ja),
+                    expected.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        Ok(Self {
+            nrows,
+            ncols,
+            cells,
+        })
+    }
+
+    /// 行優先で格納した物理値から格子を構築する。
+    #[cfg(test)]
+    pub(crate) fn from_cells(nrows: u32, ncols: u32, cells: Vec<Option<V>>) -> Self {
+        assert_eq!(nrows as usize * ncols as usize, cells.len());
+
+        Self {
+            nrows,
+            ncols,
+            cells,
+        }
+    }
+
+    /// 行数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 行数
+    pub fn nrows(&self) -> u32 {
+        self.nrows
+    }
+
+    /// 列数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 列数
+    pub fn ncols(&self) -> u32 {
+        self.ncols
+    }
+
+    /// 格子の物理値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `row` - 行番号（0始まり、北から南）
+    /// * `col` - 列番号（0始まり、西から東）
+    ///
+    /// # 戻り値
+    ///
+    /// 格子の物理値。行番号または列番号が範囲外の場合は`None`、格子が欠測値の場合は`Some(None)`
+    pub fn get(&self, row: u32, col: u32) -> Option<Option<V>> {
+        if self.nrows <= row || self.ncols <= col {
+            return None;
+        }
+
+        Some(self.cells[row as usize * self.ncols as usize + col as usize])
+    }
+}
+
+impl<V> Grid<V>
+where
+    V: Copy + Into<f64> + Send + Sync,
+{
+    /// ブロックマッチングで、次の時刻の格子への各ブロックの移動量を推定する。
+    ///
+    /// 格子を`block`×`block`の重ならないブロックに分割し、各ブロックを上下左右に`block / 2`
+    /// （最小1）格子の範囲で移動させ、次の時刻の格子との平均絶対誤差が最小となる移動量を求める。
+    /// 比較する格子の一方が欠測値の場合は、その格子を誤差の計算から除外する。格子の端のブロックで
+    /// 移動させたブロックが格子からはみ出す場合は、格子の内側に残った格子のみで誤差を計算する。
+    /// また、物理値が
+    /// 正の格子を含まないブロックは、移動量を推定できないため結果に含めない。
+    /// 計算量はブロックの大きさの4乗に比例するため、`block`で計算量を調整する。
+    /// feature`rayon`が有効な場合は、ブロック単位で並列に計算する。
+    ///
+    /// # 引数
+    ///
+    /// * `next` - 次の時刻の格子
+    /// * `block` - ブロックの1辺の格子数
+    ///
+    /// # 戻り値
+    ///
+    /// `(ブロック中心の行番号, ブロック中心の列番号, 行方向の移動量, 列方向の移動量)`の
+    /// コレクション。行方向は南、列方向は東を正とする。
+    pub(crate) fn motion_vectors(
+        &self,
+        next: &Grid<V>,
+        block: usize,
+    ) -> ReaderResult<Vec<(f64, f64, i64, i64)>> {
+        if block == 0 {
+            return Err(ReaderError::Unexpected(
+                "ブロックの大きさは1以上でなければなりません。".into(),
+            ));
+        }
+        if self.nrows != next.nrows || self.ncols != next.ncols {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "格子の行数と列数({}×{})が、次の時刻の格子の行数と列数({}×{})と一致しません。",
+                    self.nrows, self.ncols, next.nrows, next.ncols
+                )
+                .into(),
+            ));
+        }
+
+        let (nrows, ncols) = (self.nrows as usize, self.ncols as usize);
+        let blocks: Vec<(usize, usize)> = (0..nrows / block)
+            .flat_map(|r| (0..ncols / block).map(move |c| (r * block, c * block)))
+            .collect();
+        let estimate = |&(top, left): &(usize, usize)| self.block_motion(next, top, left, block);
+        #[cfg(feature = "rayon")]
+        let vectors = {
+            use rayon::prelude::*;
+            blocks.par_iter().filter_map(estimate).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let vectors = blocks.iter().filter_map(estimate).collect();
+
+        Ok(vectors)
+    }
+
+    /// 1つのブロックの移動量を推定する。
+    fn block_motion(
+        &self,
+        next: &Grid<V>,
+        top: usize,
+        left: usize,
+        block: usize,
+    ) -> Option<(f64, f64, i64, i64)> {
+        let ncols = self.ncols as usize;
+        let value = |cells: &[Option<V>], row: usize, col: usize| -> Option<f64> {
+            cells[row * ncols + col].map(Into::into)
+        };
+        // 物理値が正の格子を含まないブロックは、移動量を推定しない
+        let has_echo = (top..top + block)
+            .any(|r| (left..left + block).any(|c| value(&self.cells, r, c).unwrap_or(0.0) > 0.0));
+        if !has_echo {
+            return None;
+        }
+
+        let search = (block / 2).max(1) as i64;
+        let (nrows, ncols) = (self.nrows as i64, self.ncols as i64);
+        // (平均絶対誤差, 移動量の大きさ, 行方向の移動量, 列方向の移動量)
+        let mut best: Option<(f64, i64, i64, i64)> = None;
+        for dr in -search..=search {
+            for dc in -search..=search {
+                // 移動させたブロックが格子からはみ出す場合は、移動量を変えずに格子の内側に残った
+                // 格子のみを比較する
+                let mut sum = 0.0;
+                let mut count = 0;
+                let mut overlap = 0;
+                for r in 0..block {
+                    for c in 0..block {
+                        let (moved_row, moved_col) =
+                            (top as i64 + r as i64 + dr, left as i64 + c as i64 + dc);
+                        if !(0..nrows).contains(&moved_row) || !(0..ncols).contains(&moved_col) {
+                            continue;
+                        }
+                        overlap += 1;
+                        let current = value(&self.cells, top + r, left + c);
+                        let moved = value(&next.cells, moved_row as usize, moved_col as usize);
+                        if let (Some(current), Some(moved)) = (current, moved) {
+                            sum += (current - moved).abs();
+                            count += 1;
+                        }
+                    }
+                }
+                // 格子の内側に残った格子がブロックの半分未満の場合は、比較する格子が少なく誤差が
+                // 偶然小さくなりやすいため比較しない
+                if overlap * 2 < block * block || count == 0 {
+                    continue;
+                }
+                let score = sum / count as f64;
+                let magnitude = dr.abs() + dc.abs();
+                // 誤差が等しい場合は、移動量が小さい方を採用
+                let better = match best {
+                    None => true,
+                    Some((s, m, _, _)) => score < s || (score == s && magnitude < m),
+                };
+                if better {
+                    best = Some((score, magnitude, dr, dc));
+                }
+            }
+        }
+        let center = (block as f64 - 1.0) / 2.0;
+
+        best.map(|(_, _, dr, dc)| (top as f64 + center, left as f64 + center, dr, dc))
+    }
+}
+
+/// 走査順のインデックスに対応する格子の行番号と列番号を返す。
+///
+/// # 引数
+///
+/// * `index` - 走査順のインデックス
+/// * `nrows` - 行数
+/// * `ncols` - 列数
+/// * `scanning_mode` - 走査モード
+///
+/// # 戻り値
+///
+/// 北を上、西を左とした行番号と列番号
+pub(crate) fn cell_position(
+    index: usize,
+    nrows: usize,
+    ncols: usize,
+    scanning_mode: u8,
+) -> (usize, usize) {
+    // 走査方向に沿った位置を計算
+    let boustrophedon = scanning_mode & SCANNING_MODE_BOUSTROPHEDON != 0;
+    let (i, j) = if scanning_mode & SCANNING_MODE_CONSECUTIVE_J == 0 {
+        let (i, j) = (index % ncols, index / ncols);
+        match boustrophedon && j % 2 == 1 {
+            true => (ncols - 1 - i, j),
+            false => (i, j),
+        }
+    } else {
+        let (i, j) = (index / nrows, index % nrows);
+        match boustrophedon && i % 2 == 1 {
+            true => (i, nrows - 1 - j),
+            false => (i, j),
+        }
+    };
+    // 走査方向を北を上、西を左とした行番号と列番号に変換
+    let col = if scanning_mode & SCANNING_MODE_NEGATIVE_I == 0 {
+        i
+    } else {
+        ncols - 1 - i
+    };
+    let row = if scanning_mode & SCANNING_MODE_POSITIVE_J == 0 {
+        j
+    } else {
+        nrows - 1 - j
+    };
+
+    (row, col)
+}
+
+/// 格子系定義
+///
+/// 第3節に記録されている格子点数、最初及び最後の格子点、増分並びに走査モードを保持する。
+/// テンソル化するファイルや時系列のファイル間では、格子系定義が一致しなければならない。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Getter)]
+pub struct GridDefinition {
+    /// 緯線に沿った格子点数（列数）
+    #[getter(ret = "val")]
+    number_of_along_lat_points: u32,
+    /// 経線に沿った格子点数（行数）
+    #[getter(ret = "val")]
+    number_of_along_lon_points: u32,
+    /// 最初の格子点の緯度（10e-6度単位）
+    #[getter(ret = "val")]
+    lat_of_first_grid_point: u32,
+    /// 最初の格子点の経度（10e-6度単位）
+    #[getter(ret = "val")]
+    lon_of_first_grid_point: u32,
+    /// 最後の格子点の緯度（10e-6度単位）
+    #[getter(ret = "val")]
+    lat_of_last_grid_point: u32,
+    /// 最後の格子点の経度（10e-6度単位）
+    #[getter(ret = "val")]
+    lon_of_last_grid_point: u32,
+    /// i方向（経度方向）の増分（10e-6度単位）
+    #[getter(ret = "val")]
+    i_direction_increment: u32,
+    /// j方向（緯度方向）の増分（10e-6度単位）
+    #[getter(ret = "val")]
+    j_direction_increment: u32,
+    /// 走査モード
+    #[getter(ret = "val")]
+    scanning_mode: u8,
+}
+
+impl From<&Section3_0> for GridDefinition {
+    fn from(section3: &Section3_0) -> Self {
+        Self {
+            number_of_along_lat_points: section3.number_of_along_lat_points(),
+            number_of_along_lon_points: section3.number_of_along_lon_points(),
+            lat_of_first_grid_point: section3.lat_of_first_grid_point(),
+            lon_of_first_grid_point: section3.lon_of_first_grid_point(),
+            lat_of_last_grid_point: section3.lat_of_last_grid_point(),
+            lon_of_last_grid_point: section3.lon_of_last_grid_point(),
+            i_direction_increment: section3.i_direction_increment(),
+            j_direction_increment: section3.j_direction_increment(),
+            scanning_mode: section3.scanning_mode(),
+        }
+    }
+}
+
+impl GridDefinition {
+    /// 最初の格子点から増分と格子点数で格子系定義を構築する。
+    ///
+    /// 最後の格子点は、走査モードに従って最初の格子点から増分だけ離れた格子点とする。
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_first_grid_point(
+        nrows: u32,
+        ncols: u32,
+        lat_first: u32,
+        lon_first: u32,
+        lat_inc: u32,
+        lon_inc: u32,
+        scanning_mode: u8,
+    ) -> Self {
+        let lat_span = nrows.saturating_sub(1) * lat_inc;
+        let lon_span = ncols.saturating_sub(1) * lon_inc;
+        Self {
+            number_of_along_lat_points: ncols,
+            number_of_along_lon_points: nrows,
+            lat_of_first_grid_point: lat_first,
+            lon_of_first_grid_point: lon_first,
+            lat_of_last_grid_point: match scanning_mode & SCANNING_MODE_POSITIVE_J {
+                0 => lat_first.saturating_sub(lat_span),
+                _ => lat_first.saturating_add(lat_span),
+            },
+            lon_of_last_grid_point: match scanning_mode & SCANNING_MODE_NEGATIVE_I {
+                0 => lon_first.saturating_add(lon_span),
+                _ => lon_first.saturating_sub(lon_span),
+            },
+            i_direction_increment: lon_inc,
+            j_direction_increment: lat_inc,
+            scanning_mode,
+        }
+    }
+}
+
+/// 格子の行番号と列番号から、格子点の緯度と経度を返す。
+///
+/// 行番号と列番号は、走査モードによらず北を上、西を左とした格子の位置である。
+///
+/// # 引数
+///
+/// * `row` - 行番号（最も北の行が0）
+/// * `col` - 列番号（最も西の列が0）
+/// * `def` - 格子系定義
+///
+/// # 戻り値
+///
+/// 格子点の緯度と経度（度単位）を格納したタプル
+pub fn grid_point_to_coord(row: u32, col: u32, def: &GridDefinition) -> (f64, f64) {
+    let (lat, lon) = micro_degree_of_grid_point(row, col, def);
+
+    (lat as f64 / 1_000_000.0, lon as f64 / 1_000_000.0)
+}
+
+/// 緯度と経度から、最も近い格子点の行番号と列番号を返す。
+///
+/// # 引数
+///
+/// * `lat` - 緯度（度単位）
+/// * `lon` - 経度（度単位）
+/// * `def` - 格子系定義
+///
+/// # 戻り値
+///
+/// 北を上、西を左とした行番号と列番号を格納したタプル。座標が格子の範囲から増分の半分以上
+/// 離れている場合は`None`
+pub fn coord_to_grid_point(lat: f64, lon: f64, def: &GridDefinition) -> Option<(u32, u32)> {
+    let nrows = def.number_of_along_lon_points as f64;
+    let ncols = def.number_of_along_lat_points as f64;
+    // 最初の格子点から走査方向に何番目の格子点かを計算
+    let j =
+        (lat * 1_000_000.0 - def.lat_of_first_grid_point as f64) / def.j_direction_increment as f64;
+    let j = match def.scanning_mode & SCANNING_MODE_POSITIVE_J {
+        0 => -j,
+        _ => j,
+    }
+    .round();
+    let i =
+        (lon * 1_000_000.0 - def.lon_of_first_grid_point as f64) / def.i_direction_increment as f64;
+    let i = match def.scanning_mode & SCANNING_MODE_NEGATIVE_I {
+        0 => i,
+        _ => -i,
+    }
+    .round();
+    if !(0.0..nrows).contains(&j) || !(0.0..ncols).contains(&i) {
+        return None;
+    }
+    // 走査方向を北を上、西を左とした行番号と列番号に変換
+    let row = match def.scanning_mode & SCANNING_MODE_POSITIVE_J {
+        0 => j,
+        _ => nrows - 1.0 - j,
+    };
+    let col = match def.scanning_mode & SCANNING_MODE_NEGATIVE_I {
+        0 => i,
+        _ => ncols - 1.0 - i,
+    };
+
+    Some((row as u32, col as u32))
+}
+
+/// ガウス格子の緯度を、北から順に返す。
+///
+/// ガウス緯度は、`2N`次のルジャンドル多項式の根`x`に対する`asin(x)`である。根は
+/// ニュートン法で求める。
+///
+/// # 引数
+///
+/// * `n` - 極と赤道の間の緯線の数`N`
+///
+/// # 戻り値
+///
+/// 北から南の順に並べた`2N`本の緯度（度単位）
+pub fn gaussian_latitudes(n: u32) -> Vec<f64> {
+    let degree = 2 * n as usize;
+    let mut latitudes = vec![0.0; degree];
+    // 根は赤道に対して対称であるため、北半球の根を求めて南半球に反転する
+    for i in 0..n as usize {
+        // 根の初期値
+        let mut x = (std::f64::consts::PI * (i as f64 + 0.75) / (degree as f64 + 0.5)).cos();
+        for _ in 0..100 {
+            // 漸化式でルジャンドル多項式の値とその導関数の値を計算
+            let (mut p0, mut p1) = (1.0, x);
+            for k in 2..=degree {
+                (p0, p1) = (
+                    p1,
+                    ((2 * k - 1) as f64 * x * p1 - (k - 1) as f64 * p0) / k as f64,
+                );
+            }
+            let dp = degree as f64 * (x * p1 - p0) / (x * x - 1.0);
+            let dx = p1 / dp;
+            x -= dx;
+            if dx.abs() < 1e-15 {
+                break;
+            }
+        }
+        let lat = x.asin().to_degrees();
+        latitudes[i] = lat;
+        latitudes[degree - 1 - i] = -lat;
+    }
+
+    latitudes
+}
+
+/// 格子の行番号と列番号から、格子点の緯度と経度を10e-6度単位で返す。
+pub(crate) fn micro_degree_of_grid_point(row: u32, col: u32, def: &GridDefinition) -> (i64, i64) {
+    let lat_first = def.lat_of_first_grid_point as i64;
+    let lon_first = def.lon_of_first_grid_point as i64;
+    let lat_inc = def.j_direction_increment as i64;
+    let lon_inc = def.i_direction_increment as i64;
+    let lat = match def.scanning_mode & SCANNING_MODE_POSITIVE_J {
+        0 => lat_first - row as i64 * lat_inc,
+        _ => lat_first + (def.number_of_along_lon_points as i64 - 1 - row as i64) * lat_inc,
+    };
+    let lon = match def.scanning_mode & SCANNING_MODE_NEGATIVE_I {
+        0 => lon_first + col as i64 * lon_inc,
+        _ => lon_first - (def.number_of_along_lat_points as i64 - 1 - col as i64) * lon_inc,
+    };
+
+    (lat, lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cell_position, coord_to_grid_point, gaussian_latitudes, grid_point_to_coord, Grid,
+        GridDefinition,
+    };
+    use crate::reader::value_iter::iter_from_run_length;
+    use crate::reader::ReaderError;
+
+    #[test]
+    fn gaussian_latitudes_ok() {
+        // N=1の場合は、2次のルジャンドル多項式の根 ±1/√3
+        let expected = (1.0 / 3f64.sqrt()).asin().to_degrees();
+        let latitudes = gaussian_latitudes(1);
+        assert_eq!(2, latitudes.len());
+        assert!((latitudes[0] - expected).abs() < 1e-9);
+        assert!((latitudes[1] + expected).abs() < 1e-9);
+
+        // N=8のガウス緯度（北半球）
+        let expected = [
+            81.650591, 70.834640, 59.954859, 49.060719, 38.161212, 27.259208, 16.355933, 5.452040,
+        ];
+        let latitudes = gaussian_latitudes(8);
+        assert_eq!(16, latitudes.len());
+        for (i, lat) in expected.iter().enumerate() {
+            assert!((latitudes[i] - lat).abs() < 1e-6, "{}: {}", i, latitudes[i]);
+            assert!(
+                (latitudes[15 - i] + lat).abs() < 1e-6,
+                "{}: {}",
+                i,
+                latitudes[15 - i]
+            );
+        }
+        // 北から南の順
+        assert!(latitudes.windows(2).all(|w| w[1] < w[0]));
+    }
+
+    #[test]
+    fn to_grid_ok() {
+        // 2行3列の格子で、北西から順にレベル値1, 2, 0（欠測）, 4, 5, 6
+        let level_values: Vec<u16> = (1..=6).map(|v| v * 10).collect();
+        let iter = iter_from_run_length(&[1, 2, 0, 4, 5, 6], 2, 3, 8, 6, &level_values);
+        let grid = Grid::from_values(iter, 2, 3, 6, 0x00).unwrap();
+        assert_eq!(2, grid.nrows());
+        assert_eq!(3, grid.ncols());
+        assert_eq!(Some(Some(10)), grid.get(0, 0));
+        assert_eq!(Some(None), grid.get(0, 2));
+        assert_eq!(Some(Some(60)), grid.get(1, 2));
+        assert_eq!(None, grid.get(2, 0));
+        assert_eq!(None, grid.get(0, 3));
+    }
+
+    #[test]
+    fn to_grid_with_mismatched_number_of_points_err() {
+        let level_values: Vec<u16> = (1..=6).collect();
+        let iter = iter_from_run_length(&[1, 2, 3, 4, 5, 6], 2, 3, 8, 6, &level_values);
+        assert!(Grid::from_values(iter, 3, 3, 6, 0x00).is_err());
+    }
+
+    #[test]
+    fn to_grid_with_excess_values_err() {
+        // 2行3列の格子の値を、2行2列の格子として読み込む
+        let level_values: Vec<u16> = (1..=6).collect();
+        let iter = iter_from_run_length(&[1, 2, 3, 4, 5, 6], 2, 3, 8, 6, &level_values);
+        match Grid::from_values(iter, 2, 2, 4, 0x00) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("展開した格子の数(6)"));
+                assert!(message.contains("行数と列数の積(4)"));
+            }
+            _ => panic!("展開した格子の数が格子点数を超えたエラーを想定しています。"),
+        }
+    }
+
+    /// 行番号と列番号から物理値を返す関数で格子を構築する。
+    fn grid_of(nrows: u32, ncols: u32, f: impl Fn(i64, i64) -> Option<u16>) -> Grid {
+        let cells = (0..nrows as i64)
+            .flat_map(|r| (0..ncols as i64).map(move |c| (r, c)))
+            .map(|(r, c)| f(r, c))
+            .collect();
+        Grid::from_cells(nrows, ncols, cells)
+    }
+
+    /// 平行移動させても一致しない物理値を返す。
+    fn pattern(r: i64, c: i64) -> Option<u16> {
+        Some(((r * 31 + c * 17 + (r * c).rem_euclid(13)).rem_euclid(97)) as u16)
+    }
+
+    #[test]
+    fn motion_vectors_of_translated_grid_ok() {
+        // 南に1格子、東に2格子平行移動させた格子
+        let current = grid_of(24, 24, pattern);
+        let next = grid_of(24, 24, |r, c| pattern(r - 1, c - 2));
+        let vectors = current.motion_vectors(&next, 8).unwrap();
+        assert_eq!(9, vectors.len());
+        assert_eq!((3.5, 3.5), (vectors[0].0, vectors[0].1));
+        // 移動させると格子からはみ出す南端及び東端のブロックも含めて、全てのブロックの移動量が一致
+        assert!(vectors
+            .iter()
+            .any(|&(row, col, _, _)| row == 19.5 && col == 19.5));
+        for (row, col, dr, dc) in vectors {
+            assert_eq!((1, 2), (dr, dc), "ブロック中心({}, {})", row, col);
+        }
+    }
+
+    #[test]
+    fn motion_vectors_skip_blocks_without_echo() {
+        // 北半分のみ降水がある格子
+        let current = grid_of(16, 16, |r, c| if r < 8 { pattern(r, c) } else { Some(0) });
+        let vectors = current.motion_vectors(&current, 8).unwrap();
+        assert_eq!(2, vectors.len());
+        assert!(vectors.iter().all(|&(_, _, dr, dc)| (dr, dc) == (0, 0)));
+    }
+
+    #[test]
+    fn motion_vectors_err() {
+        let current = grid_of(16, 16, pattern);
+        let next = grid_of(8, 16, pattern);
+        assert!(current.motion_vectors(&next, 8).is_err());
+        assert!(current.motion_vectors(&current, 0).is_err());
+    }
+
+    #[test]
+    fn cell_position_follows_scanning_mode() {
+        // 2行3列の格子
+        assert_eq!((0, 0), cell_position(0, 2, 3, 0x00));
+        assert_eq!((0, 2), cell_position(0, 2, 3, 0x80));
+        assert_eq!((1, 0), cell_position(0, 2, 3, 0x40));
+        assert_eq!((1, 0), cell_position(1, 2, 3, 0x20));
+        assert_eq!((1, 2), cell_position(3, 2, 3, 0x10));
+        assert_eq!((0, 1), cell_position(3, 2, 3, 0x30));
+    }
+
+    #[test]
+    fn grid_point_to_coord_follows_scanning_mode() {
+        // 2行3列、緯度経度とも1度間隔で、北西端が(36, 140)の格子
+        let northwest = GridDefinition::from_first_grid_point(
+            2,
+            3,
+            36_000_000,
+            140_000_000,
+            1_000_000,
+            1_000_000,
+            0x00,
+        );
+        assert_eq!((36.0, 140.0), grid_point_to_coord(0, 0, &northwest));
+        assert_eq!((35.0, 142.0), grid_point_to_coord(1, 2, &northwest));
+        // 南東端の格子点から北西に向かって走査する同じ格子
+        let southeast = GridDefinition::from_first_grid_point(
+            2,
+            3,
+            35_000_000,
+            142_000_000,
+            1_000_000,
+            1_000_000,
+            0xC0,
+        );
+        assert_eq!((36.0, 140.0), grid_point_to_coord(0, 0, &southeast));
+        assert_eq!((35.0, 142.0), grid_point_to_coord(1, 2, &southeast));
+    }
+
+    #[test]
+    fn coord_to_grid_point_round_trip() {
+        for scanning_mode in [0x00, 0x40, 0x80, 0xC0, 0x20, 0x10] {
+            let (lat_first, lon_first) = match scanning_mode & 0xC0 {
+                0x00 => (36_004_167, 118_006_250),
+                0x40 => (35_995_833, 118_006_250),
+                0x80 => (36_004_167, 118_018_750),
+                _ => (35_995_833, 118_018_750),
+            };
+            let def = GridDefinition::from_first_grid_point(
+                2,
+                3,
+                lat_first,
+                lon_first,
+                8_334,
+                6_250,
+                scanning_mode,
+            );
+            for row in 0..2 {
+                for col in 0..3 {
+                    let (lat, lon) = grid_point_to_coord(row, col, &def);
+                    assert_eq!(Some((row, col)), coord_to_grid_point(lat, lon, &def));
+                    // 増分の半分未満だけずれた座標は、同じ格子点に対応
+                    assert_eq!(
+                        Some((row, col)),
+                        coord_to_grid_point(lat - 0.004, lon + 0.003, &def)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn coord_to_grid_point_out_of_grid_none() {
+        let def = GridDefinition::from_first_grid_point(
+            2,
+            3,
+            36_000_000,
+            140_000_000,
+            1_000_000,
+            1_000_000,
+            0x00,
+        );
+        assert_eq!(None, coord_to_grid_point(36.6, 140.0, &def));
+        assert_eq!(None, coord_to_grid_point(34.4, 140.0, &def));
+        assert_eq!(None, coord_to_grid_point(35.0, 139.4, &def));
+        assert_eq!(None, coord_to_grid_point(35.0, 142.6, &def));
+    }
+}
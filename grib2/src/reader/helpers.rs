@@ -0,0 +1,4 @@
+pub use super::sections::{
+    read_datetime, read_i16, read_i32, read_i64, read_i8, read_str, read_u16, read_u32, read_u64,
+    read_u8, validate_str, validate_u32, validate_u8,
+};
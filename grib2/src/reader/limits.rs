@@ -0,0 +1,127 @@
+/// GRIB2ファイルを読み込むときの上限
+///
+/// 巨大なファイルや悪意のある入力でメモリを使い果たさないように、節の長さや、節の長さから
+/// 確保する配列の要素数に上限を設ける。上限は節を読み込む前に、節の長さなどから検証する。
+///
+/// また、第5節のレベル別物理値が単調非減少であるかを、節を読み込んだ後に検証する厳格モードを
+/// 選択できる。既定では検証しない。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadLimits {
+    /// 節の長さの上限（バイト）
+    max_section_bytes: usize,
+    /// 第5節に記録されているレベル別物理値の数の上限
+    max_level_values: usize,
+    /// 第4節に記録されているメソモデル予想値の結合比率の計算領域数の上限
+    max_calculation_areas: usize,
+    /// 第5節のレベル別物理値が単調非減少であるか検証するか
+    validate_levels: bool,
+}
+
+impl Default for ReadLimits {
+    /// 既定の上限を返す。
+    ///
+    /// 節の長さは256MiB、レベル別物理値の数と結合比率の計算領域数は、2バイトで表現できる最大値
+    /// とする。
+    fn default() -> Self {
+        Self {
+            max_section_bytes: 256 * 1024 * 1024,
+            max_level_values: u16::MAX as usize,
+            max_calculation_areas: u16::MAX as usize,
+            validate_levels: false,
+        }
+    }
+}
+
+impl ReadLimits {
+    /// 節の長さの上限を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 節の長さの上限（バイト）
+    pub fn max_section_bytes(&self) -> usize {
+        self.max_section_bytes
+    }
+
+    /// レベル別物理値の数の上限を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// レベル別物理値の数の上限
+    pub fn max_level_values(&self) -> usize {
+        self.max_level_values
+    }
+
+    /// メソモデル予想値の結合比率の計算領域数の上限を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// メソモデル予想値の結合比率の計算領域数の上限
+    pub fn max_calculation_areas(&self) -> usize {
+        self.max_calculation_areas
+    }
+
+    /// レベル別物理値が単調非減少であるか検証するかを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 検証する場合は`true`
+    pub fn validate_levels(&self) -> bool {
+        self.validate_levels
+    }
+
+    /// 節の長さの上限を設定する。
+    ///
+    /// # 引数
+    ///
+    /// * `max_section_bytes` - 節の長さの上限（バイト）
+    ///
+    /// # 戻り値
+    ///
+    /// 上限を設定した読み込みの上限
+    pub fn with_max_section_bytes(mut self, max_section_bytes: usize) -> Self {
+        self.max_section_bytes = max_section_bytes;
+        self
+    }
+
+    /// レベル別物理値の数の上限を設定する。
+    ///
+    /// # 引数
+    ///
+    /// * `max_level_values` - レベル別物理値の数の上限
+    ///
+    /// # 戻り値
+    ///
+    /// 上限を設定した読み込みの上限
+    pub fn with_max_level_values(mut self, max_level_values: usize) -> Self {
+        self.max_level_values = max_level_values;
+        self
+    }
+
+    /// メソモデル予想値の結合比率の計算領域数の上限を設定する。
+    ///
+    /// # 引数
+    ///
+    /// * `max_calculation_areas` - メソモデル予想値の結合比率の計算領域数の上限
+    ///
+    /// # 戻り値
+    ///
+    /// 上限を設定した読み込みの上限
+    pub fn with_max_calculation_areas(mut self, max_calculation_areas: usize) -> Self {
+        self.max_calculation_areas = max_calculation_areas;
+        self
+    }
+
+    /// レベル別物理値が単調非減少であるか検証するかを設定する。
+    ///
+    /// # 引数
+    ///
+    /// * `validate_levels` - 検証する場合は`true`
+    ///
+    /// # 戻り値
+    ///
+    /// 検証の有無を設定した読み込みの上限
+    pub fn with_validate_levels(mut self, validate_levels: bool) -> Self {
+        self.validate_levels = validate_levels;
+        self
+    }
+}
@@ -2,10 +2,13 @@ use std::io::{Seek, SeekFrom};
 use std::{fs::File, path::Path};
 
 use super::sections::{
-    FromReader, Section0, Section1, Section2, Section3_0, Section4_50000, Section5_200i16,
-    Section6, Section7_200, Section8,
+    impl_leading_section_getters, is_section8, validate_section_limits,
+    write_leading_sections_debug_info, FromReader, LeadingSections, MessageBoundary, Section0,
+    Section1, Section2, Section3_0, Section4_50000, Section5_200i16, Section6, Section7_200,
+    Section8,
 };
-use super::{FileReader, Grib2ValueIter, ReaderError, ReaderResult};
+use super::value_iter::RunLength;
+use super::{FileReader, Grib2ValueIter, ReadLimits, ReaderError, ReaderResult};
 
 /// 実況及び3時間先までの土砂災害警戒判定リーダー
 ///
@@ -19,7 +22,8 @@ where
     section1: Section1,
     section2: Section2,
     section3: Section3_0,
-    judgments: [LswjSections; 4],
+    /// 土砂災害警戒判定時間をインデックスとした第4節から第7節
+    judgments: [Option<LswjSections>; 4],
     section8: Section8,
 }
 
@@ -31,11 +35,26 @@ pub struct LswjSections {
 }
 
 impl LswjSections {
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    fn from_reader(
+        reader: &mut FileReader,
+        boundary: &MessageBoundary,
+        limits: &ReadLimits,
+    ) -> ReaderResult<Self> {
+        validate_section_limits(reader, 4, limits)?;
         let section4 = Section4_50000::from_reader(reader)?;
+        boundary.validate(reader, "第4節")?;
+        validate_section_limits(reader, 5, limits)?;
         let section5 = Section5_200i16::from_reader(reader)?;
+        boundary.validate(reader, "第5節")?;
+        if limits.validate_levels() {
+            section5.validate_levels()?;
+        }
+        validate_section_limits(reader, 6, limits)?;
         let section6 = Section6::from_reader(reader)?;
+        boundary.validate(reader, "第6節")?;
+        validate_section_limits(reader, 7, limits)?;
         let section7 = Section7_200::from_reader(reader)?;
+        boundary.validate(reader, "第7節")?;
 
         Ok(Self {
             section4,
@@ -45,6 +64,25 @@ impl LswjSections {
         })
     }
 
+    /// 第4節の予報時間から、土砂災害警戒判定時間を返す。
+    fn hour(&self) -> ReaderResult<LswjHour> {
+        let duration = self.section4.forecast_duration()?;
+        let minutes = duration.whole_minutes();
+        if minutes % 60 == 0 {
+            if let Ok(hour) = LswjHour::try_from((minutes / 60) as usize) {
+                return Ok(hour);
+            }
+        }
+
+        Err(ReaderError::Unexpected(
+            format!(
+                "第4節:予報時間({}分)に対応する土砂災害警戒判定時間がありません。",
+                minutes
+            )
+            .into(),
+        ))
+    }
+
     /// 第4節:プロダクト定義節を返す。
     ///
     /// # 戻り値
@@ -113,18 +151,46 @@ where
     ///
     /// 土砂災害警戒判定メッシュリーダー
     pub fn new(path: P) -> ReaderResult<Self> {
+        Self::new_with_limits(path, ReadLimits::default())
+    }
+
+    /// ファイルパスと読み込みの上限を受け取り、土砂災害警戒判定メッシュリーダーを構築する。
+    ///
+    /// 第2節以降の節は、読み込む前に節の長さなどが上限以下であることを確認し、上限を超えている
+    /// 場合はメモリを確保せずにエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    /// * `limits` - 読み込みの上限
+    ///
+    /// # 戻り値
+    ///
+    /// 土砂災害警戒判定メッシュリーダー
+    pub fn new_with_limits(path: P, limits: ReadLimits) -> ReaderResult<Self> {
         let file =
-            File::open(path.as_ref()).map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            File::open(path.as_ref()).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
-        let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2::from_reader(&mut reader)?;
-        let section3 = Section3_0::from_reader(&mut reader)?;
-        let actual = LswjSections::from_reader(&mut reader)?;
-        let hour1 = LswjSections::from_reader(&mut reader)?;
-        let hour2 = LswjSections::from_reader(&mut reader)?;
-        let hour3 = LswjSections::from_reader(&mut reader)?;
+        let LeadingSections {
+            section0,
+            section1,
+            section2,
+            section3,
+            boundary,
+        } = LeadingSections::from_reader_with_limits(&mut reader, &limits)?;
+        // 第8節に達するまで第4節から第7節を読み込み、予報時間ごとにインデックス化
+        let mut judgments: [Option<LswjSections>; 4] = Default::default();
+        while !is_section8(&mut reader)? {
+            let judgment = LswjSections::from_reader(&mut reader, &boundary, &limits)?;
+            let hour = judgment.hour()?;
+            if judgments[hour as usize].replace(judgment).is_some() {
+                return Err(ReaderError::Unexpected(
+                    format!("{}の土砂災害警戒判定が複数記録されています。", hour).into(),
+                ));
+            }
+        }
         let section8 = Section8::from_reader(&mut reader)?;
+        boundary.validate_end(&mut reader)?;
 
         Ok(LswjReader {
             path,
@@ -132,74 +198,86 @@ where
             section1,
             section2,
             section3,
-            judgments: [actual, hour1, hour2, hour3],
+            judgments,
             section8,
         })
     }
 
-    /// 第0節:指示節を返す。
+    impl_leading_section_getters!();
+
+    /// 第4節から第7節を返す。
     ///
-    /// # 戻り値
+    /// # 引数
     ///
-    /// 第0節:指示節
-    pub fn section0(&self) -> &Section0 {
-        &self.section0
-    }
-
-    /// 第1節:識別節を返す。
+    /// * `hour` - 土砂災害警戒判定時間
     ///
     /// # 戻り値
     ///
-    /// 第1節:識別節
-    pub fn section1(&self) -> &Section1 {
-        &self.section1
-    }
-
-    /// 第2節:地域使用節を返す。
+    /// 第4節から第7節
     ///
-    /// # 戻り値
+    /// # パニック
     ///
-    /// 第2節:地域使用節
-    pub fn section2(&self) -> &Section2 {
-        &self.section2
+    /// ファイルに記録されていない土砂災害警戒判定時間を指定した場合
+    pub fn judgment(&self, hour: LswjHour) -> &LswjSections {
+        self.get_judgment(hour)
+            .unwrap_or_else(|| panic!("{}の土砂災害警戒判定は記録されていません。", hour))
     }
 
-    /// 第3節:格子系定義節を返す。
+    /// 第4節から第7節を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `hour` - 土砂災害警戒判定時間
     ///
     /// # 戻り値
     ///
-    /// 第3節:格子系定義節
-    pub fn section3(&self) -> &Section3_0 {
-        &self.section3
+    /// 第4節から第7節。ファイルに記録されていない土砂災害警戒判定時間の場合は`None`
+    pub fn get_judgment(&self, hour: LswjHour) -> Option<&LswjSections> {
+        self.judgments[hour as usize].as_ref()
     }
 
-    /// 第4節から第7節を返す。
+    /// ファイルに記録されている土砂災害警戒判定時間を返す。
     ///
     /// # 戻り値
     ///
-    /// 第4節から第7節
-    pub fn judgment(&self, hour: LswjHour) -> &LswjSections {
-        &self.judgments[hour as usize]
+    /// 実況から順に並べた土砂災害警戒判定時間
+    pub fn hours(&self) -> Vec<LswjHour> {
+        self.judgments
+            .iter()
+            .enumerate()
+            .filter(|(_, judgment)| judgment.is_some())
+            .map(|(i, _)| LswjHour::try_from(i).unwrap())
+            .collect()
     }
 
-    /// 第8節:終端節を返す。
+    /// ランレングス圧縮符号を走査するイテレーターを返す。
     ///
     /// # 戻り値
     ///
-    /// 第8節:終端節
-    pub fn section8(&self) -> &Section8 {
-        &self.section8
+    /// ランレングス圧縮符号を走査するイテレーター
+    pub fn values(&mut self, hour: LswjHour) -> ReaderResult<Grib2ValueIter<'static, i16>> {
+        self.values_at_hour(hour)
     }
 
-    /// ランレングス圧縮符号を走査するイテレーターを返す。
+    /// 指定した土砂災害警戒判定時間の第7節にシークして、ランレングス圧縮符号を走査する
+    /// イテレーターを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `hour` - 土砂災害警戒判定時間
     ///
     /// # 戻り値
     ///
-    /// ランレングス圧縮符号を走査するイテレーター
-    pub fn values(&mut self, hour: LswjHour) -> ReaderResult<Grib2ValueIter<'_, i16>> {
-        let judgment = &self.judgments[hour as usize];
+    /// ランレングス圧縮符号を走査するイテレーター。ファイルに記録されていない土砂災害警戒判定時間の
+    /// 場合はエラー
+    pub fn values_at_hour(&mut self, hour: LswjHour) -> ReaderResult<Grib2ValueIter<'static, i16>> {
+        let judgment = self.get_judgment(hour).ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!("{}の土砂災害警戒判定は記録されていません。", hour).into(),
+            )
+        })?;
         let file = File::open(self.path.as_ref())
-            .map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            .map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
         reader
             .seek(SeekFrom::Start(
@@ -210,7 +288,7 @@ where
             })?;
 
         Ok(Grib2ValueIter::new(
-            reader,
+            RunLength::reader(reader),
             judgment.section7.run_length_bytes(),
             self.section3.number_of_data_points(),
             self.section3.lat_of_first_grid_point(),
@@ -218,10 +296,13 @@ where
             self.section3.lon_of_last_grid_point(),
             self.section3.j_direction_increment(),
             self.section3.i_direction_increment(),
+            self.section3.scanning_mode(),
             judgment.section5.bits_per_value() as u16,
             judgment.section5.max_level_value(),
+            judgment.section5.decimal_scale_factor(),
             judgment.section5.level_values(),
-        ))
+        )
+        .with_bitmap(judgment.section6.shared_bitmap()))
     }
 
     /// 全ての節を出力する。
@@ -229,17 +310,18 @@ where
     where
         W: std::io::Write,
     {
-        self.section0.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section1.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section2.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section3.debug_info(writer)?;
-        writeln!(writer)?;
-        for i in 0..=3usize {
-            writeln!(writer, "{}", LswjHour::try_from(i).unwrap())?;
-            self.judgments[i].debug_info(writer)?;
+        write_leading_sections_debug_info(
+            writer,
+            &self.section0,
+            &self.section1,
+            &self.section2,
+            &self.section3,
+        )?;
+        for (i, judgment) in self.judgments.iter().enumerate() {
+            if let Some(judgment) = judgment {
+                writeln!(writer, "{}", LswjHour::try_from(i).unwrap())?;
+                judgment.debug_info(writer)?;
+            }
         }
         self.section8.debug_info(writer)?;
         writeln!(writer)?;
@@ -281,3 +363,72 @@ impl TryFrom<usize> for LswjHour {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LswjHour, LswjReader};
+    use crate::reader::sections::{small_lswj_bytes, TempPath};
+    use crate::reader::{ReadLimits, ReaderError};
+
+    /// 指定した予報時間（分）の2行2列の格子の土砂災害警戒判定のGRIB2ファイルを一時ファイルと
+    /// して作成する。
+    fn small_lswj_file(forecast_minutes: &[i32]) -> TempPath {
+        TempPath::file(&small_lswj_bytes(forecast_minutes))
+    }
+
+    #[test]
+    fn values_at_hour_ok() {
+        // 実況と2時間予想だけを記録したファイル
+        let path = small_lswj_file(&[0, 120]);
+        let mut reader = LswjReader::new(&path).unwrap();
+        assert_eq!(vec![LswjHour::Actual, LswjHour::Hour2], reader.hours());
+        assert!(reader.get_judgment(LswjHour::Hour1).is_none());
+        assert!(reader.get_judgment(LswjHour::Hour2).is_some());
+        for hour in [LswjHour::Actual, LswjHour::Hour2] {
+            let levels = reader
+                .values_at_hour(hour)
+                .unwrap()
+                .map(|value| value.unwrap().level)
+                .collect::<Vec<_>>();
+            assert_eq!(vec![1, 2, 3, 0], levels);
+        }
+    }
+
+    #[test]
+    fn new_with_limits_rejects_too_many_level_values() {
+        // 2時間予想の第5節のレベル別物理値の数（3）が上限を超える
+        let path = small_lswj_file(&[0, 120]);
+        let limits = ReadLimits::default().with_max_level_values(2);
+        match LswjReader::new_with_limits(&path, limits) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("レベル別物理値の数(3)が上限(2)"))
+            }
+            _ => panic!("レベル別物理値の数が上限を超えるエラーを想定しています。"),
+        }
+        assert!(LswjReader::new_with_limits(&path, ReadLimits::default()).is_ok());
+    }
+
+    #[test]
+    fn values_at_missing_hour_err() {
+        let path = small_lswj_file(&[0, 120]);
+        let mut reader = LswjReader::new(&path).unwrap();
+        for hour in [LswjHour::Hour1, LswjHour::Hour3] {
+            assert!(matches!(
+                reader.values_at_hour(hour),
+                Err(ReaderError::Unexpected(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn new_with_unknown_or_duplicated_hour_err() {
+        // 4時間予想と、実況が重複したファイル
+        for minutes in [vec![0, 240], vec![0, 60, 0]] {
+            let path = small_lswj_file(&minutes);
+            assert!(matches!(
+                LswjReader::new(&path),
+                Err(ReaderError::Unexpected(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use num_format::{Locale, ToFormattedString};
+
+use super::{ReaderError, ReaderResult};
+
+/// 格子マスク
+///
+/// 流域などの領域に含まれる格子を、格子の走査順（北西端から東方向、その後南方向）に
+/// `true`で表現する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridMask {
+    /// 行数（経線に沿った格子点数）
+    nrows: u32,
+    /// 列数（緯線に沿った格子点数）
+    ncols: u32,
+    /// 格子が領域に含まれるかを走査順に格納したコレクション
+    cells: Vec<bool>,
+}
+
+impl GridMask {
+    /// 格子マスクを構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `nrows` - 行数（経線に沿った格子点数）
+    /// * `ncols` - 列数（緯線に沿った格子点数）
+    /// * `cells` - 格子が領域に含まれるかを走査順に格納したコレクション
+    ///
+    /// # 戻り値
+    ///
+    /// 格子マスク
+    pub fn new(nrows: u32, ncols: u32, cells: Vec<bool>) -> ReaderResult<Self> {
+        let expected = nrows as usize * ncols as usize;
+        if cells.len() != expected {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "格子マスクの要素数({})が行数と列数の積({})と一致しません。",
+                    cells.len().to_formatted_string(&Locale::ja),
+                    expected.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        Ok(Self {
+            nrows,
+            ncols,
+            cells,
+        })
+    }
+
+    /// 行数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 行数
+    pub fn nrows(&self) -> u32 {
+        self.nrows
+    }
+
+    /// 列数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 列数
+    pub fn ncols(&self) -> u32 {
+        self.ncols
+    }
+
+    /// 走査順のインデックスの格子が領域に含まれるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - 格子の走査順のインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// 格子が領域に含まれる場合は`true`
+    pub fn contains(&self, index: usize) -> bool {
+        self.cells.get(index).copied().unwrap_or(false)
+    }
+}
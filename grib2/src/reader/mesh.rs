@@ -0,0 +1,157 @@
+/// 標準地域メッシュの区画
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshLevel {
+    /// 第1次地域区画（緯度40分、経度1度、約80km）
+    First,
+    /// 第2次地域区画（緯度5分、経度7分30秒、約10km）
+    Second,
+    /// 第3次地域区画（緯度30秒、経度45秒、約1km）
+    Third,
+}
+
+impl MeshLevel {
+    /// 座標が含まれるメッシュのメッシュコードを返す。
+    ///
+    /// 区画の番号は`mesh_parts`で求める。メッシュの境界上にある座標は、境界の北側及び東側の
+    /// メッシュに含まれるものとする。
+    ///
+    /// # 引数
+    ///
+    /// * `lat` - 緯度（10e-6度単位）
+    /// * `lon` - 経度（10e-6度単位）
+    ///
+    /// # 戻り値
+    ///
+    /// メッシュコード（第1次地域区画は4桁、第2次地域区画は6桁、第3次地域区画は8桁）。
+    /// 座標が標準地域メッシュの範囲外の場合は`None`
+    pub fn mesh_code(self, lat: u32, lon: u32) -> Option<u64> {
+        let (p, u, q, v, r, w) = mesh_parts(lat, lon)?;
+
+        Some(match self {
+            Self::First => p * 100 + u,
+            Self::Second => p * 10_000 + u * 100 + q * 10 + v,
+            Self::Third => p * 1_000_000 + u * 10_000 + q * 1_000 + v * 100 + r * 10 + w,
+        })
+    }
+}
+
+/// 標準地域メッシュのメッシュコードを構成する数字を返す。
+///
+/// 第1次地域区画は緯度40分、経度1度、第2次地域区画はそれを縦横8等分、第3次地域区画は
+/// さらに縦横10等分した区画である。座標を第3次地域区画の大きさ（緯度30秒、経度45秒）を
+/// 単位とした番号に整数演算で変換して、各区画の番号を求める。
+///
+/// 10e-6度単位の座標は、区画の境界を10e-6度単位に丸めた座標と一致する場合に境界上にあるものと
+/// みなす。これにより、北緯35度20分のように10e-6度単位で割り切れない境界上の座標も、境界の北側
+/// 及び東側のメッシュに含まれる。
+///
+/// # 引数
+///
+/// * `lat` - 緯度（10e-6度単位）
+/// * `lon` - 経度（10e-6度単位）
+///
+/// # 戻り値
+///
+/// 第1次地域区画の緯度と経度、第2次地域区画の緯度と経度、第3次地域区画の緯度と経度の番号。
+/// 標準地域メッシュの対象外である、緯度が66度40分以上、または経度が100度未満もしくは200度以上の
+/// 場合は`None`
+pub(crate) fn mesh_parts(lat: u32, lon: u32) -> Option<(u64, u64, u64, u64, u64, u64)> {
+    // 第3次地域区画を単位とした番号（緯度1度 = 120区画、経度1度 = 80区画）
+    // 10e-6度の半分だけ北東にずらして、境界を丸めた座標を境界の北側及び東側に含める
+    let lat_index = (lat as u64 * 2 + 1) * 120 / 2_000_000;
+    let lon_index = (lon as u64 * 2 + 1) * 80 / 2_000_000;
+    // 第1次地域区画: 緯度40分（80区画）、経度1度（80区画）
+    let p = lat_index / 80;
+    let u = (lon_index / 80).checked_sub(100)?;
+    if 100 <= p || 100 <= u {
+        return None;
+    }
+    // 第2次地域区画: 緯度5分、経度7分30秒（10区画）
+    let q = lat_index % 80 / 10;
+    let v = lon_index % 80 / 10;
+    // 第3次地域区画: 緯度30秒、経度45秒
+    let r = lat_index % 10;
+    let w = lon_index % 10;
+
+    Some((p, u, q, v, r, w))
+}
+
+/// 同じメッシュに含まれる複数の格子の物理値を集約する方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    /// 最大値
+    Max,
+    /// 平均値
+    Mean,
+}
+
+/// メッシュごとに物理値を集約するアキュムレーター
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MeshAccumulator {
+    /// 物理値の合計
+    sum: f64,
+    /// 物理値の最大値
+    max: f64,
+    /// 物理値の数
+    count: u32,
+}
+
+impl MeshAccumulator {
+    /// 物理値を追加する。
+    pub(crate) fn push(&mut self, value: f64) {
+        self.max = if self.count == 0 {
+            value
+        } else {
+            self.max.max(value)
+        };
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// 集約した物理値を返す。
+    pub(crate) fn result(&self, aggregation: Aggregation) -> f64 {
+        match aggregation {
+            Aggregation::Max => self.max,
+            Aggregation::Mean => self.sum / self.count as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MeshLevel;
+
+    #[test]
+    fn mesh_code_of_tokyo_station() {
+        let (lat, lon) = (35_681_236, 139_767_125);
+        assert_eq!(Some(5339), MeshLevel::First.mesh_code(lat, lon));
+        assert_eq!(Some(533946), MeshLevel::Second.mesh_code(lat, lon));
+        assert_eq!(Some(53394611), MeshLevel::Third.mesh_code(lat, lon));
+    }
+
+    #[test]
+    fn mesh_code_on_boundary_belongs_to_north_east_mesh() {
+        // 第2次地域区画533946の南西端
+        let (lat, lon) = (35_666_667, 139_750_000);
+        assert_eq!(Some(53394600), MeshLevel::Third.mesh_code(lat, lon));
+        // 第1次地域区画5339の南西端（北緯35度20分は10e-6度単位で割り切れない）
+        let (lat, lon) = (35_333_333, 139_000_000);
+        assert_eq!(Some(53390000), MeshLevel::Third.mesh_code(lat, lon));
+        assert_eq!(Some(52387799), MeshLevel::Third.mesh_code(lat - 1, lon - 1));
+    }
+
+    #[test]
+    fn mesh_code_out_of_range_is_none() {
+        // 経度100度未満
+        assert_eq!(None, MeshLevel::First.mesh_code(35_000_000, 99_999_999));
+        // 経度200度以上
+        assert_eq!(None, MeshLevel::First.mesh_code(35_000_000, 200_000_000));
+        // 緯度66度40分以上
+        assert_eq!(None, MeshLevel::First.mesh_code(66_666_667, 139_000_000));
+        // 範囲内の最北端及び最東端の区画
+        assert_eq!(
+            Some(99997799),
+            MeshLevel::Third.mesh_code(66_666_666, 199_999_999)
+        );
+    }
+}
@@ -0,0 +1,679 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use num_format::{Locale, ToFormattedString};
+
+use super::sections::{
+    write_section, AnyTemplate4, FromReader, LeadingSections, Section0, Section1, Section2,
+    Section3, Section3_0, Section4, Section5, Section5Variant, Section6, Section7, Section7_200,
+    Section8, Template3_0, Template4_50008, Template5_200u16, Template7_200, ToWriter,
+};
+use super::{FileReader, ReaderError, ReaderResult};
+
+/// GRIB報のメタデータ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageMeta {
+    /// ファイル内のGRIB報の開始位置
+    offset: u64,
+    /// GRIB報全体のバイト数
+    total_length: u64,
+    /// 資料分野
+    discipline: u8,
+    /// GRIB版番号
+    edition_number: u8,
+}
+
+impl MessageMeta {
+    /// ファイル内のGRIB報の開始位置を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// ファイル内のGRIB報の開始位置
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// GRIB報全体のバイト数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB報全体のバイト数
+    pub fn total_length(&self) -> u64 {
+        self.total_length
+    }
+
+    /// 資料分野を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 資料分野
+    pub fn discipline(&self) -> u8 {
+        self.discipline
+    }
+
+    /// GRIB版番号を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB版番号
+    pub fn edition_number(&self) -> u8 {
+        self.edition_number
+    }
+}
+
+/// 読み込めた節のみを保持するGRIB報
+///
+/// 読み込みに失敗した節以降の節は`None`となる。
+pub struct Grib2Message<T3, T4, T5, T7> {
+    pub(crate) section0: Option<Section0>,
+    pub(crate) section1: Option<Section1>,
+    pub(crate) section2: Option<Section2>,
+    pub(crate) section3: Option<Section3<T3>>,
+    pub(crate) section4: Option<Section4<T4>>,
+    pub(crate) section5: Option<Section5<T5>>,
+    pub(crate) section6: Option<Section6>,
+    pub(crate) section7: Option<Section7<T7>>,
+    pub(crate) section8: Option<Section8>,
+    /// 第7節に記録されているランレングス圧縮符号列
+    pub(crate) run_length: Option<Vec<u8>>,
+}
+
+impl<T3, T4, T5, T7> Default for Grib2Message<T3, T4, T5, T7> {
+    fn default() -> Self {
+        Self {
+            section0: None,
+            section1: None,
+            section2: None,
+            section3: None,
+            section4: None,
+            section5: None,
+            section6: None,
+            section7: None,
+            section8: None,
+            run_length: None,
+        }
+    }
+}
+
+impl<T3, T4, T5, T7> Grib2Message<T3, T4, T5, T7> {
+    /// 第0節:指示節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第0節:指示節
+    pub fn section0(&self) -> Option<&Section0> {
+        self.section0.as_ref()
+    }
+
+    /// 第1節:識別節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第1節:識別節
+    pub fn section1(&self) -> Option<&Section1> {
+        self.section1.as_ref()
+    }
+
+    /// 第2節:地域使用節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第2節:地域使用節
+    pub fn section2(&self) -> Option<&Section2> {
+        self.section2.as_ref()
+    }
+
+    /// 第3節:格子系定義節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第3節:格子系定義節
+    pub fn section3(&self) -> Option<&Section3<T3>> {
+        self.section3.as_ref()
+    }
+
+    /// 第4節:プロダクト定義節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第4節:プロダクト定義節
+    pub fn section4(&self) -> Option<&Section4<T4>> {
+        self.section4.as_ref()
+    }
+
+    /// 第5節:資料表現節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第5節:資料表現節
+    pub fn section5(&self) -> Option<&Section5<T5>> {
+        self.section5.as_ref()
+    }
+
+    /// 第6節:ビットマップ節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第6節:ビットマップ節
+    pub fn section6(&self) -> Option<&Section6> {
+        self.section6.as_ref()
+    }
+
+    /// 第7節:資料節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第7節:資料節
+    pub fn section7(&self) -> Option<&Section7<T7>> {
+        self.section7.as_ref()
+    }
+
+    /// 第8節:終端節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第8節:終端節
+    pub fn section8(&self) -> Option<&Section8> {
+        self.section8.as_ref()
+    }
+
+    /// 第7節に記録されているランレングス圧縮符号列を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// ランレングス圧縮符号列
+    pub fn run_length(&self) -> Option<&[u8]> {
+        self.run_length.as_deref()
+    }
+
+    /// 全ての節を読み込めたか確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// 全ての節を読み込めた場合は`()`、読み込めなかった節がある場合は、読み込めなかった節を
+    /// メッセージに含めたエラー
+    pub fn ensure_all_fields(&self) -> ReaderResult<()> {
+        let missing = [
+            (self.section0.is_none(), "第0節:指示節"),
+            (self.section1.is_none(), "第1節:識別節"),
+            (self.section2.is_none(), "第2節:地域使用節"),
+            (self.section3.is_none(), "第3節:格子系定義節"),
+            (self.section4.is_none(), "第4節:プロダクト定義節"),
+            (self.section5.is_none(), "第5節:資料表現節"),
+            (self.section6.is_none(), "第6節:ビットマップ節"),
+            (self.section7.is_none(), "第7節:資料節"),
+            (self.section8.is_none(), "第8節:終端節"),
+        ]
+        .into_iter()
+        .filter_map(|(is_missing, name)| is_missing.then_some(name))
+        .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "GRIB報に読み込めなかった節があります: {}",
+                    missing.join("、")
+                )
+                .into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Grib2Message<Template3_0, Template4_50008, Template5_200u16, Template7_200> {
+    /// GRIB報を、規格どおりのバイト並びでGRIB2形式のバイト列として書き込む。
+    ///
+    /// 各節の長さとGRIB報全体のバイト数は、書き込む内容から再計算する。第7節には、読み込んだ
+    /// ランレングス圧縮符号列をそのまま書き込む。読み込み時に読み飛ばした節の末尾の未知の
+    /// フィールドは書き込まない。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 書き込み先
+    ///
+    /// # 戻り値
+    ///
+    /// 全ての節を書き込めた場合は`()`、読み込めなかった節がある場合はエラー
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        self.ensure_all_fields()?;
+        let run_length = self.run_length.as_deref().ok_or_else(|| {
+            ReaderError::Unexpected(
+                "第7節:ランレングス圧縮符号列を読み込んでいないため、書き込めません。".into(),
+            )
+        })?;
+
+        // 第1節から第8節までを書き込んで、GRIB報全体のバイト数を求める
+        let mut body = vec![];
+        self.section1.as_ref().unwrap().to_writer(&mut body)?;
+        self.section2.as_ref().unwrap().to_writer(&mut body)?;
+        self.section3.as_ref().unwrap().to_writer(&mut body)?;
+        self.section4.as_ref().unwrap().to_writer(&mut body)?;
+        self.section5.as_ref().unwrap().to_writer(&mut body)?;
+        self.section6.as_ref().unwrap().to_writer(&mut body)?;
+        write_section(&mut body, 7, run_length)?;
+        self.section8.as_ref().unwrap().to_writer(&mut body)?;
+        let total_length = 16 + body.len() as u64;
+
+        self.section0
+            .as_ref()
+            .unwrap()
+            .to_writer_with_total_length(writer, total_length)?;
+        writer.write_all(&body).map_err(|e| {
+            ReaderError::Unexpected(format!("GRIB報の書き込みに失敗しました: {}", e).into())
+        })
+    }
+}
+
+/// 全ての節を保持するGRIB報
+///
+/// 第4節はテンプレートの型を決めずに`AnyTemplate4`で、第5節はテンプレート番号で区別した列挙型で
+/// 保持する。第4節から第7節までを繰り返すGRIB報には対応しない。
+#[derive(Debug, Clone)]
+pub struct Grib2Sections {
+    section0: Section0,
+    section1: Section1,
+    section2: Section2,
+    section3: Section3_0,
+    section4: Section4<AnyTemplate4>,
+    section5: Section5Variant,
+    section6: Section6,
+    section7: Section7_200,
+    section8: Section8,
+}
+
+impl Grib2Sections {
+    /// GRIB2ファイルから1つのGRIB報の全ての節を読み込む。
+    ///
+    /// 引数`reader`のファイルポインタは、GRIB報の開始位置にあることを想定している。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// 全ての節を保持するGRIB報
+    pub fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        let LeadingSections {
+            section0,
+            section1,
+            section2,
+            section3,
+            boundary,
+        } = LeadingSections::from_reader(reader)?;
+        let section4 = Section4::from_reader_dynamic(reader)?;
+        boundary.validate(reader, "第4節")?;
+        let section5 = Section5Variant::from_reader(reader)?;
+        boundary.validate(reader, "第5節")?;
+        let section6 = Section6::from_reader(reader)?;
+        boundary.validate(reader, "第6節")?;
+        let section7 = Section7_200::from_reader(reader)?;
+        boundary.validate(reader, "第7節")?;
+        let section8 = Section8::from_reader(reader)?;
+        boundary.validate_end(reader)?;
+
+        Ok(Self {
+            section0,
+            section1,
+            section2,
+            section3,
+            section4,
+            section5,
+            section6,
+            section7,
+            section8,
+        })
+    }
+
+    /// 第0節:指示節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第0節:指示節
+    pub fn section0(&self) -> &Section0 {
+        &self.section0
+    }
+
+    /// 第1節:識別節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第1節:識別節
+    pub fn section1(&self) -> &Section1 {
+        &self.section1
+    }
+
+    /// 第2節:地域使用節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第2節:地域使用節
+    pub fn section2(&self) -> &Section2 {
+        &self.section2
+    }
+
+    /// 第3節:格子系定義節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第3節:格子系定義節
+    pub fn section3(&self) -> &Section3_0 {
+        &self.section3
+    }
+
+    /// 第4節:プロダクト定義節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第4節:プロダクト定義節
+    pub fn section4(&self) -> &Section4<AnyTemplate4> {
+        &self.section4
+    }
+
+    /// 第5節:資料表現節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第5節:資料表現節
+    pub fn section5(&self) -> &Section5Variant {
+        &self.section5
+    }
+
+    /// 第6節:ビットマップ節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第6節:ビットマップ節
+    pub fn section6(&self) -> &Section6 {
+        &self.section6
+    }
+
+    /// 第7節:資料節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第7節:資料節
+    pub fn section7(&self) -> &Section7_200 {
+        &self.section7
+    }
+
+    /// 第8節:終端節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第8節:終端節
+    pub fn section8(&self) -> &Section8 {
+        &self.section8
+    }
+}
+
+/// ファイルに連結されたGRIB報を順に走査するイテレーター
+///
+/// 各GRIB報の第0節に記録されたGRIB報全体のバイト数を使用して、次のGRIB報の開始位置にシークする。
+/// GRIB報の境界に"GRIB"以外のバイト列があるなど、GRIB報を読み込めなかった場合はエラーを返して
+/// 走査を終了する。
+pub struct MessageIter<R = File> {
+    /// リーダー
+    reader: FileReader<R>,
+    /// ファイルのバイト数
+    file_length: u64,
+    /// 次のGRIB報の開始位置
+    position: u64,
+    /// 走査を終了したかを示すフラグ
+    finished: bool,
+}
+
+impl MessageIter<File> {
+    /// ファイルに連結されたGRIB報を順に走査するイテレーターを構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB報を走査するイテレーター
+    pub fn open<P: AsRef<Path>>(path: P) -> ReaderResult<Self> {
+        let file =
+            File::open(path.as_ref()).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
+        let file_length = file
+            .metadata()
+            .map_err(|e| ReaderError::ReadError(e.to_string().into()))?
+            .len();
+
+        Ok(Self {
+            reader: FileReader::new(file),
+            file_length,
+            position: 0,
+            finished: false,
+        })
+    }
+}
+
+impl<R> MessageIter<R>
+where
+    R: Read + Seek,
+{
+    /// リーダーから読み込めるバイト列に連結されたGRIB報を順に走査するイテレーターを構築する。
+    ///
+    /// 引数`reader`の先頭から走査する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2形式のバイト列を読み込むリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB報を走査するイテレーター
+    pub fn from_reader(mut reader: R) -> ReaderResult<Self> {
+        let file_length = reader
+            .seek(SeekFrom::End(0))
+            .map_err(|e| ReaderError::ReadError(e.to_string().into()))?;
+
+        Ok(Self {
+            reader: FileReader::new(reader),
+            file_length,
+            position: 0,
+            finished: false,
+        })
+    }
+
+    fn read_message(&mut self) -> ReaderResult<MessageMeta> {
+        let offset = self.position;
+        self.reader.seek(SeekFrom::Start(offset)).map_err(|_| {
+            ReaderError::ReadError(
+                format!(
+                    "GRIB報の開始位置({})へのシークに失敗しました。",
+                    offset.to_formatted_string(&Locale::ja)
+                )
+                .into(),
+            )
+        })?;
+        let section0 = Section0::from_reader(&mut self.reader).map_err(|e| {
+            ReaderError::Unexpected(
+                format!(
+                    "{}バイト目からGRIB報を読み込めませんでした。GRIB報の境界に不正なバイト列が存在する可能性があります: {}",
+                    offset.to_formatted_string(&Locale::ja),
+                    e
+                )
+                .into(),
+            )
+        })?;
+        let total_length = section0.total_length() as u64;
+        let end = offset.checked_add(total_length).ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!(
+                    "{}バイト目から始まるGRIB報全体のバイト数({})が大きすぎます。",
+                    offset.to_formatted_string(&Locale::ja),
+                    total_length.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            )
+        })?;
+        if total_length < 16 + 4 || self.file_length < end {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "{}バイト目から始まるGRIB報全体のバイト数({})が不正です。",
+                    offset.to_formatted_string(&Locale::ja),
+                    total_length.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+        // 第8節:終端節を確認
+        self.reader
+            .seek(SeekFrom::Start(end - 4))
+            .map_err(|_| ReaderError::ReadError("第8節:終端節へのシークに失敗しました。".into()))?;
+        Section8::from_reader(&mut self.reader)?;
+        self.position = end;
+
+        Ok(MessageMeta {
+            offset,
+            total_length,
+            discipline: section0.discipline(),
+            edition_number: section0.edition_number(),
+        })
+    }
+}
+
+impl<R> Iterator for MessageIter<R>
+where
+    R: Read + Seek,
+{
+    type Item = ReaderResult<MessageMeta>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished || self.file_length <= self.position {
+            return None;
+        }
+        let result = self.read_message();
+        if result.is_err() {
+            self.finished = true;
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::MessageIter;
+    use crate::reader::sections::{insert_section2, small_prr_bytes, TempPath};
+    use crate::reader::{PrrMessage, PrrReader, ReaderError};
+
+    #[test]
+    fn ensure_all_fields_with_missing_sections_err() {
+        let message = PrrMessage::default();
+        match message.ensure_all_fields() {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("第0節:指示節"));
+                assert!(message.contains("第8節:終端節"));
+            }
+            _ => panic!("読み込めなかった節があるエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn write_to_round_trip_ok() {
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let file = TempPath::file(&bytes);
+        let (message, errors) = PrrReader::new_partial(&file);
+        assert!(errors.is_empty());
+        let message = message.unwrap();
+        assert_eq!(Some(&[1, 2, 3, 0][..]), message.run_length());
+
+        let mut written = vec![];
+        message.write_to(&mut written).unwrap();
+        assert_eq!(bytes, written);
+
+        // 第2節を記録したファイル
+        let bytes = insert_section2(bytes, &[0x01, 0x02, 0x03]);
+        let file = TempPath::file(&bytes);
+        let (message, errors) = PrrReader::new_partial(&file);
+        assert!(errors.is_empty());
+        let mut written = vec![];
+        message.unwrap().write_to(&mut written).unwrap();
+        assert_eq!(bytes, written);
+    }
+
+    #[test]
+    fn write_to_with_missing_sections_err() {
+        let message = PrrMessage::default();
+        assert!(message.write_to(&mut vec![]).is_err());
+    }
+
+    /// 第0節と第8節のみで構成されたGRIB報のバイト列を返す。
+    fn message_bytes(discipline: u8) -> Vec<u8> {
+        let mut bytes = b"GRIB".to_vec();
+        bytes.extend_from_slice(&[0x00, 0x00, discipline, 0x02]);
+        bytes.extend_from_slice(&24u64.to_be_bytes());
+        bytes.extend_from_slice(&[0x00; 4]);
+        bytes.extend_from_slice(b"7777");
+
+        bytes
+    }
+
+    fn messages_of(bytes: &[u8]) -> MessageIter<Cursor<Vec<u8>>> {
+        MessageIter::from_reader(Cursor::new(bytes.to_vec())).unwrap()
+    }
+
+    #[test]
+    fn messages_ok() {
+        let mut bytes = message_bytes(0);
+        bytes.extend(message_bytes(1));
+        let messages: Vec<_> = messages_of(&bytes).map(|m| m.unwrap()).collect();
+        assert_eq!(2, messages.len());
+        assert_eq!((0, 24, 0), {
+            let m = messages[0];
+            (m.offset(), m.total_length(), m.discipline())
+        });
+        assert_eq!((24, 24, 1), {
+            let m = messages[1];
+            (m.offset(), m.total_length(), m.discipline())
+        });
+    }
+
+    #[test]
+    fn messages_from_reader_ok() {
+        let mut bytes = message_bytes(0);
+        bytes.extend(message_bytes(1));
+        let offsets: Vec<_> = MessageIter::from_reader(Cursor::new(bytes))
+            .unwrap()
+            .map(|m| m.unwrap().offset())
+            .collect();
+        assert_eq!(vec![0, 24], offsets);
+    }
+
+    #[test]
+    fn messages_with_garbage_between_messages_err() {
+        let mut bytes = message_bytes(0);
+        bytes.extend_from_slice(&[0x00; 3]);
+        bytes.extend(message_bytes(1));
+        let mut messages = messages_of(&bytes);
+        assert!(messages.next().unwrap().is_ok());
+        assert!(messages.next().unwrap().is_err());
+        assert!(messages.next().is_none());
+    }
+
+    #[test]
+    fn messages_with_overflowing_total_length_err() {
+        // 2番目のGRIB報全体のバイト数を、開始位置と加算するとオーバーフローする値に変更
+        let mut bytes = message_bytes(0);
+        let mut second = message_bytes(1);
+        second[8..16].copy_from_slice(&u64::MAX.to_be_bytes());
+        bytes.extend(second);
+        let mut messages = messages_of(&bytes);
+        assert!(messages.next().unwrap().is_ok());
+        match messages.next() {
+            Some(Err(ReaderError::Unexpected(message))) => {
+                assert!(message.contains("大きすぎます"))
+            }
+            _ => panic!("GRIB報全体のバイト数が大きすぎるエラーを想定しています。"),
+        }
+        assert!(messages.next().is_none());
+    }
+}
@@ -2,34 +2,145 @@ use std::borrow::Cow;
 use std::fs::File;
 use std::io::BufReader;
 
+mod audit;
+#[cfg(feature = "tiff")]
+mod cog;
+mod color;
 pub mod fprr;
 mod fpsw;
+mod grid;
+/// 独自のテンプレートを実装するための、GRIB2ファイルの値の読み込みヘルパー
+pub mod helpers;
+mod limits;
 mod lswj;
+mod mask;
+mod mesh;
+mod message;
+mod parameter;
+mod product;
 mod prr;
 mod psw;
+mod registry;
 mod sections;
+mod time_series;
 mod value;
 mod value_iter;
 
+pub use audit::{audit_directory, AuditResult, ValidationCheck, ValidationReport};
+pub use color::ColorMap;
 pub use fprr::FprrReader;
 pub use fpsw::FPswReader;
+pub use grid::{
+    coord_to_grid_point, gaussian_latitudes, grid_point_to_coord, Grid, GridDefinition,
+};
+pub use limits::ReadLimits;
 pub use lswj::{LswjHour, LswjReader};
-pub use prr::PrrReader;
+pub use mask::GridMask;
+pub use mesh::{Aggregation, MeshLevel};
+pub use message::{Grib2Message, Grib2Sections, MessageIter, MessageMeta};
+pub use parameter::{parameter_name, parameter_unit};
+pub use product::{open_auto, Grib2Product};
+pub use prr::{PrrMessage, PrrReader};
 pub use psw::PswReader;
-pub use value::Grib2Value;
-pub use value_iter::Grib2ValueIter;
+pub use registry::{ReadSeek, TemplateDecoder, TemplateRegistry};
+pub use sections::{
+    AnyTemplate4, DebugOptions, FromReader, LevelValue, ProductDefinition, Section0, Section1,
+    Section2, Section3, Section3_0, Section3_40, Section4, Section4_0, Section4_50000,
+    Section4_50008, Section4_50009, Section4_8, Section5, Section5Variant, Section5_200i16,
+    Section5_200u16, Section6, Section7, Section7_200, Section8, Template3_0, Template3_40,
+    Template4_0, Template4_50000, Template4_50008, Template4_50009, Template4_8, Template5_200,
+    Template5_200i16, Template5_200u16, Template7_200, TemplateFromReader,
+    TemplateFromReaderWithSize, TimeRangeSpec,
+};
+pub use time_series::Grib2TimeSeries;
+pub use value::{Grib2Cell, Grib2Value, Grib2ValueRaw, PrecipitationUnit, RoundMode, UnitValues};
+pub use value_iter::{
+    Grib2CellIter, Grib2LenientIter, Grib2RawValueIter, Grib2ValueIter, Grib2WithinIter,
+};
 
 #[derive(thiserror::Error, Clone, Debug)]
 pub enum ReaderError {
     #[error("ファイルが見つかりません: {0}")]
-    NotFount(Cow<'static, str>),
+    NotFound(Cow<'static, str>),
     #[error("ファイルの読み込みに失敗しました: {0}")]
     ReadError(Cow<'static, str>),
+    #[error("ファイルの読み込みに失敗しました(オフセット: {offset}): {message}")]
+    ReadErrorAt {
+        /// 読み込みに失敗したファイルの位置（バイト）
+        offset: usize,
+        /// メッセージ
+        message: Cow<'static, str>,
+    },
     #[error("{0}")]
     Unexpected(Cow<'static, str>),
+    #[error(
+        "{name}:{year}年{month}月{day}日{hour}時{minute}分{second}秒を日時に変換できませんでした。"
+    )]
+    InvalidDateTime {
+        /// 読み込んだ値の名前
+        name: Cow<'static, str>,
+        /// 年
+        year: u16,
+        /// 月
+        month: u8,
+        /// 日
+        day: u8,
+        /// 時
+        hour: u8,
+        /// 分
+        minute: u8,
+        /// 秒
+        second: u8,
+    },
 }
 
-type FileReader = BufReader<File>;
+impl ReaderError {
+    /// `ReaderError::NotFound`の旧名
+    #[deprecated(note = "`ReaderError::NotFound`を使用してください。")]
+    #[allow(non_upper_case_globals)]
+    pub const NotFount: fn(Cow<'static, str>) -> Self = Self::NotFound;
+
+    /// ファイルが見つからないエラーか確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// ファイルが見つからないエラーの場合は`true`
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound(_))
+    }
+
+    /// ファイルの読み込みに失敗したエラーか確認する。
+    ///
+    /// 読み込みに失敗した位置を保持するエラーも含む。
+    ///
+    /// # 戻り値
+    ///
+    /// ファイルの読み込みに失敗したエラーの場合は`true`
+    pub fn is_read_error(&self) -> bool {
+        matches!(self, Self::ReadError(_) | Self::ReadErrorAt { .. })
+    }
+
+    /// ファイルに記録された日時が不正なエラーか確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// 日時が不正なエラーの場合は`true`
+    pub fn is_invalid_datetime(&self) -> bool {
+        matches!(self, Self::InvalidDateTime { .. })
+    }
+
+    /// ファイルの内容が想定と異なるなど、予期しないエラーか確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// 予期しないエラーの場合は`true`
+    pub fn is_unexpected(&self) -> bool {
+        matches!(self, Self::Unexpected(_))
+    }
+}
+
+/// GRIB2ファイルリーダー
+pub type FileReader<R = File> = BufReader<R>;
 
 pub type ReaderResult<T> = Result<T, ReaderError>;
 
@@ -83,6 +194,7 @@ impl TryFrom<u8> for ForecastHour6 {
 }
 
 /// 土壌雨量指数タンク
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C)]
 pub enum PswTank {
     /// 全タンク
@@ -116,6 +228,195 @@ impl TryFrom<u8> for PswTank {
     }
 }
 
+/// 統計処理の種類（符号表4.10）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum StatProc {
+    /// 平均
+    Average = 0,
+    /// 積算
+    Accumulation = 1,
+    /// 最大
+    Maximum = 2,
+    /// 最小
+    Minimum = 3,
+    /// 差（期間の終了時の値から開始時の値を引いた値）
+    Difference = 4,
+    /// 二乗平均平方根
+    RootMeanSquare = 5,
+    /// 標準偏差
+    StandardDeviation = 6,
+    /// 共分散
+    Covariance = 7,
+    /// 差（期間の開始時の値から終了時の値を引いた値）
+    InverseDifference = 8,
+    /// 比
+    Ratio = 9,
+}
+
+impl std::fmt::Display for StatProc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Average => write!(f, "平均"),
+            Self::Accumulation => write!(f, "積算"),
+            Self::Maximum => write!(f, "最大"),
+            Self::Minimum => write!(f, "最小"),
+            Self::Difference => write!(f, "差（終了時－開始時）"),
+            Self::RootMeanSquare => write!(f, "二乗平均平方根"),
+            Self::StandardDeviation => write!(f, "標準偏差"),
+            Self::Covariance => write!(f, "共分散"),
+            Self::InverseDifference => write!(f, "差（開始時－終了時）"),
+            Self::Ratio => write!(f, "比"),
+        }
+    }
+}
+
+impl TryFrom<u8> for StatProc {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Average),
+            1 => Ok(Self::Accumulation),
+            2 => Ok(Self::Maximum),
+            3 => Ok(Self::Minimum),
+            4 => Ok(Self::Difference),
+            5 => Ok(Self::RootMeanSquare),
+            6 => Ok(Self::StandardDeviation),
+            7 => Ok(Self::Covariance),
+            8 => Ok(Self::InverseDifference),
+            9 => Ok(Self::Ratio),
+            _ => Err("StatProcに変換できる数値は0から9までです。"),
+        }
+    }
+}
+
+/// 参照時刻の意味（符号表1.2）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum ReferenceTimeSignificance {
+    /// 解析
+    Analysis = 0,
+    /// 予報の開始
+    StartOfForecast = 1,
+    /// 予報の検証時刻
+    VerifyingTimeOfForecast = 2,
+    /// 観測時刻
+    ObservationTime = 3,
+}
+
+impl std::fmt::Display for ReferenceTimeSignificance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Analysis => write!(f, "解析"),
+            Self::StartOfForecast => write!(f, "予報の開始"),
+            Self::VerifyingTimeOfForecast => write!(f, "予報の検証時刻"),
+            Self::ObservationTime => write!(f, "観測時刻"),
+        }
+    }
+}
+
+impl TryFrom<u8> for ReferenceTimeSignificance {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Analysis),
+            1 => Ok(Self::StartOfForecast),
+            2 => Ok(Self::VerifyingTimeOfForecast),
+            3 => Ok(Self::ObservationTime),
+            _ => Err("ReferenceTimeSignificanceに変換できる数値は0から3までです。"),
+        }
+    }
+}
+
+/// 作成処理の種類（符号表4.3）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum GeneratingProcess {
+    /// 解析
+    Analysis = 0,
+    /// 初期化
+    Initialization = 1,
+    /// 予報
+    Forecast = 2,
+    /// バイアス補正予報
+    BiasCorrectedForecast = 3,
+    /// アンサンブル予報
+    EnsembleForecast = 4,
+    /// 確率予報
+    ProbabilityForecast = 5,
+    /// 予報誤差
+    ForecastError = 6,
+    /// 解析誤差
+    AnalysisError = 7,
+    /// 観測
+    Observation = 8,
+    /// 気候値
+    Climatological = 9,
+    /// 確率加重予報
+    ProbabilityWeightedForecast = 10,
+    /// バイアス補正アンサンブル予報
+    BiasCorrectedEnsembleForecast = 11,
+    /// 後処理解析
+    PostProcessedAnalysis = 12,
+    /// 後処理予報
+    PostProcessedForecast = 13,
+    /// ナウキャスト
+    Nowcast = 14,
+    /// ハインドキャスト
+    Hindcast = 15,
+}
+
+impl std::fmt::Display for GeneratingProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Analysis => write!(f, "解析"),
+            Self::Initialization => write!(f, "初期化"),
+            Self::Forecast => write!(f, "予報"),
+            Self::BiasCorrectedForecast => write!(f, "バイアス補正予報"),
+            Self::EnsembleForecast => write!(f, "アンサンブル予報"),
+            Self::ProbabilityForecast => write!(f, "確率予報"),
+            Self::ForecastError => write!(f, "予報誤差"),
+            Self::AnalysisError => write!(f, "解析誤差"),
+            Self::Observation => write!(f, "観測"),
+            Self::Climatological => write!(f, "気候値"),
+            Self::ProbabilityWeightedForecast => write!(f, "確率加重予報"),
+            Self::BiasCorrectedEnsembleForecast => write!(f, "バイアス補正アンサンブル予報"),
+            Self::PostProcessedAnalysis => write!(f, "後処理解析"),
+            Self::PostProcessedForecast => write!(f, "後処理予報"),
+            Self::Nowcast => write!(f, "ナウキャスト"),
+            Self::Hindcast => write!(f, "ハインドキャスト"),
+        }
+    }
+}
+
+impl TryFrom<u8> for GeneratingProcess {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Analysis),
+            1 => Ok(Self::Initialization),
+            2 => Ok(Self::Forecast),
+            3 => Ok(Self::BiasCorrectedForecast),
+            4 => Ok(Self::EnsembleForecast),
+            5 => Ok(Self::ProbabilityForecast),
+            6 => Ok(Self::ForecastError),
+            7 => Ok(Self::AnalysisError),
+            8 => Ok(Self::Observation),
+            9 => Ok(Self::Climatological),
+            10 => Ok(Self::ProbabilityWeightedForecast),
+            11 => Ok(Self::BiasCorrectedEnsembleForecast),
+            12 => Ok(Self::PostProcessedAnalysis),
+            13 => Ok(Self::PostProcessedForecast),
+            14 => Ok(Self::Nowcast),
+            15 => Ok(Self::Hindcast),
+            _ => Err("GeneratingProcessに変換できる数値は0から15までです。"),
+        }
+    }
+}
+
 pub(crate) fn vec_to_fixed_array<T, const N: usize>(v: Vec<T>) -> ReaderResult<[T; N]> {
     v.try_into().map_err(|v: Vec<T>| {
         ReaderError::Unexpected(
@@ -128,3 +429,135 @@ pub(crate) fn vec_to_fixed_array<T, const N: usize>(v: Vec<T>) -> ReaderResult<[
         )
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{
+        FPswReader, FprrReader, GeneratingProcess, Grib2Value, Grib2ValueIter, Grib2ValueRaw,
+        LswjReader, PrrReader, PswReader, ReaderError,
+    };
+
+    fn assert_send<T: Send>() {}
+
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn readers_are_send_and_sync() {
+        // ファイル単位でスレッドに分けて処理できるように、リーダーと値はスレッド間で送受信及び
+        // 共有できる
+        assert_send::<PrrReader>();
+        assert_sync::<PrrReader>();
+        assert_send::<FprrReader<PathBuf>>();
+        assert_sync::<FprrReader<PathBuf>>();
+        assert_send::<LswjReader<PathBuf>>();
+        assert_sync::<LswjReader<PathBuf>>();
+        assert_send::<PswReader<PathBuf>>();
+        assert_sync::<PswReader<PathBuf>>();
+        assert_send::<FPswReader<PathBuf>>();
+        assert_sync::<FPswReader<PathBuf>>();
+        assert_send::<Grib2Value<f64>>();
+        assert_sync::<Grib2Value<f64>>();
+        assert_send::<Grib2ValueRaw<u16>>();
+        assert_sync::<Grib2ValueRaw<u16>>();
+        assert_send::<ReaderError>();
+        assert_sync::<ReaderError>();
+        // 値を読み込むイテレーターはスレッド間で送信できるが、共有はできない
+        assert_send::<Grib2ValueIter<'static, u16>>();
+    }
+
+    #[test]
+    fn reader_error_classification_ok() {
+        let not_found = match PrrReader::new("not_exists.bin") {
+            Err(e) => e,
+            Ok(_) => panic!("ファイルが見つからないエラーを想定しています。"),
+        };
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_read_error());
+        assert!(!not_found.is_unexpected());
+
+        let read_error = ReaderError::ReadError("read".into());
+        let read_error_at = ReaderError::ReadErrorAt {
+            offset: 16,
+            message: "read".into(),
+        };
+        for e in [&read_error, &read_error_at] {
+            assert!(!e.is_not_found());
+            assert!(e.is_read_error());
+            assert!(!e.is_unexpected());
+        }
+
+        let unexpected = ReaderError::Unexpected("unexpected".into());
+        assert!(!unexpected.is_not_found());
+        assert!(!unexpected.is_read_error());
+        assert!(unexpected.is_unexpected());
+        assert!(!unexpected.is_invalid_datetime());
+
+        let invalid_datetime = ReaderError::InvalidDateTime {
+            name: "datetime".into(),
+            year: 2024,
+            month: 2,
+            day: 30,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        };
+        assert!(invalid_datetime.is_invalid_datetime());
+        assert!(!invalid_datetime.is_unexpected());
+        assert_eq!(
+            "datetime:2024年2月30日0時0分0秒を日時に変換できませんでした。",
+            invalid_datetime.to_string()
+        );
+    }
+
+    #[test]
+    fn generating_process_ok() {
+        let expected = [
+            (0, GeneratingProcess::Analysis, "解析"),
+            (1, GeneratingProcess::Initialization, "初期化"),
+            (2, GeneratingProcess::Forecast, "予報"),
+            (
+                3,
+                GeneratingProcess::BiasCorrectedForecast,
+                "バイアス補正予報",
+            ),
+            (4, GeneratingProcess::EnsembleForecast, "アンサンブル予報"),
+            (5, GeneratingProcess::ProbabilityForecast, "確率予報"),
+            (6, GeneratingProcess::ForecastError, "予報誤差"),
+            (7, GeneratingProcess::AnalysisError, "解析誤差"),
+            (8, GeneratingProcess::Observation, "観測"),
+            (9, GeneratingProcess::Climatological, "気候値"),
+            (
+                10,
+                GeneratingProcess::ProbabilityWeightedForecast,
+                "確率加重予報",
+            ),
+            (
+                11,
+                GeneratingProcess::BiasCorrectedEnsembleForecast,
+                "バイアス補正アンサンブル予報",
+            ),
+            (12, GeneratingProcess::PostProcessedAnalysis, "後処理解析"),
+            (13, GeneratingProcess::PostProcessedForecast, "後処理予報"),
+            (14, GeneratingProcess::Nowcast, "ナウキャスト"),
+            (15, GeneratingProcess::Hindcast, "ハインドキャスト"),
+        ];
+        for (code, process, name) in expected {
+            assert_eq!(process, GeneratingProcess::try_from(code).unwrap());
+            assert_eq!(code, process as u8);
+            assert_eq!(name, process.to_string());
+        }
+        // 地域で使用する値及び欠測値は変換できない
+        for code in [16, 192, 255] {
+            assert!(GeneratingProcess::try_from(code).is_err());
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_not_fount_ok() {
+        let e = ReaderError::NotFount("not found".into());
+        assert!(matches!(e, ReaderError::NotFound(_)));
+    }
+}
@@ -0,0 +1,132 @@
+/// 気象分野（資料分野0）のパラメータカテゴリーとパラメータ番号に対応するパラメータの名前
+///
+/// 符号表4.2の主要なパラメータと、気象庁が地域表で定義するパラメータを収録する。
+const PARAMETER_NAMES: &[(u8, u8, &str)] = &[
+    // カテゴリー0: 温度
+    (0, 0, "気温"),
+    (0, 4, "最高気温"),
+    (0, 5, "最低気温"),
+    (0, 6, "露点温度"),
+    // カテゴリー1: 湿度
+    (1, 0, "比湿"),
+    (1, 1, "相対湿度"),
+    (1, 7, "降水強度"),
+    (1, 8, "総降水量"),
+    (1, 52, "総降水強度"),
+    // カテゴリー1: 湿度（気象庁地域表）
+    (1, 200, "1時間降水量"),
+    (1, 206, "土壌雨量指数"),
+    (1, 208, "土砂災害警戒判定"),
+    // カテゴリー2: 運動量
+    (2, 0, "風向"),
+    (2, 1, "風速"),
+    (2, 2, "風のu成分"),
+    (2, 3, "風のv成分"),
+    (2, 22, "突風"),
+    // カテゴリー3: 質量
+    (3, 0, "気圧"),
+    (3, 1, "海面更正気圧"),
+];
+
+/// 気象分野（資料分野0）のパラメータカテゴリーとパラメータ番号に対応するパラメータの単位
+///
+/// 符号表4.2の単位と、気象庁が地域表で定義するパラメータのうち単位が定まるものを収録する。
+const PARAMETER_UNITS: &[(u8, u8, &str)] = &[
+    // カテゴリー0: 温度
+    (0, 0, "K"),
+    (0, 4, "K"),
+    (0, 5, "K"),
+    (0, 6, "K"),
+    // カテゴリー1: 湿度
+    (1, 0, "kg/kg"),
+    (1, 1, "%"),
+    (1, 7, "kg m-2 s-1"),
+    (1, 8, "kg m-2"),
+    (1, 52, "kg m-2 s-1"),
+    // カテゴリー1: 湿度（気象庁地域表）
+    (1, 200, "mm/h"),
+    // カテゴリー2: 運動量
+    (2, 0, "degree true"),
+    (2, 1, "m/s"),
+    (2, 2, "m/s"),
+    (2, 3, "m/s"),
+    (2, 22, "m/s"),
+    // カテゴリー3: 質量
+    (3, 0, "Pa"),
+    (3, 1, "Pa"),
+];
+
+/// 気象分野（資料分野0）のパラメータカテゴリーとパラメータ番号から、パラメータの名前を返す。
+///
+/// # 引数
+///
+/// * `category` - パラメータカテゴリー
+/// * `number` - パラメータ番号
+///
+/// # 戻り値
+///
+/// パラメータの名前。表に収録されていないパラメータの場合は`None`
+pub fn parameter_name(category: u8, number: u8) -> Option<&'static str> {
+    PARAMETER_NAMES
+        .iter()
+        .find(|(c, n, _)| *c == category && *n == number)
+        .map(|(_, _, name)| *name)
+}
+
+/// 気象分野（資料分野0）のパラメータカテゴリーとパラメータ番号から、パラメータの単位を返す。
+///
+/// # 引数
+///
+/// * `category` - パラメータカテゴリー
+/// * `number` - パラメータ番号
+///
+/// # 戻り値
+///
+/// パラメータの単位。表に収録されていないパラメータの場合は`None`
+pub fn parameter_unit(category: u8, number: u8) -> Option<&'static str> {
+    PARAMETER_UNITS
+        .iter()
+        .find(|(c, n, _)| *c == category && *n == number)
+        .map(|(_, _, unit)| *unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parameter_name, parameter_unit, PARAMETER_NAMES, PARAMETER_UNITS};
+
+    #[test]
+    fn parameter_name_ok() {
+        assert_eq!(Some("気温"), parameter_name(0, 0));
+        assert_eq!(Some("総降水量"), parameter_name(1, 8));
+        assert_eq!(Some("風速"), parameter_name(2, 1));
+        assert_eq!(Some("1時間降水量"), parameter_name(1, 200));
+        assert_eq!(Some("土壌雨量指数"), parameter_name(1, 206));
+        assert_eq!(Some("土砂災害警戒判定"), parameter_name(1, 208));
+    }
+
+    #[test]
+    fn parameter_name_with_unknown_parameter_none() {
+        assert_eq!(None, parameter_name(1, 255));
+        assert_eq!(None, parameter_name(255, 0));
+    }
+
+    #[test]
+    fn parameter_unit_ok() {
+        assert_eq!(Some("K"), parameter_unit(0, 0));
+        assert_eq!(Some("mm/h"), parameter_unit(1, 200));
+        assert_eq!(None, parameter_unit(1, 208));
+        // 単位を収録したパラメータは、名前も収録している
+        assert!(PARAMETER_UNITS
+            .iter()
+            .all(|(c, n, _)| parameter_name(*c, *n).is_some()));
+    }
+
+    #[test]
+    fn parameter_names_are_unique() {
+        for (i, (category, number, _)) in PARAMETER_NAMES.iter().enumerate() {
+            assert!(PARAMETER_NAMES[i + 1..]
+                .iter()
+                .all(|(c, n, _)| (c, n) != (category, number)));
+        }
+    }
+}
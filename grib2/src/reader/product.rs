@@ -0,0 +1,206 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use super::sections::{
+    LeadingSections, Section3_0, Section4, DEFAULT_PRODUCT_DEFINITION_TEMPLATE_NUMBER,
+    PROCESSED_PRODUCT_DEFINITION_TEMPLATE_NUMBER,
+    RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER, RADAR_PRODUCT_DEFINITION_TEMPLATE_NUMBER,
+};
+use super::{
+    FPswReader, FileReader, FprrReader, LswjReader, PrrReader, PswReader, ReaderError, ReaderResult,
+};
+
+/// 資料分野:気象分野
+const METEOROLOGICAL_DISCIPLINE: u8 = 0;
+/// 資料の種類:解析値
+const TYPE_OF_ANALYSIS: u8 = 0;
+/// 資料の種類:予報値
+const TYPE_OF_FORECAST: u8 = 1;
+
+/// ファイルの内容から判別したプロダクトと、そのプロダクトのリーダー
+pub enum Grib2Product {
+    /// 1kmメッシュ解析雨量
+    Prr(Box<PrrReader>),
+    /// 降水短時間予報
+    Fprr(Box<FprrReader<PathBuf>>),
+    /// 土壌雨量指数実況値
+    Psw(Box<PswReader<PathBuf>>),
+    /// 土壌雨量指数予測値
+    FPsw(Box<FPswReader<PathBuf>>),
+    /// 土砂災害警戒判定メッシュ情報
+    Lswj(Box<LswjReader<PathBuf>>),
+}
+
+impl Grib2Product {
+    /// 第3節:格子系定義節を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 第3節:格子系定義節
+    pub fn section3(&self) -> &Section3_0 {
+        match self {
+            Self::Prr(reader) => reader.section3(),
+            Self::Fprr(reader) => reader.section3(),
+            Self::Psw(reader) => reader.section3(),
+            Self::FPsw(reader) => reader.section3(),
+            Self::Lswj(reader) => reader.section3(),
+        }
+    }
+}
+
+impl std::fmt::Display for Grib2Product {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Prr(_) => write!(f, "1kmメッシュ解析雨量"),
+            Self::Fprr(_) => write!(f, "降水短時間予報"),
+            Self::Psw(_) => write!(f, "土壌雨量指数実況値"),
+            Self::FPsw(_) => write!(f, "土壌雨量指数予測値"),
+            Self::Lswj(_) => write!(f, "土砂災害警戒判定メッシュ情報"),
+        }
+    }
+}
+
+/// ファイルのプロダクトを判別して、プロダクトに対応するリーダーを構築する。
+///
+/// 第0節の資料分野、第1節の資料の種類及び第4節のプロダクト定義テンプレート番号を先読みして、
+/// プロダクトを判別する。
+///
+/// # 引数
+///
+/// * `path` - GRIB2形式のファイルのパス
+///
+/// # 戻り値
+///
+/// プロダクトとそのリーダー。プロダクトを判別できない場合はエラー
+pub fn open_auto<P: AsRef<Path>>(path: P) -> ReaderResult<Grib2Product> {
+    let path = path.as_ref();
+
+    open_product(path, detect_product(path)?)
+}
+
+/// ファイルの内容から判別したプロダクトの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProductKind {
+    /// 1kmメッシュ解析雨量
+    Prr,
+    /// 降水短時間予報
+    Fprr,
+    /// 土壌雨量指数実況値
+    Psw,
+    /// 土壌雨量指数予測値
+    FPsw,
+    /// 土砂災害警戒判定メッシュ情報
+    Lswj,
+}
+
+/// ファイルの第0節から第4節までを先読みして、プロダクトの種類を判別する。
+///
+/// # 引数
+///
+/// * `path` - GRIB2形式のファイルのパス
+///
+/// # 戻り値
+///
+/// プロダクトの種類。プロダクトを判別できない場合はエラー
+pub(crate) fn detect_product(path: &Path) -> ReaderResult<ProductKind> {
+    let (discipline, type_of_processed_data, template_number) = peek_product(path)?;
+    if discipline != METEOROLOGICAL_DISCIPLINE {
+        return Err(ReaderError::Unexpected(
+            format!("資料分野({})のプロダクトには対応していません。", discipline).into(),
+        ));
+    }
+    match (template_number, type_of_processed_data) {
+        (RADAR_PRODUCT_DEFINITION_TEMPLATE_NUMBER, _) => Ok(ProductKind::Prr),
+        (RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER, _) => Ok(ProductKind::Fprr),
+        (DEFAULT_PRODUCT_DEFINITION_TEMPLATE_NUMBER, TYPE_OF_ANALYSIS) => Ok(ProductKind::Psw),
+        (DEFAULT_PRODUCT_DEFINITION_TEMPLATE_NUMBER, TYPE_OF_FORECAST) => Ok(ProductKind::FPsw),
+        (PROCESSED_PRODUCT_DEFINITION_TEMPLATE_NUMBER, _) => Ok(ProductKind::Lswj),
+        _ => Err(ReaderError::Unexpected(
+            format!(
+                "プロダクト定義テンプレート番号({})及び資料の種類({})のプロダクトを判別できません。",
+                template_number, type_of_processed_data
+            )
+            .into(),
+        )),
+    }
+}
+
+/// プロダクトの種類に対応するリーダーを構築する。
+///
+/// # 引数
+///
+/// * `path` - GRIB2形式のファイルのパス
+/// * `kind` - プロダクトの種類
+///
+/// # 戻り値
+///
+/// プロダクトとそのリーダー
+pub(crate) fn open_product(path: &Path, kind: ProductKind) -> ReaderResult<Grib2Product> {
+    let path_buf = path.to_path_buf();
+    match kind {
+        ProductKind::Prr => Ok(Grib2Product::Prr(Box::new(PrrReader::new(path)?))),
+        ProductKind::Fprr => Ok(Grib2Product::Fprr(Box::new(FprrReader::new(path_buf)?))),
+        ProductKind::Psw => Ok(Grib2Product::Psw(Box::new(PswReader::new(path_buf)?))),
+        ProductKind::FPsw => Ok(Grib2Product::FPsw(Box::new(FPswReader::new(path_buf)?))),
+        ProductKind::Lswj => Ok(Grib2Product::Lswj(Box::new(LswjReader::new(path_buf)?))),
+    }
+}
+
+/// ファイルの第0節から第4節までを読み込み、プロダクトの判別に必要な値を返す。
+///
+/// # 戻り値
+///
+/// 資料分野、資料の種類及びプロダクト定義テンプレート番号を格納したタプル
+fn peek_product(path: &Path) -> ReaderResult<(u8, u8, u16)> {
+    let file = File::open(path).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
+    let mut reader = FileReader::new(file);
+    let LeadingSections {
+        section0, section1, ..
+    } = LeadingSections::from_reader(&mut reader)?;
+    let section4 = Section4::from_reader_dynamic(&mut reader)?;
+
+    Ok((
+        section0.discipline(),
+        section1.type_of_processed_data(),
+        section4.product_definition_template_number(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open_auto, Grib2Product};
+    use crate::reader::sections::{small_prr_bytes, TempPath};
+    use crate::reader::ReaderError;
+
+    fn open_auto_bytes(bytes: Vec<u8>) -> Result<Grib2Product, ReaderError> {
+        open_auto(TempPath::file(&bytes))
+    }
+
+    #[test]
+    fn open_auto_prr_ok() {
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let product = open_auto_bytes(bytes).unwrap();
+        assert!(matches!(product, Grib2Product::Prr(_)));
+        assert_eq!("1kmメッシュ解析雨量", product.to_string());
+    }
+
+    #[test]
+    fn open_auto_with_unknown_product_err() {
+        // 第4節のプロダクト定義テンプレート番号を50010に変更
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let section4 = bytes.len() - 4 - 9 - 6 - 23 - 82;
+        bytes[section4 + 7..section4 + 9].copy_from_slice(&50010u16.to_be_bytes());
+        match open_auto_bytes(bytes) {
+            Err(ReaderError::Unexpected(message)) => assert!(message.contains("50010")),
+            _ => panic!("プロダクトを判別できないエラーを想定しています。"),
+        }
+
+        // 第0節の資料分野を1（水文分野）に変更
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        bytes[6] = 1;
+        match open_auto_bytes(bytes) {
+            Err(ReaderError::Unexpected(message)) => assert!(message.contains("資料分野(1)")),
+            _ => panic!("資料分野に対応していないエラーを想定しています。"),
+        }
+    }
+}
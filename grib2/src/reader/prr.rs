@@ -1,23 +1,50 @@
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Seek, SeekFrom};
+#[cfg(feature = "memmap2")]
+use std::io::Cursor;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::Arc;
 
 use num_format::{Locale, ToFormattedString};
+use time::{Duration, OffsetDateTime};
 
+#[cfg(feature = "tiff")]
+use super::cog::{write_cog, CogGeoreference};
+use super::grid::{cell_position, micro_degree_of_grid_point};
 use super::sections::{
-    FromReader, Section0, Section1, Section2, Section3_0, Section4_50008, Section5_200u16,
-    Section6, Section7_200, Section8,
+    decimal_scale, to_rfc3339, validate_section_limits, FromReader, LeadingSections,
+    MessageBoundary, ProductDefinition, Section0, Section1, Section2, Section3_0, Section4_50008,
+    Section5_200u16, Section6, Section7_200, Section8, Template3_0, Template4_50008,
+    Template5_200u16, Template7_200,
+};
+use super::value::{Grib2Value, PrecipitationUnit, UnitValues};
+use super::value_iter::{
+    encode_run_length, Grib2LenientIter, Grib2RawValueIter, Grib2ValueIter, Grib2WithinIter,
+    RunLength,
+};
+use super::{
+    grid_point_to_coord, parameter_name, parameter_unit, Aggregation, FileReader,
+    GeneratingProcess, Grib2Message, Grid, GridDefinition, GridMask, MeshLevel, MessageIter,
+    ReadLimits, ReaderError, ReaderResult, StatProc, ValidationCheck, ValidationReport,
 };
-use super::value_iter::Grib2ValueIter;
-use super::{FileReader, ReaderError, ReaderResult};
+
+/// 読み込めた節のみを保持する1kmメッシュ解析雨量のGRIB報
+pub type PrrMessage = Grib2Message<Template3_0, Template4_50008, Template5_200u16, Template7_200>;
 
 /// 1kmメッシュ解析雨量リーダー
-pub struct PrrReader<P>
+///
+/// 型引数`R`は、GRIB2形式のバイト列を読み込むリーダーの型で、ファイルパスから構築した場合は
+/// `BufReader<File>`になる。
+///
+/// `R`が`Send`及び`Sync`の場合、解析雨量リーダーも`Send`及び`Sync`であるため、ファイルごとに
+/// リーダーを構築して別のスレッドで処理できる。
+pub struct PrrReader<R = FileReader>
 where
-    P: AsRef<Path>,
+    R: Read + Seek,
 {
-    /// 読み込むGRIB2ファイルのパス
-    path: P,
+    /// GRIB2形式のバイト列を読み込むリーダー
+    reader: R,
     /// 第0節:指示節
     section0: Section0,
     /// 第1節:識別節
@@ -36,12 +63,13 @@ where
     section7: Section7_200,
     /// 第８節:終端節
     section8: Section8,
+    /// 第5節のレベル別物理値を差し替えたレベル別物理値
+    level_values: Option<Vec<u16>>,
+    /// `cache_run_length`でキャッシュした第7節のランレングス圧縮符号列
+    run_length_cache: Option<Arc<[u8]>>,
 }
 
-impl<P> PrrReader<P>
-where
-    P: AsRef<Path>,
-{
+impl PrrReader<FileReader> {
     /// ファイルパスを受け取り、解析雨量リーダーを構築する。
     ///
     /// # 引数
@@ -51,32 +79,270 @@ where
     /// # 戻り値
     ///
     /// 解析雨量リーダー
-    pub fn new(path: P) -> ReaderResult<Self> {
+    pub fn new<P: AsRef<Path>>(path: P) -> ReaderResult<Self> {
+        Self::new_with_limits(path, ReadLimits::default())
+    }
+
+    /// ファイルパスと読み込みの上限を受け取り、1kmメッシュ解析雨量リーダーを返す。
+    ///
+    /// 第3節以降の節は、読み込む前に節の長さなどが上限以下であることを確認し、上限を超えている
+    /// 場合はメモリを確保せずにエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    /// * `limits` - 読み込みの上限
+    ///
+    /// # 戻り値
+    ///
+    /// 1kmメッシュ解析雨量リーダー
+    pub fn new_with_limits<P: AsRef<Path>>(path: P, limits: ReadLimits) -> ReaderResult<Self> {
         let file =
-            File::open(path.as_ref()).map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            File::open(path.as_ref()).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
+
+        Self::from_reader_with_limits(file, limits)
+    }
+
+    /// ファイルパスを受け取り、読み込めた節のみを保持するGRIB報を返す。
+    ///
+    /// 節の読み込みに失敗した場合、それ以降の節は読み込まない。例えば、第3節まで読み込めた場合は、
+    /// 格子系定義を利用できる。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// 読み込めた節を保持するGRIB報（第0節を読み込めなかった場合は`None`）と、読み込み中に発生した
+    /// エラーのコレクション
+    pub fn new_partial<P: AsRef<Path>>(path: P) -> (Option<PrrMessage>, Vec<ReaderError>) {
+        let mut errors = vec![];
+        let file = match File::open(path.as_ref()) {
+            Ok(file) => file,
+            Err(e) => {
+                errors.push(ReaderError::NotFound(e.to_string().into()));
+                return (None, errors);
+            }
+        };
+        let mut reader = FileReader::new(file);
+        let mut message = PrrMessage::default();
+        if let Err(e) = Self::read_partial(&mut reader, &ReadLimits::default(), &mut message) {
+            errors.push(e);
+        }
+        if let Some(section3) = &message.section3 {
+            if let Err(e) = section3.validate_scanning_mode() {
+                errors.push(e);
+            }
+        }
+        if let (Some(section3), Some(section5), Some(section6)) =
+            (&message.section3, &message.section5, &message.section6)
+        {
+            if let Err(e) = validate_number_of_values(section3, section5, section6) {
+                errors.push(e);
+            }
+        }
+        if message.section0.is_none() {
+            return (None, errors);
+        }
+
+        (Some(message), errors)
+    }
+
+    /// ファイルパスを受け取り、解析雨量のGRIB2ファイルとしての妥当性を検査する。
+    ///
+    /// 節の長さの整合、第8節の終端のマーカー、第3節と第5節の資料点数の一致、及びランレングス
+    /// 圧縮符号列を展開した格子の数を順に検査し、検査項目ごとの合否を返す。前提となる検査に
+    /// 不合格の検査項目は検査しない。
+    ///
+    /// 第7節までの節の読み込みやランレングス圧縮符号列の読み込みに失敗した場合と、第8節までに
+    /// 読み込んだバイト数が第0節に記録されたGRIB報全体のバイト数と一致しない場合は、節の長さの
+    /// 整合を不合格とする。第8節の読み込みに失敗した場合は、終端のマーカーを不合格とする。
+    /// ランレングス圧縮符号列は、節を検査するときに読み込んだバイト列から展開する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// 検査結果。ファイルを開けなかった場合はエラー
+    pub fn validate<P: AsRef<Path>>(path: P) -> ReaderResult<ValidationReport> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
-        let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2::from_reader(&mut reader)?;
-        let section3 = Section3_0::from_reader(&mut reader)?;
+        let mut message = PrrMessage::default();
+        let result = Self::read_partial_sections(&mut reader, &ReadLimits::default(), &mut message);
+
+        // 第7節まで読み込め、第8節を読み込んだ後の読み込み位置がGRIB報の終端と一致する場合は、
+        // 節の長さが整合している
+        let (section_lengths, end_marker) = match result {
+            Ok(boundary) => match Section8::from_reader(&mut reader) {
+                Ok(section8) => {
+                    message.section8 = Some(section8);
+                    (
+                        ValidationCheck::from_result(boundary.validate_end(&mut reader)),
+                        ValidationCheck::Passed,
+                    )
+                }
+                Err(e) => (ValidationCheck::Passed, ValidationCheck::Failed(e)),
+            },
+            Err(e) => (ValidationCheck::Failed(e), ValidationCheck::Skipped),
+        };
+        let number_of_points = match (&message.section3, &message.section5, &message.section6) {
+            (Some(section3), Some(section5), Some(section6)) => ValidationCheck::from_result(
+                validate_number_of_values(section3, section5, section6),
+            ),
+            _ => ValidationCheck::Skipped,
+        };
+        let expanded_points = match (
+            section_lengths.is_passed() && end_marker.is_passed() && number_of_points.is_passed(),
+            &message.section3,
+            &message.section5,
+            &message.section6,
+            &message.section7,
+            message.run_length.take(),
+        ) {
+            (true, Some(section3), Some(section5), Some(section6), Some(section7), Some(bytes)) => {
+                ValidationCheck::from_result(
+                    value_iter(
+                        section3,
+                        section5,
+                        section6,
+                        section7,
+                        section5.level_values(),
+                        RunLength::bytes(bytes),
+                    )
+                    .level_histogram()
+                    .map(|_| ()),
+                )
+            }
+            _ => ValidationCheck::Skipped,
+        };
+
+        Ok(ValidationReport {
+            path: path.to_path_buf(),
+            section_lengths,
+            end_marker,
+            number_of_points,
+            expanded_points,
+        })
+    }
+}
+
+impl<R> PrrReader<FileReader<R>>
+where
+    R: Read + Seek,
+{
+    /// GRIB2形式のバイト列を読み込むリーダーを受け取り、解析雨量リーダーを構築する。
+    ///
+    /// ファイルパスを使用せず、引数`reader`をシークして読み込むため、メモリ上のバイト列
+    /// （例: `Cursor<Vec<u8>>`）やネットワークから受信したバイト列を読み込める。引数`reader`の
+    /// 先頭から読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2形式のバイト列を読み込むリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// 解析雨量リーダー
+    pub fn from_reader(reader: R) -> ReaderResult<Self> {
+        Self::from_reader_with_limits(reader, ReadLimits::default())
+    }
+
+    /// GRIB2形式のバイト列を読み込むリーダーと読み込みの上限を受け取り、解析雨量リーダーを構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2形式のバイト列を読み込むリーダー
+    /// * `limits` - 読み込みの上限
+    ///
+    /// # 戻り値
+    ///
+    /// 解析雨量リーダー
+    pub fn from_reader_with_limits(reader: R, limits: ReadLimits) -> ReaderResult<Self> {
+        Self::read_with_limits(FileReader::new(reader), limits)
+    }
+}
+
+#[cfg(feature = "memmap2")]
+impl PrrReader<Cursor<memmap2::Mmap>> {
+    /// ファイルパスを受け取り、ファイルをメモリマップして解析雨量リーダーを構築する。
+    ///
+    /// 節及びランレングス圧縮符号列は、メモリマップ上のバイト列から直接読み込むため、
+    /// `values`メソッドなどでファイルを読み直すシステムコールが発生しない。大きなファイルを
+    /// 繰り返し処理する場合に使用する。
+    ///
+    /// # Safety
+    ///
+    /// 返したリーダーがファイルをメモリマップしている間（リーダーを破棄するまで）、ファイルを
+    /// 変更または切り詰めてはならない。このプロセス及び他のプロセスがファイルを変更した場合の
+    /// 動作は未定義である。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// 解析雨量リーダー
+    pub unsafe fn from_mmap<P: AsRef<Path>>(path: P) -> ReaderResult<Self> {
+        let file =
+            File::open(path.as_ref()).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
+        // SAFETY: リーダーを破棄するまでファイルが変更されないことを呼び出し側が保証する
+        let mmap = unsafe { memmap2::Mmap::map(&file) }
+            .map_err(|e| ReaderError::ReadError(e.to_string().into()))?;
+
+        Self::read_with_limits(Cursor::new(mmap), ReadLimits::default())
+    }
+
+    /// 第7節のランレングス圧縮符号列を、メモリマップ上のバイト列として返す。
+    ///
+    /// # 戻り値
+    ///
+    /// ランレングス圧縮符号列
+    pub fn run_length_bytes(&self) -> &[u8] {
+        let start = self.section7.run_length_position();
+
+        &self.reader.get_ref()[start..start + self.section7.run_length_bytes()]
+    }
+}
+
+impl<R> PrrReader<R>
+where
+    R: Read + Seek,
+{
+    fn read_with_limits(mut reader: R, limits: ReadLimits) -> ReaderResult<Self> {
+        let LeadingSections {
+            section0,
+            section1,
+            section2,
+            section3,
+            boundary,
+        } = LeadingSections::from_reader_with_limits(&mut reader, &limits)?;
+        validate_section_limits(&mut reader, 4, &limits)?;
         let section4 = Section4_50008::from_reader(&mut reader)?;
+        boundary.validate(&mut reader, "第4節")?;
+        validate_section_limits(&mut reader, 5, &limits)?;
         let section5 = Section5_200u16::from_reader(&mut reader)?;
+        boundary.validate(&mut reader, "第5節")?;
+        if limits.validate_levels() {
+            section5.validate_levels()?;
+        }
+        validate_section_limits(&mut reader, 6, &limits)?;
         let section6 = Section6::from_reader(&mut reader)?;
+        boundary.validate(&mut reader, "第6節")?;
+        validate_section_limits(&mut reader, 7, &limits)?;
         let section7 = Section7_200::from_reader(&mut reader)?;
+        boundary.validate(&mut reader, "第7節")?;
         let section8 = Section8::from_reader(&mut reader)?;
+        boundary.validate_end(&mut reader)?;
 
-        if section3.number_of_data_points() != section5.number_of_values() {
-            return Err(ReaderError::Unexpected(
-                format!(
-                    "第3節に記録されている資料点数({})と第5節に記録されている全資料点({})が一致しません。",
-                    section3.number_of_data_points().to_formatted_string(&Locale::ja),
-                    section5.number_of_values().to_formatted_string(&Locale::ja),
-                ).into(),
-            ));
-        }
+        validate_number_of_values(&section3, &section5, &section6)?;
 
         Ok(PrrReader {
-            path,
+            reader,
             section0,
             section1,
             section2,
@@ -86,9 +352,75 @@ where
             section6,
             section7,
             section8,
+            level_values: None,
+            run_length_cache: None,
         })
     }
 
+    fn read_partial(
+        reader: &mut R,
+        limits: &ReadLimits,
+        message: &mut PrrMessage,
+    ) -> ReaderResult<()> {
+        let boundary = Self::read_partial_sections(reader, limits, message)?;
+        let section8 = Section8::from_reader(reader)?;
+        boundary.validate_end(reader)?;
+        message.section8 = Some(section8);
+
+        Ok(())
+    }
+
+    /// 第0節から第7節までを読み込めた節から順に`message`に記録し、第7節のランレングス圧縮符号列を
+    /// 読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `limits` - 読み込みの上限
+    /// * `message` - 読み込めた節を記録するGRIB報
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB報の境界。読み込み位置は第8節の先頭にある
+    fn read_partial_sections(
+        reader: &mut R,
+        limits: &ReadLimits,
+        message: &mut PrrMessage,
+    ) -> ReaderResult<MessageBoundary> {
+        let LeadingSections { boundary, .. } =
+            LeadingSections::read_recording(reader, limits, message)?;
+        validate_section_limits(reader, 4, limits)?;
+        let section4 = Section4_50008::from_reader(reader)?;
+        boundary.validate(reader, "第4節")?;
+        message.section4 = Some(section4);
+        validate_section_limits(reader, 5, limits)?;
+        let section5 = Section5_200u16::from_reader(reader)?;
+        boundary.validate(reader, "第5節")?;
+        if limits.validate_levels() {
+            section5.validate_levels()?;
+        }
+        message.section5 = Some(section5);
+        validate_section_limits(reader, 6, limits)?;
+        let section6 = Section6::from_reader(reader)?;
+        boundary.validate(reader, "第6節")?;
+        message.section6 = Some(section6);
+        validate_section_limits(reader, 7, limits)?;
+        let section7 = Section7_200::from_reader(reader)?;
+        boundary.validate(reader, "第7節")?;
+        // ランレングス圧縮符号列を読み込み、読み込み位置を第7節の末尾に戻す
+        let mut run_length = vec![0; section7.run_length_bytes()];
+        message.section7 = Some(section7);
+        reader
+            .seek(SeekFrom::Current(-(run_length.len() as i64)))
+            .and_then(|_| reader.read_exact(&mut run_length))
+            .map_err(|_| {
+                ReaderError::ReadError("ランレングス圧縮符号列の読み込みに失敗しました。".into())
+            })?;
+        message.run_length = Some(run_length);
+
+        Ok(boundary)
+    }
+
     /// 第0節:指示節を返す。
     ///
     /// # 戻り値
@@ -170,45 +502,735 @@ where
         &self.section8
     }
 
+    /// 第4節に記録されている作成処理の種類を、符号表4.3の列挙型で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 作成処理の種類。符号表に定義されていない値の場合はエラー
+    pub fn generating_process_kind(&self) -> ReaderResult<GeneratingProcess> {
+        self.section4.template4().generating_process_kind()
+    }
+
+    /// 値の走査に使用するレベル別物理値を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// レベル別物理値。差し替えていない場合は第5節に記録されているレベル別物理値
+    pub fn level_values(&self) -> &[u16] {
+        self.level_values
+            .as_deref()
+            .unwrap_or(self.section5.level_values())
+    }
+
+    /// 値の走査に使用するレベル別物理値に、尺度因子を適用した物理値（物理値 / 10^尺度因子）を返す。
+    ///
+    /// 引数`include_missing`が`true`の場合は、レベル0（欠測）に対応する`f64::NAN`を先頭に
+    /// 格納するため、返したベクタの添字とレベル値が一致する。
+    ///
+    /// # 引数
+    ///
+    /// * `include_missing` - レベル0（欠測）を含めるかどうか
+    ///
+    /// # 戻り値
+    ///
+    /// 尺度因子を適用したレベル別物理値
+    pub fn level_physical_values(&self, include_missing: bool) -> Vec<f64> {
+        let scale = decimal_scale(self.section5.decimal_scale_factor());
+        include_missing
+            .then_some(f64::NAN)
+            .into_iter()
+            .chain(self.level_values().iter().map(|&v| v as f64 / scale))
+            .collect()
+    }
+
+    /// 物理値が収まるレベル値を、尺度因子を適用したレベル別物理値から二分探索して返す。
+    ///
+    /// レベル値`n`のレベル別物理値以上で、レベル値`n + 1`のレベル別物理値未満の物理値に対して
+    /// `n`を返す。最大のレベル値には、そのレベル別物理値と等しい物理値だけが収まる。
+    ///
+    /// # 引数
+    ///
+    /// * `physical` - 物理値
+    ///
+    /// # 戻り値
+    ///
+    /// 物理値が収まるレベル値。物理値がレベル別物理値の範囲外の場合や、レベル別物理値が昇順に
+    /// 並んでいない場合は`None`
+    pub fn level_of_value(&self, physical: f64) -> Option<u16> {
+        let values = self.level_physical_values(false);
+        if values.windows(2).any(|w| w[1] < w[0]) {
+            return None;
+        }
+        if physical.is_nan() || values.last().is_none_or(|&last| last < physical) {
+            return None;
+        }
+        match values.partition_point(|&v| v <= physical) {
+            0 => None,
+            level => Some(level as u16),
+        }
+    }
+
+    /// 値の走査に使用するレベル別物理値を、物理値の単位と値域を検証してから差し替える。
+    ///
+    /// 以下のいずれかに該当する場合は、レベル別物理値を差し替えずにエラーを返す。
+    ///
+    /// * `expected_unit`が、第4節のパラメータカテゴリーとパラメータ番号から求めた単位と一致しない。
+    /// * レベル別物理値の数が、今回の圧縮に用いたレベルの最大値より少ない。
+    /// * レベル別物理値が昇順に並んでいない。
+    /// * レベル別物理値の最大値が、第5節に記録されているレベル別物理値の最大値と比べて10倍以上
+    ///   異なる（尺度因子が一致しないと考えられる）。
+    /// * レベル別物理値と第5節に記録されているレベル別物理値の一方のみが、全て0である。
+    ///
+    /// # 引数
+    ///
+    /// * `levels` - 差し替えるレベル別物理値
+    /// * `expected_unit` - 差し替えるレベル別物理値の単位
+    pub fn with_level_values_checked(
+        &mut self,
+        levels: Vec<u16>,
+        expected_unit: &str,
+    ) -> ReaderResult<()> {
+        let category = self.section4.parameter_category();
+        let number = self.section4.parameter_number();
+        let unit = parameter_unit(category, number).ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!(
+                    "第4節のパラメータ(カテゴリー{}、番号{})の単位を判別できません。",
+                    category, number
+                )
+                .into(),
+            )
+        })?;
+        if expected_unit != unit {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "レベル別物理値の単位({})が、第4節のパラメータ(カテゴリー{}、番号{})の単位({})と一致しません。",
+                    expected_unit, category, number, unit
+                )
+                .into(),
+            ));
+        }
+        validate_level_values(
+            self.section5.level_values(),
+            self.section5.max_level_value(),
+            &levels,
+        )?;
+        self.level_values = Some(levels);
+
+        Ok(())
+    }
+
+    /// 格子の原点座標と画素の大きさから、GDAL互換のアフィン変換係数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// `[原点の経度, 画素の幅, 回転, 原点の緯度, 回転, 画素の高さ]`（度単位）
+    pub fn affine_transform(&self) -> [f64; 6] {
+        self.section3.affine_transform()
+    }
+
+    /// 資料の参照時刻を、UTCオフセット付きのRFC3339形式の文字列で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 資料の参照時刻（例: `2023-09-10T12:00:00Z`）
+    pub fn referenced_at_rfc3339(&self) -> String {
+        to_rfc3339(self.section1.referenced_at())
+    }
+
+    /// 資料の参照時刻を、日本標準時（UTC+09:00）で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 日本標準時の資料の参照時刻
+    pub fn referenced_at_jst(&self) -> OffsetDateTime {
+        self.section1.referenced_at_jst()
+    }
+
+    /// 資料の参照時刻に予報時間を加えた予報の対象時刻を、UTCオフセット付きのRFC3339形式の文字列で
+    /// 返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 予報の対象時刻（例: `2023-09-10T12:00:00Z`）
+    pub fn forecast_valid_time_rfc3339(&self) -> ReaderResult<String> {
+        self.section4
+            .forecast_valid_time(self.section1.referenced_at())
+            .map(to_rfc3339)
+    }
+
+    /// 統計処理の種類を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 統計処理の種類。符号表4.10に定義されていない値の場合はエラー
+    pub fn stat_proc_kind(&self) -> ReaderResult<StatProc> {
+        self.section4.stat_proc_kind()
+    }
+
+    /// 第4節のパラメータカテゴリーとパラメータ番号から、パラメータの名前を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// パラメータの名前（解析雨量は「1時間降水量」）。表に収録されていないパラメータの場合は`None`
+    pub fn parameter_name(&self) -> Option<&'static str> {
+        parameter_name(
+            self.section4.parameter_category(),
+            self.section4.parameter_number(),
+        )
+    }
+
+    /// 期間の単位の指示符に従って、予報時間を期間に変換して返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 予報時間（解析雨量は-60分）
+    pub fn forecast_duration(&self) -> ReaderResult<Duration> {
+        self.section4.forecast_duration()
+    }
+
+    /// 資料の参照時刻に予報時間を加えた、予報の対象時刻を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 予報の対象時刻（UTC）
+    pub fn forecast_valid_at(&self) -> ReaderResult<OffsetDateTime> {
+        self.section4
+            .forecast_valid_time(self.section1.referenced_at())
+    }
+
+    /// ファイルに連結されたGRIB報のメタデータを順に走査するイテレーターを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB報のメタデータを走査するイテレーター
+    pub fn messages(&mut self) -> ReaderResult<MessageIter<&mut R>> {
+        MessageIter::from_reader(&mut self.reader)
+    }
+
     /// ランレングス圧縮符号を走査するイテレーターを返す。
     ///
+    /// 第7節のランレングス圧縮符号列をメモリに読み込んでから走査するため、イテレーターはリーダーを
+    /// 借用しない。したがって、イテレーターを保持したままリーダーを操作したり、イテレーターを別の
+    /// スレッドに移動したりできる。
+    ///
+    /// `cache_run_length`でランレングス圧縮符号列をキャッシュしている場合は、リーダーから読み込まずに
+    /// キャッシュから走査する。
+    ///
     /// # 戻り値
     ///
     /// ランレングス圧縮符号を走査するイテレーター
-    pub fn values(&mut self) -> ReaderResult<Grib2ValueIter<'_, u16>> {
-        let file = File::open(self.path.as_ref())
-            .map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
-        let mut reader = FileReader::new(file);
-        reader
+    pub fn values(&mut self) -> ReaderResult<Grib2ValueIter<'static, u16>> {
+        match &self.run_length_cache {
+            Some(cache) => Ok(self.value_iter(RunLength::bytes(Arc::clone(cache)))),
+            None => {
+                let run_length = self.run_length_bytes_raw()?;
+                Ok(self.value_iter(RunLength::bytes(run_length)))
+            }
+        }
+    }
+
+    /// 第7節のランレングス圧縮符号列をメモリにキャッシュする。
+    ///
+    /// キャッシュした後は、`values`などを呼び出すたびにリーダーをシークしてランレングス圧縮符号列を
+    /// 読み込まずに、キャッシュからイテレーターを構築する。ランレングス圧縮符号列の大きさのメモリを
+    /// 保持し続けるため、同じファイルを何度も走査する場合に利用する。既にキャッシュしている場合は
+    /// 何もしない。
+    ///
+    /// # 戻り値
+    ///
+    /// `()`。ランレングス圧縮符号列を読み込めなかった場合はエラー
+    pub fn cache_run_length(&mut self) -> ReaderResult<()> {
+        if self.run_length_cache.is_none() {
+            self.run_length_cache = Some(Arc::from(self.run_length_bytes_raw()?));
+        }
+
+        Ok(())
+    }
+
+    /// `cache_run_length`でキャッシュしたランレングス圧縮符号列を破棄して、メモリを解放する。
+    ///
+    /// 破棄した後に構築したイテレーターは、リーダーからランレングス圧縮符号列を読み込む。
+    /// 破棄する前に構築したイテレーターは、そのまま走査できる。
+    pub fn clear_run_length_cache(&mut self) {
+        self.run_length_cache = None;
+    }
+
+    /// ランレングス圧縮符号列を受け取り、ランレングス圧縮符号を走査するイテレーターを構築する。
+    fn value_iter(&self, run_length: RunLength<'static>) -> Grib2ValueIter<'static, u16> {
+        value_iter(
+            &self.section3,
+            &self.section5,
+            &self.section6,
+            &self.section7,
+            self.level_values
+                .as_deref()
+                .unwrap_or(self.section5.level_values()),
+            run_length,
+        )
+    }
+
+    /// 第7節のランレングス圧縮符号列を、バイト列として読み込んで返す。
+    ///
+    /// ランレングス圧縮符号を展開せずに、デバッグや再圧縮に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// 第7節に記録されているランレングス圧縮符号列
+    pub fn run_length_bytes_raw(&mut self) -> ReaderResult<Vec<u8>> {
+        self.seek_to_run_length()?;
+        let mut bytes = vec![0; self.section7.run_length_bytes()];
+        self.reader.read_exact(&mut bytes).map_err(|_| {
+            ReaderError::ReadError("ランレングス圧縮符号列の読み込みに失敗しました。".into())
+        })?;
+
+        Ok(bytes)
+    }
+
+    /// リーダーの読み込み位置を、第7節のランレングス圧縮符号列の開始位置に移動する。
+    fn seek_to_run_length(&mut self) -> ReaderResult<()> {
+        self.reader
             .seek(SeekFrom::Start(self.section7.run_length_position() as u64))
             .map_err(|_| {
                 ReaderError::ReadError("ランレングス圧縮符号列のシークに失敗しました。".into())
             })?;
 
-        Ok(Grib2ValueIter::new(
-            reader,
-            self.section7.run_length_bytes(),
-            self.section3.number_of_data_points(),
-            self.section3.lat_of_first_grid_point(),
-            self.section3.lon_of_first_grid_point(),
-            self.section3.lon_of_last_grid_point(),
-            self.section3.j_direction_increment(),
-            self.section3.i_direction_increment(),
-            self.section5.bits_per_value() as u16,
-            self.section5.max_level_value(),
-            self.section5.level_values(),
-        ))
+        Ok(())
     }
 
-    /// 全ての節を出力する。
-    pub fn debug_info<W>(&self, writer: &mut W) -> std::io::Result<()>
-    where
-        W: std::io::Write,
-    {
-        self.section0.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section1.debug_info(writer)?;
-        writeln!(writer)?;
+    /// 座標を10e-6度単位の整数で保持する解析雨量を返すイテレーターを返す。
+    ///
+    /// 座標を度単位に変換しないため、座標をキーとしたメッシュコードのキャッシュなど、座標を
+    /// 整数のまま扱う場合に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// 座標を10e-6度単位の整数で保持する解析雨量を返すイテレーター
+    pub fn values_raw(&mut self) -> ReaderResult<Grib2RawValueIter<'static, u16>> {
+        Ok(self.values()?.into_raw())
+    }
+
+    /// ランレングス圧縮符号列が壊れていてもエラーを返さずに、全ての格子の解析雨量を返すイテレーターを
+    /// 返す。
+    ///
+    /// ランレングス圧縮符号列の展開中にエラーが発生した場合は、それ以降の格子を欠測値として返す。
+    /// 発生したエラーは`Grib2LenientIter::error`で確認できる。第7節を読み込めない場合など、
+    /// イテレーターを構築できない場合はエラーを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 全ての格子の解析雨量を返すイテレーター
+    pub fn values_lenient(&mut self) -> ReaderResult<Grib2LenientIter<'static, u16>> {
+        Ok(self.values()?.lenient())
+    }
+
+    /// 全ての格子の解析雨量を、走査順に格納したベクタとして返す。
+    ///
+    /// feature`rayon`が有効な場合は、ランレングス圧縮符号を展開した後、各格子の座標と解析雨量を
+    /// 並列に計算する。結果の順番は、`values`メソッドで走査した場合と一致する。
+    ///
+    /// # 戻り値
+    ///
+    /// 走査順に格納した解析雨量
+    pub fn par_values(&mut self) -> ReaderResult<Vec<Grib2Value<u16>>> {
+        self.values()?.collect_parallel()
+    }
+
+    /// 矩形領域に含まれる格子の値のみを走査するイテレーターを返す。
+    ///
+    /// 矩形領域の境界に一致する格子は、矩形領域に含まれるものとする。
+    ///
+    /// # 引数
+    ///
+    /// * `min_lat` - 矩形領域の最小緯度（度単位）
+    /// * `min_lon` - 矩形領域の最小経度（度単位）
+    /// * `max_lat` - 矩形領域の最大緯度（度単位）
+    /// * `max_lon` - 矩形領域の最大経度（度単位）
+    ///
+    /// # 戻り値
+    ///
+    /// 矩形領域に含まれる格子の値を走査するイテレーター
+    pub fn within(
+        &mut self,
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    ) -> ReaderResult<Grib2WithinIter<'static, u16>> {
+        Ok(self.values()?.within(min_lat, min_lon, max_lat, max_lon))
+    }
+
+    /// 矩形領域に含まれる格子のみを切り出したGRIB報を返す。
+    ///
+    /// 矩形領域の境界に一致する格子は、矩形領域に含まれるものとする。第3節の資料点数、格子点数
+    /// 並びに最初及び最後の格子点と、第5節の全資料点の数及び今回の圧縮に用いたレベルの最大値を
+    /// 切り出した格子に合わせて更新し、切り出した格子のレベル値を再度ランレングス圧縮して第7節を
+    /// 生成する。走査モードは元のファイルと同じとする。`Grib2Message::write_to`と組み合わせて、
+    /// 切り出したGRIB報をファイルに保存できる。ビットマップを適用するファイルには対応しない。
+    ///
+    /// # 引数
+    ///
+    /// * `min_lat` - 矩形領域の最小緯度（度単位）
+    /// * `min_lon` - 矩形領域の最小経度（度単位）
+    /// * `max_lat` - 矩形領域の最大緯度（度単位）
+    /// * `max_lon` - 矩形領域の最大経度（度単位）
+    ///
+    /// # 戻り値
+    ///
+    /// 矩形領域に含まれる格子のみを切り出したGRIB報。矩形領域に格子が含まれない場合はエラー
+    pub fn subset(
+        &mut self,
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    ) -> ReaderResult<PrrMessage> {
+        if self.section6.bitmap().is_some() {
+            return Err(ReaderError::Unexpected(
+                "ビットマップを適用するファイルの切り出しには対応していません。".into(),
+            ));
+        }
+        let definition = GridDefinition::from(&self.section3);
+        let nrows = definition.number_of_along_lon_points() as usize;
+        let ncols = definition.number_of_along_lat_points() as usize;
+        let scanning_mode = definition.scanning_mode();
+        if self.section3.number_of_data_points() as usize != nrows * ncols {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "第3節に記録されている資料点数({})が行数と列数の積({})と一致しません。",
+                    self.section3
+                        .number_of_data_points()
+                        .to_formatted_string(&Locale::ja),
+                    (nrows * ncols).to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        // 矩形領域に含まれる行と列の範囲を求める
+        let rows = index_range(nrows, |row| {
+            let (lat, _) = grid_point_to_coord(row as u32, 0, &definition);
+            min_lat <= lat && lat <= max_lat
+        });
+        let cols = index_range(ncols, |col| {
+            let (_, lon) = grid_point_to_coord(0, col as u32, &definition);
+            min_lon <= lon && lon <= max_lon
+        });
+        let ((first_row, last_row), (first_col, last_col)) = match (rows, cols) {
+            (Some(rows), Some(cols)) => (rows, cols),
+            _ => {
+                return Err(ReaderError::Unexpected(
+                    format!(
+                        "矩形領域(緯度{}～{}、経度{}～{})に格子が含まれていません。",
+                        min_lat, max_lat, min_lon, max_lon
+                    )
+                    .into(),
+                ))
+            }
+        };
+
+        // 北を上、西を左とした格子のレベル値を展開
+        let mut levels = vec![0u16; nrows * ncols];
+        for (index, value) in self.values_raw()?.enumerate() {
+            let (row, col) = cell_position(index, nrows, ncols, scanning_mode);
+            levels[row * ncols + col] = value?.level();
+        }
+
+        // 切り出した格子のレベル値を、走査モードに従った順番に並べて再度ランレングス圧縮
+        let sub_nrows = last_row - first_row + 1;
+        let sub_ncols = last_col - first_col + 1;
+        let sub_levels = (0..sub_nrows * sub_ncols)
+            .map(|index| {
+                let (row, col) = cell_position(index, sub_nrows, sub_ncols, scanning_mode);
+                levels[(first_row + row) * ncols + first_col + col]
+            })
+            .collect::<Vec<_>>();
+        let maxv = sub_levels.iter().copied().max().unwrap_or(0);
+        let run_length =
+            encode_run_length(&sub_levels, maxv, self.section5.bits_per_value() as u16)?;
+
+        // 切り出した格子の格子系定義を構築
+        let (row, col) = cell_position(0, sub_nrows, sub_ncols, scanning_mode);
+        let (lat_first, lon_first) = micro_degree_of_grid_point(
+            (first_row + row) as u32,
+            (first_col + col) as u32,
+            &definition,
+        );
+        let sub_definition = GridDefinition::from_first_grid_point(
+            sub_nrows as u32,
+            sub_ncols as u32,
+            lat_first as u32,
+            lon_first as u32,
+            definition.j_direction_increment(),
+            definition.i_direction_increment(),
+            scanning_mode,
+        );
+
+        Ok(PrrMessage {
+            section0: Some(self.section0.clone()),
+            section1: Some(self.section1),
+            section2: Some(self.section2.clone()),
+            section3: Some(self.section3.with_grid_definition(&sub_definition)),
+            section4: Some(self.section4.clone()),
+            section5: Some(
+                self.section5
+                    .with_number_of_values(sub_levels.len() as u32, maxv),
+            ),
+            section6: Some(self.section6.clone()),
+            section7: Some(Section7_200::from_run_length_bytes(run_length.len())),
+            section8: Some(self.section8.clone()),
+            run_length: Some(run_length),
+        })
+    }
+
+    /// 流域ごとに、流域に含まれる格子の解析雨量の平均を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `basins` - 流域を表現する格子マスクのスライス
+    ///
+    /// # 戻り値
+    ///
+    /// 流域ごとの解析雨量の平均（流域内の格子がすべて欠測値の場合は`None`）
+    pub fn basin_means(&mut self, basins: &[GridMask]) -> ReaderResult<Vec<Option<f64>>> {
+        self.values()?.basin_means(basins)
+    }
+
+    /// 標準地域メッシュごとに、メッシュに含まれる格子の解析雨量を集約する。
+    ///
+    /// # 引数
+    ///
+    /// * `level` - 集約するメッシュの区画
+    /// * `aggregation` - 同じメッシュに含まれる複数の格子の解析雨量を集約する方法
+    ///
+    /// # 戻り値
+    ///
+    /// メッシュコードをキー、集約した解析雨量（mm/h）を値とした`HashMap`
+    pub fn aggregate_by_mesh(
+        &mut self,
+        level: MeshLevel,
+        aggregation: Aggregation,
+    ) -> ReaderResult<HashMap<u64, f64>> {
+        self.values()?.aggregate_by_mesh(level, aggregation)
+    }
+
+    /// 尺度因子を適用した解析雨量を、走査順に格納した実数の配列として返す。
+    ///
+    /// # 引数
+    ///
+    /// * `missing` - 欠測値の格子に格納する値（例: `f32::NAN`、`-1.0`）
+    ///
+    /// # 戻り値
+    ///
+    /// 資料点数と同じ長さの解析雨量の配列
+    pub fn to_flat_f32(&mut self, missing: f32) -> ReaderResult<Vec<f32>> {
+        self.values()?.to_flat_f32(missing)
+    }
+
+    /// 全ての格子に対する欠測値の格子の比率を返す。
+    ///
+    /// 第6節のビットマップにより資料値が存在しない格子は、欠測値の格子として数える。
+    ///
+    /// # 戻り値
+    ///
+    /// 欠測値の格子の比率（0.0以上1.0以下）
+    pub fn missing_ratio(&mut self) -> ReaderResult<f64> {
+        self.values()?.missing_ratio()
+    }
+
+    /// レベル値ごとに、そのレベル値の格子の数を集計する。
+    ///
+    /// # 戻り値
+    ///
+    /// インデックスをレベル値、要素を格子の数としたベクタ。長さはレベル別物理値の数に1を
+    /// 加えた値で、インデックス0は欠測値の格子の数
+    pub fn level_histogram(&mut self) -> ReaderResult<Vec<u32>> {
+        self.values()?.level_histogram()
+    }
+
+    /// 尺度因子を適用した解析雨量が閾値以上の格子を1とした、1格子1ビットのマスクを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `threshold` - 閾値
+    ///
+    /// # 戻り値
+    ///
+    /// 資料点数と同じビット長のマスク
+    #[cfg(feature = "bitvec")]
+    pub fn to_bitvec_mask(
+        &mut self,
+        threshold: f64,
+    ) -> ReaderResult<bitvec::vec::BitVec<u8, bitvec::order::Msb0>> {
+        self.values()?.to_bitvec_mask(threshold)
+    }
+
+    /// 解析雨量を、北を上、西を左とした行×列の格子として返す。
+    ///
+    /// 行数は経線に沿った格子点数、列数は緯線に沿った格子点数で、第3節の走査モードに従って
+    /// 格子を充填する。
+    ///
+    /// # 戻り値
+    ///
+    /// 解析雨量の格子（欠測値の格子は`None`）
+    pub fn to_grid(&mut self) -> ReaderResult<Grid> {
+        let nrows = self.section3.number_of_along_lon_points();
+        let ncols = self.section3.number_of_along_lat_points();
+        let number_of_points = self.section3.number_of_data_points();
+        let scanning_mode = self.section3.scanning_mode();
+
+        Grid::from_values(
+            self.values()?,
+            nrows,
+            ncols,
+            number_of_points,
+            scanning_mode,
+        )
+    }
+
+    /// 次の時刻の解析雨量ファイルとのブロックマッチングで、降水域の移動ベクトルを推定する。
+    ///
+    /// 2つのファイルの格子系定義が一致しない場合はエラーを返す。ブロックマッチングの詳細は
+    /// [`Grid`]の格子から推定する方法に従い、物理値が正の格子を含まないブロックは結果に含めない。
+    /// 計算量はブロックの大きさの4乗に比例するため、`block`で計算量を調整する。
+    ///
+    /// # 引数
+    ///
+    /// * `next` - 次の時刻の解析雨量ファイルのパス
+    /// * `block` - ブロックの1辺の格子数
+    ///
+    /// # 戻り値
+    ///
+    /// `(ブロック中心の緯度, ブロック中心の経度, 東西方向の移動量, 南北方向の移動量)`（度単位）の
+    /// コレクション。移動量は東及び北を正とする。
+    pub fn motion_vectors<Q: AsRef<Path>>(
+        &mut self,
+        next: Q,
+        block: usize,
+    ) -> ReaderResult<Vec<(f64, f64, f64, f64)>> {
+        let mut next = PrrReader::new(next)?;
+        if GridDefinition::from(&self.section3) != GridDefinition::from(next.section3()) {
+            return Err(ReaderError::Unexpected(
+                "次の時刻のファイルの格子系定義が、ファイルの格子系定義と一致しません。".into(),
+            ));
+        }
+        let vectors = self.to_grid()?.motion_vectors(&next.to_grid()?, block)?;
+
+        // 格子の行番号と列番号を緯度と経度に変換
+        let t = &self.section3;
+        let north =
+            t.lat_of_first_grid_point().max(t.lat_of_last_grid_point()) as f64 / 1_000_000.0;
+        let west = t.lon_of_first_grid_point().min(t.lon_of_last_grid_point()) as f64 / 1_000_000.0;
+        let lat_inc = t.j_direction_increment() as f64 / 1_000_000.0;
+        let lon_inc = t.i_direction_increment() as f64 / 1_000_000.0;
+
+        Ok(vectors
+            .into_iter()
+            .map(|(row, col, dr, dc)| {
+                (
+                    north - row * lat_inc,
+                    west + col * lon_inc,
+                    dc as f64 * lon_inc,
+                    -dr as f64 * lat_inc,
+                )
+            })
+            .collect())
+    }
+
+    /// 解析雨量（mm/h）に統計処理した時間の長さを乗じて、降水量（mm）に変換した値を走査する
+    /// イテレーターを返す。
+    ///
+    /// 物理値には尺度因子を適用する。第4節の統計処理の時間の単位が不明な場合はエラーを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 単位（mm）を添えた降水量のイテレーター
+    pub fn to_accumulated_mm(
+        &mut self,
+    ) -> ReaderResult<UnitValues<impl Iterator<Item = ReaderResult<Grib2Value<f64>>> + '_>> {
+        let hours = self.section4.stat_proc_hours().ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!(
+                    "第4節の統計処理の時間の単位({})または長さ({})から、統計処理した期間を特定できません。",
+                    self.section4.stat_proc_time_unit(),
+                    self.section4.stat_proc_time_length(),
+                )
+                .into(),
+            )
+        })?;
+
+        Ok(UnitValues::new(
+            PrecipitationUnit::Millimeters,
+            self.values()?.scaled(hours),
+        ))
+    }
+
+    /// 解析雨量を、`lat,lon,level,value`のヘッダを持つCSV形式で出力する。
+    ///
+    /// 欠測値の解析雨量は空欄で出力する。格子は1つずつ出力するため、全ての格子をメモリに保持しない。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先
+    pub fn write_csv<W: std::io::Write>(&mut self, writer: &mut W) -> ReaderResult<()> {
+        self.values()?.write_csv(writer)
+    }
+
+    /// 解析雨量を、COG（Cloud Optimized GeoTIFF）形式で出力する。
+    ///
+    /// 尺度因子を適用した解析雨量を32ビット浮動小数点数で、欠測値をNaNで出力する。画像は内部で
+    /// タイルに分割し、オーバービューを生成して、全てのIFDをファイルの先頭に配置する。地理座標系は
+    /// 第3節の地球の形状から決定し、GRS80楕円体（4）はEPSG:4019、WGS84（5）はEPSG:4326とする。
+    /// 全ての格子をメモリに保持する。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先（先頭に位置していること）
+    #[cfg(feature = "tiff")]
+    pub fn write_cog<W: std::io::Write + Seek>(&mut self, writer: &mut W) -> ReaderResult<()> {
+        let t = &self.section3;
+        let epsg = match t.shape_of_earth() {
+            4 => 4019,
+            5 => 4326,
+            shape => {
+                return Err(ReaderError::Unexpected(
+                    format!("地球の形状({})に対応する地理座標系がありません。", shape).into(),
+                ))
+            }
+        };
+        let georeference = CogGeoreference {
+            west: t.lon_of_first_grid_point().min(t.lon_of_last_grid_point()) as f64 / 1_000_000.0,
+            north: t.lat_of_first_grid_point().max(t.lat_of_last_grid_point()) as f64 / 1_000_000.0,
+            lon_inc: t.i_direction_increment() as f64 / 1_000_000.0,
+            lat_inc: t.j_direction_increment() as f64 / 1_000_000.0,
+            epsg,
+        };
+        let decimal_scale_factor = self.section5.decimal_scale_factor();
+        let grid = self.to_grid()?;
+
+        write_cog(writer, &grid, decimal_scale_factor, &georeference)
+    }
+
+    /// 解析雨量を、PostgreSQLの`COPY ... FROM STDIN`のテキスト形式で出力する。
+    ///
+    /// 1行に1格子の緯度、経度及び解析雨量をタブ区切りで出力し、欠測値は`\N`（NULL）で出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先
+    pub fn write_pg_copy<W: std::io::Write>(&mut self, writer: &mut W) -> ReaderResult<()> {
+        self.values()?.write_pg_copy(writer)
+    }
+
+    /// 全ての節を出力する。
+    pub fn debug_info<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.section0.debug_info(writer)?;
+        writeln!(writer)?;
+        self.section1.debug_info(writer)?;
+        writeln!(writer)?;
         self.section2.debug_info(writer)?;
         writeln!(writer)?;
         self.section3.debug_info(writer)?;
@@ -226,4 +1248,926 @@ where
 
         Ok(())
     }
+
+    /// 全ての節をJSON形式で出力する。
+    ///
+    /// feature`json`が有効な場合に利用できる。`debug_info`と同じ項目を、節ごとのオブジェクトとして
+    /// 出力する。16進数で書式化する項目は文字列、それ以外の数値は数値として出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先
+    #[cfg(feature = "json")]
+    pub fn write_sections_json<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        let sections = serde_json::json!({
+            "section0": self.section0.debug_info_json(),
+            "section1": self.section1.debug_info_json(),
+            "section2": self.section2.debug_info_json(),
+            "section3": self.section3.debug_info_json(),
+            "section4": self.section4.debug_info_json(),
+            "section5": self.section5.debug_info_json(),
+            "section6": self.section6.debug_info_json(),
+            "section7": self.section7.debug_info_json(),
+            "section8": self.section8.debug_info_json(),
+        });
+        serde_json::to_writer_pretty(&mut *writer, &sections)?;
+        writeln!(writer)
+    }
+}
+
+impl PrrReader<FileReader> {
+    /// 複数の解析雨量ファイルを読み込み、時刻×緯度×経度の3次元テンソルを返す。
+    ///
+    /// テンソルは、引数`paths`の順番で各ファイルの格子を並べた平坦な配列で、各ファイルの格子は
+    /// 走査順に格納する。全てのファイルの格子系定義が一致しない場合はエラーを返す。
+    /// feature`rayon`が有効な場合は、ファイル単位で並列に展開する。
+    ///
+    /// # 引数
+    ///
+    /// * `paths` - GRIB2形式のファイルのパスのスライス
+    /// * `missing` - 欠測値の格子に格納する値（例: `f32::NAN`、`-1.0`）
+    ///
+    /// # 戻り値
+    ///
+    /// 平坦な配列と、`(時刻数, 緯度方向の格子点数, 経度方向の格子点数)`の形状
+    pub fn to_tensor<P>(
+        paths: &[P],
+        missing: f32,
+    ) -> ReaderResult<(Vec<f32>, (usize, usize, usize))>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        if paths.is_empty() {
+            return Err(ReaderError::Unexpected(
+                "テンソル化するファイルが指定されていません。".into(),
+            ));
+        }
+
+        let read = |path: &P| -> ReaderResult<(GridDefinition, Vec<f32>)> {
+            let mut reader = PrrReader::new(path.as_ref())?;
+            let definition = GridDefinition::from(reader.section3());
+            let values = reader.to_flat_f32(missing)?;
+
+            Ok((definition, values))
+        };
+        #[cfg(feature = "rayon")]
+        let grids = {
+            use rayon::prelude::*;
+            paths
+                .par_iter()
+                .map(read)
+                .collect::<ReaderResult<Vec<_>>>()?
+        };
+        #[cfg(not(feature = "rayon"))]
+        let grids = paths.iter().map(read).collect::<ReaderResult<Vec<_>>>()?;
+
+        // 全てのファイルの格子系定義が一致するか確認
+        let definition = grids[0].0;
+        if let Some(i) = grids.iter().position(|(d, _)| *d != definition) {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "{}番目のファイルの格子系定義が、1番目のファイルの格子系定義と一致しません。",
+                    i + 1
+                )
+                .into(),
+            ));
+        }
+
+        let shape = (
+            grids.len(),
+            definition.number_of_along_lon_points() as usize,
+            definition.number_of_along_lat_points() as usize,
+        );
+        let mut tensor = Vec::with_capacity(shape.0 * shape.1 * shape.2);
+        for (_, values) in grids {
+            tensor.extend(values);
+        }
+
+        Ok((tensor, shape))
+    }
+}
+
+/// 条件を満たす連続した添字の範囲を返す。
+///
+/// # 引数
+///
+/// * `len` - 添字の数
+/// * `contains` - 添字が範囲に含まれるか判定する関数
+///
+/// # 戻り値
+///
+/// 条件を満たす最初と最後の添字を格納したタプル。条件を満たす添字がない場合は`None`
+fn index_range<F: Fn(usize) -> bool>(len: usize, contains: F) -> Option<(usize, usize)> {
+    let first = (0..len).find(|&index| contains(index))?;
+    let last = (0..len).rev().find(|&index| contains(index))?;
+
+    Some((first, last))
+}
+
+/// 第3節、第5節から第7節及びランレングス圧縮符号列から、ランレングス圧縮符号を走査する
+/// イテレーターを構築する。
+///
+/// # 引数
+///
+/// * `section3` - 第3節
+/// * `section5` - 第5節
+/// * `section6` - 第6節
+/// * `section7` - 第7節
+/// * `level_values` - レベル別物理値
+/// * `run_length` - ランレングス圧縮符号列
+///
+/// # 戻り値
+///
+/// ランレングス圧縮符号を走査するイテレーター
+fn value_iter(
+    section3: &Section3_0,
+    section5: &Section5_200u16,
+    section6: &Section6,
+    section7: &Section7_200,
+    level_values: &[u16],
+    run_length: RunLength<'static>,
+) -> Grib2ValueIter<'static, u16> {
+    Grib2ValueIter::new(
+        run_length,
+        section7.run_length_bytes(),
+        section3.number_of_data_points(),
+        section3.lat_of_first_grid_point(),
+        section3.lon_of_first_grid_point(),
+        section3.lon_of_last_grid_point(),
+        section3.j_direction_increment(),
+        section3.i_direction_increment(),
+        section3.scanning_mode(),
+        section5.bits_per_value() as u16,
+        section5.max_level_value(),
+        section5.decimal_scale_factor(),
+        level_values,
+    )
+    .with_bitmap(section6.shared_bitmap())
+}
+
+/// 第5節に記録されている全資料点の数が、第3節の資料点数及び第6節のビットマップと整合するか確認する。
+///
+/// ビットマップを適用する場合、全資料点の数はビットマップで資料値が存在するとされた格子の数と
+/// 一致しなければならない。
+fn validate_number_of_values(
+    section3: &Section3_0,
+    section5: &Section5_200u16,
+    section6: &Section6,
+) -> ReaderResult<()> {
+    let number_of_points = section3.number_of_data_points();
+    let expected = match section6.bitmap() {
+        Some(bitmap) => {
+            if bitmap.len() < number_of_points as usize {
+                return Err(ReaderError::Unexpected(
+                    format!(
+                        "第6節に記録されているビットマップのビット数({})が、第3節に記録されている資料点数({})より少ないです。",
+                        bitmap.len().to_formatted_string(&Locale::ja),
+                        number_of_points.to_formatted_string(&Locale::ja),
+                    )
+                    .into(),
+                ));
+            }
+            bitmap[..number_of_points as usize]
+                .iter()
+                .filter(|&&bit| bit)
+                .count() as u32
+        }
+        None => number_of_points,
+    };
+    if expected != section5.number_of_values() {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "第3節に記録されている資料点数及び第6節のビットマップから求めた資料値の数({})と第5節に記録されている全資料点({})が一致しません。",
+                expected.to_formatted_string(&Locale::ja),
+                section5.number_of_values().to_formatted_string(&Locale::ja),
+            )
+            .into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// 差し替えるレベル別物理値が、第5節に記録されているレベル別物理値と整合するか確認する。
+///
+/// # 引数
+///
+/// * `current` - 第5節に記録されているレベル別物理値
+/// * `maxv` - 今回の圧縮に用いたレベルの最大値
+/// * `levels` - 差し替えるレベル別物理値
+fn validate_level_values(current: &[u16], maxv: u16, levels: &[u16]) -> ReaderResult<()> {
+    if levels.len() < maxv as usize {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "レベル別物理値の数({})が、今回の圧縮に用いたレベルの最大値({})より少ないです。",
+                levels.len(),
+                maxv
+            )
+            .into(),
+        ));
+    }
+    if let Some(i) = levels.windows(2).position(|w| w[1] < w[0]) {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "レベル別物理値が昇順に並んでいません(レベル{}: {}、レベル{}: {})。",
+                i + 1,
+                levels[i],
+                i + 2,
+                levels[i + 1]
+            )
+            .into(),
+        ));
+    }
+    // 尺度因子が異なる場合、物理値の最大値は10のべき乗倍で異なる
+    let current_max = current.iter().copied().max().unwrap_or(0) as u64;
+    let levels_max = levels.iter().copied().max().unwrap_or(0) as u64;
+    // 全て0のテーブルは最大値の比を求められないため、両方が全て0の場合のみ整合するとみなす
+    match (current_max, levels_max) {
+        (0, 0) => return Ok(()),
+        (0, _) | (_, 0) => {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "レベル別物理値の最大値({})と第5節に記録されているレベル別物理値の最大値({})の一方のみが0です。",
+                    levels_max, current_max
+                )
+                .into(),
+            ))
+        }
+        _ => {}
+    }
+    if current_max * 10 <= levels_max || levels_max * 10 <= current_max {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "レベル別物理値の最大値({})が、第5節に記録されているレベル別物理値の最大値({})と整合しません。",
+                levels_max, current_max
+            )
+            .into(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{validate_level_values, PrrReader};
+    use crate::reader::sections::{
+        insert_section2, section0_to_section3_bytes, small_prr_bytes, TempPath,
+    };
+    use crate::reader::{
+        GeneratingProcess, Grib2Sections, ReadLimits, ReaderError, Template4_50008,
+        ValidationCheck, ValidationReport,
+    };
+
+    #[test]
+    fn generating_process_kind_ok() {
+        let mut bytes = small_prr_bytes(36_000_000, [1, 2, 3, 0]);
+        let reader = PrrReader::from_reader(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(
+            GeneratingProcess::Analysis,
+            reader.generating_process_kind().unwrap()
+        );
+
+        // 第4節の作成処理の種類を予報に変更
+        bytes[120] = 2;
+        let reader = PrrReader::from_reader(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(
+            GeneratingProcess::Forecast,
+            reader.generating_process_kind().unwrap()
+        );
+
+        // 符号表に定義されていない作成処理の種類
+        bytes[120] = 192;
+        let reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        assert_eq!(192, reader.section4().type_of_generating_process());
+        match reader.generating_process_kind() {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("作成処理の種類(192)"));
+            }
+            _ => panic!("作成処理の種類を変換できないエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn new_with_limits_rejects_too_large_section() {
+        // 第3節まで正常で、第4節の節の長さが過大なファイル
+        let mut bytes = section0_to_section3_bytes(1_000);
+        bytes.extend_from_slice(&0x7FFF_FFFFu32.to_be_bytes());
+        bytes.push(4);
+        let path = TempPath::file(&bytes);
+
+        let result = PrrReader::new_with_limits(&path, ReadLimits::default());
+        assert!(matches!(result, Err(ReaderError::Unexpected(_))));
+    }
+
+    #[test]
+    fn new_with_limits_rejects_too_many_level_values() {
+        // 第4節まで正常で、第5節のレベル別物理値が上限を超えるファイル
+        let mut bytes = section0_to_section3_bytes(1_000);
+        let mut section4 = vec![0x00, 0x00, 0x00, 0x52, 0x04, 0x00, 0x00, 0xC3, 0x58];
+        section4.extend_from_slice(&[0x00; 25]);
+        section4.extend_from_slice(&[0x07, 0xE5, 0x08, 0x11, 0x00, 0x00, 0x00]);
+        section4.push(0x01);
+        section4.extend_from_slice(&[0x00; 16 + 24]);
+        bytes.extend_from_slice(&section4);
+        // 節の長さから求めたレベル別物理値の数は100
+        bytes.extend_from_slice(&(17u32 + 2 * 100).to_be_bytes());
+        bytes.extend_from_slice(&[0x05, 0x00, 0x83, 0x40, 0x00, 0x00, 0xC8]);
+        let path = TempPath::file(&bytes);
+
+        let limits = ReadLimits::default().with_max_level_values(98);
+        match PrrReader::new_with_limits(&path, limits) {
+            Err(ReaderError::Unexpected(message)) => assert!(message.contains("レベル別物理値")),
+            _ => panic!("レベル別物理値の数が上限を超えたエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn from_reader_with_limits_validates_levels() {
+        // 第4節まで正常で、第5節のレベル別物理値が減少しているバイト列
+        let mut bytes = section0_to_section3_bytes(1_000);
+        let mut section4 = vec![0x00, 0x00, 0x00, 0x52, 0x04, 0x00, 0x00, 0xC3, 0x58];
+        section4.extend_from_slice(&[0x00; 25]);
+        section4.extend_from_slice(&[0x07, 0xE5, 0x08, 0x11, 0x00, 0x00, 0x00]);
+        section4.push(0x01);
+        section4.extend_from_slice(&[0x00; 16 + 24]);
+        bytes.extend_from_slice(&section4);
+        bytes.extend_from_slice(&(17u32 + 2 * 3).to_be_bytes());
+        bytes.extend_from_slice(&[0x05, 0x00, 0x83, 0x40, 0x00, 0x00, 0xC8, 0x08]);
+        bytes.extend_from_slice(&[0x00, 0x03, 0x00, 0x03, 0x01]);
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x14, 0x00, 0x0A]);
+
+        // 既定では検証しないため、第5節の後の第6節を読み込むときにエラーになる
+        match PrrReader::from_reader_with_limits(Cursor::new(bytes.clone()), ReadLimits::default())
+        {
+            Err(err) => assert!(!err.to_string().contains("単調")),
+            _ => panic!("第6節の読み込みに失敗したエラーを想定しています。"),
+        }
+        let limits = ReadLimits::default().with_validate_levels(true);
+        match PrrReader::from_reader_with_limits(Cursor::new(bytes), limits) {
+            Err(ReaderError::Unexpected(message)) => assert!(message.contains("単調")),
+            _ => panic!("レベル別物理値が減少しているエラーを想定しています。"),
+        }
+    }
+
+    fn validate_bytes(bytes: Vec<u8>) -> ValidationReport {
+        PrrReader::validate(TempPath::file(&bytes)).unwrap()
+    }
+
+    #[test]
+    fn validate_ok() {
+        let path = TempPath::file(&small_prr_bytes(36_004_167, [1, 2, 3, 0]));
+        let report = PrrReader::validate(&path).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(path.path(), report.path());
+    }
+
+    #[test]
+    fn validate_with_broken_files_err() {
+        // 第5節の途中で終わるファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        bytes.truncate(bytes.len() - 4 - 9 - 6 - 10);
+        let report = validate_bytes(bytes);
+        assert!(!report.is_valid());
+        assert!(matches!(
+            report.section_lengths(),
+            ValidationCheck::Failed(_)
+        ));
+        assert!(matches!(report.end_marker(), ValidationCheck::Skipped));
+        assert!(matches!(
+            report.number_of_points(),
+            ValidationCheck::Skipped
+        ));
+        assert!(matches!(report.expanded_points(), ValidationCheck::Skipped));
+
+        // 第8節の終端のマーカーが壊れているファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let len = bytes.len();
+        bytes[len - 1] = b'6';
+        let report = validate_bytes(bytes);
+        assert!(report.section_lengths().is_passed());
+        assert!(matches!(report.end_marker(), ValidationCheck::Failed(_)));
+        assert!(report.number_of_points().is_passed());
+        assert!(matches!(report.expanded_points(), ValidationCheck::Skipped));
+
+        // 第0節に記録されたGRIB報全体のバイト数が、第8節までのバイト数より大きいファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let total_length = bytes.len() as u64 + 1;
+        bytes[8..16].copy_from_slice(&total_length.to_be_bytes());
+        let report = validate_bytes(bytes);
+        match report.section_lengths() {
+            ValidationCheck::Failed(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("GRIB報全体のバイト数"))
+            }
+            _ => panic!("GRIB報全体のバイト数が一致しないエラーを想定しています。"),
+        }
+        assert!(report.end_marker().is_passed());
+        assert!(matches!(report.expanded_points(), ValidationCheck::Skipped));
+
+        // 第5節の全資料点の数が第3節の資料点数と一致しないファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        // 第5節は、第6節（6バイト）、第7節（9バイト）及び第8節（4バイト）の前の23バイト
+        let section5 = bytes.len() - 4 - 9 - 6 - 23;
+        bytes[section5 + 5..section5 + 9].copy_from_slice(&5u32.to_be_bytes());
+        let report = validate_bytes(bytes);
+        assert!(report.section_lengths().is_passed());
+        assert!(report.end_marker().is_passed());
+        assert!(matches!(
+            report.number_of_points(),
+            ValidationCheck::Failed(_)
+        ));
+        assert!(matches!(report.expanded_points(), ValidationCheck::Skipped));
+
+        // ランレングス圧縮符号列を展開した格子の数が資料点数を超えるファイル
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 6]);
+        let report = validate_bytes(bytes);
+        assert!(report.number_of_points().is_passed());
+        assert!(matches!(
+            report.expanded_points(),
+            ValidationCheck::Failed(_)
+        ));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn validate_with_missing_file_err() {
+        let path = std::env::temp_dir().join("grib2_validate_missing_file.bin");
+        assert!(matches!(
+            PrrReader::validate(path),
+            Err(ReaderError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn values_end_at_last_grid_point() {
+        // i方向（経度方向）とj方向（緯度方向）の増分が異なる2行2列の格子
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let section3 = *reader.section3();
+        assert_ne!(
+            section3.i_direction_increment(),
+            section3.j_direction_increment()
+        );
+        let values = reader
+            .values_raw()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let first = values.first().unwrap();
+        assert_eq!(section3.lat_of_first_grid_point() as i32, first.lat_i32());
+        assert_eq!(section3.lon_of_first_grid_point(), first.lon_u32());
+        let last = values.last().unwrap();
+        assert_eq!(section3.lat_of_last_grid_point() as i32, last.lat_i32());
+        assert_eq!(section3.lon_of_last_grid_point(), last.lon_u32());
+    }
+
+    #[test]
+    fn values_are_independent_of_reader() {
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let values = reader.values().unwrap();
+        // イテレーターを保持したまま、リーダーを可変借用できる
+        let expected = reader
+            .values()
+            .unwrap()
+            .map(|value| value.unwrap().value())
+            .collect::<Vec<_>>();
+        drop(reader);
+
+        // イテレーターを別のスレッドに移動できる
+        let handle = std::thread::spawn(move || {
+            values
+                .map(|value| value.unwrap().value())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(expected, handle.join().unwrap());
+        assert_eq!(vec![Some(10), Some(20), Some(30), None], expected);
+
+        // 座標を整数で保持するイテレーターと、矩形領域のイテレーターも、リーダーを借用しない
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let raws = reader.values_raw().unwrap();
+        let within = reader.within(-90.0, -180.0, 90.0, 180.0).unwrap();
+        assert!(reader.cache_run_length().is_ok());
+        drop(reader);
+        assert_eq!(4, raws.count());
+        assert_eq!(4, within.count());
+    }
+
+    #[test]
+    fn values_with_empty_run_length_ok() {
+        // 第7節のランレングス圧縮符号列を削除して、データ長を0にした全欠測のファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let section7 = bytes.len() - 4 - 9;
+        bytes.drain(section7 + 5..section7 + 9);
+        bytes[section7..section7 + 4].copy_from_slice(&5u32.to_be_bytes());
+        let total_length = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_length.to_be_bytes());
+
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        assert_eq!(0, reader.section7().run_length_bytes());
+        let values = reader
+            .values()
+            .unwrap()
+            .map(|value| value.unwrap().value())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![None; 4], values);
+    }
+
+    #[test]
+    fn from_reader_with_inconsistent_total_length_err() {
+        // 第8節の後に0を埋めて、埋めたバイト数を第0節のGRIB報全体の長さに含めたファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        bytes.extend_from_slice(&[0x00; 8]);
+        let total_length = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_length.to_be_bytes());
+        match PrrReader::from_reader(Cursor::new(bytes)) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains(&format!("({})と一致しません", total_length)));
+            }
+            _ => panic!("GRIB報全体の長さが一致しないエラーを想定しています。"),
+        }
+
+        // 第7節のランレングス圧縮符号列を切り詰めて、第0節のGRIB報全体の長さを更新していない
+        // ファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let section7 = bytes.len() - 4 - 9;
+        bytes.drain(section7 + 7..section7 + 9);
+        bytes[section7..section7 + 4].copy_from_slice(&7u32.to_be_bytes());
+        match PrrReader::from_reader(Cursor::new(bytes)) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.starts_with("第8節までに読み込んだバイト数"));
+            }
+            _ => panic!("GRIB報全体の長さが一致しないエラーを想定しています。"),
+        }
+
+        // GRIB報の後に続くバイト列は、次のGRIB報の可能性があるため確認しない
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        bytes.extend_from_slice(&[0x00; 8]);
+        assert!(PrrReader::from_reader(Cursor::new(bytes)).is_ok());
+    }
+
+    #[test]
+    fn from_reader_with_other_disciplines_ok() {
+        // 陸面水文分野(1)及び海洋分野(10)の資料分野を記録したファイルも読み込める
+        for discipline in [1, 10] {
+            let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+            bytes[6] = discipline;
+            let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+            assert_eq!(discipline, reader.section0().discipline());
+            assert_eq!(4, reader.values().unwrap().count());
+        }
+    }
+
+    #[test]
+    fn from_reader_with_invalid_referenced_at_err() {
+        // 第1節の資料の参照時刻を2024年2月30日にしたファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        bytes[30] = 2;
+        bytes[31] = 30;
+        match PrrReader::from_reader(Cursor::new(bytes)) {
+            Err(ReaderError::InvalidDateTime {
+                year, month, day, ..
+            }) => assert_eq!((2024, 2, 30), (year, month, day)),
+            _ => panic!("日時が不正なエラーにならなければなりません。"),
+        }
+    }
+
+    #[test]
+    fn from_reader_with_section2_ok() {
+        // 第2節を省略したファイル
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let reader = PrrReader::from_reader(Cursor::new(bytes.clone())).unwrap();
+        assert!(reader.section2().bytes().is_empty());
+
+        // 第2節を記録したファイルでも、第3節以降の読み込み位置がずれない
+        let body = [0x4A, 0x4D, 0x41, 0x00, 0xFF];
+        let bytes = insert_section2(bytes, &body);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        assert_eq!(&body[..], reader.section2().bytes());
+        assert_eq!(4, reader.section3().number_of_data_points());
+        assert_eq!(36_004_167, reader.section3().lat_of_first_grid_point());
+        let levels = reader
+            .values()
+            .unwrap()
+            .map(|value| value.unwrap().level)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1, 2, 3, 0], levels);
+    }
+
+    #[test]
+    fn level_of_value_ok() {
+        // レベル別物理値が1.0、2.0及び3.0のファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let section5 = bytes.len() - 4 - 9 - 6 - 23;
+        bytes[section5 + 16] = 1;
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let expected = [
+            (0.99, None),
+            (1.0, Some(1)),
+            (1.5, Some(1)),
+            (1.99, Some(1)),
+            (2.0, Some(2)),
+            (2.99, Some(2)),
+            (3.0, Some(3)),
+            (3.01, None),
+            (f64::NAN, None),
+        ];
+        for (physical, level) in expected {
+            assert_eq!(level, reader.level_of_value(physical), "{}", physical);
+        }
+
+        // レベル別物理値が昇順に並んでいない場合
+        reader.level_values = Some(vec![10, 30, 20]);
+        assert_eq!(None, reader.level_of_value(2.0));
+
+        // 今回の圧縮に用いたレベルの最大値が2で、レベル別物理値が3つ記録されているファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 2, 0]);
+        let section5 = bytes.len() - 4 - 9 - 6 - 23;
+        bytes[section5 + 12..section5 + 14].copy_from_slice(&2u16.to_be_bytes());
+        bytes[section5 + 16] = 1;
+        let reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        assert_eq!(2, reader.section5().max_level_value());
+        assert_eq!(Some(2), reader.level_of_value(2.5));
+        assert_eq!(Some(3), reader.level_of_value(3.0));
+    }
+
+    #[test]
+    fn level_physical_values_ok() {
+        // 第5節のデータ代表値の尺度因子を0、1、2及び-1（符号と絶対値で0x81）にしたファイル
+        let expected = [
+            (0, vec![10.0, 20.0, 30.0]),
+            (1, vec![1.0, 2.0, 3.0]),
+            (2, vec![0.1, 0.2, 0.3]),
+            (0x81, vec![100.0, 200.0, 300.0]),
+        ];
+        for (decimal_scale_factor, expected) in expected {
+            let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+            let section5 = bytes.len() - 4 - 9 - 6 - 23;
+            bytes[section5 + 16] = decimal_scale_factor;
+            let reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+            assert_eq!(expected, reader.level_physical_values(false));
+
+            // レベル0（欠測）を含める場合は、添字とレベル値が一致する
+            let values = reader.level_physical_values(true);
+            assert_eq!(4, values.len());
+            assert!(values[0].is_nan());
+            assert_eq!(expected, values[1..]);
+        }
+    }
+
+    #[test]
+    fn subset_ok() {
+        // 2行2列の格子から、北の行のみを切り出す
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let message = reader.subset(36.0, 118.0, 36.01, 118.1).unwrap();
+        let section3 = message.section3().unwrap();
+        assert_eq!(2, section3.number_of_data_points());
+        assert_eq!((2, 1), {
+            let s = section3;
+            (
+                s.number_of_along_lat_points(),
+                s.number_of_along_lon_points(),
+            )
+        });
+        assert_eq!(36_004_167, section3.lat_of_last_grid_point());
+        assert_eq!(2, message.section5().unwrap().number_of_values());
+
+        // 書き出したGRIB報を読み込むと、矩形領域内の格子の値のみを返す
+        let mut written = vec![];
+        message.write_to(&mut written).unwrap();
+        let mut subset = PrrReader::from_reader(Cursor::new(written)).unwrap();
+        let values = subset
+            .values()
+            .unwrap()
+            .map(|value| value.unwrap())
+            .map(|value| (value.lat, value.lon, value.value()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (36.004167, 118.00625, Some(10)),
+                (36.004167, 118.01875, Some(20))
+            ],
+            values
+        );
+
+        // 南東の1格子のみを切り出す
+        let message = reader.subset(35.99, 118.01, 36.0, 118.02).unwrap();
+        let mut written = vec![];
+        message.write_to(&mut written).unwrap();
+        let mut subset = PrrReader::from_reader(Cursor::new(written)).unwrap();
+        assert_eq!(1, subset.section3().number_of_data_points());
+        let values = subset
+            .values()
+            .unwrap()
+            .map(|value| value.unwrap().value())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![None], values);
+    }
+
+    #[test]
+    fn subset_without_grid_points_err() {
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        match reader.subset(40.0, 130.0, 41.0, 131.0) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("格子が含まれていません"))
+            }
+            _ => panic!("矩形領域に格子が含まれていないエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn run_length_bytes_raw_ok() {
+        let levels = [1, 2, 3, 0];
+        let bytes = small_prr_bytes(36_004_167, levels);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let run_length = reader.run_length_bytes_raw().unwrap();
+        assert_eq!(reader.section7().run_length_bytes(), run_length.len());
+        assert_eq!(levels.to_vec(), run_length);
+        // ランレングス圧縮符号列を読み込んだ後も、値を走査できる
+        assert_eq!(4, reader.values().unwrap().count());
+    }
+
+    #[test]
+    fn cache_run_length_ok() {
+        let levels = [1, 2, 3, 0];
+        let bytes = small_prr_bytes(36_004_167, levels);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let expected = reader
+            .values()
+            .unwrap()
+            .map(|value| value.unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        // キャッシュしてから2回走査した結果が、キャッシュせずに走査した結果と一致する
+        reader.cache_run_length().unwrap();
+        for _ in 0..2 {
+            let values = reader
+                .values()
+                .unwrap()
+                .map(|value| value.unwrap().to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(expected, values);
+        }
+        assert_eq!(
+            4,
+            reader.values().unwrap().collect_parallel().unwrap().len()
+        );
+
+        // 破棄する前に構築したイテレーターは、破棄した後も走査できる
+        let iter = reader.values().unwrap();
+        reader.clear_run_length_cache();
+        assert_eq!(4, iter.count());
+    }
+
+    #[test]
+    fn from_reader_with_limits_rejects_too_large_section() {
+        // 第3節まで正常で、第4節の節の長さが過大なバイト列
+        let mut bytes = section0_to_section3_bytes(1_000);
+        bytes.extend_from_slice(&0x7FFF_FFFFu32.to_be_bytes());
+        bytes.push(4);
+
+        let result = PrrReader::from_reader_with_limits(Cursor::new(bytes), ReadLimits::default());
+        assert!(matches!(result, Err(ReaderError::Unexpected(_))));
+    }
+
+    #[test]
+    fn new_partial_returns_sections_before_failed_section() {
+        // 第3節まで正常で、第4節の節番号が不正なファイル
+        let mut bytes = section0_to_section3_bytes(1_000);
+        bytes.extend_from_slice(&34u32.to_be_bytes());
+        bytes.push(9);
+        bytes.extend_from_slice(&[0x00; 29]);
+        let path = TempPath::file(&bytes);
+
+        let (message, errors) = PrrReader::new_partial(&path);
+        let message = message.unwrap();
+        assert!(message.section0().is_some());
+        assert!(message.section1().is_some());
+        assert!(message.section2().is_some());
+        assert_eq!(
+            2_560,
+            message.section3().unwrap().number_of_along_lat_points()
+        );
+        assert!(message.section4().is_none());
+        assert!(message.section8().is_none());
+        match message.ensure_all_fields() {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("第4節:プロダクト定義節"));
+                assert!(!message.contains("第3節"));
+            }
+            _ => panic!("第4節以降の節を読み込めなかったエラーを想定しています。"),
+        }
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn new_partial_reads_same_sections_as_new() {
+        let bytes = insert_section2(small_prr_bytes(36_004_167, [1, 2, 3, 0]), &[0x01, 0x02]);
+        let path = TempPath::file(&bytes);
+        let reader = PrrReader::new(&path).unwrap();
+        let (message, errors) = PrrReader::new_partial(&path);
+        assert!(errors.is_empty());
+        let message = message.unwrap();
+        assert!(message.ensure_all_fields().is_ok());
+
+        let debug = |section: &dyn std::fmt::Debug| format!("{:?}", section);
+        assert_eq!(debug(reader.section0()), debug(message.section0().unwrap()));
+        assert_eq!(debug(reader.section1()), debug(message.section1().unwrap()));
+        assert_eq!(debug(reader.section2()), debug(message.section2().unwrap()));
+        assert_eq!(debug(reader.section3()), debug(message.section3().unwrap()));
+        assert_eq!(debug(reader.section4()), debug(message.section4().unwrap()));
+        assert_eq!(debug(reader.section5()), debug(message.section5().unwrap()));
+        assert_eq!(debug(reader.section7()), debug(message.section7().unwrap()));
+        assert_eq!(debug(reader.section8()), debug(message.section8().unwrap()));
+
+        // 全ての節を保持するGRIB報も、第0節から第3節を同じように読み込む
+        let sections = Grib2Sections::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(debug(reader.section0()), debug(sections.section0()));
+        assert_eq!(debug(reader.section1()), debug(sections.section1()));
+        assert_eq!(debug(reader.section2()), debug(sections.section2()));
+        assert_eq!(debug(reader.section3()), debug(sections.section3()));
+        match sections.section4().clone().downcast::<Template4_50008>() {
+            Some(section4) => assert_eq!(debug(reader.section4()), debug(&section4)),
+            None => panic!("テンプレート4.50008を想定しています。"),
+        }
+    }
+
+    #[test]
+    fn new_partial_rejects_section_over_limit() {
+        // 第7節の長さが、読み込みの上限（256MiB）を超えるファイル
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let section7 = bytes.len() - 4 - 9;
+        bytes[section7..section7 + 4].copy_from_slice(&0xFFFF_FFF0u32.to_be_bytes());
+        let path = TempPath::file(&bytes);
+        let (message, errors) = PrrReader::new_partial(&path);
+        let message = message.unwrap();
+        assert!(message.section6().is_some());
+        assert!(message.section7().is_none());
+        match &errors[..] {
+            [ReaderError::Unexpected(message)] => {
+                assert!(message.contains("第7節:節の長さ(4294967280)が上限"))
+            }
+            _ => panic!("第7節の長さが上限を超えるエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn new_partial_with_missing_file_none() {
+        let (message, errors) = PrrReader::new_partial("not_found.bin");
+        assert!(message.is_none());
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn validate_level_values_ok() {
+        assert!(validate_level_values(&[0, 10, 20, 30], 4, &[0, 5, 15, 25]).is_ok());
+        assert!(validate_level_values(&[0, 10, 20, 30], 3, &[0, 5, 15]).is_ok());
+        // 両方が全て0
+        assert!(validate_level_values(&[0, 0, 0], 3, &[0, 0, 0]).is_ok());
+    }
+
+    #[test]
+    fn validate_level_values_err() {
+        // レベル別物理値が不足
+        assert!(validate_level_values(&[0, 10, 20, 30], 4, &[0, 10, 20]).is_err());
+        // 昇順ではない
+        assert!(validate_level_values(&[0, 10, 20, 30], 4, &[0, 20, 10, 30]).is_err());
+        // 尺度因子が異なる
+        assert!(validate_level_values(&[0, 10, 20, 30], 4, &[0, 100, 200, 300]).is_err());
+        assert!(validate_level_values(&[0, 10, 20, 30], 4, &[0, 1, 2, 3]).is_err());
+        // 一方のみが全て0
+        assert!(validate_level_values(&[0, 10, 20, 30], 4, &[0, 0, 0, 0]).is_err());
+        assert!(validate_level_values(&[0, 0, 0, 0], 4, &[0, 10, 20, 30]).is_err());
+    }
+
+    #[test]
+    fn with_level_values_checked_compares_unit_with_parameter() {
+        // 第4節のパラメータを1時間降水量（カテゴリー1、番号200、mm/h）に変更
+        let mut bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let section4 = bytes.len() - 4 - 9 - 6 - 23 - 82;
+        bytes[section4 + 9..section4 + 11].copy_from_slice(&[1, 200]);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        let levels = reader.level_values().to_vec();
+        match reader.with_level_values_checked(levels.clone(), "mm") {
+            Err(ReaderError::Unexpected(message)) => assert!(message.contains("mm/h")),
+            _ => panic!("単位が一致しないエラーを想定しています。"),
+        }
+        reader
+            .with_level_values_checked(levels.clone(), "mm/h")
+            .unwrap();
+        assert_eq!(levels, reader.level_values());
+
+        // 第4節のパラメータが気温（カテゴリー0、番号0、K）の場合は、mm/hのテーブルで差し替えない
+        let bytes = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+        assert!(reader.with_level_values_checked(levels, "mm/h").is_err());
+    }
 }
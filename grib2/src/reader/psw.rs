@@ -2,9 +2,11 @@ use std::io::{Seek, SeekFrom};
 use std::{fs::File, path::Path};
 
 use super::sections::{
-    FromReader, PswSections, Section0, Section1, Section2, Section3_0, Section8,
+    impl_leading_section_getters, write_leading_sections_debug_info, FromReader, LeadingSections,
+    PswSections, Section0, Section1, Section2, Section3_0, Section8,
 };
-use super::{FileReader, Grib2ValueIter, PswTank, ReaderError, ReaderResult};
+use super::value_iter::RunLength;
+use super::{FileReader, Grib2ValueIter, PswTank, ReadLimits, ReaderError, ReaderResult};
 
 /// 土壌雨量指数値リーダー
 pub struct PswReader<P>
@@ -38,17 +40,38 @@ where
     ///
     /// 土壌雨量指数実況値リーダー
     pub fn new(path: P) -> ReaderResult<Self> {
+        Self::new_with_limits(path, ReadLimits::default())
+    }
+
+    /// ファイルパスと読み込みの上限を受け取り、土壌雨量指数実況値リーダーを返す。
+    ///
+    /// 第2節以降の節は、読み込む前に節の長さなどが上限以下であることを確認し、上限を超えている
+    /// 場合はメモリを確保せずにエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - GRIB2形式のファイルのパス
+    /// * `limits` - 読み込みの上限
+    ///
+    /// # 戻り値
+    ///
+    /// 土壌雨量指数実況値リーダー
+    pub fn new_with_limits(path: P, limits: ReadLimits) -> ReaderResult<Self> {
         let file =
-            File::open(path.as_ref()).map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            File::open(path.as_ref()).map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
-        let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2::from_reader(&mut reader)?;
-        let section3 = Section3_0::from_reader(&mut reader)?;
-        let all_tanks = PswSections::from_reader(&mut reader)?;
-        let first_tank = PswSections::from_reader(&mut reader)?;
-        let second_tank = PswSections::from_reader(&mut reader)?;
+        let LeadingSections {
+            section0,
+            section1,
+            section2,
+            section3,
+            boundary,
+        } = LeadingSections::from_reader_with_limits(&mut reader, &limits)?;
+        let all_tanks = PswSections::from_reader(&mut reader, &boundary, &limits)?;
+        let first_tank = PswSections::from_reader(&mut reader, &boundary, &limits)?;
+        let second_tank = PswSections::from_reader(&mut reader, &boundary, &limits)?;
         let section8 = Section8::from_reader(&mut reader)?;
+        boundary.validate_end(&mut reader)?;
 
         Ok(Self {
             path,
@@ -61,41 +84,7 @@ where
         })
     }
 
-    /// 第0節:指示節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第0節:指示節
-    pub fn section0(&self) -> &Section0 {
-        &self.section0
-    }
-
-    /// 第1節:識別節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第1節:識別節
-    pub fn section1(&self) -> &Section1 {
-        &self.section1
-    }
-
-    /// 第2節:地域使用節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第2節:地域使用節
-    pub fn section2(&self) -> &Section2 {
-        &self.section2
-    }
-
-    /// 第3節:格子系定義節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第3節:格子系定義節
-    pub fn section3(&self) -> &Section3_0 {
-        &self.section3
-    }
+    impl_leading_section_getters!();
 
     /// 全タンクを返す。
     ///
@@ -124,19 +113,10 @@ where
         &self.tanks[PswTank::Second as usize]
     }
 
-    /// 第8節:終端節を返す。
-    ///
-    /// # 戻り値
-    ///
-    /// 第8節:終端節
-    pub fn section8(&self) -> &Section8 {
-        &self.section8
-    }
-
-    fn value_iter(&mut self, tank: PswTank) -> ReaderResult<Grib2ValueIter<'_, u16>> {
+    fn value_iter(&mut self, tank: PswTank) -> ReaderResult<Grib2ValueIter<'static, u16>> {
         let value_sections = &self.tanks[tank as usize];
         let file = File::open(self.path.as_ref())
-            .map_err(|e| ReaderError::NotFount(e.to_string().into()))?;
+            .map_err(|e| ReaderError::NotFound(e.to_string().into()))?;
         let mut reader = FileReader::new(file);
         reader
             .seek(SeekFrom::Start(
@@ -147,7 +127,7 @@ where
             })?;
 
         Ok(Grib2ValueIter::new(
-            reader,
+            RunLength::reader(reader),
             value_sections.section7().run_length_bytes(),
             self.section3.number_of_data_points(),
             self.section3.lat_of_first_grid_point(),
@@ -155,10 +135,13 @@ where
             self.section3.lon_of_last_grid_point(),
             self.section3.j_direction_increment(),
             self.section3.i_direction_increment(),
+            self.section3.scanning_mode(),
             value_sections.section5().bits_per_value() as u16,
             value_sections.section5().max_level_value(),
+            value_sections.section5().decimal_scale_factor(),
             value_sections.section5().level_values(),
-        ))
+        )
+        .with_bitmap(value_sections.section6().shared_bitmap()))
     }
 
     /// 全タンクの値を返すイテレーターを返す。
@@ -166,7 +149,7 @@ where
     /// # 戻り値
     ///
     /// 全タンクの値を返すイテレーター
-    pub fn all_tanks_value_iter(&mut self) -> ReaderResult<Grib2ValueIter<'_, u16>> {
+    pub fn all_tanks_value_iter(&mut self) -> ReaderResult<Grib2ValueIter<'static, u16>> {
         self.value_iter(PswTank::All)
     }
 
@@ -175,7 +158,7 @@ where
     /// # 戻り値
     ///
     /// 第一タンクの値を返すイテレーター
-    pub fn first_tank_value_iter(&mut self) -> ReaderResult<Grib2ValueIter<'_, u16>> {
+    pub fn first_tank_value_iter(&mut self) -> ReaderResult<Grib2ValueIter<'static, u16>> {
         self.value_iter(PswTank::First)
     }
 
@@ -184,7 +167,7 @@ where
     /// # 戻り値
     ///
     /// 第二タンクの値を返すイテレーター
-    pub fn second_tank_value_iter(&mut self) -> ReaderResult<Grib2ValueIter<'_, u16>> {
+    pub fn second_tank_value_iter(&mut self) -> ReaderResult<Grib2ValueIter<'static, u16>> {
         self.value_iter(PswTank::Second)
     }
 
@@ -193,14 +176,13 @@ where
     where
         W: std::io::Write,
     {
-        self.section0.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section1.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section2.debug_info(writer)?;
-        writeln!(writer)?;
-        self.section3.debug_info(writer)?;
-        writeln!(writer)?;
+        write_leading_sections_debug_info(
+            writer,
+            &self.section0,
+            &self.section1,
+            &self.section2,
+            &self.section3,
+        )?;
         writeln!(writer, "全タンク:")?;
         self.all_tanks_sections().debug_info(writer)?;
         writeln!(writer)?;
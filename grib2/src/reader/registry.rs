@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+use super::{ReaderError, ReaderResult};
+
+/// テンプレートを読み込むデコーダが受け取るリーダー
+///
+/// デコーダはリーダーの型によらずに登録できるように、トレイトオブジェクトとしてリーダーを
+/// 受け取る。`Read`と`Seek`を実装する全ての型が実装する。
+pub trait ReadSeek: Read + Seek {}
+
+impl<R: Read + Seek> ReadSeek for R {}
+
+/// テンプレートを読み込むデコーダ
+///
+/// 引数のリーダーのファイルポインタは、テンプレートの開始位置にあることを想定している。
+pub type TemplateDecoder<T> = Box<dyn Fn(&mut dyn ReadSeek) -> ReaderResult<T>>;
+
+/// テンプレート番号とデコーダの対応を管理するレジストリ
+///
+/// 節を読み込むときに、節に記録されたテンプレート番号に対応するデコーダを解決して、
+/// テンプレートを読み込む。第4節は、`TemplateRegistry<AnyTemplate4>`に登録したデコーダで
+/// テンプレートの型を決めずに読み込める。
+///
+/// ```text
+/// let mut registry = TemplateRegistry::<MyTemplate3>::default();
+/// registry.register(30, Box::new(|mut reader| MyTemplate3::from_reader(&mut reader, 30)));
+/// let section3 = Section3::from_reader_with_registry(&mut reader, &registry)?;
+/// ```
+pub struct TemplateRegistry<T> {
+    /// テンプレート番号をキー、デコーダを値とするマップ
+    decoders: HashMap<u16, TemplateDecoder<T>>,
+}
+
+impl<T> Default for TemplateRegistry<T> {
+    fn default() -> Self {
+        Self {
+            decoders: HashMap::new(),
+        }
+    }
+}
+
+impl<T> TemplateRegistry<T> {
+    /// テンプレート番号にデコーダを登録する。
+    ///
+    /// 既にデコーダが登録されているテンプレート番号を指定した場合は、デコーダを置き換える。
+    ///
+    /// # 引数
+    ///
+    /// * `template_number` - テンプレート番号
+    /// * `decoder` - テンプレートを読み込むデコーダ
+    ///
+    /// # 戻り値
+    ///
+    /// レジストリ
+    pub fn register(&mut self, template_number: u16, decoder: TemplateDecoder<T>) -> &mut Self {
+        self.decoders.insert(template_number, decoder);
+
+        self
+    }
+
+    /// テンプレート番号にデコーダが登録されているか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `template_number` - テンプレート番号
+    ///
+    /// # 戻り値
+    ///
+    /// デコーダが登録されている場合は`true`
+    pub fn contains(&self, template_number: u16) -> bool {
+        self.decoders.contains_key(&template_number)
+    }
+
+    /// テンプレート番号に対応するデコーダを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `template_number` - テンプレート番号
+    ///
+    /// # 戻り値
+    ///
+    /// テンプレートを読み込むデコーダ
+    pub fn resolve(&self, template_number: u16) -> ReaderResult<&TemplateDecoder<T>> {
+        self.decoders.get(&template_number).ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!(
+                    "テンプレート番号{}のデコーダが登録されていません。",
+                    template_number
+                )
+                .into(),
+            )
+        })
+    }
+
+    /// テンプレート番号に対応するデコーダでテンプレートを読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - ファイルリーダー
+    /// * `template_number` - テンプレート番号
+    ///
+    /// # 戻り値
+    ///
+    /// テンプレート
+    pub fn decode<R: Read + Seek>(&self, reader: &mut R, template_number: u16) -> ReaderResult<T> {
+        let decoder = self.resolve(template_number)?;
+
+        decoder(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::TemplateRegistry;
+    use crate::reader::sections::{AnyTemplate4, Template3_0};
+    use crate::reader::ReaderError;
+
+    #[test]
+    fn register_and_resolve_ok() {
+        let mut registry = TemplateRegistry::<u8>::default();
+        registry.register(30, Box::new(|_| Ok(30)));
+        assert!(registry.contains(30));
+        assert!(registry.resolve(30).is_ok());
+    }
+
+    #[test]
+    fn resolve_unregistered_template_number_err() {
+        let registry = TemplateRegistry::<u8>::default();
+        assert!(!registry.contains(30));
+        assert!(matches!(
+            registry.resolve(30),
+            Err(ReaderError::Unexpected(_))
+        ));
+    }
+
+    #[test]
+    fn standard_registry_contains_standard_templates() {
+        let registry = TemplateRegistry::<Template3_0>::standard();
+        assert!(registry.contains(0));
+        assert!(!registry.contains(30));
+        let registry = TemplateRegistry::<AnyTemplate4>::standard();
+        for template_number in [0, 8, 50000, 50008, 50009] {
+            assert!(registry.contains(template_number));
+        }
+        assert!(!registry.contains(50010));
+    }
+
+    #[test]
+    fn decode_with_registered_decoder_ok() {
+        let mut registry = TemplateRegistry::<u16>::default();
+        registry.register(
+            30,
+            Box::new(|reader| {
+                let mut buf = [0; 2];
+                reader.read_exact(&mut buf).unwrap();
+                Ok(u16::from_be_bytes(buf))
+            }),
+        );
+        let mut reader = Cursor::new(vec![0x12, 0x34]);
+        assert_eq!(0x1234, registry.decode(&mut reader, 30).unwrap());
+        assert!(registry.decode(&mut reader, 40).is_err());
+    }
+}
@@ -1,30 +1,45 @@
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
+use std::sync::Arc;
 
-use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+use time::format_description::well_known::Rfc3339;
+use time::macros::offset;
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
 
-use super::{FileReader, ReaderError, ReaderResult};
-use macros::{Getter, SectionDebugInfo, TemplateDebugInfo};
+use super::{
+    gaussian_latitudes, parameter_name, GeneratingProcess, Grib2Message, GridDefinition, PswTank,
+    ReadLimits, ReaderError, ReaderResult, ReferenceTimeSignificance, StatProc, TemplateRegistry,
+};
+use macros::{Getter, SectionDebugInfo, TemplateDebugInfo, TemplateGetter};
 
 /// 第0節:GRIB版番号
 const EDITION_NUMBER: u8 = 2;
 
+/// 第0節:節の長さ（バイト）
+const SECTION0_BYTES: u64 = 16;
+
 /// 第1節:節の長さ（バイト）
 const SECTION1_BYTES: u32 = 21;
 
 /// 第3節:格子系定義テンプレート番号
 const LAT_LON_GRID_DEFINITION_TEMPLATE_NUMBER: u16 = 0; // 緯度・経度格子
+const GAUSSIAN_GRID_DEFINITION_TEMPLATE_NUMBER: u16 = 40; // ガウス緯度・経度格子
 
 /// 第４節:プロダクト定義テンプレート番号
-const DEFAULT_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 0; // デフォルト
-const PROCESSED_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 50000; // 他のプロダクトを元に加工・処理されたプロダクト
-const RADAR_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 50008; // レーダーなどに基づく解析プロダクト
-const RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 50009; // レーダーなどに基づく予測プロダクト
+pub(crate) const DEFAULT_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 0; // デフォルト
+pub(crate) const STAT_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 8; // ある時間区間における統計値
+pub(crate) const PROCESSED_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 50000; // 他のプロダクトを元に加工・処理されたプロダクト
+pub(crate) const RADAR_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 50008; // レーダーなどに基づく解析プロダクト
+pub(crate) const RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER: u16 = 50009; // レーダーなどに基づく予測プロダクト
 
 /// 第5節:資料表現テンプレート番号
 const RUN_LENGTH_DATA_REPRESENTATION_TEMPLATE_NUMBER: u16 = 200; // ランレングス圧縮
 
-/// 第6節:節の長さ（バイト）
-const SECTION6_BYTES: u32 = 6;
+/// 第6節:ビットマップを含まない節の長さ（バイト）
+const SECTION6_BYTES: usize = 6;
+
+/// 第6節:ビットマップ指示符
+const BITMAP_INDICATOR_PRESENT: u8 = 0; // ビットマップを第6節に記録
+const BITMAP_INDICATOR_NOT_APPLY: u8 = 255; // ビットマップを適用しない
 
 /// 第8節:終端のマーカー
 const SECTION8_END_MARKER: &str = "7777";
@@ -81,9 +96,12 @@ pub struct Section1 {
     type_of_processed_data: u8,
 }
 
-#[derive(Debug, Clone, Copy, SectionDebugInfo)]
+#[derive(Debug, Clone, SectionDebugInfo)]
 #[section(number = 2, name = "地域使用節")]
-pub struct Section2;
+pub struct Section2 {
+    /// 節番号の直後から節の末尾までのバイト列（節が省略されている場合は空）
+    bytes: Vec<u8>,
+}
 
 #[derive(Debug, Clone, Copy, Getter, SectionDebugInfo)]
 #[section(number = 3, name = "格子系定義節")]
@@ -173,6 +191,71 @@ pub struct Template3_0 {
     scanning_mode: u8,
 }
 
+/// テンプレート3.40
+///
+/// ガウス格子は、緯度が等間隔ではなく、極と赤道の間の緯線の数`N`から求めるガウス緯度に
+/// 格子点を配置する。南半球の緯度を表現するため、緯度は符号ありで保持する。
+#[derive(Debug, Clone, Copy, Getter, TemplateDebugInfo)]
+pub struct Template3_40 {
+    #[getter(ret = "val")]
+    #[debug_info(name = "地球の形状")]
+    shape_of_earth: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "地球球体の半径の尺度因子")]
+    scale_factor_of_radius_of_spherical_earth: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "地球球体の尺度付き半径")]
+    scaled_value_of_radius_of_spherical_earth: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "地球回転楕円体の長軸の尺度因子")]
+    scale_factor_of_earth_major_axis: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "地球回転楕円体の長軸の尺度付きの長さ")]
+    scaled_value_of_earth_major_axis: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "地球回転楕円体の短軸の尺度因子")]
+    scale_factor_of_earth_minor_axis: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "地球回転楕円体の短軸の尺度付きの長さ")]
+    scaled_value_of_earth_minor_axis: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "緯線に沿った格子点数")]
+    number_of_along_lat_points: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "経線に沿った格子点数")]
+    number_of_along_lon_points: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "原作成領域の基本角")]
+    basic_angle_of_initial_product_domain: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "端点の経度及び緯度並びに方向増分の定義に使われる基本角の細分")]
+    subdivisions_of_basic_angle: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "最初の格子点の緯度（10e-6度単位）")]
+    lat_of_first_grid_point: i32,
+    #[debug_info(name = "最初の格子点の経度（10e-6度単位）")]
+    #[getter(ret = "val")]
+    lon_of_first_grid_point: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "分解能及び成分フラグ")]
+    resolution_and_component_flags: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "最後の格子点の緯度（10e-6度単位）")]
+    lat_of_last_grid_point: i32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "最後の格子点の経度（10e-6度単位）")]
+    lon_of_last_grid_point: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "i方向（経度方向）の増分（10e-6度単位）")]
+    i_direction_increment: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "極と赤道の間の緯線の数")]
+    number_of_parallels_between_pole_and_equator: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "走査モード")]
+    scanning_mode: u8,
+}
+
 #[derive(Debug, Clone, Copy, Default, Getter, SectionDebugInfo)]
 #[section(number = 4, name = "プロダクト定義節")]
 pub struct Section4<T> {
@@ -221,22 +304,22 @@ pub struct Template4_0 {
     #[debug_info(name = "予報時間")]
     forecast_time: i32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の種類")]
+    #[debug_info(name = "第一固定面の種類", data_type = "missing")]
     type_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度因子")]
+    #[debug_info(name = "第一固定面の尺度因子", data_type = "missing")]
     scale_factor_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度付きの値")]
+    #[debug_info(name = "第一固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_first_fixed_surface: u32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の種類")]
+    #[debug_info(name = "第二固定面の種類", data_type = "missing")]
     type_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度因子")]
+    #[debug_info(name = "第二固定面の尺度因子", data_type = "missing")]
     scale_factor_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度付きの値")]
+    #[debug_info(name = "第二固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_second_fixed_surface: u32,
 }
 
@@ -271,22 +354,22 @@ pub struct Template4_50000 {
     #[debug_info(name = "予報時間")]
     forecast_time: i32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の種類")]
+    #[debug_info(name = "第一固定面の種類", data_type = "missing")]
     type_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度因子")]
+    #[debug_info(name = "第一固定面の尺度因子", data_type = "missing")]
     scale_factor_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度付きの値")]
+    #[debug_info(name = "第一固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_first_fixed_surface: u32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の種類")]
+    #[debug_info(name = "第二固定面の種類", data_type = "missing")]
     type_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度因子")]
+    #[debug_info(name = "第二固定面の尺度因子", data_type = "missing")]
     scale_factor_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度付きの値")]
+    #[debug_info(name = "第二固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_second_fixed_surface: u32,
     #[getter(ret = "val")]
     #[debug_info(name = "資料作成に用いた関連資料の名称1")]
@@ -308,9 +391,38 @@ pub struct Template4_50000 {
     minutes_from_source_document2: u8,
 }
 
-/// テンプレート4.50008
-#[derive(Debug, Clone, Copy, Getter, TemplateDebugInfo)]
-pub struct Template4_50008 {
+/// 統計処理の時間範囲の仕様
+///
+/// 統計処理したプロダクトのテンプレートは、統計を算出するために使用した時間間隔を記述する
+/// 期間の仕様の数だけ、この仕様を繰り返し記録する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Getter, TemplateDebugInfo)]
+pub struct TimeRangeSpec {
+    #[getter(ret = "val")]
+    #[debug_info(name = "統計処理の種類")]
+    type_of_stat_proc: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "統計処理の時間増分の種類")]
+    type_of_stat_proc_time_increment: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "統計処理の時間の単位の指示符")]
+    stat_proc_time_unit: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "統計処理した時間の長さ")]
+    stat_proc_time_length: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "連続的な資料場間の増分に関する時間の単位の指示符")]
+    successive_time_unit: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "連続的な資料場間の時間の増分")]
+    successive_time_increment: u32,
+}
+
+/// テンプレート4.8
+///
+/// 国際標準の、ある時間区間における統計値のテンプレートである。固定面の後に全時間間隔の終了時と
+/// 統計処理の時間範囲の仕様を記録する。
+#[derive(Debug, Clone, Getter, TemplateDebugInfo)]
+pub struct Template4_8 {
     #[getter(ret = "val")]
     #[debug_info(name = "パラメータカテゴリー")]
     parameter_category: u8,
@@ -339,22 +451,22 @@ pub struct Template4_50008 {
     #[debug_info(name = "予報時間")]
     forecast_time: i32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の種類")]
+    #[debug_info(name = "第一固定面の種類", data_type = "missing")]
     type_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度因子")]
+    #[debug_info(name = "第一固定面の尺度因子", data_type = "missing")]
     scale_factor_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度付きの値")]
+    #[debug_info(name = "第一固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_first_fixed_surface: u32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の種類")]
+    #[debug_info(name = "第二固定面の種類", data_type = "missing")]
     type_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度因子")]
+    #[debug_info(name = "第二固定面の尺度因子", data_type = "missing")]
     scale_factor_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度付きの値")]
+    #[debug_info(name = "第二固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_second_fixed_surface: u32,
     #[getter(ret = "val")]
     #[debug_info(name = "全時間間隔の終了時(UTC)")]
@@ -365,24 +477,71 @@ pub struct Template4_50008 {
     #[getter(ret = "val")]
     #[debug_info(name = "統計処理における欠測資料の総数")]
     number_of_missing_values: u32,
+    #[getter(ret = "ref", rty = "&[TimeRangeSpec]")]
+    #[debug_template(name = "統計処理の時間範囲の仕様")]
+    time_range_specs: Vec<TimeRangeSpec>,
+}
+
+/// テンプレート4.50008
+#[derive(Debug, Clone, Getter, TemplateDebugInfo)]
+pub struct Template4_50008 {
     #[getter(ret = "val")]
-    #[debug_info(name = "統計処理の種類")]
-    type_of_stat_proc: u8,
+    #[debug_info(name = "パラメータカテゴリー")]
+    parameter_category: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "統計処理の時間増分の種類")]
-    type_of_stat_proc_time_increment: u8,
+    #[debug_info(name = "パラメータ番号")]
+    parameter_number: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "統計処理の時間の単位の指示符")]
-    stat_proc_time_unit: u8,
+    #[debug_info(name = "作成処理の種類")]
+    type_of_generating_process: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "統計処理した時間の長さ")]
-    stat_proc_time_length: u32,
+    #[debug_info(name = "背景作成処理識別符")]
+    background_process: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "連続的な資料場間の増分に関する時間の単位の指示符")]
-    successive_time_unit: u8,
+    #[debug_info(name = "予報の作成処理識別符")]
+    generating_process_identifier: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "連続的な資料場間の時間の増分")]
-    successive_time_increment: u32,
+    #[debug_info(name = "観測資料の参照時刻からの締切時間（時）")]
+    hours_after_data_cutoff: u16,
+    #[getter(ret = "val")]
+    #[debug_info(name = "観測資料の参照時刻からの締切時間（分）")]
+    minutes_after_data_cutoff: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "期間の単位の指示符")]
+    indicator_of_unit_of_time_range: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "予報時間")]
+    forecast_time: i32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "第一固定面の種類", data_type = "missing")]
+    type_of_first_fixed_surface: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "第一固定面の尺度因子", data_type = "missing")]
+    scale_factor_of_first_fixed_surface: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "第一固定面の尺度付きの値", data_type = "missing")]
+    scaled_value_of_first_fixed_surface: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "第二固定面の種類", data_type = "missing")]
+    type_of_second_fixed_surface: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "第二固定面の尺度因子", data_type = "missing")]
+    scale_factor_of_second_fixed_surface: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "第二固定面の尺度付きの値", data_type = "missing")]
+    scaled_value_of_second_fixed_surface: u32,
+    #[getter(ret = "val")]
+    #[debug_info(name = "全時間間隔の終了時(UTC)")]
+    end_of_all_time_intervals: OffsetDateTime,
+    #[getter(ret = "val")]
+    #[debug_info(name = "統計を算出するために使用した時間間隔を記述する期間の仕様の数")]
+    number_of_time_range_specs: u8,
+    #[getter(ret = "val")]
+    #[debug_info(name = "統計処理における欠測資料の総数")]
+    number_of_missing_values: u32,
+    #[getter(ret = "ref", rty = "&[TimeRangeSpec]")]
+    #[debug_template(name = "統計処理の時間範囲の仕様")]
+    time_range_specs: Vec<TimeRangeSpec>,
     #[getter(ret = "val")]
     #[debug_info(name = "レーダー等運用情報その1", fmt = "0x{:08X}")]
     radar_info1: u64,
@@ -425,22 +584,22 @@ pub struct Template4_50009 {
     #[debug_info(name = "予報時間")]
     forecast_time: i32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の種類")]
+    #[debug_info(name = "第一固定面の種類", data_type = "missing")]
     type_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度因子")]
+    #[debug_info(name = "第一固定面の尺度因子", data_type = "missing")]
     scale_factor_of_first_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第一固定面の尺度付きの値")]
+    #[debug_info(name = "第一固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_first_fixed_surface: u32,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の種類")]
+    #[debug_info(name = "第二固定面の種類", data_type = "missing")]
     type_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度因子")]
+    #[debug_info(name = "第二固定面の尺度因子", data_type = "missing")]
     scale_factor_of_second_fixed_surface: u8,
     #[getter(ret = "val")]
-    #[debug_info(name = "第二固定面の尺度付きの値")]
+    #[debug_info(name = "第二固定面の尺度付きの値", data_type = "missing")]
     scaled_value_of_second_fixed_surface: u32,
     #[getter(ret = "val")]
     #[debug_info(name = "全時間間隔の終了時(UTC)")]
@@ -451,24 +610,9 @@ pub struct Template4_50009 {
     #[getter(ret = "val")]
     #[debug_info(name = "統計処理における欠測資料の総数")]
     number_of_missing_values: u32,
-    #[getter(ret = "val")]
-    #[debug_info(name = "統計処理の種類")]
-    type_of_stat_proc: u8,
-    #[getter(ret = "val")]
-    #[debug_info(name = "統計処理の時間増分の種類")]
-    type_of_stat_proc_time_increment: u8,
-    #[getter(ret = "val")]
-    #[debug_info(name = "統計処理の時間の単位の指示符")]
-    stat_proc_time_unit: u8,
-    #[getter(ret = "val")]
-    #[debug_info(name = "統計処理した時間の長さ")]
-    stat_proc_time_length: u32,
-    #[getter(ret = "val")]
-    #[debug_info(name = "連続的な資料場間の増分に関する時間の単位の指示符")]
-    successive_time_unit: u8,
-    #[getter(ret = "val")]
-    #[debug_info(name = "連続的な資料場間の時間の増分")]
-    successive_time_increment: u32,
+    #[getter(ret = "ref", rty = "&[TimeRangeSpec]")]
+    #[debug_template(name = "統計処理の時間範囲の仕様")]
+    time_range_specs: Vec<TimeRangeSpec>,
     #[getter(ret = "val")]
     #[debug_info(name = "レーダー等運用情報その1", fmt = "0x{:08X}")]
     radar_info1: u64,
@@ -516,8 +660,11 @@ pub struct Section5<T> {
 }
 
 /// テンプレート5.200
-#[derive(Debug, Clone, Getter, TemplateDebugInfo)]
-pub struct Template5_200u16 {
+///
+/// レベル値に対応するデータ代表値の型を`V`で指定する。データ代表値のバイト数は、`V`のバイト数に従う。
+#[derive(Debug, Clone, Getter, TemplateGetter, TemplateDebugInfo)]
+#[template_getter(section = "Section5", member = "template5")]
+pub struct Template5_200<V: LevelValue> {
     #[getter(ret = "val")]
     #[debug_info(name = "今回の圧縮に用いたレベルの最大値")]
     max_level_value: u16,
@@ -528,7 +675,7 @@ pub struct Template5_200u16 {
     #[debug_info(name = "データ代表値の尺度因子")]
     decimal_scale_factor: u8,
     /// レベル値と物理値(mm/h)の対応を格納するコレクション
-    #[getter(ret = "ref", rty = "&[u16]")]
+    #[getter(ret = "ref", rty = "&[V]")]
     #[debug_info(
         name = "レベルmに対応するデータ代表値",
         data_type = "serial",
@@ -536,34 +683,57 @@ pub struct Template5_200u16 {
         start = 1,
         fmt = "{}"
     )]
-    level_values: Vec<u16>,
+    level_values: Vec<V>,
 }
 
-/// テンプレート5.200
-#[derive(Debug, Clone, Getter, TemplateDebugInfo)]
-pub struct Template5_200i16 {
-    #[getter(ret = "val")]
-    #[debug_info(name = "今回の圧縮に用いたレベルの最大値")]
-    max_level_value: u16,
-    #[getter(ret = "val")]
-    #[debug_info(name = "データの取り得るレベルの最大値")]
-    number_of_level_values: u16,
-    #[getter(ret = "val")]
-    #[debug_info(name = "データ代表値の尺度因子")]
-    decimal_scale_factor: u8,
-    /// レベル値と物理値(mm/h)の対応を格納するコレクション
-    #[getter(ret = "ref", rty = "&[i16]")]
-    #[debug_info(
-        name = "レベルmに対応するデータ代表値",
-        data_type = "serial",
-        header = "レベル{}",
-        start = 1,
-        fmt = "{}"
-    )]
-    level_values: Vec<i16>,
+/// テンプレート5.200のレベル値に対応するデータ代表値の型
+pub trait LevelValue: Copy + std::fmt::Debug + std::fmt::Display {
+    /// データ代表値のバイト数
+    const BYTES: usize;
+
+    /// データ代表値を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - ファイルリーダ
+    /// * `name` - 読み込む値の名前
+    ///
+    /// # 戻り値
+    ///
+    /// データ代表値
+    fn read<R: Read + Seek>(reader: &mut R, name: &str) -> ReaderResult<Self>;
+
+    /// データ代表値を書き込む。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 書き込み先
+    /// * `name` - 書き込む値の名前
+    fn write<W: Write>(self, writer: &mut W, name: &str) -> ReaderResult<()>;
 }
 
-#[derive(Debug, Clone, Copy, Getter, SectionDebugInfo)]
+macro_rules! impl_level_value {
+    ($type:ty, $read:ident, $write:ident) => {
+        impl LevelValue for $type {
+            const BYTES: usize = std::mem::size_of::<$type>();
+
+            fn read<R: Read + Seek>(reader: &mut R, name: &str) -> ReaderResult<Self> {
+                $read(reader, name)
+            }
+
+            fn write<W: Write>(self, writer: &mut W, name: &str) -> ReaderResult<()> {
+                $write(writer, self, name)
+            }
+        }
+    };
+}
+
+impl_level_value!(u8, read_u8, write_u8);
+impl_level_value!(u16, read_u16, write_u16);
+impl_level_value!(u32, read_u32, write_u32);
+impl_level_value!(i16, read_i16, write_i16);
+
+#[derive(Debug, Clone, Getter, SectionDebugInfo)]
 #[section(number = 6, name = "ビットマップ節")]
 pub struct Section6 {
     #[getter(ret = "val")]
@@ -572,6 +742,8 @@ pub struct Section6 {
     #[getter(ret = "val")]
     #[debug_info(name = "ビットマップ指示符")]
     bitmap_indicator: u8,
+    /// ビットマップ（格子に資料値が存在する場合は`true`）
+    bitmap: Option<Arc<[bool]>>,
 }
 
 #[derive(Debug, Clone, Copy, Getter, SectionDebugInfo)]
@@ -620,6 +792,58 @@ macro_rules! validate_template_number {
     };
 }
 
+/// `logging`フィーチャーが有効な場合に、`log`クレートの`debug!`で節の読み込みの途中経過を
+/// 出力する文を展開するマクロ
+///
+/// `logging`フィーチャーが無効な場合は何も展開しないため、引数は評価されない。
+macro_rules! debug_log {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "logging")]
+        log::debug!($($arg)+)
+    };
+}
+
+/// `logging`フィーチャーが有効な場合に、`log`クレートの`trace!`で節の読み込みの途中経過を
+/// 出力する文を展開するマクロ
+///
+/// `logging`フィーチャーが無効な場合は何も展開しないため、引数は評価されない。
+macro_rules! trace_log {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "logging")]
+        log::trace!($($arg)+)
+    };
+}
+
+/// 標準テンプレートを登録したテンプレートレジストリを構築する関数を実装するマクロ
+macro_rules! impl_standard_registry {
+    ($template:ty, $template_number:ident) => {
+        impl TemplateRegistry<$template> {
+            /// 標準テンプレートを登録したテンプレートレジストリを構築する。
+            ///
+            /// # 戻り値
+            ///
+            /// テンプレートレジストリ
+            pub fn standard() -> Self {
+                let mut registry = Self::default();
+                registry.register(
+                    $template_number,
+                    Box::new(|mut reader| {
+                        <$template as TemplateFromReader<u16>>::from_reader(
+                            &mut reader,
+                            $template_number,
+                        )
+                    }),
+                );
+
+                registry
+            }
+        }
+    };
+}
+
+impl_standard_registry!(Template3_0, LAT_LON_GRID_DEFINITION_TEMPLATE_NUMBER);
+impl_standard_registry!(Template3_40, GAUSSIAN_GRID_DEFINITION_TEMPLATE_NUMBER);
+
 impl FromReader for Section0 {
     /// GRIB2ファイルから第0節:指示節を読み込む。
     ///
@@ -630,7 +854,7 @@ impl FromReader for Section0 {
     /// # 戻り値
     ///
     /// 第0節: 指示節
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
         // GRIB: 4バイト
         let grib = validate_str(reader, "第0節:GRIB", 4, "GRIB")?;
         // 保留: 2バイト
@@ -641,6 +865,11 @@ impl FromReader for Section0 {
         let edition_number = validate_u8(reader, EDITION_NUMBER, "第0節:GRIB版番号")?;
         // GRIB報全体の長さ: 8バイト
         let total_length = read_u64(reader, "第0節:GRIB報全体の長さ")? as usize;
+        debug_log!(
+            "第0節: 資料分野={}、GRIB報全体の長さ={}バイトを検出",
+            discipline,
+            total_length
+        );
 
         Ok(Self {
             grib,
@@ -662,7 +891,7 @@ impl FromReader for Section1 {
     /// # 戻り値
     ///
     /// 第1節: 識別節
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
         // 節の長さ: 4bytes
         let section_bytes = validate_u32(reader, SECTION1_BYTES, "第1節:節の長さ")? as usize;
         // 節番号
@@ -683,6 +912,7 @@ impl FromReader for Section1 {
         let production_status_of_processed_data = read_u8(reader, "第1節:作成ステータス")?;
         // 資料の種類
         let type_of_processed_data = read_u8(reader, "第1節:資料の種類")?;
+        debug_log!("第1節: 資料の参照時刻={}を検出", referenced_at);
 
         Ok(Self {
             section_bytes,
@@ -698,18 +928,85 @@ impl FromReader for Section1 {
     }
 }
 
+impl Section1 {
+    /// 資料の参照時刻を、日本標準時（UTC+09:00）で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 日本標準時の資料の参照時刻
+    pub fn referenced_at_jst(&self) -> OffsetDateTime {
+        self.referenced_at.to_offset(JST_OFFSET)
+    }
+
+    /// 参照時刻の意味を、符号表1.2の列挙型で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 参照時刻の意味。符号表に定義されていない値の場合はエラー
+    pub fn reference_time_significance(&self) -> ReaderResult<ReferenceTimeSignificance> {
+        ReferenceTimeSignificance::try_from(self.significance_of_reference_time).map_err(|e| {
+            ReaderError::Unexpected(
+                format!(
+                    "第1節:参照時刻の意味({})を変換できません。{}",
+                    self.significance_of_reference_time, e
+                )
+                .into(),
+            )
+        })
+    }
+}
+
 impl Section2 {
     /// GRIB2ファイルから第2節:地域使用節を読み込む。
     ///
+    /// 第2節は省略できるため、次の節の節番号が2の場合だけ、節の内容をバイト列として読み込む。
+    ///
     /// # 引数
     ///
-    /// * `_reader` - GRIB2ファイルリーダー
+    /// * `reader` - GRIB2ファイルリーダー
     ///
     /// # 戻り値
     ///
     /// 第2節:地域使用節
-    pub(crate) fn from_reader(_reader: &mut FileReader) -> ReaderResult<Self> {
-        Ok(Self)
+    pub(crate) fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        if peek_section_number(reader)? != 2 {
+            return Ok(Self { bytes: vec![] });
+        }
+        // 節の長さ: 4バイト
+        let section_bytes = read_u32(reader, "第2節:節の長さ")?;
+        // 節番号: 1バイト
+        validate_u8(reader, 2, "第2節:節番号")?;
+        // 地域使用: 節の長さ - 5バイト
+        let body_bytes = section_bytes.checked_sub(4 + 1).ok_or_else(|| {
+            ReaderError::Unexpected(format!("第2節:節の長さ({})が不正です。", section_bytes).into())
+        })?;
+        let mut bytes = vec![];
+        reader
+            .take(body_bytes as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|_| {
+                ReaderError::ReadError("第2節:地域使用の読み込みに失敗しました。".into())
+            })?;
+        if bytes.len() != body_bytes as usize {
+            return Err(ReaderError::ReadError(
+                format!(
+                    "第2節:地域使用({}バイト)の読み込みに失敗しました。",
+                    body_bytes
+                )
+                .into(),
+            ));
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// 節番号の直後から節の末尾までのバイト列を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 地域使用節のバイト列。節が省略されている場合は空
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
     }
 }
 
@@ -726,8 +1023,48 @@ where
     /// # 戻り値
     ///
     /// 第3節: 格子系定義節
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
-        // 節の長さ: 4バイト
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        Self::read_with(reader, <T as TemplateFromReader<u16>>::from_reader)
+    }
+}
+
+impl<T> Section3<T> {
+    /// テンプレート3を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// テンプレート3
+    pub fn template3(&self) -> &T {
+        &self.template3
+    }
+
+    /// GRIB2ファイルから第3節:格子系定義節を読み込む。
+    ///
+    /// テンプレート3は、格子系定義テンプレート番号に対応するレジストリのデコーダで読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `registry` - テンプレートレジストリ
+    ///
+    /// # 戻り値
+    ///
+    /// 第3節: 格子系定義節
+    pub fn from_reader_with_registry<R: Read + Seek>(
+        reader: &mut R,
+        registry: &TemplateRegistry<T>,
+    ) -> ReaderResult<Self> {
+        Self::read_with(reader, |reader, template_number| {
+            registry.decode(reader, template_number)
+        })
+    }
+
+    fn read_with<R: Read + Seek, F>(reader: &mut R, decode: F) -> ReaderResult<Self>
+    where
+        F: FnOnce(&mut R, u16) -> ReaderResult<T>,
+    {
+        let start = section_start(reader, "第3節")?;
+        // 節の長さ: 4バイト
         let section_bytes = read_u32(reader, "第3節:節の長さ")? as usize;
         // 節番号: 1バイト
         validate_u8(reader, 3, "第3節:節番号")?;
@@ -743,8 +1080,14 @@ where
             read_u8(reader, "第3節:格子点数を定義するリストの説明")?;
         // 格子系定義テンプレート番号: 2バイト
         let grid_definition_template_number = read_u16(reader, "第3節:格子系定義テンプレート番号")?;
+        debug_log!(
+            "第3節: 格子系定義テンプレート番号={}を検出",
+            grid_definition_template_number
+        );
         // テンプレート3
-        let template3 = T::from_reader(reader, grid_definition_template_number)?;
+        let template3 = decode(reader, grid_definition_template_number)?;
+        // 未知のフィールドがある場合は節の末尾まで読み飛ばす
+        skip_to_section_end(reader, start, section_bytes, "第3節")?;
 
         Ok(Self {
             section_bytes,
@@ -759,7 +1102,7 @@ where
 }
 
 impl TemplateFromReader<u16> for Template3_0 {
-    fn from_reader(reader: &mut FileReader, template_number: u16) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
         // 格子系定義テンプレート番号を確認
         validate_template_number!(
             "第3節:格子系定義テンプレート番号",
@@ -836,11 +1179,176 @@ impl TemplateFromReader<u16> for Template3_0 {
     }
 }
 
+impl TemplateFromReader<u16> for Template3_40 {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
+        // 格子系定義テンプレート番号を確認
+        validate_template_number!(
+            "第3節:格子系定義テンプレート番号",
+            template_number,
+            GAUSSIAN_GRID_DEFINITION_TEMPLATE_NUMBER
+        );
+        // 地球の形状: 1バイト
+        let shape_of_earth = read_u8(reader, "第3節:地球の形状")?;
+        // 地球球体の半径の尺度因子: 1バイト
+        let scale_factor_of_radius_of_spherical_earth =
+            read_u8(reader, "第3節:地球球体の半径の尺度因子")?;
+        // 地球球体の尺度付き半径: 4バイト
+        let scaled_value_of_radius_of_spherical_earth =
+            read_u32(reader, "第3節:地球球体の尺度付き半径")?;
+        // 地球回転楕円体の長軸の尺度因子: 1バイト
+        let scale_factor_of_earth_major_axis =
+            read_u8(reader, "第3節:地球回転楕円体の長軸の尺度因子")?;
+        // 地球回転楕円体の長軸の尺度付きの長さ: 4バイト
+        let scaled_value_of_earth_major_axis =
+            read_u32(reader, "第3節:地球回転楕円体の長軸の尺度付きの長さ")?;
+        // 地球回転楕円体の短軸の尺度因子: 1バイト
+        let scale_factor_of_earth_minor_axis =
+            read_u8(reader, "第3節:地球回転楕円体の短軸の尺度因子")?;
+        // 地球回転楕円体の短軸の尺度付きの長さ: 4バイト
+        let scaled_value_of_earth_minor_axis =
+            read_u32(reader, "第3節:地球回転楕円体の短軸の尺度付きの長さ")?;
+        // 緯線に沿った格子点数: 4バイト
+        let number_of_along_lat_points = read_u32(reader, "第3節:緯線に沿った格子点数")?;
+        // 経線に沿った格子点数: 4バイト
+        let number_of_along_lon_points = read_u32(reader, "第3節:経線に沿った格子点数")?;
+        // 原作成領域の基本角: 4バイト
+        let basic_angle_of_initial_product_domain = read_u32(reader, "第3節:原作成領域の基本角")?;
+        // 端点の経度及び緯度並びに方向増分の定義に使われる基本角の細分: 4バイト
+        let subdivisions_of_basic_angle =
+            read_u32(reader, "第3節:端点の経度及び緯度並びに方向増分の定義")?;
+        // 最初の格子点の緯度（10e-6度単位）: 4バイト
+        let lat_of_first_grid_point = read_i32(reader, "第3節:最初の格子点の緯度")?;
+        // 最初の格子点の経度（10e-6度単位）: 4バイト
+        let lon_of_first_grid_point = read_u32(reader, "第3節:最初の格子点の経度")?;
+        // 分解能及び成分フラグ: 1バイト
+        let resolution_and_component_flags = read_u8(reader, "第3節:分解能及び成分フラグ")?;
+        // 最後の格子点の緯度（10e-6度単位）: 4バイト
+        let lat_of_last_grid_point = read_i32(reader, "第3節:最後の格子点の緯度")?;
+        // 最後の格子点の経度（10e-6度単位）: 4バイト
+        let lon_of_last_grid_point = read_u32(reader, "第3節:最後の格子点の経度")?;
+        // i方向（経度方向）の増分（10e-6度単位）: 4バイト
+        let i_direction_increment = read_u32(reader, "第3節:i方向の増分")?;
+        // 極と赤道の間の緯線の数: 4バイト
+        let number_of_parallels_between_pole_and_equator =
+            read_u32(reader, "第3節:極と赤道の間の緯線の数")?;
+        // 走査モード: 1バイト
+        let scanning_mode = read_u8(reader, "第3節:走査モード")?;
+
+        Ok(Self {
+            shape_of_earth,
+            scale_factor_of_radius_of_spherical_earth,
+            scaled_value_of_radius_of_spherical_earth,
+            scale_factor_of_earth_major_axis,
+            scaled_value_of_earth_major_axis,
+            scale_factor_of_earth_minor_axis,
+            scaled_value_of_earth_minor_axis,
+            number_of_along_lat_points,
+            number_of_along_lon_points,
+            basic_angle_of_initial_product_domain,
+            subdivisions_of_basic_angle,
+            lat_of_first_grid_point,
+            lon_of_first_grid_point,
+            resolution_and_component_flags,
+            lat_of_last_grid_point,
+            lon_of_last_grid_point,
+            i_direction_increment,
+            number_of_parallels_between_pole_and_equator,
+            scanning_mode,
+        })
+    }
+}
+
+impl Template3_40 {
+    /// 格子の行ごとの緯度を、北の行から順に返す。
+    ///
+    /// 極と赤道の間の緯線の数`N`から全球の`2N`本のガウス緯度を計算し、最初と最後の格子点の緯度の
+    /// 範囲に含まれる緯度を取り出す。領域を切り出したガウス格子の場合も、行ごとの緯度を返す。
+    ///
+    /// 最初と最後の格子点の緯度は、ガウス緯度を丸めて記録されているため、ガウス緯度の最小の間隔の
+    /// 半分を許容誤差として範囲を広げて比較する。
+    ///
+    /// # 戻り値
+    ///
+    /// 行ごとの緯度（度単位）。取り出した緯度の数が経線に沿った格子点数と一致しない場合はエラー
+    pub fn latitudes(&self) -> ReaderResult<Vec<f64>> {
+        let north = self
+            .lat_of_first_grid_point
+            .max(self.lat_of_last_grid_point) as f64
+            / 1_000_000.0;
+        let south = self
+            .lat_of_first_grid_point
+            .min(self.lat_of_last_grid_point) as f64
+            / 1_000_000.0;
+        let gaussian = gaussian_latitudes(self.number_of_parallels_between_pole_and_equator);
+        let tolerance = gaussian
+            .windows(2)
+            .map(|w| w[0] - w[1])
+            .fold(f64::INFINITY, f64::min)
+            / 2.0;
+        let latitudes = gaussian
+            .into_iter()
+            .filter(|&lat| south - tolerance < lat && lat < north + tolerance)
+            .collect::<Vec<_>>();
+        if latitudes.len() != self.number_of_along_lon_points as usize {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "第3節:最初と最後の格子点の間のガウス緯度の数({})が、経線に沿った格子点数({})と一致しません。",
+                    latitudes.len(),
+                    self.number_of_along_lon_points
+                )
+                .into(),
+            ));
+        }
+
+        Ok(latitudes)
+    }
+}
+
 impl<T> FromReader for Section4<T>
 where
     T: TemplateFromReader<u16>,
 {
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        Self::read_with(reader, <T as TemplateFromReader<u16>>::from_reader)
+    }
+}
+
+impl<T> Section4<T> {
+    /// テンプレート4を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// テンプレート4
+    pub fn template4(&self) -> &T {
+        &self.template4
+    }
+
+    /// GRIB2ファイルから第4節:プロダクト定義節を読み込む。
+    ///
+    /// テンプレート4は、プロダクト定義テンプレート番号に対応するレジストリのデコーダで読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `registry` - テンプレートレジストリ
+    ///
+    /// # 戻り値
+    ///
+    /// 第4節: プロダクト定義節
+    pub fn from_reader_with_registry<R: Read + Seek>(
+        reader: &mut R,
+        registry: &TemplateRegistry<T>,
+    ) -> ReaderResult<Self> {
+        Self::read_with(reader, |reader, template_number| {
+            registry.decode(reader, template_number)
+        })
+    }
+
+    fn read_with<R: Read + Seek, F>(reader: &mut R, decode: F) -> ReaderResult<Self>
+    where
+        F: FnOnce(&mut R, u16) -> ReaderResult<T>,
+    {
+        let start = section_start(reader, "第4節")?;
         // 節の長さ: 4バイト
         let section_bytes = read_u32(reader, "第4節:節の長さ")? as usize;
         // 節番号: 1バイト
@@ -851,8 +1359,15 @@ where
         // プロダクト定義テンプレート番号: 2バイト
         let product_definition_template_number =
             read_u16(reader, "第4節:プロダクト定義テンプレート番号")?;
+        debug_log!(
+            "第4節: プロダクト定義テンプレート番号={}を検出",
+            product_definition_template_number
+        );
         // テンプレート4
-        let template4 = T::from_reader(reader, product_definition_template_number)?;
+        let template4 = decode(reader, product_definition_template_number)?;
+        // テンプレートを読み込んだバイト数が節の長さを超えていないか確認して、未知のフィールドが
+        // ある場合は節の末尾まで読み飛ばす
+        skip_to_section_end(reader, start, section_bytes, "第4節")?;
 
         Ok(Self {
             section_bytes,
@@ -864,47 +1379,31 @@ where
 }
 
 impl TemplateFromReader<u16> for Template4_0 {
-    fn from_reader(reader: &mut FileReader, template_number: u16) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
         // プロダクト定義テンプレート番号を確認
         validate_template_number!(
             "第4節:プロダクト定義テンプレート番号",
             template_number,
             DEFAULT_PRODUCT_DEFINITION_TEMPLATE_NUMBER
         );
-        // パラメータカテゴリー: 1バイト
-        let parameter_category = read_u8(reader, "第4節:パラメータカテゴリー")?;
-        // パラメータ番号: 1バイト
-        let parameter_number = read_u8(reader, "第4節:パラメータ番号")?;
-        // 作成処理の種類: 1バイト
-        let type_of_generating_process = read_u8(reader, "第4節:作成処理の種類")?;
-        // 背景作成処理識別符: 1バイト
-        let background_process = read_u8(reader, "第4節:背景作成処理識別符")?;
-        // 予報の作成処理識別符: 1バイト
-        let generating_process_identifier = read_u8(reader, "第4節:予報の作成処理識別符")?;
-        // 観測資料の参照時刻からの締切時間（時）: 2バイト
-        let hours_after_data_cutoff =
-            read_u16(reader, "第4節:観測資料の参照時刻からの締切時間（時）")?;
-        // 観測資料の参照時刻からの締切時間（分）: 1バイト
-        let minutes_after_data_cutoff =
-            read_u8(reader, "第4節:観測資料の参照時刻からの締切時間（分）")?;
-        // 期間の単位の指示符: 1バイト
-        let indicator_of_unit_of_time_range = read_u8(reader, "第4節:期間の単位の指示符")?;
-        // 予報時間: 4バイト
-        let forecast_time = read_i32(reader, "第4節:予報時間")?;
-        // 第一固定面の種類: 1バイト
-        let type_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の種類")?;
-        // 第一固定面の尺度因子: 1バイト
-        let scale_factor_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の尺度因子")?;
-        // 第一固定面の尺度付きの値: 4バイト
-        let scaled_value_of_first_fixed_surface =
-            read_u32(reader, "第4節:第一固定面の尺度付きの値")?;
-        // 第二固定面の種類: 1バイト
-        let type_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の種類")?;
-        // 第二固定面の尺度因子: 1バイト
-        let scale_factor_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の尺度因子")?;
-        // 第二固定面の尺度付きの値: 4バイト
-        let scaled_value_of_second_fixed_surface =
-            read_u32(reader, "第4節:第二固定面の尺度付きの値")?;
+        // パラメータカテゴリーから第二固定面の尺度付きの値まで
+        let ProductHeader {
+            parameter_category,
+            parameter_number,
+            type_of_generating_process,
+            background_process,
+            generating_process_identifier,
+            hours_after_data_cutoff,
+            minutes_after_data_cutoff,
+            indicator_of_unit_of_time_range,
+            forecast_time,
+            type_of_first_fixed_surface,
+            scale_factor_of_first_fixed_surface,
+            scaled_value_of_first_fixed_surface,
+            type_of_second_fixed_surface,
+            scale_factor_of_second_fixed_surface,
+            scaled_value_of_second_fixed_surface,
+        } = read_product_header(reader)?;
 
         Ok(Self {
             parameter_category,
@@ -927,47 +1426,31 @@ impl TemplateFromReader<u16> for Template4_0 {
 }
 
 impl TemplateFromReader<u16> for Template4_50000 {
-    fn from_reader(reader: &mut FileReader, template_number: u16) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
         // プロダクト定義テンプレート番号を確認
         validate_template_number!(
             "第4節:プロダクト定義テンプレート番号",
             template_number,
             PROCESSED_PRODUCT_DEFINITION_TEMPLATE_NUMBER
         );
-        // パラメータカテゴリー: 1バイト
-        let parameter_category = read_u8(reader, "第4節:パラメータカテゴリー")?;
-        // パラメータ番号: 1バイト
-        let parameter_number = read_u8(reader, "第4節:パラメータ番号")?;
-        // 作成処理の種類: 1バイト
-        let type_of_generating_process = read_u8(reader, "第4節:作成処理の種類")?;
-        // 背景作成処理識別符: 1バイト
-        let background_process = read_u8(reader, "第4節:背景作成処理識別符")?;
-        // 解析又は予報の作成処理識別符: 1バイト
-        let generating_process_identifier = read_u8(reader, "第4節:解析又は予報の作成処理識別符")?;
-        // 観測資料の参照時刻からの締切時間（時）: 2バイト
-        let hours_after_data_cutoff =
-            read_u16(reader, "第4節:観測資料の参照時刻からの締切時間（時）")?;
-        // 観測資料の参照時刻からの締切時間（分）: 1バイト
-        let minutes_after_data_cutoff =
-            read_u8(reader, "第4節:観測資料の参照時刻からの締切時間（分）")?;
-        // 期間の単位の指示符: 1バイト
-        let indicator_of_unit_of_time_range = read_u8(reader, "第4節:期間の単位の指示符")?;
-        // 予報時間: 4バイト
-        let forecast_time = read_i32(reader, "第4節:予報時間")?;
-        // 第一固定面の種類: 1バイト
-        let type_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の種類")?;
-        // 第一固定面の尺度因子: 1バイト
-        let scale_factor_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の尺度因子")?;
-        // 第一固定面の尺度付きの値: 4バイト
-        let scaled_value_of_first_fixed_surface =
-            read_u32(reader, "第4節:第一固定面の尺度付きの値")?;
-        // 第二固定面の種類: 1バイト
-        let type_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の種類")?;
-        // 第二固定面の尺度因子: 1バイト
-        let scale_factor_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の尺度因子")?;
-        // 第二固定面の尺度付きの値: 4バイト
-        let scaled_value_of_second_fixed_surface =
-            read_u32(reader, "第4節:第二固定面の尺度付きの値")?;
+        // パラメータカテゴリーから第二固定面の尺度付きの値まで
+        let ProductHeader {
+            parameter_category,
+            parameter_number,
+            type_of_generating_process,
+            background_process,
+            generating_process_identifier,
+            hours_after_data_cutoff,
+            minutes_after_data_cutoff,
+            indicator_of_unit_of_time_range,
+            forecast_time,
+            type_of_first_fixed_surface,
+            scale_factor_of_first_fixed_surface,
+            scaled_value_of_first_fixed_surface,
+            type_of_second_fixed_surface,
+            scale_factor_of_second_fixed_surface,
+            scaled_value_of_second_fixed_surface,
+        } = read_product_header(reader)?;
         // 資料作成に用いた関連資料の名称1: 1バイト
         let source_document1 = read_u8(reader, "第4節:資料作成に用いた関連資料の名称1")?;
         // 上記関連資料の解析時刻と参照時刻との差（時）1: 2バイト
@@ -1011,78 +1494,39 @@ impl TemplateFromReader<u16> for Template4_50000 {
     }
 }
 
-impl TemplateFromReader<u16> for Template4_50008 {
-    fn from_reader(reader: &mut FileReader, template_number: u16) -> ReaderResult<Self> {
+impl TemplateFromReader<u16> for Template4_8 {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
         // プロダクト定義テンプレート番号を確認
         validate_template_number!(
             "第4節:プロダクト定義テンプレート番号",
             template_number,
-            RADAR_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+            STAT_PRODUCT_DEFINITION_TEMPLATE_NUMBER
         );
-        // パラメータカテゴリー: 1バイト
-        let parameter_category = read_u8(reader, "第4節:パラメータカテゴリー")?;
-        // パラメータ番号: 1バイト
-        let parameter_number = read_u8(reader, "第4節:パラメータ番号")?;
-        // 作成処理の種類: 1バイト
-        let type_of_generating_process = read_u8(reader, "第4節:作成処理の種類")?;
-        // 背景作成処理識別符: 1バイト
-        let background_process = read_u8(reader, "第4節:背景作成処理識別符")?;
-        // 予報の作成処理識別符: 1バイト
-        let generating_process_identifier = read_u8(reader, "第4節:予報の作成処理識別符")?;
-        // 観測資料の参照時刻からの締切時間（時）: 2バイト
-        let hours_after_data_cutoff =
-            read_u16(reader, "第4節:観測資料の参照時刻からの締切時間（時）")?;
-        // 観測資料の参照時刻からの締切時間（分）: 1バイト
-        let minutes_after_data_cutoff =
-            read_u8(reader, "第4節:観測資料の参照時刻からの締切時間（分）")?;
-        // 期間の単位の指示符: 1バイト
-        let indicator_of_unit_of_time_range = read_u8(reader, "第4節:期間の単位の指示符")?;
-        // 予報時間: 4バイト
-        let forecast_time = read_i32(reader, "第4節:予報時間")?;
-        // 第一固定面の種類: 1バイト
-        let type_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の種類")?;
-        // 第一固定面の尺度因子: 1バイト
-        let scale_factor_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の尺度因子")?;
-        // 第一固定面の尺度付きの値: 4バイト
-        let scaled_value_of_first_fixed_surface =
-            read_u32(reader, "第4節:第一固定面の尺度付きの値")?;
-        // 第二固定面の種類: 1バイト
-        let type_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の種類")?;
-        // 第二固定面の尺度因子: 1バイト
-        let scale_factor_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の尺度因子")?;
-        // 第二固定面の尺度付きの値: 4バイト
-        let scaled_value_of_second_fixed_surface =
-            read_u32(reader, "第4節:第二固定面の尺度付きの値")?;
-        // 全時間間隔の終了時: 7バイト
-        let end_of_all_time_intervals = read_datetime(reader, "第4節:全時間間隔の終了時")?;
-        // 統計を算出するために使用した時間間隔を記述する期間の仕様の数: 1バイト
-        let number_of_time_range_specs = read_u8(
-            reader,
-            "第4節:統計を算出するために使用した時間間隔を記述する期間の仕様の数",
-        )?;
-        // 統計処理における欠測資料の総数: 4バイト
-        let number_of_missing_values = read_u32(reader, "第4節:統計処理における欠測資料の総数")?;
-        // 統計処理の種類: 1バイト
-        let type_of_stat_proc = read_u8(reader, "第4節:統計処理の種類")?;
-        // 統計処理の時間増分の種類: 1バイト
-        let type_of_stat_proc_time_increment = read_u8(reader, "第4節:統計処理の時間増分の種類")?;
-        // 統計処理の時間の単位の指示符: 1バイト
-        let stat_proc_time_unit = read_u8(reader, "第4節:統計処理の時間の単位の指示符")?;
-        // 統計処理した期間の長さ: 4バイト
-        let stat_proc_time_length = read_u32(reader, "第4節:統計処理の時間増分の長さ")?;
-        // 連続的な資料場間の増分に関する時間の単位の指示符: 1バイト
-        let successive_time_unit = read_u8(
-            reader,
-            "第4節:連続的な資料場間の増分に関する時間の単位の指示符",
-        )?;
-        // 連続的な資料場間の時間の増分: 4バイト
-        let successive_time_increment = read_u32(reader, "第4節:連続的な資料場間の時間の増分")?;
-        // レーダー等運用情報その1: 8バイト
-        let radar_info1 = read_u64(reader, "第4節:レーダー等運用情報その1")?;
-        // レーダー等運用情報その2: 8バイト
-        let radar_info2 = read_u64(reader, "第4節:レーダー等運用情報その2")?;
-        // 雨量計運用情報: 8バイト
-        let rain_gauge_info = read_u64(reader, "第4節:雨量計運用情報の読み込みに失敗しました。")?;
+        // パラメータカテゴリーから第二固定面の尺度付きの値まで
+        let ProductHeader {
+            parameter_category,
+            parameter_number,
+            type_of_generating_process,
+            background_process,
+            generating_process_identifier,
+            hours_after_data_cutoff,
+            minutes_after_data_cutoff,
+            indicator_of_unit_of_time_range,
+            forecast_time,
+            type_of_first_fixed_surface,
+            scale_factor_of_first_fixed_surface,
+            scaled_value_of_first_fixed_surface,
+            type_of_second_fixed_surface,
+            scale_factor_of_second_fixed_surface,
+            scaled_value_of_second_fixed_surface,
+        } = read_product_header(reader)?;
+        // 全時間間隔の終了時から連続的な資料場間の時間の増分まで
+        let StatPeriod {
+            end_of_all_time_intervals,
+            number_of_time_range_specs,
+            number_of_missing_values,
+            time_range_specs,
+        } = read_stat_period(reader)?;
 
         Ok(Self {
             parameter_category,
@@ -1103,103 +1547,50 @@ impl TemplateFromReader<u16> for Template4_50008 {
             end_of_all_time_intervals,
             number_of_time_range_specs,
             number_of_missing_values,
-            type_of_stat_proc,
-            type_of_stat_proc_time_increment,
-            stat_proc_time_unit,
-            stat_proc_time_length,
-            successive_time_unit,
-            successive_time_increment,
-            radar_info1,
-            radar_info2,
-            rain_gauge_info,
+            time_range_specs,
         })
     }
 }
 
-impl TemplateFromReader<u16> for Template4_50009 {
-    fn from_reader(reader: &mut FileReader, template_number: u16) -> ReaderResult<Self> {
+impl TemplateFromReader<u16> for Template4_50008 {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
         // プロダクト定義テンプレート番号を確認
         validate_template_number!(
             "第4節:プロダクト定義テンプレート番号",
             template_number,
-            RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+            RADAR_PRODUCT_DEFINITION_TEMPLATE_NUMBER
         );
-        // パラメータカテゴリー: 1バイト
-        let parameter_category = read_u8(reader, "第4節:パラメータカテゴリー")?;
-        // パラメータ番号: 1バイト
-        let parameter_number = read_u8(reader, "第4節:パラメータ番号")?;
-        // 作成処理の種類: 1バイト
-        let type_of_generating_process = read_u8(reader, "第4節:作成処理の種類")?;
-        // 背景作成処理識別符: 1バイト
-        let background_process = read_u8(reader, "第4節:背景作成処理識別符")?;
-        // 予報の作成処理識別符: 1バイト
-        let generating_process_identifier = read_u8(reader, "第4節:予報の作成処理識別符")?;
-        // 観測資料の参照時刻からの締切時間（時）: 2バイト
-        let hours_after_data_cutoff =
-            read_u16(reader, "第4節:観測資料の参照時刻からの締切時間（時）")?;
-        // 観測資料の参照時刻からの締切時間（分）: 1バイト
-        let minutes_after_data_cutoff =
-            read_u8(reader, "第4節:観測資料の参照時刻からの締切時間（分）")?;
-        // 期間の単位の指示符: 1バイト
-        let indicator_of_unit_of_time_range = read_u8(reader, "第4節:期間の単位の指示符")?;
-        // 予報時間: 4バイト
-        let forecast_time = read_i32(reader, "第4節:予報時間")?;
-        // 第一固定面の種類: 1バイト
-        let type_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の種類")?;
-        // 第一固定面の尺度因子: 1バイト
-        let scale_factor_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の尺度因子")?;
-        // 第一固定面の尺度付きの値: 4バイト
-        let scaled_value_of_first_fixed_surface =
-            read_u32(reader, "第4節:第一固定面の尺度付きの値")?;
-        // 第二固定面の種類: 1バイト
-        let type_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の種類")?;
-        // 第二固定面の尺度因子: 1バイト
-        let scale_factor_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の尺度因子")?;
-        // 第二固定面の尺度付きの値: 4バイト
-        let scaled_value_of_second_fixed_surface =
-            read_u32(reader, "第4節:第二固定面の尺度付きの値")?;
-        // 全時間間隔の終了時: 7バイト
-        let end_of_all_time_intervals = read_datetime(reader, "第4節:全時間間隔の終了時")?;
-        // 統計を算出するために使用した時間間隔を記述する期間の仕様の数: 1バイト
-        let number_of_time_range_specs = read_u8(
-            reader,
-            "第4節:統計を算出するために使用した時間間隔を記述する期間の仕様の数",
-        )?;
-        // 統計処理における欠測資料の総数: 4バイト
-        let number_of_missing_values = read_u32(reader, "第4節:統計処理における欠測資料の総数")?;
-        // 統計処理の種類: 1バイト
-        let type_of_stat_proc = read_u8(reader, "第4節:統計処理の種類")?;
-        // 統計処理の時間増分の種類: 1バイト
-        let type_of_stat_proc_time_increment = read_u8(reader, "第4節:統計処理の時間増分の種類")?;
-        // 統計処理の時間の単位の指示符: 1バイト
-        let stat_proc_time_unit = read_u8(reader, "第4節:統計処理の時間の単位の指示符")?;
-        // 統計処理した期間の長さ: 4バイト
-        let stat_proc_time_length = read_u32(reader, "第4節:統計処理の時間増分の長さ")?;
-        // 連続的な資料場間の増分に関する時間の単位の指示符: 1バイト
-        let successive_time_unit = read_u8(
-            reader,
-            "第4節:連続的な資料場間の増分に関する時間の単位の指示符",
-        )?;
-        // 連続的な資料場間の時間の増分: 4バイト
-        let successive_time_increment = read_u32(reader, "第4節:連続的な資料場間の時間の増分")?;
+        // パラメータカテゴリーから第二固定面の尺度付きの値まで
+        let ProductHeader {
+            parameter_category,
+            parameter_number,
+            type_of_generating_process,
+            background_process,
+            generating_process_identifier,
+            hours_after_data_cutoff,
+            minutes_after_data_cutoff,
+            indicator_of_unit_of_time_range,
+            forecast_time,
+            type_of_first_fixed_surface,
+            scale_factor_of_first_fixed_surface,
+            scaled_value_of_first_fixed_surface,
+            type_of_second_fixed_surface,
+            scale_factor_of_second_fixed_surface,
+            scaled_value_of_second_fixed_surface,
+        } = read_product_header(reader)?;
+        // 全時間間隔の終了時から連続的な資料場間の時間の増分まで
+        let StatPeriod {
+            end_of_all_time_intervals,
+            number_of_time_range_specs,
+            number_of_missing_values,
+            time_range_specs,
+        } = read_stat_period(reader)?;
         // レーダー等運用情報その1: 8バイト
         let radar_info1 = read_u64(reader, "第4節:レーダー等運用情報その1")?;
         // レーダー等運用情報その2: 8バイト
         let radar_info2 = read_u64(reader, "第4節:レーダー等運用情報その2")?;
         // 雨量計運用情報: 8バイト
         let rain_gauge_info = read_u64(reader, "第4節:雨量計運用情報の読み込みに失敗しました。")?;
-        // メソモデル予想値の結合比率の計算領域数
-        let number_of_calculation_areas =
-            read_u16(reader, "メソモデル予想値の結合比率の計算領域数")?;
-        // メソモデル予想値の結合比率の尺度因子
-        let scale_factor_of_combined_ratio =
-            read_u8(reader, "メソモデル予想値の結合比率の尺度因子")?;
-        // 各領域のメソモデル予想値の結合比率
-        let mut combined_ratios_of_forecast_areas = vec![];
-        for _ in 0..number_of_calculation_areas {
-            combined_ratios_of_forecast_areas
-                .push(read_u16(reader, "各領域のメソモデル予想値の結合比率")?);
-        }
 
         Ok(Self {
             parameter_category,
@@ -1220,42 +1611,268 @@ impl TemplateFromReader<u16> for Template4_50009 {
             end_of_all_time_intervals,
             number_of_time_range_specs,
             number_of_missing_values,
-            type_of_stat_proc,
-            type_of_stat_proc_time_increment,
-            stat_proc_time_unit,
-            stat_proc_time_length,
-            successive_time_unit,
-            successive_time_increment,
+            time_range_specs,
             radar_info1,
             radar_info2,
             rain_gauge_info,
-            number_of_calculation_areas,
-            scale_factor_of_combined_ratio,
-            combined_ratios_of_forecast_areas,
         })
     }
 }
 
-impl<T> FromReader for Section5<T>
-where
-    T: TemplateFromReaderWithSize<u16>,
-{
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
-        // 節の長さ: 4バイト
-        let section_bytes = read_u32(reader, "第5節:節の長さ")? as usize;
-        // 節番号: 1バイト
-        validate_u8(reader, 5, "第5節:節番号")?;
-        // 全資料点の数: 4バイト
-        let number_of_values = read_u32(reader, "第5節:全資料点の数")?;
-        // 資料表現テンプレート番号: 2バイト
+impl TemplateFromReader<u16> for Template4_50009 {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
+        // プロダクト定義テンプレート番号を確認
+        validate_template_number!(
+            "第4節:プロダクト定義テンプレート番号",
+            template_number,
+            RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+        );
+        // パラメータカテゴリーから第二固定面の尺度付きの値まで
+        let ProductHeader {
+            parameter_category,
+            parameter_number,
+            type_of_generating_process,
+            background_process,
+            generating_process_identifier,
+            hours_after_data_cutoff,
+            minutes_after_data_cutoff,
+            indicator_of_unit_of_time_range,
+            forecast_time,
+            type_of_first_fixed_surface,
+            scale_factor_of_first_fixed_surface,
+            scaled_value_of_first_fixed_surface,
+            type_of_second_fixed_surface,
+            scale_factor_of_second_fixed_surface,
+            scaled_value_of_second_fixed_surface,
+        } = read_product_header(reader)?;
+        // 全時間間隔の終了時から連続的な資料場間の時間の増分まで
+        let StatPeriod {
+            end_of_all_time_intervals,
+            number_of_time_range_specs,
+            number_of_missing_values,
+            time_range_specs,
+        } = read_stat_period(reader)?;
+        // レーダー等運用情報その1: 8バイト
+        let radar_info1 = read_u64(reader, "第4節:レーダー等運用情報その1")?;
+        // レーダー等運用情報その2: 8バイト
+        let radar_info2 = read_u64(reader, "第4節:レーダー等運用情報その2")?;
+        // 雨量計運用情報: 8バイト
+        let rain_gauge_info = read_u64(reader, "第4節:雨量計運用情報の読み込みに失敗しました。")?;
+        // メソモデル予想値の結合比率の計算領域数
+        let number_of_calculation_areas =
+            read_u16(reader, "メソモデル予想値の結合比率の計算領域数")?;
+        // メソモデル予想値の結合比率の尺度因子
+        let scale_factor_of_combined_ratio =
+            read_u8(reader, "メソモデル予想値の結合比率の尺度因子")?;
+        // 各領域のメソモデル予想値の結合比率
+        let mut combined_ratios_of_forecast_areas = vec![];
+        for _ in 0..number_of_calculation_areas {
+            combined_ratios_of_forecast_areas
+                .push(read_u16(reader, "各領域のメソモデル予想値の結合比率")?);
+        }
+
+        Ok(Self {
+            parameter_category,
+            parameter_number,
+            type_of_generating_process,
+            background_process,
+            generating_process_identifier,
+            hours_after_data_cutoff,
+            minutes_after_data_cutoff,
+            indicator_of_unit_of_time_range,
+            forecast_time,
+            type_of_first_fixed_surface,
+            scale_factor_of_first_fixed_surface,
+            scaled_value_of_first_fixed_surface,
+            type_of_second_fixed_surface,
+            scale_factor_of_second_fixed_surface,
+            scaled_value_of_second_fixed_surface,
+            end_of_all_time_intervals,
+            number_of_time_range_specs,
+            number_of_missing_values,
+            time_range_specs,
+            radar_info1,
+            radar_info2,
+            rain_gauge_info,
+            number_of_calculation_areas,
+            scale_factor_of_combined_ratio,
+            combined_ratios_of_forecast_areas,
+        })
+    }
+}
+
+/// テンプレート4の各テンプレートに共通する、パラメータカテゴリーから第二固定面の尺度付きの値までの項目
+struct ProductHeader {
+    parameter_category: u8,
+    parameter_number: u8,
+    type_of_generating_process: u8,
+    background_process: u8,
+    generating_process_identifier: u8,
+    hours_after_data_cutoff: u16,
+    minutes_after_data_cutoff: u8,
+    indicator_of_unit_of_time_range: u8,
+    forecast_time: i32,
+    type_of_first_fixed_surface: u8,
+    scale_factor_of_first_fixed_surface: u8,
+    scaled_value_of_first_fixed_surface: u32,
+    type_of_second_fixed_surface: u8,
+    scale_factor_of_second_fixed_surface: u8,
+    scaled_value_of_second_fixed_surface: u32,
+}
+
+/// テンプレート4の各テンプレートに共通する項目を読み込む。
+fn read_product_header<R: Read + Seek>(reader: &mut R) -> ReaderResult<ProductHeader> {
+    // パラメータカテゴリー: 1バイト
+    let parameter_category = read_u8(reader, "第4節:パラメータカテゴリー")?;
+    // パラメータ番号: 1バイト
+    let parameter_number = read_u8(reader, "第4節:パラメータ番号")?;
+    // 作成処理の種類: 1バイト
+    let type_of_generating_process = read_u8(reader, "第4節:作成処理の種類")?;
+    // 背景作成処理識別符: 1バイト
+    let background_process = read_u8(reader, "第4節:背景作成処理識別符")?;
+    // 予報の作成処理識別符: 1バイト
+    let generating_process_identifier = read_u8(reader, "第4節:予報の作成処理識別符")?;
+    // 観測資料の参照時刻からの締切時間（時）: 2バイト
+    let hours_after_data_cutoff = read_u16(reader, "第4節:観測資料の参照時刻からの締切時間（時）")?;
+    // 観測資料の参照時刻からの締切時間（分）: 1バイト
+    let minutes_after_data_cutoff =
+        read_u8(reader, "第4節:観測資料の参照時刻からの締切時間（分）")?;
+    // 期間の単位の指示符: 1バイト
+    let indicator_of_unit_of_time_range = read_u8(reader, "第4節:期間の単位の指示符")?;
+    // 予報時間: 4バイト
+    let forecast_time = read_i32(reader, "第4節:予報時間")?;
+    // 第一固定面の種類: 1バイト
+    let type_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の種類")?;
+    // 第一固定面の尺度因子: 1バイト
+    let scale_factor_of_first_fixed_surface = read_u8(reader, "第4節:第一固定面の尺度因子")?;
+    // 第一固定面の尺度付きの値: 4バイト
+    let scaled_value_of_first_fixed_surface = read_u32(reader, "第4節:第一固定面の尺度付きの値")?;
+    // 第二固定面の種類: 1バイト
+    let type_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の種類")?;
+    // 第二固定面の尺度因子: 1バイト
+    let scale_factor_of_second_fixed_surface = read_u8(reader, "第4節:第二固定面の尺度因子")?;
+    // 第二固定面の尺度付きの値: 4バイト
+    let scaled_value_of_second_fixed_surface = read_u32(reader, "第4節:第二固定面の尺度付きの値")?;
+
+    Ok(ProductHeader {
+        parameter_category,
+        parameter_number,
+        type_of_generating_process,
+        background_process,
+        generating_process_identifier,
+        hours_after_data_cutoff,
+        minutes_after_data_cutoff,
+        indicator_of_unit_of_time_range,
+        forecast_time,
+        type_of_first_fixed_surface,
+        scale_factor_of_first_fixed_surface,
+        scaled_value_of_first_fixed_surface,
+        type_of_second_fixed_surface,
+        scale_factor_of_second_fixed_surface,
+        scaled_value_of_second_fixed_surface,
+    })
+}
+
+/// 統計処理したプロダクトのテンプレートに共通する、全時間間隔の終了時から連続的な資料場間の
+/// 時間の増分までの項目
+struct StatPeriod {
+    end_of_all_time_intervals: OffsetDateTime,
+    number_of_time_range_specs: u8,
+    number_of_missing_values: u32,
+    time_range_specs: Vec<TimeRangeSpec>,
+}
+
+/// 統計処理したプロダクトのテンプレートに共通する項目を読み込む。
+fn read_stat_period<R: Read + Seek>(reader: &mut R) -> ReaderResult<StatPeriod> {
+    // 全時間間隔の終了時: 7バイト
+    let end_of_all_time_intervals = read_datetime(reader, "第4節:全時間間隔の終了時")?;
+    // 統計を算出するために使用した時間間隔を記述する期間の仕様の数: 1バイト
+    let number_of_time_range_specs = read_u8(
+        reader,
+        "第4節:統計を算出するために使用した時間間隔を記述する期間の仕様の数",
+    )?;
+    if number_of_time_range_specs == 0 {
+        return Err(ReaderError::Unexpected(
+            "第4節:統計を算出するために使用した時間間隔を記述する期間の仕様の数が0です。".into(),
+        ));
+    }
+    // 統計処理における欠測資料の総数: 4バイト
+    let number_of_missing_values = read_u32(reader, "第4節:統計処理における欠測資料の総数")?;
+    // 統計処理の時間範囲の仕様: 12バイト × 期間の仕様の数
+    let time_range_specs = (0..number_of_time_range_specs)
+        .map(|_| read_time_range_spec(reader))
+        .collect::<ReaderResult<Vec<_>>>()?;
+
+    Ok(StatPeriod {
+        end_of_all_time_intervals,
+        number_of_time_range_specs,
+        number_of_missing_values,
+        time_range_specs,
+    })
+}
+
+/// 統計処理の時間範囲の仕様を読み込む。
+fn read_time_range_spec<R: Read + Seek>(reader: &mut R) -> ReaderResult<TimeRangeSpec> {
+    // 統計処理の種類: 1バイト
+    let type_of_stat_proc = read_u8(reader, "第4節:統計処理の種類")?;
+    // 統計処理の時間増分の種類: 1バイト
+    let type_of_stat_proc_time_increment = read_u8(reader, "第4節:統計処理の時間増分の種類")?;
+    // 統計処理の時間の単位の指示符: 1バイト
+    let stat_proc_time_unit = read_u8(reader, "第4節:統計処理の時間の単位の指示符")?;
+    // 統計処理した期間の長さ: 4バイト
+    let stat_proc_time_length = read_u32(reader, "第4節:統計処理の時間増分の長さ")?;
+    // 連続的な資料場間の増分に関する時間の単位の指示符: 1バイト
+    let successive_time_unit = read_u8(
+        reader,
+        "第4節:連続的な資料場間の増分に関する時間の単位の指示符",
+    )?;
+    // 連続的な資料場間の時間の増分: 4バイト
+    let successive_time_increment = read_u32(reader, "第4節:連続的な資料場間の時間の増分")?;
+
+    Ok(TimeRangeSpec {
+        type_of_stat_proc,
+        type_of_stat_proc_time_increment,
+        stat_proc_time_unit,
+        stat_proc_time_length,
+        successive_time_unit,
+        successive_time_increment,
+    })
+}
+
+impl<T> FromReader for Section5<T>
+where
+    T: TemplateFromReaderWithSize<u16>,
+{
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        let start = section_start(reader, "第5節")?;
+        // 節の長さ: 4バイト
+        let section_bytes = read_u32(reader, "第5節:節の長さ")? as usize;
+        // 節番号: 1バイト
+        validate_u8(reader, 5, "第5節:節番号")?;
+        // 全資料点の数: 4バイト
+        let number_of_values = read_u32(reader, "第5節:全資料点の数")?;
+        // 資料表現テンプレート番号: 2バイト
         let data_representation_template_number =
             read_u16(reader, "第5節:資料表現テンプレート番号")?;
         // 1データのビット数: 1バイト
         let bits_per_value = read_u8(reader, "第5節:1データのビット数")?;
+        debug_log!(
+            "第5節: 資料表現テンプレート番号={}、全資料点の数={}を検出",
+            data_representation_template_number,
+            number_of_values
+        );
         // テンプレート5
-        let template_bytes = section_bytes - (4 + 1 + 4 + 2 + 1);
+        let template_bytes = section_bytes
+            .checked_sub(4 + 1 + 4 + 2 + 1)
+            .ok_or_else(|| {
+                ReaderError::Unexpected(
+                    format!("第5節:節の長さ({})が不正です。", section_bytes).into(),
+                )
+            })?;
         let template5 =
             T::from_reader(reader, data_representation_template_number, template_bytes)?;
+        // 未知のフィールドがある場合は節の末尾まで読み飛ばす
+        skip_to_section_end(reader, start, section_bytes, "第5節")?;
 
         Ok(Self {
             section_bytes,
@@ -1267,43 +1884,80 @@ where
     }
 }
 
-impl TemplateFromReaderWithSize<u16> for Template5_200u16 {
-    fn from_reader(
-        reader: &mut FileReader,
-        template_number: u16,
-        template_bytes: usize,
-    ) -> ReaderResult<Self> {
-        // 資料表現テンプレート番号を確認
-        validate_template_number!(
-            "第5節:資料表現テンプレート番号",
-            template_number,
-            RUN_LENGTH_DATA_REPRESENTATION_TEMPLATE_NUMBER
-        );
-        // 今回の圧縮に用いたレベルの最大値: 2バイト
-        let max_level_value = read_u16(reader, "第5節:今回の圧縮に用いたレベルの最大値")?;
-        // データの取り得るレベルの最大値: 2バイト
-        let number_of_level_values = read_u16(reader, "第5節:レベルの最大値")?;
-        // データ代表値の尺度因子: 1バイト
-        let decimal_scale_factor = read_u8(reader, "第5節:データ代表値の尺度因子")?;
-        // レベルmに対応するデータ代表値
-        let number_of_levels = (template_bytes - (2 + 2 + 1)) / 2;
-        let mut level_values = Vec::new();
-        for _ in 0..number_of_levels {
-            level_values.push(read_u16(reader, "第5節:レベルmに対応するデータ代表値")?);
+impl<V> Section5<Template5_200<V>>
+where
+    V: LevelValue + PartialOrd,
+{
+    /// レベル別物理値の数がデータの取り得るレベルの最大値と一致し、レベル別物理値が単調非減少で
+    /// あるか確認する。
+    ///
+    /// 破損したファイルでは、レベル番号が上がるとレベル別物理値が減少する場合がある。
+    ///
+    /// # 戻り値
+    ///
+    /// レベル別物理値の数が一致して単調非減少の場合は`()`、数が一致しない場合は両者の数を含む
+    /// エラー、減少している場合は減少したレベル番号と値を含むエラー
+    pub fn validate_levels(&self) -> ReaderResult<()> {
+        let level_values = &self.template5.level_values;
+        let number_of_level_values = self.template5.number_of_level_values;
+        if level_values.len() != number_of_level_values as usize {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "第5節:記録されているレベル別物理値の数({})が、データの取り得るレベルの最大値({})と一致しません。",
+                    level_values.len(),
+                    number_of_level_values
+                )
+                .into(),
+            ));
+        }
+        match level_values.windows(2).position(|w| w[1] < w[0]) {
+            Some(i) => Err(ReaderError::Unexpected(
+                format!(
+                    "第5節:レベル{}の物理値({})が、レベル{}の物理値({})より小さく、単調非減少ではありません。",
+                    i + 2,
+                    level_values[i + 1],
+                    i + 1,
+                    level_values[i]
+                )
+                .into(),
+            )),
+            None => Ok(()),
         }
+    }
+}
 
-        Ok(Self {
-            max_level_value,
-            number_of_level_values,
-            decimal_scale_factor,
-            level_values,
-        })
+impl<V> Section5<Template5_200<V>>
+where
+    V: LevelValue,
+{
+    /// 全資料点の数と今回の圧縮に用いたレベルの最大値を差し替えた第5節を返す。
+    ///
+    /// 領域を切り出したGRIB報を構築するときに使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `number_of_values` - 全資料点の数
+    /// * `max_level_value` - 今回の圧縮に用いたレベルの最大値
+    ///
+    /// # 戻り値
+    ///
+    /// 全資料点の数と今回の圧縮に用いたレベルの最大値を差し替えた第5節
+    pub(crate) fn with_number_of_values(
+        &self,
+        number_of_values: u32,
+        max_level_value: u16,
+    ) -> Self {
+        let mut section5 = self.clone();
+        section5.number_of_values = number_of_values;
+        section5.template5.max_level_value = max_level_value;
+
+        section5
     }
 }
 
-impl TemplateFromReaderWithSize<u16> for Template5_200i16 {
-    fn from_reader(
-        reader: &mut FileReader,
+impl<V: LevelValue> TemplateFromReaderWithSize<u16> for Template5_200<V> {
+    fn from_reader<R: Read + Seek>(
+        reader: &mut R,
         template_number: u16,
         template_bytes: usize,
     ) -> ReaderResult<Self> {
@@ -1319,11 +1973,20 @@ impl TemplateFromReaderWithSize<u16> for Template5_200i16 {
         let number_of_level_values = read_u16(reader, "第5節:レベルの最大値")?;
         // データ代表値の尺度因子: 1バイト
         let decimal_scale_factor = read_u8(reader, "第5節:データ代表値の尺度因子")?;
-        // レベルmに対応するデータ代表値
-        let number_of_levels = (template_bytes - (2 + 2 + 1)) / 2;
+        // レベルmに対応するデータ代表値: Vのバイト数
+        let level_bytes = template_bytes.checked_sub(2 + 2 + 1).ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!(
+                    "第5節:テンプレート5.200のバイト数({})が不正です。",
+                    template_bytes
+                )
+                .into(),
+            )
+        })?;
+        let number_of_levels = level_bytes / V::BYTES;
         let mut level_values = Vec::new();
         for _ in 0..number_of_levels {
-            level_values.push(read_i16(reader, "第5節:レベルmに対応するデータ代表値")?);
+            level_values.push(V::read(reader, "第5節:レベルmに対応するデータ代表値")?);
         }
 
         Ok(Self {
@@ -1336,32 +1999,94 @@ impl TemplateFromReaderWithSize<u16> for Template5_200i16 {
 }
 
 impl FromReader for Section6 {
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
         // 節の長さ: 4バイト
-        let section_bytes = validate_u32(reader, SECTION6_BYTES, "第6節:節の長さ")? as usize;
+        let section_bytes = read_u32(reader, "第6節:節の長さ")? as usize;
         // 節番号: 1バイト
         validate_u8(reader, 6, "第6節:節番号")?;
         // ビットマップ指示符: 1バイト
         let bitmap_indicator = read_u8(reader, "第6節:ビットマップ指示符")?;
+        debug_log!("第6節: ビットマップ指示符={}を検出", bitmap_indicator);
+        // ビットマップ
+        let bitmap = match bitmap_indicator {
+            BITMAP_INDICATOR_PRESENT => {
+                let bitmap_bytes = section_bytes.checked_sub(SECTION6_BYTES).ok_or_else(|| {
+                    ReaderError::Unexpected(
+                        format!("第6節:節の長さ({})が不正です。", section_bytes).into(),
+                    )
+                })?;
+                let mut bitmap = Vec::with_capacity(bitmap_bytes * 8);
+                for _ in 0..bitmap_bytes {
+                    let octet = read_u8(reader, "第6節:ビットマップ")?;
+                    bitmap.extend((0..8).rev().map(|bit| octet & (1 << bit) != 0));
+                }
+                Some(bitmap.into())
+            }
+            BITMAP_INDICATOR_NOT_APPLY => {
+                if section_bytes != SECTION6_BYTES {
+                    return Err(ReaderError::Unexpected(
+                        format!(
+                            "第6節:節の長さの値は{}でしたが、{}でなければなりません。",
+                            section_bytes, SECTION6_BYTES
+                        )
+                        .into(),
+                    ));
+                }
+                None
+            }
+            _ => {
+                return Err(ReaderError::Unexpected(
+                    format!(
+                        "第6節:ビットマップ指示符({})に対応していません。",
+                        bitmap_indicator
+                    )
+                    .into(),
+                ));
+            }
+        };
 
         Ok(Self {
             section_bytes,
             bitmap_indicator,
+            bitmap,
         })
     }
 }
 
+impl Section6 {
+    /// ビットマップを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 格子に資料値が存在する場合に`true`を走査順に格納したビットマップ。ビットマップを適用しない
+    /// 場合は`None`
+    pub fn bitmap(&self) -> Option<&[bool]> {
+        self.bitmap.as_deref()
+    }
+
+    /// ビットマップを、値のイテレーターと共有できる形式で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 参照カウントを増やしたビットマップ。ビットマップを適用しない場合は`None`
+    pub(crate) fn shared_bitmap(&self) -> Option<Arc<[bool]>> {
+        self.bitmap.clone()
+    }
+}
+
 impl<T> FromReader for Section7<T>
 where
     T: TemplateFromReaderWithSize<u16>,
 {
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
         // 節の長さ: 4バイト
         let section_bytes = read_u32(reader, "第7節:節の長さ")? as usize;
         // 節番号: 1バイト
         validate_u8(reader, 7, "第7節:節番号")?;
         // テンプレート7
-        let template_bytes = section_bytes - (4 + 1);
+        let template_bytes = section_bytes.checked_sub(4 + 1).ok_or_else(|| {
+            ReaderError::Unexpected(format!("第7節:節の長さ({})が不正です。", section_bytes).into())
+        })?;
         let template7 = T::from_reader(
             reader,
             RUN_LENGTH_DATA_REPRESENTATION_TEMPLATE_NUMBER,
@@ -1376,8 +2101,8 @@ where
 }
 
 impl TemplateFromReaderWithSize<u16> for Template7_200 {
-    fn from_reader(
-        reader: &mut FileReader,
+    fn from_reader<R: Read + Seek>(
+        reader: &mut R,
         template_number: u16,
         template_bytes: usize,
     ) -> ReaderResult<Self> {
@@ -1400,6 +2125,10 @@ impl TemplateFromReaderWithSize<u16> for Template7_200 {
                 "第7節:ランレングス圧縮オクテット列の読み飛ばしに失敗しました。".into(),
             )
         })?;
+        debug_log!(
+            "第7節: ランレングス圧縮符号列 {} バイトをスキップ",
+            template_bytes
+        );
 
         Ok(Self {
             run_length_position,
@@ -1417,11 +2146,26 @@ pub struct PswSections {
 }
 
 impl PswSections {
-    pub(crate) fn from_reader(reader: &mut FileReader) -> ReaderResult<PswSections> {
+    pub(crate) fn from_reader<R: Read + Seek>(
+        reader: &mut R,
+        boundary: &MessageBoundary,
+        limits: &ReadLimits,
+    ) -> ReaderResult<PswSections> {
+        validate_section_limits(reader, 4, limits)?;
         let section4 = Section4_0::from_reader(reader)?;
+        boundary.validate(reader, "第4節")?;
+        validate_section_limits(reader, 5, limits)?;
         let section5 = Section5_200u16::from_reader(reader)?;
+        boundary.validate(reader, "第5節")?;
+        if limits.validate_levels() {
+            section5.validate_levels()?;
+        }
+        validate_section_limits(reader, 6, limits)?;
         let section6 = Section6::from_reader(reader)?;
+        boundary.validate(reader, "第6節")?;
+        validate_section_limits(reader, 7, limits)?;
         let section7 = Section7_200::from_reader(reader)?;
+        boundary.validate(reader, "第7節")?;
 
         Ok(PswSections {
             section4,
@@ -1485,12 +2229,13 @@ impl PswSections {
 }
 
 impl FromReader for Section8 {
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self> {
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
         // 第8節:終端マーカー
         let end_marker = read_str(reader, 4);
         match end_marker {
             Ok(end_marker) => {
                 if end_marker == SECTION8_END_MARKER {
+                    trace_log!("第8節: 終端マーカーを検出");
                     Ok(Self { end_marker })
                 } else {
                     Err(ReaderError::Unexpected(
@@ -1502,6 +2247,12 @@ impl FromReader for Section8 {
                     ))
                 }
             }
+            Err(ReaderError::ReadErrorAt { offset, .. }) => Err(ReaderError::ReadErrorAt {
+                offset,
+                message:
+                    "第8節の終了が不正です。ファイルを正確に読み込めなかった可能性があります。"
+                        .into(),
+            }),
             Err(_) => Err(ReaderError::ReadError(
                 "第8節の終了が不正です。ファイルを正確に読み込めなかった可能性があります。".into(),
             )),
@@ -1509,92 +2260,1107 @@ impl FromReader for Section8 {
     }
 }
 
-fn validate_str(
-    reader: &mut FileReader,
-    name: &str,
-    size: usize,
-    expected: &str,
-) -> ReaderResult<String> {
-    let mut buf = vec![0; size];
-    reader.read_exact(&mut buf).map_err(|_| {
-        ReaderError::ReadError(format!("{}バイトの文字列の読み込みに失敗しました。", size).into())
-    })?;
-    let value = String::from_utf8(buf).map_err(|_| {
-        ReaderError::Unexpected(format!("{}バイトの文字列のコードに失敗しました。", size).into())
-    })?;
-    if value != expected {
-        return Err(ReaderError::Unexpected(
-            format!(
-                "{}の値は{}でしたが、{}でなければなりません。",
-                name, value, expected
-            )
-            .into(),
-        ));
+impl Section0 {
+    /// 第0節:指示節を書き込む。
+    ///
+    /// GRIB報全体のバイト数は、読み込んだ値ではなく、書き出すGRIB報から再計算した値を書き込む。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 書き込み先
+    /// * `total_length` - GRIB報全体のバイト数
+    pub(crate) fn to_writer_with_total_length<W: Write>(
+        &self,
+        writer: &mut W,
+        total_length: u64,
+    ) -> ReaderResult<()> {
+        // GRIB: 4バイト
+        write_all(writer, self.grib.as_bytes(), "第0節:GRIB")?;
+        // 保留: 2バイト
+        write_u16(writer, self.reserved, "第0節:保留")?;
+        // 資料分野: 1バイト
+        write_u8(writer, self.discipline, "第0節:資料分野")?;
+        // GRIB版番号: 1バイト
+        write_u8(writer, self.edition_number, "第0節:GRIB版番号")?;
+        // GRIB報全体の長さ: 8バイト
+        write_u64(writer, total_length, "第0節:GRIB報全体の長さ")
     }
-
-    Ok(value)
 }
 
-/// 符号なし整数を読み込む関数を生成するマクロ
-macro_rules! impl_read_unsigned_int {
-    ($fname:ident, $type:ty) => {
-        fn $fname(reader: &mut FileReader, name: &str) -> ReaderResult<$type> {
-            let expected_bytes = std::mem::size_of::<$type>();
-            let mut buf = vec![0_u8; expected_bytes];
-            reader.read_exact(&mut buf).map_err(|_| {
-                ReaderError::ReadError(format!("{}の読み込みに失敗しました。", name).into())
-            })?;
+impl ToWriter for Section1 {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        let mut body = vec![];
+        write_u16(&mut body, self.center, "第1節:作成中枢")?;
+        write_u16(&mut body, self.sub_center, "第1節:作成副中枢")?;
+        write_u8(
+            &mut body,
+            self.table_version,
+            "第1節:GRIBマスター表バージョン番号",
+        )?;
+        write_u8(
+            &mut body,
+            self.local_table_version,
+            "第1節:GRIB地域表バージョン番号",
+        )?;
+        write_u8(
+            &mut body,
+            self.significance_of_reference_time,
+            "第1節:参照時刻の意味",
+        )?;
+        write_datetime(&mut body, self.referenced_at, "第1節:資料の参照時刻")?;
+        write_u8(
+            &mut body,
+            self.production_status_of_processed_data,
+            "第1節:作成ステータス",
+        )?;
+        write_u8(&mut body, self.type_of_processed_data, "第1節:資料の種類")?;
 
-            Ok(<$type>::from_be_bytes(buf.try_into().unwrap()))
-        }
-    };
+        write_section(writer, 1, &body)
+    }
 }
 
-impl_read_unsigned_int!(read_u8, u8);
-impl_read_unsigned_int!(read_u16, u16);
-impl_read_unsigned_int!(read_u32, u32);
-impl_read_unsigned_int!(read_u64, u64);
+impl ToWriter for Section2 {
+    /// 第2節:地域使用節を書き込む。節が省略されている場合は何も書き込まない。
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        if self.bytes.is_empty() {
+            return Ok(());
+        }
 
-/// 符号あり整数を読み込む関数を生成するマクロ
-macro_rules! impl_read_signed_int {
-    ($fname:ident, $type:ty) => {
-        fn $fname(reader: &mut FileReader, name: &str) -> ReaderResult<$type> {
-            let expected_bytes = std::mem::size_of::<$type>();
-            let mut buf = vec![0_u8; expected_bytes];
-            reader.read_exact(&mut buf).map_err(|_| {
-                ReaderError::ReadError(format!("{}の読み込みに失敗しました。", name).into())
-            })?;
-            let sign = if buf[0] & 0x80 == 0 { 1 } else { -1 };
-            buf[0] &= 0x7F;
+        write_section(writer, 2, &self.bytes)
+    }
+}
 
-            Ok(<$type>::from_be_bytes(buf.try_into().unwrap()) * sign)
+impl<T> ToWriter for Section3<T>
+where
+    T: ToWriter,
+{
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        let mut body = vec![];
+        write_u8(
+            &mut body,
+            self.source_of_grid_definition,
+            "第3節:格子系定義の出典",
+        )?;
+        write_u32(&mut body, self.number_of_data_points, "第3節:格子点数")?;
+        write_u8(
+            &mut body,
+            self.number_of_octets_for_number_of_points,
+            "第3節:格子点数を定義するリストのオクテット数",
+        )?;
+        write_u8(
+            &mut body,
+            self.interpretation_of_number_of_points,
+            "第3節:格子点数を定義するリストの説明",
+        )?;
+        write_u16(
+            &mut body,
+            self.grid_definition_template_number,
+            "第3節:格子系定義テンプレート番号",
+        )?;
+        self.template3.to_writer(&mut body)?;
+
+        write_section(writer, 3, &body)
+    }
+}
+
+impl ToWriter for Template3_0 {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        write_u8(writer, self.shape_of_earth, "第3節:地球の形状")?;
+        write_u8(
+            writer,
+            self.scale_factor_of_radius_of_spherical_earth,
+            "第3節:地球球体の半径の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_radius_of_spherical_earth,
+            "第3節:地球球体の尺度付き半径",
+        )?;
+        write_u8(
+            writer,
+            self.scale_factor_of_earth_major_axis,
+            "第3節:地球回転楕円体の長軸の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_earth_major_axis,
+            "第3節:地球回転楕円体の長軸の尺度付きの長さ",
+        )?;
+        write_u8(
+            writer,
+            self.scale_factor_of_earth_minor_axis,
+            "第3節:地球回転楕円体の短軸の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_earth_minor_axis,
+            "第3節:地球回転楕円体の短軸の尺度付きの長さ",
+        )?;
+        write_u32(
+            writer,
+            self.number_of_along_lat_points,
+            "第3節:緯線に沿った格子点数",
+        )?;
+        write_u32(
+            writer,
+            self.number_of_along_lon_points,
+            "第3節:経線に沿った格子点数",
+        )?;
+        write_u32(
+            writer,
+            self.basic_angle_of_initial_product_domain,
+            "第3節:原作成領域の基本角",
+        )?;
+        write_u32(
+            writer,
+            self.subdivisions_of_basic_angle,
+            "第3節:端点の経度及び緯度並びに方向増分の定義",
+        )?;
+        write_u32(
+            writer,
+            self.lat_of_first_grid_point,
+            "第3節:最初の格子点の緯度",
+        )?;
+        write_u32(
+            writer,
+            self.lon_of_first_grid_point,
+            "第3節:最初の格子点の経度",
+        )?;
+        write_u8(
+            writer,
+            self.resolution_and_component_flags,
+            "第3節:分解能及び成分フラグ",
+        )?;
+        write_u32(
+            writer,
+            self.lat_of_last_grid_point,
+            "第3節:最後の格子点の緯度",
+        )?;
+        write_u32(
+            writer,
+            self.lon_of_last_grid_point,
+            "第3節:最後の格子点の経度",
+        )?;
+        write_u32(writer, self.i_direction_increment, "第3節:i方向の増分")?;
+        write_u32(writer, self.j_direction_increment, "第3節:j方向の増分")?;
+        write_u8(writer, self.scanning_mode, "第3節:走査モード")
+    }
+}
+
+impl ToWriter for Template3_40 {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        write_u8(writer, self.shape_of_earth, "第3節:地球の形状")?;
+        write_u8(
+            writer,
+            self.scale_factor_of_radius_of_spherical_earth,
+            "第3節:地球球体の半径の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_radius_of_spherical_earth,
+            "第3節:地球球体の尺度付き半径",
+        )?;
+        write_u8(
+            writer,
+            self.scale_factor_of_earth_major_axis,
+            "第3節:地球回転楕円体の長軸の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_earth_major_axis,
+            "第3節:地球回転楕円体の長軸の尺度付きの長さ",
+        )?;
+        write_u8(
+            writer,
+            self.scale_factor_of_earth_minor_axis,
+            "第3節:地球回転楕円体の短軸の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_earth_minor_axis,
+            "第3節:地球回転楕円体の短軸の尺度付きの長さ",
+        )?;
+        write_u32(
+            writer,
+            self.number_of_along_lat_points,
+            "第3節:緯線に沿った格子点数",
+        )?;
+        write_u32(
+            writer,
+            self.number_of_along_lon_points,
+            "第3節:経線に沿った格子点数",
+        )?;
+        write_u32(
+            writer,
+            self.basic_angle_of_initial_product_domain,
+            "第3節:原作成領域の基本角",
+        )?;
+        write_u32(
+            writer,
+            self.subdivisions_of_basic_angle,
+            "第3節:端点の経度及び緯度並びに方向増分の定義",
+        )?;
+        write_i32(
+            writer,
+            self.lat_of_first_grid_point,
+            "第3節:最初の格子点の緯度",
+        )?;
+        write_u32(
+            writer,
+            self.lon_of_first_grid_point,
+            "第3節:最初の格子点の経度",
+        )?;
+        write_u8(
+            writer,
+            self.resolution_and_component_flags,
+            "第3節:分解能及び成分フラグ",
+        )?;
+        write_i32(
+            writer,
+            self.lat_of_last_grid_point,
+            "第3節:最後の格子点の緯度",
+        )?;
+        write_u32(
+            writer,
+            self.lon_of_last_grid_point,
+            "第3節:最後の格子点の経度",
+        )?;
+        write_u32(writer, self.i_direction_increment, "第3節:i方向の増分")?;
+        write_u32(
+            writer,
+            self.number_of_parallels_between_pole_and_equator,
+            "第3節:極と赤道の間の緯線の数",
+        )?;
+        write_u8(writer, self.scanning_mode, "第3節:走査モード")
+    }
+}
+
+impl<T> ToWriter for Section4<T>
+where
+    T: ToWriter,
+{
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        let mut body = vec![];
+        write_u16(
+            &mut body,
+            self.number_of_after_template_points,
+            "第4節:テンプレート直後の座標値の数",
+        )?;
+        write_u16(
+            &mut body,
+            self.product_definition_template_number,
+            "第4節:プロダクト定義テンプレート番号",
+        )?;
+        self.template4.to_writer(&mut body)?;
+
+        write_section(writer, 4, &body)
+    }
+}
+
+impl ToWriter for Template4_50008 {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        write_u8(
+            writer,
+            self.parameter_category,
+            "第4節:パラメータカテゴリー",
+        )?;
+        write_u8(writer, self.parameter_number, "第4節:パラメータ番号")?;
+        write_u8(
+            writer,
+            self.type_of_generating_process,
+            "第4節:作成処理の種類",
+        )?;
+        write_u8(writer, self.background_process, "第4節:背景作成処理識別符")?;
+        write_u8(
+            writer,
+            self.generating_process_identifier,
+            "第4節:予報の作成処理識別符",
+        )?;
+        write_u16(
+            writer,
+            self.hours_after_data_cutoff,
+            "第4節:観測資料の参照時刻からの締切時間（時）",
+        )?;
+        write_u8(
+            writer,
+            self.minutes_after_data_cutoff,
+            "第4節:観測資料の参照時刻からの締切時間（分）",
+        )?;
+        write_u8(
+            writer,
+            self.indicator_of_unit_of_time_range,
+            "第4節:期間の単位の指示符",
+        )?;
+        write_i32(writer, self.forecast_time, "第4節:予報時間")?;
+        write_u8(
+            writer,
+            self.type_of_first_fixed_surface,
+            "第4節:第一固定面の種類",
+        )?;
+        write_u8(
+            writer,
+            self.scale_factor_of_first_fixed_surface,
+            "第4節:第一固定面の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_first_fixed_surface,
+            "第4節:第一固定面の尺度付きの値",
+        )?;
+        write_u8(
+            writer,
+            self.type_of_second_fixed_surface,
+            "第4節:第二固定面の種類",
+        )?;
+        write_u8(
+            writer,
+            self.scale_factor_of_second_fixed_surface,
+            "第4節:第二固定面の尺度因子",
+        )?;
+        write_u32(
+            writer,
+            self.scaled_value_of_second_fixed_surface,
+            "第4節:第二固定面の尺度付きの値",
+        )?;
+        write_datetime(
+            writer,
+            self.end_of_all_time_intervals,
+            "第4節:全時間間隔の終了時",
+        )?;
+        write_u8(
+            writer,
+            self.number_of_time_range_specs,
+            "第4節:統計を算出するために使用した時間間隔を記述する期間の仕様の数",
+        )?;
+        write_u32(
+            writer,
+            self.number_of_missing_values,
+            "第4節:統計処理における欠測資料の総数",
+        )?;
+        for spec in &self.time_range_specs {
+            spec.to_writer(writer)?;
+        }
+        write_u64(writer, self.radar_info1, "第4節:レーダー等運用情報その1")?;
+        write_u64(writer, self.radar_info2, "第4節:レーダー等運用情報その2")?;
+        write_u64(writer, self.rain_gauge_info, "第4節:雨量計運用情報")
+    }
+}
+
+impl ToWriter for TimeRangeSpec {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        write_u8(writer, self.type_of_stat_proc, "第4節:統計処理の種類")?;
+        write_u8(
+            writer,
+            self.type_of_stat_proc_time_increment,
+            "第4節:統計処理の時間増分の種類",
+        )?;
+        write_u8(
+            writer,
+            self.stat_proc_time_unit,
+            "第4節:統計処理の時間の単位の指示符",
+        )?;
+        write_u32(
+            writer,
+            self.stat_proc_time_length,
+            "第4節:統計処理の時間増分の長さ",
+        )?;
+        write_u8(
+            writer,
+            self.successive_time_unit,
+            "第4節:連続的な資料場間の増分に関する時間の単位の指示符",
+        )?;
+        write_u32(
+            writer,
+            self.successive_time_increment,
+            "第4節:連続的な資料場間の時間の増分",
+        )
+    }
+}
+
+impl<T> ToWriter for Section5<T>
+where
+    T: ToWriter,
+{
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        let mut body = vec![];
+        write_u32(&mut body, self.number_of_values, "第5節:全資料点の数")?;
+        write_u16(
+            &mut body,
+            self.data_representation_template_number,
+            "第5節:資料表現テンプレート番号",
+        )?;
+        write_u8(&mut body, self.bits_per_value, "第5節:1データのビット数")?;
+        self.template5.to_writer(&mut body)?;
+
+        write_section(writer, 5, &body)
+    }
+}
+
+impl<V: LevelValue> ToWriter for Template5_200<V> {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        write_u16(
+            writer,
+            self.max_level_value,
+            "第5節:今回の圧縮に用いたレベルの最大値",
+        )?;
+        write_u16(writer, self.number_of_level_values, "第5節:レベルの最大値")?;
+        write_u8(
+            writer,
+            self.decimal_scale_factor,
+            "第5節:データ代表値の尺度因子",
+        )?;
+        for value in &self.level_values {
+            value.write(writer, "第5節:レベルmに対応するデータ代表値")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ToWriter for Section6 {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        let mut body = vec![];
+        write_u8(&mut body, self.bitmap_indicator, "第6節:ビットマップ指示符")?;
+        // ビットマップは、先頭の格子を最上位ビットとして1オクテットに8格子ずつ詰める
+        if let Some(bitmap) = self.bitmap.as_deref() {
+            for bits in bitmap.chunks(8) {
+                let octet = bits
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &bit)| bit)
+                    .fold(0u8, |octet, (i, _)| octet | (0x80 >> i));
+                write_u8(&mut body, octet, "第6節:ビットマップ")?;
+            }
+        }
+
+        write_section(writer, 6, &body)
+    }
+}
+
+impl ToWriter for Section8 {
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()> {
+        write_all(writer, SECTION8_END_MARKER.as_bytes(), "第8節:終端マーカー")
+    }
+}
+
+/// GRIB報の境界
+///
+/// GRIB報の開始位置と、第0節に記録されたGRIB報全体のバイト数を記憶して、節を読み込んだ後の
+/// ファイルポインタがGRIB報の終端を超えていないか確認する。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MessageBoundary {
+    /// GRIB報の開始位置
+    start: u64,
+    /// GRIB報全体のバイト数
+    total_length: u64,
+}
+
+impl MessageBoundary {
+    /// 第0節を読み込んだ直後のファイルリーダーから、GRIB報の境界を構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 第0節を読み込んだ直後のGRIB2ファイルリーダー
+    /// * `section0` - 第0節:指示節
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB報の境界
+    pub(crate) fn new<R: Read + Seek>(reader: &mut R, section0: &Section0) -> ReaderResult<Self> {
+        let position = reader.stream_position().map_err(|_| {
+            ReaderError::ReadError("第0節:GRIB報の開始位置の記憶に失敗しました。".into())
+        })?;
+
+        Ok(Self {
+            start: position.saturating_sub(SECTION0_BYTES),
+            total_length: section0.total_length() as u64,
+        })
+    }
+
+    /// 読み込んだバイト数が、GRIB報全体のバイト数を超えていないか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `name` - 直前に読み込んだ節の名前
+    pub(crate) fn validate<R: Read + Seek>(&self, reader: &mut R, name: &str) -> ReaderResult<()> {
+        let position = reader.stream_position().map_err(|_| {
+            ReaderError::ReadError(format!("{}:読み込み位置の取得に失敗しました。", name).into())
+        })?;
+        let read_bytes = position.saturating_sub(self.start);
+        if self.total_length < read_bytes {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "{}までに読み込んだバイト数({})が、GRIB報全体のバイト数({})を超えました。ファイルが破損しているか、正確に読み込めなかった可能性があります。",
+                    name, read_bytes, self.total_length
+                )
+                .into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 第8節を読み込んだ後の読み込み位置が、第0節に記録されたGRIB報の終端と一致するか確認する。
+    ///
+    /// GRIB報全体のバイト数に対して節が短い場合は、ファイルが切り詰められているか、GRIB報に
+    /// 余分なバイトが含まれている。GRIB報の後に続くバイト列は、次のGRIB報の可能性があるため
+    /// 確認しない。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 第8節を読み込んだ直後のGRIB2ファイルリーダー
+    pub(crate) fn validate_end<R: Read + Seek>(&self, reader: &mut R) -> ReaderResult<()> {
+        self.validate(reader, "第8節")?;
+        let position = reader.stream_position().map_err(|_| {
+            ReaderError::ReadError("第8節:読み込み位置の取得に失敗しました。".into())
+        })?;
+        let read_bytes = position.saturating_sub(self.start);
+        if read_bytes != self.total_length {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "第8節までに読み込んだバイト数({})が、GRIB報全体のバイト数({})と一致しません。ファイルが切り詰められているか、余分なバイトを含んでいる可能性があります。",
+                    read_bytes, self.total_length
+                )
+                .into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// 複数のプロダクトを格納するGRIB報で、プロダクトに共通する第0節から第3節
+pub(crate) struct LeadingSections {
+    pub(crate) section0: Section0,
+    pub(crate) section1: Section1,
+    pub(crate) section2: Section2,
+    pub(crate) section3: Section3_0,
+    /// GRIB報の範囲
+    pub(crate) boundary: MessageBoundary,
+}
+
+impl LeadingSections {
+    /// GRIB2ファイルの先頭から第3節までを読み込む。
+    ///
+    /// 各節を読み込んだ後、GRIB報の範囲を超えていないことと、第3節の走査モードが格子点数と
+    /// 整合することを確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// 第0節から第3節とGRIB報の範囲
+    pub(crate) fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        Self::from_reader_with_limits(reader, &ReadLimits::default())
+    }
+
+    /// 読み込みの上限を確認しながら、GRIB2ファイルの先頭から第3節までを読み込む。
+    ///
+    /// 第2節及び第3節は、読み込む前に節の長さが上限以下であることを確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `limits` - 読み込みの上限
+    ///
+    /// # 戻り値
+    ///
+    /// 第0節から第3節とGRIB報の範囲
+    pub(crate) fn from_reader_with_limits<R: Read + Seek>(
+        reader: &mut R,
+        limits: &ReadLimits,
+    ) -> ReaderResult<Self> {
+        let sections = Self::read_recording(
+            reader,
+            limits,
+            &mut Grib2Message::<Template3_0, (), (), ()>::default(),
+        )?;
+        sections.section3.validate_scanning_mode()?;
+
+        Ok(sections)
+    }
+
+    /// 読み込んだ節を`message`にも格納しながら、GRIB2ファイルの先頭から第3節までを読み込む。
+    ///
+    /// 節の読み込みに失敗した場合でも、それまでに読み込めた節は`message`に格納されている。
+    /// 第3節の走査モードは確認しない。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `limits` - 読み込みの上限
+    /// * `message` - 読み込んだ節を格納するGRIB報
+    ///
+    /// # 戻り値
+    ///
+    /// 第0節から第3節とGRIB報の範囲
+    pub(crate) fn read_recording<R: Read + Seek, T4, T5, T7>(
+        reader: &mut R,
+        limits: &ReadLimits,
+        message: &mut Grib2Message<Template3_0, T4, T5, T7>,
+    ) -> ReaderResult<Self> {
+        let section0 = Section0::from_reader(reader)?;
+        let boundary = MessageBoundary::new(reader, &section0)?;
+        message.section0 = Some(section0.clone());
+        let section1 = Section1::from_reader(reader)?;
+        boundary.validate(reader, "第1節")?;
+        message.section1 = Some(section1);
+        if peek_section_number(reader)? == 2 {
+            validate_section_limits(reader, 2, limits)?;
+        }
+        let section2 = Section2::from_reader(reader)?;
+        boundary.validate(reader, "第2節")?;
+        message.section2 = Some(section2.clone());
+        validate_section_limits(reader, 3, limits)?;
+        let section3 = Section3_0::from_reader(reader)?;
+        boundary.validate(reader, "第3節")?;
+        message.section3 = Some(section3);
+
+        Ok(Self {
+            section0,
+            section1,
+            section2,
+            section3,
+            boundary,
+        })
+    }
+}
+
+/// 第0節から第3節を、節ごとに空行で区切って出力する。
+pub(crate) fn write_leading_sections_debug_info<W: std::io::Write>(
+    writer: &mut W,
+    section0: &Section0,
+    section1: &Section1,
+    section2: &Section2,
+    section3: &Section3_0,
+) -> std::io::Result<()> {
+    section0.debug_info(writer)?;
+    writeln!(writer)?;
+    section1.debug_info(writer)?;
+    writeln!(writer)?;
+    section2.debug_info(writer)?;
+    writeln!(writer)?;
+    section3.debug_info(writer)?;
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+/// 複数のプロダクトを格納するGRIB報のリーダーに、第0節から第3節及び第8節を返すメソッドを
+/// 実装するマクロ
+macro_rules! impl_leading_section_getters {
+    () => {
+        /// 第0節:指示節を返す。
+        ///
+        /// # 戻り値
+        ///
+        /// 第0節:指示節
+        pub fn section0(&self) -> &Section0 {
+            &self.section0
+        }
+
+        /// 第1節:識別節を返す。
+        ///
+        /// # 戻り値
+        ///
+        /// 第1節:識別節
+        pub fn section1(&self) -> &Section1 {
+            &self.section1
+        }
+
+        /// 第2節:地域使用節を返す。
+        ///
+        /// # 戻り値
+        ///
+        /// 第2節:地域使用節
+        pub fn section2(&self) -> &Section2 {
+            &self.section2
+        }
+
+        /// 第3節:格子系定義節を返す。
+        ///
+        /// # 戻り値
+        ///
+        /// 第3節:格子系定義節
+        pub fn section3(&self) -> &Section3_0 {
+            &self.section3
+        }
+
+        /// 第8節:終端節を返す。
+        ///
+        /// # 戻り値
+        ///
+        /// 第8節:終端節
+        pub fn section8(&self) -> &Section8 {
+            &self.section8
         }
     };
 }
+pub(crate) use impl_leading_section_getters;
+
+/// 節の先頭から現在位置までに読み込んだバイト数が、節の長さを超えていないか確認する。
+///
+/// # 引数
+///
+/// * `reader` - 節を読み込んだ直後のGRIB2ファイルリーダー
+/// * `start` - 節の先頭の位置
+/// * `section_bytes` - 節の長さ
+/// * `name` - 節の名前
+///
+/// # 戻り値
+///
+/// 節の先頭から現在位置までに読み込んだバイト数
+fn validate_section_bytes<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    section_bytes: usize,
+    name: &str,
+) -> ReaderResult<u64> {
+    let position = reader.stream_position().map_err(|_| {
+        ReaderError::ReadError(format!("{}:読み込み位置の取得に失敗しました。", name).into())
+    })?;
+    let read_bytes = position.saturating_sub(start);
+    if read_bytes > section_bytes as u64 {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "{}の長さは{}バイトですが、{}バイト読み込みました。",
+                name, section_bytes, read_bytes
+            )
+            .into(),
+        ));
+    }
+
+    Ok(read_bytes)
+}
+
+/// 既知のフィールドを読み込んだ後、節の末尾まで読み飛ばす。
+///
+/// 新しいGRIB2では、既知のフィールドの後に未知のフィールドが追加されている場合がある。
+/// 節の先頭で読み込んだ節の長さを使って節の末尾に移動することで、未知のフィールドがあっても
+/// 次の節を読み込めるようにする。
+///
+/// # 引数
+///
+/// * `reader` - 節の既知のフィールドを読み込んだ直後のGRIB2ファイルリーダー
+/// * `start` - 節の先頭の位置
+/// * `section_bytes` - 節の長さ
+/// * `name` - 節の名前
+fn skip_to_section_end<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    section_bytes: usize,
+    name: &str,
+) -> ReaderResult<()> {
+    let read_bytes = validate_section_bytes(reader, start, section_bytes, name)?;
+    let skip = section_bytes as u64 - read_bytes;
+    if 0 < skip {
+        trace_log!("{}: 未知のフィールド {} バイトを読み飛ばし", name, skip);
+        reader.seek_relative(skip as i64).map_err(|_| {
+            ReaderError::ReadError(
+                format!("{}:節の末尾までの読み飛ばしに失敗しました。", name).into(),
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// 節の先頭の位置を取得する。
+///
+/// # 引数
+///
+/// * `reader` - 節の先頭に位置するGRIB2ファイルリーダー
+/// * `name` - 節の名前
+fn section_start<R: Seek>(reader: &mut R, name: &str) -> ReaderResult<u64> {
+    reader.stream_position().map_err(|_| {
+        ReaderError::ReadError(format!("{}:読み込み位置の取得に失敗しました。", name).into())
+    })
+}
+
+/// 現在位置から指定したバイト数だけ後ろに記録されている2バイトの値を、ファイルポインタを
+/// 移動せずに読み込む。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `skip` - 現在位置から値までのバイト数
+/// * `name` - 読み込む値の名前
+///
+/// # 戻り値
+///
+/// 読み込んだ値
+fn peek_u16<R: Read + Seek>(reader: &mut R, skip: i64, name: &str) -> ReaderResult<u16> {
+    reader.seek_relative(skip).map_err(|_| {
+        ReaderError::ReadError(format!("{}へのシークに失敗しました。", name).into())
+    })?;
+    let value = read_u16(reader, name);
+    reader.seek_relative(-(skip + 2)).map_err(|_| {
+        ReaderError::ReadError(format!("{}からのシークに失敗しました。", name).into())
+    })?;
+
+    value
+}
+
+/// ファイルポインタを移動せずに、現在位置から第8節:終端節が記録されているか確認する。
+///
+/// # 引数
+///
+/// * `reader` - 節の先頭に位置するGRIB2ファイルリーダー
+///
+/// # 戻り値
+///
+/// 現在位置から第8節が記録されている場合は`true`
+pub(crate) fn is_section8<R: Read + Seek>(reader: &mut R) -> ReaderResult<bool> {
+    let value = peek_u32(reader, 0, "節の長さまたは第8節:終端")?;
+
+    Ok(value.to_be_bytes() == SECTION8_END_MARKER.as_bytes())
+}
+
+/// ファイルポインタを移動せずに、現在位置から記録されている節の節番号を読み込む。
+///
+/// # 引数
+///
+/// * `reader` - 節の先頭に位置するGRIB2ファイルリーダー
+///
+/// # 戻り値
+///
+/// 節番号
+pub(crate) fn peek_section_number<R: Read + Seek>(reader: &mut R) -> ReaderResult<u8> {
+    reader
+        .seek_relative(4)
+        .map_err(|_| ReaderError::ReadError("節番号へのシークに失敗しました。".into()))?;
+    let value = read_u8(reader, "節番号");
+    reader
+        .seek_relative(-5)
+        .map_err(|_| ReaderError::ReadError("節番号からのシークに失敗しました。".into()))?;
+
+    value
+}
+
+/// 現在位置から指定したバイト数だけ後ろに記録されている4バイトの値を、ファイルポインタを
+/// 移動せずに読み込む。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `skip` - 現在位置から値までのバイト数
+/// * `name` - 読み込む値の名前
+///
+/// # 戻り値
+///
+/// 読み込んだ値
+fn peek_u32<R: Read + Seek>(reader: &mut R, skip: i64, name: &str) -> ReaderResult<u32> {
+    reader.seek_relative(skip).map_err(|_| {
+        ReaderError::ReadError(format!("{}へのシークに失敗しました。", name).into())
+    })?;
+    let value = read_u32(reader, name);
+    reader.seek_relative(-(skip + 4)).map_err(|_| {
+        ReaderError::ReadError(format!("{}からのシークに失敗しました。", name).into())
+    })?;
+
+    value
+}
 
-impl_read_signed_int!(read_i16, i16);
-impl_read_signed_int!(read_i32, i32);
-//fn read_i32(reader: &mut FileReader, name: &str) -> ReaderResult<i32> {
-//    let expected_bytes = std::mem::size_of::<i32>();
-//    let mut buf = vec![0_u8; expected_bytes];
-//    reader.read_exact(&mut buf).map_err(|_| {
-//        ReaderError::ReadError(format!("{}の読み込みに失敗しました。", name).into())
-//    })?;
-//    // 最上位ビットを確認(0であれば正の数、1であれば負の数)
-//    let sign = if buf[0] & 0x80 == 0 { 1 } else { -1 };
-//    // 最上位ビットを0にした結果をデコード
-//    buf[0] &= 0x7F;
-//
-//    Ok(<i32>::from_be_bytes(buf.try_into().unwrap()) * sign)
-//}
+/// 節を読み込む前に、節の長さなどから確保する配列の要素数が読み込みの上限以下であるか確認する。
+///
+/// 節の長さを確認した後、第4節はテンプレート4.50009のメソモデル予想値の結合比率の計算領域数を、
+/// 第5節はテンプレート5.200の節の長さから求めたレベル別物理値の数を確認する。
+///
+/// # 引数
+///
+/// * `reader` - 節の先頭に位置するGRIB2ファイルリーダー
+/// * `section_number` - 節番号
+/// * `limits` - 読み込みの上限
+pub(crate) fn validate_section_limits<R: Read + Seek>(
+    reader: &mut R,
+    section_number: u8,
+    limits: &ReadLimits,
+) -> ReaderResult<()> {
+    let name = format!("第{}節", section_number);
+    let section_bytes = peek_u32(reader, 0, &format!("{}:節の長さ", name))? as usize;
+    if limits.max_section_bytes() < section_bytes {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "{}:節の長さ({})が上限({})を超えています。",
+                name,
+                section_bytes,
+                limits.max_section_bytes()
+            )
+            .into(),
+        ));
+    }
+    match section_number {
+        4 => {
+            let template_number =
+                peek_u16(reader, 4 + 1 + 2, "第4節:プロダクト定義テンプレート番号")?;
+            if template_number == RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER {
+                // 節の先頭からメソモデル予想値の結合比率の計算領域数までのバイト数
+                let skip = 4 + 1 + 2 + 2 + 25 + 24 + 24;
+                let areas = peek_u16(reader, skip, "第4節:メソモデル予想値の結合比率の計算領域数")?;
+                if limits.max_calculation_areas() < areas as usize {
+                    return Err(ReaderError::Unexpected(
+                        format!(
+                            "第4節:メソモデル予想値の結合比率の計算領域数({})が上限({})を超えています。",
+                            areas,
+                            limits.max_calculation_areas()
+                        )
+                        .into(),
+                    ));
+                }
+            }
+        }
+        5 => {
+            let template_number = peek_u16(reader, 4 + 1 + 4, "第5節:資料表現テンプレート番号")?;
+            if template_number == RUN_LENGTH_DATA_REPRESENTATION_TEMPLATE_NUMBER {
+                // 節の先頭からレベル別物理値までのバイト数（レベル別物理値は2バイトとして数える）
+                let header_bytes = 4 + 1 + 4 + 2 + 1 + 2 + 2 + 1;
+                let levels = section_bytes.checked_sub(header_bytes).ok_or_else(|| {
+                    ReaderError::Unexpected(
+                        format!("第5節:節の長さ({})が不正です。", section_bytes).into(),
+                    )
+                })? / 2;
+                if limits.max_level_values() < levels {
+                    return Err(ReaderError::Unexpected(
+                        format!(
+                            "第5節:レベル別物理値の数({})が上限({})を超えています。",
+                            levels,
+                            limits.max_level_values()
+                        )
+                        .into(),
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// 文字列を読み込み、期待する文字列と一致するか検証する。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `name` - 読み込む値の名前
+/// * `size` - 文字列のバイト数
+/// * `expected` - 期待する文字列
+///
+/// # 戻り値
+///
+/// 読み込んだ文字列
+pub fn validate_str<R: Read + Seek>(
+    reader: &mut R,
+    name: &str,
+    size: usize,
+    expected: &str,
+) -> ReaderResult<String> {
+    let mut buf = vec![0; size];
+    read_exact_at(
+        reader,
+        &mut buf,
+        &format!("{}:{}バイトの文字列", name, size),
+    )?;
+    let value = String::from_utf8(buf).map_err(|_| {
+        ReaderError::Unexpected(format!("{}バイトの文字列のコードに失敗しました。", size).into())
+    })?;
+    if value != expected {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "{}の値は{}でしたが、{}でなければなりません。",
+                name, value, expected
+            )
+            .into(),
+        ));
+    }
+
+    Ok(value)
+}
+
+/// バッファを満たすまで読み込み、読み込みに失敗した場合は読み込みを開始した位置をエラーに含める。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `buf` - 読み込んだバイトを格納するバッファ
+/// * `name` - 読み込む値の名前
+fn read_exact_at<R: Read + Seek>(reader: &mut R, buf: &mut [u8], name: &str) -> ReaderResult<()> {
+    let offset = reader.stream_position().map_err(|_| {
+        ReaderError::ReadError(format!("{}の読み込み位置の取得に失敗しました。", name).into())
+    })? as usize;
+    reader
+        .read_exact(buf)
+        .map_err(|_| ReaderError::ReadErrorAt {
+            offset,
+            message: format!("{}の読み込みに失敗しました。", name).into(),
+        })
+}
+
+/// 符号なし整数を読み込む関数を生成するマクロ
+macro_rules! impl_read_unsigned_int {
+    ($fname:ident, $type:ty) => {
+        pub fn $fname<R: Read + Seek>(reader: &mut R, name: &str) -> ReaderResult<$type> {
+            let expected_bytes = std::mem::size_of::<$type>();
+            let mut buf = vec![0_u8; expected_bytes];
+            read_exact_at(reader, &mut buf, name)?;
+
+            Ok(<$type>::from_be_bytes(buf.try_into().unwrap()))
+        }
+    };
+}
+
+impl_read_unsigned_int!(read_u8, u8);
+impl_read_unsigned_int!(read_u16, u16);
+impl_read_unsigned_int!(read_u32, u32);
+impl_read_unsigned_int!(read_u64, u64);
+
+/// 符号と絶対値で表現された整数を、符号あり整数に変換する関数を生成するマクロ
+///
+/// GRIB2の符号あり整数は2の補数ではなく、最上位ビットを符号（0が正、1が負）、残りのビットを
+/// 絶対値とする。符号ビットのみが1の値（負のゼロ）は0に変換する。
+macro_rules! impl_from_sign_magnitude {
+    ($fname:ident, $signed:ty, $unsigned:ty) => {
+        fn $fname(value: $unsigned) -> $signed {
+            let magnitude = (value & (<$unsigned>::MAX >> 1)) as $signed;
+            match value >> (<$unsigned>::BITS - 1) {
+                0 => magnitude,
+                _ => -magnitude,
+            }
+        }
+    };
+}
+
+impl_from_sign_magnitude!(i8_from_sign_magnitude, i8, u8);
+impl_from_sign_magnitude!(i16_from_sign_magnitude, i16, u16);
+impl_from_sign_magnitude!(i32_from_sign_magnitude, i32, u32);
+impl_from_sign_magnitude!(i64_from_sign_magnitude, i64, u64);
+
+/// 符号と絶対値で表現された符号あり整数を読み込む関数を生成するマクロ
+macro_rules! impl_read_signed_int {
+    ($fname:ident, $read_fn:ident, $from_fn:ident, $type:ty) => {
+        pub fn $fname<R: Read + Seek>(reader: &mut R, name: &str) -> ReaderResult<$type> {
+            Ok($from_fn($read_fn(reader, name)?))
+        }
+    };
+}
+
+impl_read_signed_int!(read_i8, read_u8, i8_from_sign_magnitude, i8);
+impl_read_signed_int!(read_i16, read_u16, i16_from_sign_magnitude, i16);
+impl_read_signed_int!(read_i32, read_u32, i32_from_sign_magnitude, i32);
+impl_read_signed_int!(read_i64, read_u64, i64_from_sign_magnitude, i64);
 
 /// 数値を読み込み検証する関数を生成するマクロ
 macro_rules! validate_number {
     ($fname:ident, $read_fn:ident, $type:ty) => {
-        fn $fname(reader: &mut FileReader, expected: $type, name: &str) -> ReaderResult<$type> {
-            let value = $read_fn(reader, name).map_err(|_| {
-                ReaderError::ReadError(format!("{}の読み込みに失敗しました。", name).into())
-            })?;
+        pub fn $fname<R: Read + Seek>(
+            reader: &mut R,
+            expected: $type,
+            name: &str,
+        ) -> ReaderResult<$type> {
+            let value = $read_fn(reader, name)?;
             if value != expected {
                 return Err(ReaderError::Unexpected(
                     format!(
@@ -1613,56 +3379,331 @@ macro_rules! validate_number {
 validate_number!(validate_u8, read_u8, u8);
 validate_number!(validate_u32, read_u32, u32);
 
-fn read_str(reader: &mut FileReader, size: usize) -> ReaderResult<String> {
+/// UTF-8の文字列を読み込む。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `size` - 文字列のバイト数
+///
+/// # 戻り値
+///
+/// 読み込んだ文字列
+pub fn read_str<R: Read + Seek>(reader: &mut R, size: usize) -> ReaderResult<String> {
     let mut buf = vec![0; size];
-    reader.read_exact(&mut buf).map_err(|_| {
-        ReaderError::ReadError(format!("{}バイトの文字列の読み込みに失敗しました。", size).into())
-    })?;
+    read_exact_at(reader, &mut buf, &format!("{}バイトの文字列", size))?;
 
     Ok(String::from_utf8(buf).map_err(|_| {
         ReaderError::Unexpected(format!("{}バイトの文字列のコードに失敗しました。", size).into())
     }))?
 }
 
-fn read_datetime(reader: &mut FileReader, name: &str) -> ReaderResult<OffsetDateTime> {
+/// 年（2バイト）、月、日、時、分及び秒（各1バイト）で記録された日時を、UTCの日時として読み込む。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `name` - 読み込む値の名前
+///
+/// # 戻り値
+///
+/// 読み込んだ日時
+pub fn read_datetime<R: Read + Seek>(reader: &mut R, name: &str) -> ReaderResult<OffsetDateTime> {
     let year = read_u16(reader, name)?;
     let mut parts = Vec::new();
     for _ in 0..5 {
         parts.push(read_u8(reader, name)?);
     }
     // 日付と時刻を構築
-    let month = Month::try_from(parts[0]).map_err(|_| {
+    let invalid = || ReaderError::InvalidDateTime {
+        name: name.to_string().into(),
+        year,
+        month: parts[0],
+        day: parts[1],
+        hour: parts[2],
+        minute: parts[3],
+        second: parts[4],
+    };
+    let month = Month::try_from(parts[0]).map_err(|_| invalid())?;
+    let date = Date::from_calendar_date(year as i32, month, parts[1]).map_err(|_| invalid())?;
+    let time = Time::from_hms(parts[2], parts[3], parts[4]).map_err(|_| invalid())?;
+
+    Ok(PrimitiveDateTime::new(date, time).assume_utc())
+}
+
+/// バイト列を書き込む。
+///
+/// # 引数
+///
+/// * `writer` - 書き込み先
+/// * `buf` - 書き込むバイト列
+/// * `name` - 書き込む値の名前
+fn write_all<W: Write>(writer: &mut W, buf: &[u8], name: &str) -> ReaderResult<()> {
+    writer.write_all(buf).map_err(|e| {
+        ReaderError::Unexpected(format!("{}の書き込みに失敗しました: {}", name, e).into())
+    })
+}
+
+/// 符号なし整数をビッグエンディアンで書き込む関数を生成するマクロ
+macro_rules! impl_write_unsigned_int {
+    ($fname:ident, $type:ty) => {
+        fn $fname<W: Write>(writer: &mut W, value: $type, name: &str) -> ReaderResult<()> {
+            write_all(writer, &value.to_be_bytes(), name)
+        }
+    };
+}
+
+impl_write_unsigned_int!(write_u8, u8);
+impl_write_unsigned_int!(write_u16, u16);
+impl_write_unsigned_int!(write_u32, u32);
+impl_write_unsigned_int!(write_u64, u64);
+
+/// 符号あり整数を、符号と絶対値で表現した整数に変換する関数を生成するマクロ
+macro_rules! impl_to_sign_magnitude {
+    ($fname:ident, $signed:ty, $unsigned:ty) => {
+        fn $fname(value: $signed) -> $unsigned {
+            let magnitude = value.unsigned_abs() & (<$unsigned>::MAX >> 1);
+            match value < 0 {
+                true => magnitude | !(<$unsigned>::MAX >> 1),
+                false => magnitude,
+            }
+        }
+    };
+}
+
+impl_to_sign_magnitude!(i16_to_sign_magnitude, i16, u16);
+impl_to_sign_magnitude!(i32_to_sign_magnitude, i32, u32);
+
+/// 符号あり整数を、符号と絶対値で表現して書き込む関数を生成するマクロ
+macro_rules! impl_write_signed_int {
+    ($fname:ident, $write_fn:ident, $to_fn:ident, $type:ty) => {
+        fn $fname<W: Write>(writer: &mut W, value: $type, name: &str) -> ReaderResult<()> {
+            $write_fn(writer, $to_fn(value), name)
+        }
+    };
+}
+
+impl_write_signed_int!(write_i16, write_u16, i16_to_sign_magnitude, i16);
+impl_write_signed_int!(write_i32, write_u32, i32_to_sign_magnitude, i32);
+
+/// 日時を、年（2バイト）、月、日、時、分及び秒（各1バイト）で書き込む。
+fn write_datetime<W: Write>(
+    writer: &mut W,
+    datetime: OffsetDateTime,
+    name: &str,
+) -> ReaderResult<()> {
+    let year = u16::try_from(datetime.year()).map_err(|_| {
         ReaderError::Unexpected(
-            format!(
-                "{}:月の値は{}でしたが、1から12の範囲でなければなりません。",
-                name, parts[0]
-            )
-            .into(),
+            format!("{}:年({})を書き込めません。", name, datetime.year()).into(),
         )
     })?;
-    let date = Date::from_calendar_date(year as i32, month, parts[1]).map_err(|_| {
+    write_u16(writer, year, name)?;
+    for part in [
+        datetime.month() as u8,
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second(),
+    ] {
+        write_u8(writer, part, name)?;
+    }
+
+    Ok(())
+}
+
+/// 節の長さと節番号を節の内容の前に付けて、節を書き込む。
+///
+/// 節の長さは、節の内容のバイト数から再計算する。
+///
+/// # 引数
+///
+/// * `writer` - 書き込み先
+/// * `number` - 節番号
+/// * `body` - 節番号の直後から節の末尾までのバイト列
+pub(crate) fn write_section<W: Write>(writer: &mut W, number: u8, body: &[u8]) -> ReaderResult<()> {
+    let name = format!("第{}節", number);
+    let section_bytes = u32::try_from(4 + 1 + body.len()).map_err(|_| {
+        ReaderError::Unexpected(format!("{}:節の長さが4バイトで表現できません。", name).into())
+    })?;
+    write_u32(writer, section_bytes, &format!("{}:節の長さ", name))?;
+    write_u8(writer, number, &format!("{}:節番号", name))?;
+
+    write_all(writer, body, &name)
+}
+
+/// 統計処理の種類を、符号表4.10の列挙型に変換する。
+///
+/// # 引数
+///
+/// * `type_of_stat_proc` - 統計処理の種類
+///
+/// # 戻り値
+///
+/// 統計処理の種類。符号表に定義されていない値の場合はエラー
+fn stat_proc_kind(type_of_stat_proc: u8) -> ReaderResult<StatProc> {
+    StatProc::try_from(type_of_stat_proc).map_err(|e| {
         ReaderError::Unexpected(
             format!(
-                "{}:{}年{}月{}日を日付に変換できませんでした。",
-                name, year, month as u8, parts[1]
+                "第4節:統計処理の種類({})を変換できません。{}",
+                type_of_stat_proc, e
             )
             .into(),
         )
-    })?;
-    let time = Time::from_hms(parts[2], parts[3], parts[4]).map_err(|_| {
+    })
+}
+
+/// 統計処理の時間の単位と長さから、統計処理した時間の長さを時間単位で返す。
+///
+/// # 引数
+///
+/// * `unit` - 統計処理の時間の単位の指示符（符号表4.4）
+/// * `length` - 統計処理した時間の長さ
+///
+/// # 戻り値
+///
+/// 統計処理した時間の長さ（時間）。単位が不明または長さが0の場合は`None`
+fn stat_proc_hours(unit: u8, length: u32) -> Option<f64> {
+    let hours_per_unit = match unit {
+        0 => 1.0 / 60.0,    // 分
+        1 => 1.0,           // 時
+        2 => 24.0,          // 日
+        10 => 3.0,          // 3時間
+        11 => 6.0,          // 6時間
+        12 => 12.0,         // 12時間
+        13 => 1.0 / 3600.0, // 秒
+        _ => return None,
+    };
+    match length {
+        0 => None,
+        _ => Some(hours_per_unit * length as f64),
+    }
+}
+
+/// 期間の単位と長さから、期間を返す。
+///
+/// # 引数
+///
+/// * `unit` - 期間の単位の指示符（符号表4.4）
+/// * `length` - 期間の長さ
+///
+/// # 戻り値
+///
+/// 期間。単位が不明な場合は`None`
+fn time_range_duration(unit: u8, length: i32) -> Option<Duration> {
+    let length = length as i64;
+    match unit {
+        0 => Some(Duration::minutes(length)),
+        1 => Some(Duration::hours(length)),
+        2 => Some(Duration::days(length)),
+        10 => Some(Duration::hours(3 * length)),
+        11 => Some(Duration::hours(6 * length)),
+        12 => Some(Duration::hours(12 * length)),
+        13 => Some(Duration::seconds(length)),
+        _ => None,
+    }
+}
+
+/// 期間の単位の指示符に従って、予報時間を期間に変換する。
+///
+/// # 引数
+///
+/// * `unit` - 期間の単位の指示符
+/// * `forecast_time` - 予報時間
+///
+/// # 戻り値
+///
+/// 予報時間。単位が不明な場合はエラー
+fn forecast_duration(unit: u8, forecast_time: i32) -> ReaderResult<Duration> {
+    time_range_duration(unit, forecast_time).ok_or_else(|| {
         ReaderError::Unexpected(
             format!(
-                "{}:{}時{}分{}秒を時刻に変換できませんでした。",
-                name, parts[2], parts[3], parts[4]
+                "期間の単位の指示符({})に対応していないため、予報時間({})を期間に変換できません。",
+                unit, forecast_time
             )
             .into(),
         )
-    })?;
+    })
+}
 
-    Ok(PrimitiveDateTime::new(date, time).assume_utc())
+/// 日本標準時のUTCオフセット
+const JST_OFFSET: UtcOffset = offset!(+9);
+
+/// 日時を、UTCオフセット付きのRFC3339形式の文字列に変換する。
+///
+/// # 引数
+///
+/// * `datetime` - 日時
+///
+/// # 戻り値
+///
+/// RFC3339形式の文字列（例: `2023-09-10T12:00:00Z`）
+pub(crate) fn to_rfc3339(datetime: OffsetDateTime) -> String {
+    // GRIB2から読み込んだ日時は年が4桁のUTCであるため、RFC3339形式への変換は失敗しない
+    datetime
+        .to_offset(UtcOffset::UTC)
+        .format(&Rfc3339)
+        .expect("UTCの日時はRFC3339形式に変換できる")
+}
+
+/// 固定面の尺度因子と尺度付きの値から、固定面の値を返す。
+///
+/// 尺度因子は最上位ビットを符号とする符号付き整数として扱う。
+///
+/// # 引数
+///
+/// * `scale_factor` - 固定面の尺度因子
+/// * `scaled_value` - 固定面の尺度付きの値
+///
+/// # 戻り値
+///
+/// 固定面の値（尺度付きの値 / 10^尺度因子）。尺度因子または尺度付きの値が欠測（全ビット1）の
+/// 場合は`None`
+fn fixed_surface_value(scale_factor: u8, scaled_value: u32) -> Option<f64> {
+    if scale_factor == u8::MAX || scaled_value == u32::MAX {
+        return None;
+    }
+    let scale_factor = i8_from_sign_magnitude(scale_factor) as i32;
+
+    Some(scaled_value as f64 / 10f64.powi(scale_factor))
+}
+
+/// データ代表値の尺度因子から、レベル値などを物理値に変換するときの除数を返す。
+///
+/// 尺度因子は符号と絶対値で表現された符号あり整数であるため、例えば`0x81`は-1として、
+/// 物理値をレベル値の10倍とする。
+///
+/// # 引数
+///
+/// * `decimal_scale_factor` - データ代表値の尺度因子
+///
+/// # 戻り値
+///
+/// 10^尺度因子
+pub(crate) fn decimal_scale(decimal_scale_factor: u8) -> f64 {
+    10f64.powi(i8_from_sign_magnitude(decimal_scale_factor) as i32)
+}
+
+/// 第一固定面の種類と尺度付きの値から、土壌雨量指数のタンクを判定する。
+///
+/// 全タンクは第一固定面の種類が200、第一タンクと第二タンクは第一固定面の種類が201で、
+/// 尺度付きの値がタンクの番号である。
+fn psw_tank(
+    type_of_first_fixed_surface: u8,
+    scaled_value_of_first_fixed_surface: u32,
+) -> Option<PswTank> {
+    match (
+        type_of_first_fixed_surface,
+        scaled_value_of_first_fixed_surface,
+    ) {
+        (200, _) => Some(PswTank::All),
+        (201, 1) => Some(PswTank::First),
+        (201, 2) => Some(PswTank::Second),
+        _ => None,
+    }
 }
 
-pub(crate) trait FromReader {
+/// GRIB2ファイルから節を読み込むトレイト
+pub trait FromReader {
     /// 節を読み込む。
     ///
     /// # 引数
@@ -1672,12 +3713,16 @@ pub(crate) trait FromReader {
     /// # 戻り値
     ///
     /// 節
-    fn from_reader(reader: &mut FileReader) -> ReaderResult<Self>
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self>
     where
         Self: Sized;
 }
 
-pub(crate) trait TemplateFromReader<T> {
+/// GRIB2ファイルからテンプレートを読み込むトレイト
+///
+/// 型引数`T`はテンプレート番号の型である。独自のテンプレートを実装すると、`Section4<MyTemplate>`の
+/// ように節に組み込んで読み込める。
+pub trait TemplateFromReader<T> {
     /// テンプレートを読み込む。
     ///
     /// # 引数
@@ -1688,51 +3733,662 @@ pub(crate) trait TemplateFromReader<T> {
     /// # 戻り値
     ///
     /// テンプレート
-    fn from_reader(reader: &mut FileReader, template_number: T) -> ReaderResult<Self>
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: T) -> ReaderResult<Self>
     where
         Self: Sized;
 }
 
-pub(crate) trait TemplateFromReaderWithSize<T> {
+/// GRIB2ファイルから、節の長さによってバイト数が決まるテンプレートを読み込むトレイト
+pub trait TemplateFromReaderWithSize<T> {
     /// テンプレートを読み込む。
     ///
     /// # 引数
     ///
-    /// * `reader` - ファイルリーダ
-    /// * `template_number` - テンプレート番号
-    /// * `template_bytes` - テンプレートのバイト数
+    /// * `reader` - ファイルリーダ
+    /// * `template_number` - テンプレート番号
+    /// * `template_bytes` - テンプレートのバイト数
+    ///
+    /// # 戻り値
+    ///
+    /// テンプレート
+    fn from_reader<R: Read + Seek>(
+        reader: &mut R,
+        template_number: T,
+        template_bytes: usize,
+    ) -> ReaderResult<Self>
+    where
+        Self: Sized;
+}
+
+pub(crate) trait ToWriter {
+    /// 節またはテンプレートを、規格どおりのバイト並びで書き込む。
+    ///
+    /// 節の長さは、読み込んだ値ではなく、書き込む内容から再計算した値を書き込む。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 書き込み先
+    fn to_writer<W: Write>(&self, writer: &mut W) -> ReaderResult<()>;
+}
+
+pub trait DebugTemplate<W> {
+    /// テンプレートのデバッグ情報を出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先
+    fn debug_info(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.debug_info_with(writer, &DebugOptions::default())
+    }
+
+    /// 字下げなどの書式を指定して、テンプレートのデバッグ情報を出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先
+    /// * `opts` - デバッグ情報の書式
+    fn debug_info_with(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
+    where
+        W: std::io::Write;
+}
+
+/// デバッグ情報の書式
+///
+/// デフォルトの書式は、字下げを半角空白4文字、節の見出しを`第{number}節:{name}`、項目名と値の
+/// 区切りを`: `とする。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugOptions {
+    /// 字下げに使用する文字列
+    indent: String,
+    /// 節の見出しの書式（`{number}`を節番号、`{name}`を節の名前に置換）
+    section_header: String,
+    /// 項目名と値の区切り
+    separator: String,
+}
+
+impl Default for DebugOptions {
+    fn default() -> Self {
+        Self {
+            indent: String::from("    "),
+            section_header: String::from("第{number}節:{name}"),
+            separator: String::from(": "),
+        }
+    }
+}
+
+impl DebugOptions {
+    /// 字下げに使用する文字列を設定する。
+    ///
+    /// # 引数
+    ///
+    /// * `indent` - 字下げに使用する文字列
+    ///
+    /// # 戻り値
+    ///
+    /// `DebugOptions`
+    pub fn with_indent<S: Into<String>>(mut self, indent: S) -> Self {
+        self.indent = indent.into();
+
+        self
+    }
+
+    /// 節の見出しの書式を設定する。
+    ///
+    /// 書式に含まれる`{number}`は節番号、`{name}`は節の名前に置換する。
+    ///
+    /// # 引数
+    ///
+    /// * `section_header` - 節の見出しの書式
+    ///
+    /// # 戻り値
+    ///
+    /// `DebugOptions`
+    pub fn with_section_header<S: Into<String>>(mut self, section_header: S) -> Self {
+        self.section_header = section_header.into();
+
+        self
+    }
+
+    /// 項目名と値の区切りを設定する。
+    ///
+    /// # 引数
+    ///
+    /// * `separator` - 項目名と値の区切り
+    ///
+    /// # 戻り値
+    ///
+    /// `DebugOptions`
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+
+        self
+    }
+
+    /// 字下げに使用する文字列を返す。
+    pub fn indent(&self) -> &str {
+        &self.indent
+    }
+
+    /// 項目名と値の区切りを返す。
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    /// 節の見出しを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `number` - 節番号
+    /// * `name` - 節の名前
+    ///
+    /// # 戻り値
+    ///
+    /// 書式に節番号と節の名前を埋め込んだ見出し
+    pub fn section_header(&self, number: u8, name: &str) -> String {
+        self.section_header
+            .replace("{number}", &number.to_string())
+            .replace("{name}", name)
+    }
+}
+
+/// テンプレートのデバッグ情報をJSON形式で出力するトレイト
+///
+/// feature`json`が有効な場合に利用できる。
+#[cfg(feature = "json")]
+pub trait DebugTemplateJson {
+    /// テンプレートのデバッグ情報をJSONの値で返す。
+    fn debug_info_json(&self) -> serde_json::Value;
+}
+
+/// 節及びテンプレートのフィールドをJSONの値に変換するトレイト
+#[cfg(feature = "json")]
+pub trait ToJsonValue {
+    /// JSONの値に変換する。
+    fn to_json_value(&self) -> serde_json::Value;
+}
+
+#[cfg(feature = "json")]
+macro_rules! impl_to_json_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJsonValue for $ty {
+                fn to_json_value(&self) -> serde_json::Value {
+                    serde_json::Value::from(*self)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "json")]
+impl_to_json_value!(u8, u16, u32, u64, usize, i8, i16, i32, i64);
+
+#[cfg(feature = "json")]
+impl ToJsonValue for String {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(self.as_str())
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJsonValue for OffsetDateTime {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(to_rfc3339(*self))
+    }
+}
+
+impl<W, T> DebugTemplate<W> for Vec<T>
+where
+    T: DebugTemplate<Vec<u8>>,
+{
+    fn debug_info_with(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        // 要素ごとに番号を出力して、要素のデバッグ情報を字下げして出力
+        for (i, template) in self.iter().enumerate() {
+            writeln!(writer, "{}[{}]", opts.indent(), i + 1)?;
+            let mut nested = Vec::<u8>::new();
+            template.debug_info_with(&mut nested, opts)?;
+            for line in String::from_utf8_lossy(&nested).lines() {
+                writeln!(writer, "{}{}", opts.indent(), line)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: DebugTemplateJson> DebugTemplateJson for Vec<T> {
+    fn debug_info_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.iter()
+                .map(DebugTemplateJson::debug_info_json)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: ToJsonValue> ToJsonValue for Vec<T> {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::Array(self.iter().map(ToJsonValue::to_json_value).collect())
+    }
+}
+
+pub type Section3_0 = Section3<Template3_0>;
+pub type Section3_40 = Section3<Template3_40>;
+pub type Section4_0 = Section4<Template4_0>;
+pub type Section4_8 = Section4<Template4_8>;
+pub type Section4_50000 = Section4<Template4_50000>;
+pub type Section4_50008 = Section4<Template4_50008>;
+pub type Section4_50009 = Section4<Template4_50009>;
+pub type Template5_200u16 = Template5_200<u16>;
+pub type Template5_200i16 = Template5_200<i16>;
+pub type Section5_200u16 = Section5<Template5_200u16>;
+pub type Section5_200i16 = Section5<Template5_200i16>;
+pub type Section7_200 = Section7<Template7_200>;
+
+/// プロダクト定義テンプレート番号で区別したテンプレート4
+///
+/// `Section4::from_reader_dynamic`で、プロダクト定義テンプレート番号を読み込んでから
+/// テンプレートの型を決めるときに使用する。テンプレート番号に対応するテンプレートは、
+/// `TemplateRegistry<AnyTemplate4>`に登録したデコーダで読み込む。`Section4::downcast`で、
+/// 具体的なテンプレートの第4節に変換できる。
+#[derive(Debug, Clone)]
+pub enum AnyTemplate4 {
+    /// テンプレート4.0
+    Template0(Template4_0),
+    /// テンプレート4.8
+    Template8(Template4_8),
+    /// テンプレート4.50000
+    Template50000(Template4_50000),
+    /// テンプレート4.50008
+    Template50008(Template4_50008),
+    /// テンプレート4.50009
+    Template50009(Template4_50009),
+    /// 対応していないテンプレート（テンプレートの内容は読み飛ばし、テンプレート番号のみ保持）
+    Unknown(u16),
+}
+
+impl AnyTemplate4 {
+    /// テンプレートに共通する項目を参照するプロダクト定義を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// プロダクト定義。対応していないテンプレートの場合は`None`
+    pub fn product_definition(&self) -> Option<&dyn ProductDefinition> {
+        match self {
+            Self::Template0(template) => Some(template),
+            Self::Template8(template) => Some(template),
+            Self::Template50000(template) => Some(template),
+            Self::Template50008(template) => Some(template),
+            Self::Template50009(template) => Some(template),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+/// `AnyTemplate4`のバリアントと具体的なテンプレートを対応付けて、具体的なテンプレートへの変換と
+/// 標準テンプレートを登録したテンプレートレジストリを実装するマクロ
+macro_rules! impl_any_template4 {
+    ($([$variant:ident, $template:ty, $template_number:ident]),*) => {
+        $(
+            impl TryFrom<AnyTemplate4> for $template {
+                type Error = AnyTemplate4;
+
+                fn try_from(template4: AnyTemplate4) -> Result<Self, Self::Error> {
+                    match template4 {
+                        AnyTemplate4::$variant(template) => Ok(template),
+                        other => Err(other),
+                    }
+                }
+            }
+        )*
+
+        impl TemplateRegistry<AnyTemplate4> {
+            /// 標準テンプレートを登録したテンプレートレジストリを構築する。
+            ///
+            /// # 戻り値
+            ///
+            /// テンプレートレジストリ
+            pub fn standard() -> Self {
+                let mut registry = Self::default();
+                $(
+                    registry.register(
+                        $template_number,
+                        Box::new(|mut reader| {
+                            Ok(AnyTemplate4::$variant(
+                                <$template as TemplateFromReader<u16>>::from_reader(
+                                    &mut reader,
+                                    $template_number,
+                                )?,
+                            ))
+                        }),
+                    );
+                )*
+
+                registry
+            }
+        }
+    };
+}
+
+impl_any_template4!(
+    [
+        Template0,
+        Template4_0,
+        DEFAULT_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+    ],
+    [
+        Template8,
+        Template4_8,
+        STAT_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+    ],
+    [
+        Template50000,
+        Template4_50000,
+        PROCESSED_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+    ],
+    [
+        Template50008,
+        Template4_50008,
+        RADAR_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+    ],
+    [
+        Template50009,
+        Template4_50009,
+        RADAR_FORECAST_PRODUCT_DEFINITION_TEMPLATE_NUMBER
+    ]
+);
+
+impl<W> DebugTemplate<W> for AnyTemplate4 {
+    fn debug_info_with(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        match self {
+            Self::Template0(template) => template.debug_info_with(writer, opts),
+            Self::Template8(template) => template.debug_info_with(writer, opts),
+            Self::Template50000(template) => template.debug_info_with(writer, opts),
+            Self::Template50008(template) => template.debug_info_with(writer, opts),
+            Self::Template50009(template) => template.debug_info_with(writer, opts),
+            Self::Unknown(template_number) => writeln!(
+                writer,
+                "{}テンプレート4.{}には対応していないため、読み飛ばしました。",
+                opts.indent(),
+                template_number
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DebugTemplateJson for AnyTemplate4 {
+    fn debug_info_json(&self) -> serde_json::Value {
+        match self {
+            Self::Template0(template) => template.debug_info_json(),
+            Self::Template8(template) => template.debug_info_json(),
+            Self::Template50000(template) => template.debug_info_json(),
+            Self::Template50008(template) => template.debug_info_json(),
+            Self::Template50009(template) => template.debug_info_json(),
+            Self::Unknown(template_number) => serde_json::json!({
+                "unsupported_template_number": template_number,
+            }),
+        }
+    }
+}
+
+/// 統計処理したプロダクトのテンプレートに、最初の統計処理の時間範囲の仕様の項目を返すメソッドを実装する。
+///
+/// 期間の仕様を1つだけ記録していたときのゲッターとの互換を保つために実装する。期間の仕様の数は
+/// 読み込み時に1以上であることを確認している。
+macro_rules! impl_first_time_range_spec {
+    ($($template:ty),*) => {
+        $(
+            impl $template {
+                /// 統計処理の種類を返す。
+                pub fn type_of_stat_proc(&self) -> u8 {
+                    self.time_range_specs[0].type_of_stat_proc
+                }
+
+                /// 統計処理の時間増分の種類を返す。
+                pub fn type_of_stat_proc_time_increment(&self) -> u8 {
+                    self.time_range_specs[0].type_of_stat_proc_time_increment
+                }
+
+                /// 統計処理の時間の単位の指示符を返す。
+                pub fn stat_proc_time_unit(&self) -> u8 {
+                    self.time_range_specs[0].stat_proc_time_unit
+                }
+
+                /// 統計処理した時間の長さを返す。
+                pub fn stat_proc_time_length(&self) -> u32 {
+                    self.time_range_specs[0].stat_proc_time_length
+                }
+
+                /// 連続的な資料場間の増分に関する時間の単位の指示符を返す。
+                pub fn successive_time_unit(&self) -> u8 {
+                    self.time_range_specs[0].successive_time_unit
+                }
+
+                /// 連続的な資料場間の時間の増分を返す。
+                pub fn successive_time_increment(&self) -> u32 {
+                    self.time_range_specs[0].successive_time_increment
+                }
+            }
+        )*
+    };
+}
+
+impl_first_time_range_spec!(Template4_8, Template4_50008, Template4_50009);
+
+/// テンプレート4に共通する項目を返すプロダクト定義
+pub trait ProductDefinition {
+    /// パラメータカテゴリーを返す。
+    fn parameter_category(&self) -> u8;
+
+    /// パラメータ番号を返す。
+    fn parameter_number(&self) -> u8;
+
+    /// パラメータカテゴリーとパラメータ番号から、気象分野のパラメータの名前を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// パラメータの名前。表に収録されていないパラメータの場合は`None`
+    fn parameter_name(&self) -> Option<&'static str> {
+        parameter_name(self.parameter_category(), self.parameter_number())
+    }
+
+    /// 作成処理の種類を返す。
+    fn type_of_generating_process(&self) -> u8;
+
+    /// 作成処理の種類を、符号表4.3の列挙型で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 作成処理の種類。符号表に定義されていない値の場合はエラー
+    fn generating_process_kind(&self) -> ReaderResult<GeneratingProcess> {
+        let code = self.type_of_generating_process();
+        GeneratingProcess::try_from(code).map_err(|e| {
+            ReaderError::Unexpected(
+                format!("第4節:作成処理の種類({})を変換できません。{}", code, e).into(),
+            )
+        })
+    }
+
+    /// 予報の作成処理識別符を返す。
+    fn generating_process_identifier(&self) -> u8;
+
+    /// 期間の単位の指示符を返す。
+    fn indicator_of_unit_of_time_range(&self) -> u8;
+
+    /// 予報時間を返す。
+    fn forecast_time(&self) -> i32;
+
+    /// 第一固定面の種類を返す。
+    fn type_of_first_fixed_surface(&self) -> u8;
+}
+
+macro_rules! impl_product_definition {
+    ($template:ty) => {
+        impl ProductDefinition for $template {
+            fn parameter_category(&self) -> u8 {
+                self.parameter_category
+            }
+
+            fn parameter_number(&self) -> u8 {
+                self.parameter_number
+            }
+
+            fn type_of_generating_process(&self) -> u8 {
+                self.type_of_generating_process
+            }
+
+            fn generating_process_identifier(&self) -> u8 {
+                self.generating_process_identifier
+            }
+
+            fn indicator_of_unit_of_time_range(&self) -> u8 {
+                self.indicator_of_unit_of_time_range
+            }
+
+            fn forecast_time(&self) -> i32 {
+                self.forecast_time
+            }
+
+            fn type_of_first_fixed_surface(&self) -> u8 {
+                self.type_of_first_fixed_surface
+            }
+        }
+    };
+}
+
+impl_product_definition!(Template4_0);
+impl_product_definition!(Template4_8);
+impl_product_definition!(Template4_50000);
+impl_product_definition!(Template4_50008);
+impl_product_definition!(Template4_50009);
+
+impl Section4<AnyTemplate4> {
+    /// GRIB2ファイルから、テンプレートの型を決めずに第4節:プロダクト定義節を読み込む。
+    ///
+    /// 標準テンプレートを登録したテンプレートレジストリで、テンプレート4を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// 第4節: プロダクト定義節
+    pub fn from_reader_dynamic<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        Self::from_reader_dynamic_with_registry(
+            reader,
+            &TemplateRegistry::<AnyTemplate4>::standard(),
+        )
+    }
+
+    /// GRIB2ファイルから、テンプレートの型を決めずに第4節:プロダクト定義節を読み込む。
+    ///
+    /// プロダクト定義テンプレート番号を読み込んだ後、テンプレート番号に対応するレジストリの
+    /// デコーダでテンプレートを読み込む。デコーダが登録されていないテンプレート番号の場合は、
+    /// テンプレートを読み飛ばして`AnyTemplate4::Unknown`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `registry` - テンプレートレジストリ
     ///
     /// # 戻り値
     ///
-    /// テンプレート
-    fn from_reader(
-        reader: &mut FileReader,
-        template_number: T,
-        template_bytes: usize,
-    ) -> ReaderResult<Self>
-    where
-        Self: Sized;
-}
+    /// 第4節: プロダクト定義節
+    pub fn from_reader_dynamic_with_registry<R: Read + Seek>(
+        reader: &mut R,
+        registry: &TemplateRegistry<AnyTemplate4>,
+    ) -> ReaderResult<Self> {
+        let section_bytes = peek_u32(reader, 0, "第4節:節の長さ")? as usize;
+        Self::read_with(reader, |reader, template_number| {
+            if registry.contains(template_number) {
+                return registry.decode(reader, template_number);
+            }
+            // 節の長さ(4バイト)、節番号(1バイト)、テンプレート直後の座標値の数(2バイト)、
+            // プロダクト定義テンプレート番号(2バイト)を除いたテンプレートを読み飛ばす
+            let template_bytes = section_bytes.checked_sub(4 + 1 + 2 + 2).ok_or_else(|| {
+                ReaderError::Unexpected(
+                    format!("第4節:節の長さ({})が不正です。", section_bytes).into(),
+                )
+            })?;
+            debug_log!(
+                "第4節: 未対応のテンプレート {} バイトを読み飛ばし",
+                template_bytes
+            );
+            reader.seek_relative(template_bytes as i64).map_err(|_| {
+                ReaderError::ReadError("第4節:テンプレートの読み飛ばしに失敗しました。".into())
+            })?;
+            Ok(AnyTemplate4::Unknown(template_number))
+        })
+    }
 
-pub trait DebugTemplate<W> {
-    /// テンプレートのデバッグ情報を出力する。
+    /// テンプレート4を具体的なテンプレートに変換した第4節を返す。
     ///
-    /// # 引数
+    /// ```text
+    /// let section4 = Section4::from_reader_dynamic(&mut reader)?;
+    /// if let Some(section4) = section4.downcast::<Template4_50008>() {
+    ///     println!("{:?}", section4.stat_proc_hours());
+    /// }
+    /// ```
     ///
-    /// * `writer` - 出力先
-    fn debug_info(&self, writer: &mut W) -> std::io::Result<()>
+    /// # 戻り値
+    ///
+    /// 具体的なテンプレートの第4節。テンプレートが一致しない場合は`None`
+    pub fn downcast<T>(self) -> Option<Section4<T>>
     where
-        W: std::io::Write;
+        T: TryFrom<AnyTemplate4, Error = AnyTemplate4>,
+    {
+        let template4 = T::try_from(self.template4).ok()?;
+
+        Some(Section4 {
+            section_bytes: self.section_bytes,
+            number_of_after_template_points: self.number_of_after_template_points,
+            product_definition_template_number: self.product_definition_template_number,
+            template4,
+        })
+    }
 }
 
-pub type Section3_0 = Section3<Template3_0>;
-pub type Section4_0 = Section4<Template4_0>;
-pub type Section4_50000 = Section4<Template4_50000>;
-pub type Section4_50008 = Section4<Template4_50008>;
-pub type Section4_50009 = Section4<Template4_50009>;
-pub type Section5_200u16 = Section5<Template5_200u16>;
-pub type Section5_200i16 = Section5<Template5_200i16>;
-pub type Section7_200 = Section7<Template7_200>;
+/// 資料表現テンプレート番号で区別した第5節:資料表現節
+///
+/// テンプレート5.200のレベル値は、符号なし整数として保持する。
+#[derive(Debug, Clone)]
+pub enum Section5Variant {
+    /// テンプレート5.200
+    Template200(Section5_200u16),
+}
+
+impl FromReader for Section5Variant {
+    fn from_reader<R: Read + Seek>(reader: &mut R) -> ReaderResult<Self> {
+        // 節の長さ(4バイト)、節番号(1バイト)、全資料点の数(4バイト)の後に記録されている
+        // 資料表現テンプレート番号で区別
+        let template_number = peek_u16(reader, 4 + 1 + 4, "第5節:資料表現テンプレート番号")?;
+        match template_number {
+            RUN_LENGTH_DATA_REPRESENTATION_TEMPLATE_NUMBER => {
+                Ok(Self::Template200(Section5_200u16::from_reader(reader)?))
+            }
+            _ => Err(ReaderError::Unexpected(
+                format!(
+                    "第5節:資料表現テンプレート番号{}には対応していません。",
+                    template_number
+                )
+                .into(),
+            )),
+        }
+    }
+}
 
 impl Section3_0 {
     /// 地球の形状を返す。
@@ -1829,6 +4485,162 @@ impl Section3_0 {
     pub fn scanning_mode(&self) -> u8 {
         self.template3.scanning_mode
     }
+
+    /// 格子の原点座標と画素の大きさから、GDAL互換のアフィン変換係数を返す。
+    ///
+    /// 原点は最も北西の格子の北西端で、格子点は画素の中心にあるものとする。
+    /// 格子は北から南に並ぶため、画素の高さは負の値になる。
+    ///
+    /// # 戻り値
+    ///
+    /// `[原点の経度, 画素の幅, 回転, 原点の緯度, 回転, 画素の高さ]`（度単位）
+    pub fn affine_transform(&self) -> [f64; 6] {
+        let t = &self.template3;
+        let west = t.lon_of_first_grid_point.min(t.lon_of_last_grid_point) as f64;
+        let north = t.lat_of_first_grid_point.max(t.lat_of_last_grid_point) as f64;
+        let width = t.i_direction_increment as f64;
+        let height = t.j_direction_increment as f64;
+
+        [
+            (west - width / 2.0) / 1_000_000.0,
+            width / 1_000_000.0,
+            0.0,
+            (north + height / 2.0) / 1_000_000.0,
+            0.0,
+            -height / 1_000_000.0,
+        ]
+    }
+
+    /// 格子系定義を差し替えた第3節を返す。
+    ///
+    /// 資料点数、緯線及び経線に沿った格子点数、並びに最初及び最後の格子点を、格子系定義の値に
+    /// 更新する。領域を切り出したGRIB報を構築するときに使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `definition` - 格子系定義
+    ///
+    /// # 戻り値
+    ///
+    /// 格子系定義を差し替えた第3節
+    pub(crate) fn with_grid_definition(&self, definition: &GridDefinition) -> Self {
+        let mut section3 = *self;
+        section3.number_of_data_points =
+            definition.number_of_along_lat_points() * definition.number_of_along_lon_points();
+        let t = &mut section3.template3;
+        t.number_of_along_lat_points = definition.number_of_along_lat_points();
+        t.number_of_along_lon_points = definition.number_of_along_lon_points();
+        t.lat_of_first_grid_point = definition.lat_of_first_grid_point();
+        t.lon_of_first_grid_point = definition.lon_of_first_grid_point();
+        t.lat_of_last_grid_point = definition.lat_of_last_grid_point();
+        t.lon_of_last_grid_point = definition.lon_of_last_grid_point();
+
+        section3
+    }
+
+    /// 走査モードと、最初及び最後の格子点の座標の大小関係が整合するか確認する。
+    ///
+    /// i方向が正（西から東）の場合は最後の格子点の経度が最初の格子点の経度以上、負の場合は以下で
+    /// なければならない。j方向が負（北から南）の場合は最後の格子点の緯度が最初の格子点の緯度以下、
+    /// 正の場合は以上でなければならない。また、格子点数を増分から求めるため、i方向及びj方向の
+    /// 増分は0であってはならない。
+    pub fn validate_scanning_mode(&self) -> ReaderResult<()> {
+        let t = &self.template3;
+        for (name, increment) in [
+            ("i方向の増分", t.i_direction_increment),
+            ("j方向の増分", t.j_direction_increment),
+        ] {
+            if increment == 0 {
+                return Err(ReaderError::Unexpected(
+                    format!("第3節:{}が0です。", name).into(),
+                ));
+            }
+        }
+        let i_negative = t.scanning_mode & 0x80 != 0;
+        let j_positive = t.scanning_mode & 0x40 != 0;
+        let lon_consistent = match i_negative {
+            false => t.lon_of_first_grid_point <= t.lon_of_last_grid_point,
+            true => t.lon_of_last_grid_point <= t.lon_of_first_grid_point,
+        };
+        if !lon_consistent {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "第3節:走査モード(0x{:02X})のi方向と、最初の格子点の経度({})及び最後の格子点の経度({})の大小関係が矛盾しています。",
+                    t.scanning_mode, t.lon_of_first_grid_point, t.lon_of_last_grid_point
+                )
+                .into(),
+            ));
+        }
+        let lat_consistent = match j_positive {
+            false => t.lat_of_last_grid_point <= t.lat_of_first_grid_point,
+            true => t.lat_of_first_grid_point <= t.lat_of_last_grid_point,
+        };
+        if !lat_consistent {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "第3節:走査モード(0x{:02X})のj方向と、最初の格子点の緯度({})及び最後の格子点の緯度({})の大小関係が矛盾しています。",
+                    t.scanning_mode, t.lat_of_first_grid_point, t.lat_of_last_grid_point
+                )
+                .into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 格子点数、増分及び最初の格子点を比較して、一致しないフィールドを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `other` - 比較する第3節
+    ///
+    /// # 戻り値
+    ///
+    /// 一致しないフィールドの名前と、それぞれの第3節に記録されている値を記述した文字列
+    pub(crate) fn grid_differences(&self, other: &Self) -> Vec<String> {
+        let (a, b) = (&self.template3, &other.template3);
+        [
+            (
+                "格子点数",
+                self.number_of_data_points,
+                other.number_of_data_points,
+            ),
+            (
+                "緯線に沿った格子点数",
+                a.number_of_along_lat_points,
+                b.number_of_along_lat_points,
+            ),
+            (
+                "経線に沿った格子点数",
+                a.number_of_along_lon_points,
+                b.number_of_along_lon_points,
+            ),
+            (
+                "i方向の増分",
+                a.i_direction_increment,
+                b.i_direction_increment,
+            ),
+            (
+                "j方向の増分",
+                a.j_direction_increment,
+                b.j_direction_increment,
+            ),
+            (
+                "最初の格子点の緯度",
+                a.lat_of_first_grid_point,
+                b.lat_of_first_grid_point,
+            ),
+            (
+                "最初の格子点の経度",
+                a.lon_of_first_grid_point,
+                b.lon_of_first_grid_point,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, a, b)| a != b)
+        .map(|(name, a, b)| format!("{}({}と{})", name, a, b))
+        .collect()
+    }
 }
 
 impl Section4_0 {
@@ -1906,6 +4718,38 @@ impl Section4_0 {
     pub fn scaled_value_of_second_fixed_surface(&self) -> u32 {
         self.template4.scaled_value_of_second_fixed_surface
     }
+
+    /// 第一固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn first_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_first_fixed_surface,
+            self.template4.scaled_value_of_first_fixed_surface,
+        )
+    }
+
+    /// 第二固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn second_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_second_fixed_surface,
+            self.template4.scaled_value_of_second_fixed_surface,
+        )
+    }
+
+    /// 第一固定面から土壌雨量指数のタンクを判定して返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 土壌雨量指数のタンク。第一固定面からタンクを判定できない場合は`None`
+    pub fn tank(&self) -> Option<PswTank> {
+        psw_tank(
+            self.template4.type_of_first_fixed_surface,
+            self.template4.scaled_value_of_first_fixed_surface,
+        )
+    }
 }
 
 impl Section4_50000 {
@@ -1964,54 +4808,254 @@ impl Section4_50000 {
         self.template4.scale_factor_of_first_fixed_surface
     }
 
-    /// 第一固定面の尺度付きの値を返す。
-    pub fn scaled_value_of_first_fixed_surface(&self) -> u32 {
-        self.template4.scaled_value_of_first_fixed_surface
+    /// 第一固定面の尺度付きの値を返す。
+    pub fn scaled_value_of_first_fixed_surface(&self) -> u32 {
+        self.template4.scaled_value_of_first_fixed_surface
+    }
+
+    /// 第二固定面の種類を返す。
+    pub fn type_of_second_fixed_surface(&self) -> u8 {
+        self.template4.type_of_second_fixed_surface
+    }
+
+    /// 第二固定面の尺度因子を返す。
+    pub fn scale_factor_of_second_fixed_surface(&self) -> u8 {
+        self.template4.scale_factor_of_second_fixed_surface
+    }
+
+    /// 第二固定面の尺度付きの値を返す。
+    pub fn scaled_value_of_second_fixed_surface(&self) -> u32 {
+        self.template4.scaled_value_of_second_fixed_surface
+    }
+
+    /// 第一固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn first_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_first_fixed_surface,
+            self.template4.scaled_value_of_first_fixed_surface,
+        )
+    }
+
+    /// 第二固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn second_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_second_fixed_surface,
+            self.template4.scaled_value_of_second_fixed_surface,
+        )
+    }
+
+    /// 資料作成に用いた関連資料の名称1を返す。
+    pub fn source_document1(&self) -> u8 {
+        self.template4.source_document1
+    }
+
+    /// 資料作成に用いた関連資料の時間（時）1を返す。
+    pub fn hours_from_source_document1(&self) -> u16 {
+        self.template4.hours_from_source_document1
+    }
+
+    /// 資料作成に用いた関連資料の時間（分）1を返す。
+    pub fn minutes_from_source_document1(&self) -> u8 {
+        self.template4.minutes_from_source_document1
+    }
+
+    /// 資料作成に用いた関連資料の名称2を返す。
+    pub fn source_document2(&self) -> u8 {
+        self.template4.source_document2
+    }
+
+    /// 資料作成に用いた関連資料の時間（時）2を返す。
+    pub fn hours_from_source_document2(&self) -> u16 {
+        self.template4.hours_from_source_document2
+    }
+
+    /// 資料作成に用いた関連資料の時間（分）2を返す。
+    pub fn minutes_from_source_document2(&self) -> u8 {
+        self.template4.minutes_from_source_document2
+    }
+
+    /// 期間の単位の指示符に従って、予報時間を期間に変換して返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 予報時間
+    pub fn forecast_duration(&self) -> ReaderResult<Duration> {
+        forecast_duration(
+            self.template4.indicator_of_unit_of_time_range,
+            self.template4.forecast_time,
+        )
+    }
+}
+
+impl Section4_8 {
+    /// パラメータカテゴリーを返す。
+    pub fn parameter_category(&self) -> u8 {
+        self.template4.parameter_category
+    }
+
+    /// パラメータ番号を返す。
+    pub fn parameter_number(&self) -> u8 {
+        self.template4.parameter_number
+    }
+
+    /// 作成処理の種類を返す。
+    pub fn type_of_generating_process(&self) -> u8 {
+        self.template4.type_of_generating_process
+    }
+
+    /// 背景作成処理識別符を返す。
+    pub fn background_process(&self) -> u8 {
+        self.template4.background_process
+    }
+
+    /// 予報の作成処理識別符を返す。
+    pub fn generating_process_identifier(&self) -> u8 {
+        self.template4.generating_process_identifier
+    }
+
+    /// 観測資料の参照時刻からの締切時間（時）を返す。
+    pub fn hours_after_data_cutoff(&self) -> u16 {
+        self.template4.hours_after_data_cutoff
+    }
+
+    /// 観測資料の参照時刻からの締切時間（分）を返す。
+    pub fn minutes_after_data_cutoff(&self) -> u8 {
+        self.template4.minutes_after_data_cutoff
+    }
+
+    /// 期間の単位の指示符を返す。
+    pub fn indicator_of_unit_of_time_range(&self) -> u8 {
+        self.template4.indicator_of_unit_of_time_range
+    }
+
+    /// 予報時間を返す。
+    pub fn forecast_time(&self) -> i32 {
+        self.template4.forecast_time
+    }
+
+    /// 第一固定面の種類を返す。
+    pub fn type_of_first_fixed_surface(&self) -> u8 {
+        self.template4.type_of_first_fixed_surface
+    }
+
+    /// 第一固定面の尺度因子を返す。
+    pub fn scale_factor_of_first_fixed_surface(&self) -> u8 {
+        self.template4.scale_factor_of_first_fixed_surface
+    }
+
+    /// 第一固定面の尺度付きの値を返す。
+    pub fn scaled_value_of_first_fixed_surface(&self) -> u32 {
+        self.template4.scaled_value_of_first_fixed_surface
+    }
+
+    /// 第二固定面の種類を返す。
+    pub fn type_of_second_fixed_surface(&self) -> u8 {
+        self.template4.type_of_second_fixed_surface
+    }
+
+    /// 第二固定面の尺度因子を返す。
+    pub fn scale_factor_of_second_fixed_surface(&self) -> u8 {
+        self.template4.scale_factor_of_second_fixed_surface
+    }
+
+    /// 第二固定面の尺度付きの値を返す。
+    pub fn scaled_value_of_second_fixed_surface(&self) -> u32 {
+        self.template4.scaled_value_of_second_fixed_surface
+    }
+
+    /// 第一固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn first_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_first_fixed_surface,
+            self.template4.scaled_value_of_first_fixed_surface,
+        )
+    }
+
+    /// 第二固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn second_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_second_fixed_surface,
+            self.template4.scaled_value_of_second_fixed_surface,
+        )
+    }
+
+    /// 全時間間隔の終了時(UTC)を返す。
+    pub fn end_of_all_time_intervals(&self) -> OffsetDateTime {
+        self.template4.end_of_all_time_intervals
+    }
+
+    /// 統計を算出するために使用した時間間隔を記述する期間の仕様の数を返す。
+    pub fn number_of_time_range_specs(&self) -> u8 {
+        self.template4.number_of_time_range_specs
+    }
+
+    /// 統計処理における欠測資料の総数を返す。
+    pub fn number_of_missing_values(&self) -> u32 {
+        self.template4.number_of_missing_values
     }
 
-    /// 第二固定面の種類を返す。
-    pub fn type_of_second_fixed_surface(&self) -> u8 {
-        self.template4.type_of_second_fixed_surface
+    /// 統計処理の時間範囲の仕様を返す。
+    pub fn time_range_specs(&self) -> &[TimeRangeSpec] {
+        &self.template4.time_range_specs
     }
 
-    /// 第二固定面の尺度因子を返す。
-    pub fn scale_factor_of_second_fixed_surface(&self) -> u8 {
-        self.template4.scale_factor_of_second_fixed_surface
+    /// 統計処理の種類を返す。
+    pub fn type_of_stat_proc(&self) -> u8 {
+        self.template4.type_of_stat_proc()
     }
 
-    /// 第二固定面の尺度付きの値を返す。
-    pub fn scaled_value_of_second_fixed_surface(&self) -> u32 {
-        self.template4.scaled_value_of_second_fixed_surface
+    /// 統計処理の種類を、符号表4.10の列挙型で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 統計処理の種類。符号表に定義されていない値の場合はエラー
+    pub fn stat_proc_kind(&self) -> ReaderResult<StatProc> {
+        stat_proc_kind(self.template4.type_of_stat_proc())
     }
 
-    /// 資料作成に用いた関連資料の名称1を返す。
-    pub fn source_document1(&self) -> u8 {
-        self.template4.source_document1
+    /// 統計処理の時間増分の種類を返す。
+    pub fn type_of_stat_proc_time_increment(&self) -> u8 {
+        self.template4.type_of_stat_proc_time_increment()
     }
 
-    /// 資料作成に用いた関連資料の時間（時）1を返す。
-    pub fn hours_from_source_document1(&self) -> u16 {
-        self.template4.hours_from_source_document1
+    /// 統計処理の時間の単位の指示符を返す。
+    pub fn stat_proc_time_unit(&self) -> u8 {
+        self.template4.stat_proc_time_unit()
     }
 
-    /// 資料作成に用いた関連資料の時間（分）1を返す。
-    pub fn minutes_from_source_document1(&self) -> u8 {
-        self.template4.minutes_from_source_document1
+    /// 統計処理した時間の長さを返す。
+    pub fn stat_proc_time_length(&self) -> u32 {
+        self.template4.stat_proc_time_length()
     }
 
-    /// 資料作成に用いた関連資料の名称2を返す。
-    pub fn source_document2(&self) -> u8 {
-        self.template4.source_document2
+    /// 連続的な資料場間の増分に関する時間の単位の指示符を返す。
+    pub fn successive_time_unit(&self) -> u8 {
+        self.template4.successive_time_unit()
     }
 
-    /// 資料作成に用いた関連資料の時間（時）2を返す。
-    pub fn hours_from_source_document2(&self) -> u16 {
-        self.template4.hours_from_source_document2
+    /// 連続的な資料場間の時間の増分を返す。
+    pub fn successive_time_increment(&self) -> u32 {
+        self.template4.successive_time_increment()
     }
 
-    /// 資料作成に用いた関連資料の時間（分）2を返す。
-    pub fn minutes_from_source_document2(&self) -> u8 {
-        self.template4.minutes_from_source_document2
+    /// 統計処理した時間の長さを時間単位で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 統計処理した時間の長さ（時間）。統計処理の時間の単位が不明な場合は`None`
+    pub fn stat_proc_hours(&self) -> Option<f64> {
+        stat_proc_hours(
+            self.template4.stat_proc_time_unit(),
+            self.template4.stat_proc_time_length(),
+        )
     }
 }
 
@@ -2091,6 +5135,26 @@ impl Section4_50008 {
         self.template4.scaled_value_of_second_fixed_surface
     }
 
+    /// 第一固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn first_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_first_fixed_surface,
+            self.template4.scaled_value_of_first_fixed_surface,
+        )
+    }
+
+    /// 第二固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn second_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_second_fixed_surface,
+            self.template4.scaled_value_of_second_fixed_surface,
+        )
+    }
+
     /// 全時間間隔の終了時(UTC)を返す。
     pub fn end_of_all_time_intervals(&self) -> OffsetDateTime {
         self.template4.end_of_all_time_intervals
@@ -2106,34 +5170,48 @@ impl Section4_50008 {
         self.template4.number_of_missing_values
     }
 
+    /// 統計処理の時間範囲の仕様を返す。
+    pub fn time_range_specs(&self) -> &[TimeRangeSpec] {
+        &self.template4.time_range_specs
+    }
+
     /// 統計処理の種類を返す。
     pub fn type_of_stat_proc(&self) -> u8 {
-        self.template4.type_of_stat_proc
+        self.template4.type_of_stat_proc()
+    }
+
+    /// 統計処理の種類を、符号表4.10の列挙型で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 統計処理の種類。符号表に定義されていない値の場合はエラー
+    pub fn stat_proc_kind(&self) -> ReaderResult<StatProc> {
+        stat_proc_kind(self.template4.type_of_stat_proc())
     }
 
     /// 統計処理の時間増分の種類を返す。
     pub fn type_of_stat_proc_time_increment(&self) -> u8 {
-        self.template4.type_of_stat_proc_time_increment
+        self.template4.type_of_stat_proc_time_increment()
     }
 
     /// 統計処理の時間の単位の指示符を返す。
     pub fn stat_proc_time_unit(&self) -> u8 {
-        self.template4.stat_proc_time_unit
+        self.template4.stat_proc_time_unit()
     }
 
     /// 統計処理した時間の長さを返す。
     pub fn stat_proc_time_length(&self) -> u32 {
-        self.template4.stat_proc_time_length
+        self.template4.stat_proc_time_length()
     }
 
     /// 連続的な資料場間の増分に関する時間の単位の指示符を返す。
     pub fn successive_time_unit(&self) -> u8 {
-        self.template4.successive_time_unit
+        self.template4.successive_time_unit()
     }
 
     /// 連続的な資料場間の時間の増分を返す。
     pub fn successive_time_increment(&self) -> u32 {
-        self.template4.successive_time_increment
+        self.template4.successive_time_increment()
     }
 
     /// レーダー等運用情報その1を返す。
@@ -2150,6 +5228,58 @@ impl Section4_50008 {
     pub fn rain_gauge_info(&self) -> u64 {
         self.template4.rain_gauge_info
     }
+
+    /// 統計処理した時間の長さを時間単位で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 統計処理した時間の長さ（時間）。統計処理の時間の単位が不明な場合は`None`
+    pub fn stat_proc_hours(&self) -> Option<f64> {
+        stat_proc_hours(
+            self.template4.stat_proc_time_unit(),
+            self.template4.stat_proc_time_length(),
+        )
+    }
+
+    /// 期間の単位の指示符に従って、予報時間を期間に変換して返す。
+    ///
+    /// 期間の単位の指示符（符号表4.4）は、分、時、日、3時間、6時間、12時間及び秒に対応する。
+    /// 月や年など長さが一定でない単位と、符号表に定義されていない単位はエラーとする。
+    ///
+    /// # 戻り値
+    ///
+    /// 予報時間
+    pub fn forecast_duration(&self) -> ReaderResult<Duration> {
+        forecast_duration(
+            self.template4.indicator_of_unit_of_time_range,
+            self.template4.forecast_time,
+        )
+    }
+
+    /// 資料の参照時刻に予報時間を加えた、予報の対象時刻を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `referenced_at` - 資料の参照時刻
+    ///
+    /// # 戻り値
+    ///
+    /// 予報の対象時刻
+    pub fn forecast_valid_time(
+        &self,
+        referenced_at: OffsetDateTime,
+    ) -> ReaderResult<OffsetDateTime> {
+        let duration = self.forecast_duration()?;
+        referenced_at.checked_add(duration).ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!(
+                    "資料の参照時刻({})に予報時間({})を加えた予報の対象時刻を表現できません。",
+                    referenced_at, duration
+                )
+                .into(),
+            )
+        })
+    }
 }
 
 impl Section4_50009 {
@@ -2228,6 +5358,26 @@ impl Section4_50009 {
         self.template4.scaled_value_of_second_fixed_surface
     }
 
+    /// 第一固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn first_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_first_fixed_surface,
+            self.template4.scaled_value_of_first_fixed_surface,
+        )
+    }
+
+    /// 第二固定面の値（尺度付きの値 / 10^尺度因子）を返す。
+    ///
+    /// 尺度因子または尺度付きの値が欠測（全ビット1: 0xFF / 0xFFFFFFFF）の場合は`None`を返す。
+    pub fn second_fixed_surface_value(&self) -> Option<f64> {
+        fixed_surface_value(
+            self.template4.scale_factor_of_second_fixed_surface,
+            self.template4.scaled_value_of_second_fixed_surface,
+        )
+    }
+
     /// 全時間間隔の終了時(UTC)を返す。
     pub fn end_of_all_time_intervals(&self) -> OffsetDateTime {
         self.template4.end_of_all_time_intervals
@@ -2243,34 +5393,48 @@ impl Section4_50009 {
         self.template4.number_of_missing_values
     }
 
+    /// 統計処理の時間範囲の仕様を返す。
+    pub fn time_range_specs(&self) -> &[TimeRangeSpec] {
+        &self.template4.time_range_specs
+    }
+
     /// 統計処理の種類を返す。
     pub fn type_of_stat_proc(&self) -> u8 {
-        self.template4.type_of_stat_proc
+        self.template4.type_of_stat_proc()
+    }
+
+    /// 統計処理の種類を、符号表4.10の列挙型で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 統計処理の種類。符号表に定義されていない値の場合はエラー
+    pub fn stat_proc_kind(&self) -> ReaderResult<StatProc> {
+        stat_proc_kind(self.template4.type_of_stat_proc())
     }
 
     /// 統計処理の時間増分の種類を返す。
     pub fn type_of_stat_proc_time_increment(&self) -> u8 {
-        self.template4.type_of_stat_proc_time_increment
+        self.template4.type_of_stat_proc_time_increment()
     }
 
     /// 統計処理の時間の単位の指示符を返す。
     pub fn stat_proc_time_unit(&self) -> u8 {
-        self.template4.stat_proc_time_unit
+        self.template4.stat_proc_time_unit()
     }
 
     /// 統計処理した時間の長さを返す。
     pub fn stat_proc_time_length(&self) -> u32 {
-        self.template4.stat_proc_time_length
+        self.template4.stat_proc_time_length()
     }
 
     /// 連続的な資料場間の増分に関する時間の単位の指示符を返す。
     pub fn successive_time_unit(&self) -> u8 {
-        self.template4.successive_time_unit
+        self.template4.successive_time_unit()
     }
 
     /// 連続的な資料場間の時間の増分を返す。
     pub fn successive_time_increment(&self) -> u32 {
-        self.template4.successive_time_increment
+        self.template4.successive_time_increment()
     }
 
     /// レーダー等運用情報その1を返す。
@@ -2304,58 +5468,1267 @@ impl Section4_50009 {
     }
 }
 
-impl Section5_200u16 {
-    /// 今回の圧縮に用いたレベルの最大値を返す。
-    pub fn max_level_value(&self) -> u16 {
-        self.template5.max_level_value
+impl Section7_200 {
+    /// ランレングス圧縮符号列のバイト数から第7節を構築する。
+    ///
+    /// ファイルから読み込んでいないため、ランレングス圧縮符号列の開始位置は0とする。
+    ///
+    /// # 引数
+    ///
+    /// * `run_length_bytes` - ランレングス圧縮符号列のバイト数
+    ///
+    /// # 戻り値
+    ///
+    /// 第7節:資料節
+    pub(crate) fn from_run_length_bytes(run_length_bytes: usize) -> Self {
+        Self {
+            section_bytes: 4 + 1 + run_length_bytes,
+            template7: Template7_200 {
+                run_length_position: 0,
+                run_length_bytes,
+            },
+        }
+    }
+
+    /// ランレングス圧縮符号列の開始位置を返す。
+    pub fn run_length_position(&self) -> usize {
+        self.template7.run_length_position
+    }
+
+    /// ランレングス圧縮符号のバイト数を返す。
+    pub fn run_length_bytes(&self) -> usize {
+        self.template7.run_length_bytes
+    }
+}
+
+/// 第0節から第3節までを記録したGRIB2ファイルのバイト列を返す。
+///
+/// 第3節は、1kmメッシュ解析雨量と同じ2,560列×3,360行の緯度・経度格子である。
+///
+/// # 引数
+///
+/// * `total_length` - 第0節に記録するGRIB報全体のバイト数
+///
+/// # 戻り値
+///
+/// 第0節から第3節までのバイト列
+#[cfg(test)]
+pub(crate) fn section0_to_section3_bytes(total_length: u64) -> Vec<u8> {
+    // 第0節
+    let mut bytes = b"GRIB".to_vec();
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x02]);
+    bytes.extend_from_slice(&total_length.to_be_bytes());
+    // 第1節
+    bytes.extend_from_slice(&SECTION1_BYTES.to_be_bytes());
+    bytes.extend_from_slice(&[0x01, 0x00, 0x22, 0x00, 0x00, 0x02, 0x01, 0x00]);
+    bytes.extend_from_slice(&2024u16.to_be_bytes());
+    bytes.extend_from_slice(&[0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0xC8]);
+    // 第3節
+    bytes.extend_from_slice(&72u32.to_be_bytes());
+    bytes.extend_from_slice(&[0x03, 0x00]);
+    bytes.extend_from_slice(&(2_560u32 * 3_360).to_be_bytes());
+    bytes.extend_from_slice(&[0x00, 0x00]);
+    bytes.extend_from_slice(&LAT_LON_GRID_DEFINITION_TEMPLATE_NUMBER.to_be_bytes());
+    bytes.extend_from_slice(&[0x04, 0x00]);
+    bytes.extend_from_slice(&[0x00; 4 + 1 + 4 + 1 + 4]);
+    for value in [2_560u32, 3_360, 0, 0, 47_995_833, 118_006_250] {
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    bytes.push(0x30);
+    for value in [20_004_167u32, 149_993_750, 12_500, 8_333] {
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    bytes.push(0x00);
+
+    bytes
+}
+
+/// 2列×2行の緯度・経度格子を記録した1kmメッシュ解析雨量のGRIB2ファイルのバイト列を返す。
+///
+/// 格子の増分は1kmメッシュ解析雨量と同じで、レベル別物理値はレベル1から順に10、20、30である。
+///
+/// # 引数
+///
+/// * `lat_of_first_grid_point` - 最初の格子点（北西端）の緯度（10e-6度単位）
+/// * `levels` - 北西端から東方向、次に南方向に走査した各格子のレベル値（0は欠測値）
+///
+/// # 戻り値
+///
+/// 1kmメッシュ解析雨量のGRIB2ファイルのバイト列
+#[cfg(test)]
+pub(crate) fn small_prr_bytes(lat_of_first_grid_point: u32, levels: [u8; 4]) -> Vec<u8> {
+    let (lon_of_first_grid_point, i_inc, j_inc) = (118_006_250u32, 12_500u32, 8_333u32);
+    // 第0節（GRIB報全体のバイト数は最後に記録）
+    let mut bytes = b"GRIB".to_vec();
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x02]);
+    bytes.extend_from_slice(&0u64.to_be_bytes());
+    // 第1節
+    bytes.extend_from_slice(&SECTION1_BYTES.to_be_bytes());
+    bytes.extend_from_slice(&[0x01, 0x00, 0x22, 0x00, 0x00, 0x02, 0x01, 0x00]);
+    bytes.extend_from_slice(&2024u16.to_be_bytes());
+    bytes.extend_from_slice(&[0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0xC8]);
+    // 第3節
+    bytes.extend_from_slice(&72u32.to_be_bytes());
+    bytes.extend_from_slice(&[0x03, 0x00]);
+    bytes.extend_from_slice(&4u32.to_be_bytes());
+    bytes.extend_from_slice(&[0x00, 0x00]);
+    bytes.extend_from_slice(&LAT_LON_GRID_DEFINITION_TEMPLATE_NUMBER.to_be_bytes());
+    bytes.extend_from_slice(&[0x04, 0x00]);
+    bytes.extend_from_slice(&[0x00; 4 + 1 + 4 + 1 + 4]);
+    for value in [2, 2, 0, 0, lat_of_first_grid_point, lon_of_first_grid_point] {
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    bytes.push(0x30);
+    for value in [
+        lat_of_first_grid_point - j_inc,
+        lon_of_first_grid_point + i_inc,
+        i_inc,
+        j_inc,
+    ] {
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    bytes.push(0x00);
+    // 第4節
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x52, 0x04, 0x00, 0x00, 0xC3, 0x58]);
+    bytes.extend_from_slice(&[0x00; 25]);
+    bytes.extend_from_slice(&[0x07, 0xE8, 0x01, 0x01, 0x00, 0x00, 0x00]);
+    bytes.push(0x01);
+    bytes.extend_from_slice(&[0x00; 16 + 24]);
+    // 第5節
+    bytes.extend_from_slice(&(17u32 + 2 * 3).to_be_bytes());
+    bytes.push(0x05);
+    bytes.extend_from_slice(&4u32.to_be_bytes());
+    bytes.extend_from_slice(&200u16.to_be_bytes());
+    bytes.extend_from_slice(&[0x08, 0x00, 0x03, 0x00, 0x03, 0x00]);
+    for value in [10u16, 20, 30] {
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    // 第6節
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x06, 0x06, 0xFF]);
+    // 第7節
+    bytes.extend_from_slice(&(5u32 + levels.len() as u32).to_be_bytes());
+    bytes.push(0x07);
+    bytes.extend_from_slice(&levels);
+    // 第8節
+    bytes.extend_from_slice(SECTION8_END_MARKER.as_bytes());
+    let total_length = bytes.len() as u64;
+    bytes[8..16].copy_from_slice(&total_length.to_be_bytes());
+
+    bytes
+}
+
+/// 指定した予報時間（分）の第4節から第7節を記録した、2行2列の格子の土砂災害警戒判定メッシュ情報の
+/// GRIB2ファイルのバイト列を返す。
+///
+/// 第0節から第3節と第5節から第7節は、`small_prr_bytes`で作成する解析雨量と同じ構成である。
+///
+/// # 引数
+///
+/// * `forecast_minutes` - 第4節に記録する予報時間（分）
+///
+/// # 戻り値
+///
+/// 土砂災害警戒判定メッシュ情報のGRIB2ファイルのバイト列
+#[cfg(test)]
+pub(crate) fn small_lswj_bytes(forecast_minutes: &[i32]) -> Vec<u8> {
+    let prr = small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+    let mut bytes = prr[..16 + 21 + 72].to_vec();
+    for &minutes in forecast_minutes {
+        // 第4節（テンプレート4.50000）
+        bytes.extend_from_slice(&42u32.to_be_bytes());
+        bytes.extend_from_slice(&[0x04, 0x00, 0x00]);
+        bytes.extend_from_slice(&50000u16.to_be_bytes());
+        bytes.extend_from_slice(&[0x00; 8]);
+        bytes.push(0x00);
+        bytes.extend_from_slice(&minutes.to_be_bytes());
+        bytes.extend_from_slice(&[0x00; 20]);
+        // 第5節から第7節
+        bytes.extend_from_slice(&prr[prr.len() - 4 - 9 - 6 - 23..prr.len() - 4]);
+    }
+    // 第8節
+    bytes.extend_from_slice(b"7777");
+    let total_length = bytes.len() as u64;
+    bytes[8..16].copy_from_slice(&total_length.to_be_bytes());
+
+    bytes
+}
+
+/// 第1節の直後に第2節:地域使用節を挿入して、GRIB報全体のバイト数を更新する。
+///
+/// # 引数
+///
+/// * `bytes` - 第2節を省略したGRIB2ファイルのバイト列
+/// * `body` - 第2節の節番号の直後から節の末尾までのバイト列
+///
+/// # 戻り値
+///
+/// 第2節を挿入したGRIB2ファイルのバイト列
+#[cfg(test)]
+pub(crate) fn insert_section2(mut bytes: Vec<u8>, body: &[u8]) -> Vec<u8> {
+    let start = SECTION0_BYTES as usize + SECTION1_BYTES as usize;
+    let mut section2 = (4 + 1 + body.len() as u32).to_be_bytes().to_vec();
+    section2.push(0x02);
+    section2.extend_from_slice(body);
+    bytes.splice(start..start, section2);
+    let total_length = bytes.len() as u64;
+    bytes[8..16].copy_from_slice(&total_length.to_be_bytes());
+
+    bytes
+}
+
+/// テストで使用する一時ファイルまたは一時ディレクトリ
+///
+/// パスにプロセスIDと連番を含めて並行して実行するテストの間で衝突しないようにし、破棄するときに
+/// 削除する。ファイルパスを受け取るリーダーのテストで使用する。
+#[cfg(test)]
+pub(crate) struct TempPath(std::path::PathBuf);
+
+#[cfg(test)]
+impl TempPath {
+    /// 一時ファイルまたは一時ディレクトリのパスを生成する。
+    fn unique() -> std::path::PathBuf {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let number = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        std::env::temp_dir().join(format!("grib2_{}_{}", std::process::id(), number))
+    }
+
+    /// バイト列を書き込んだ一時ファイルを作成する。
+    ///
+    /// # 引数
+    ///
+    /// * `bytes` - 一時ファイルに書き込むバイト列
+    ///
+    /// # 戻り値
+    ///
+    /// 一時ファイル
+    pub(crate) fn file(bytes: &[u8]) -> Self {
+        let path = Self::unique();
+        std::fs::write(&path, bytes).unwrap();
+
+        Self(path)
+    }
+
+    /// 空の一時ディレクトリを作成する。
+    ///
+    /// # 戻り値
+    ///
+    /// 一時ディレクトリ
+    pub(crate) fn dir() -> Self {
+        let path = Self::unique();
+        std::fs::create_dir_all(&path).unwrap();
+
+        Self(path)
     }
 
-    /// データの取り得るレベルの最大値を返す。
-    pub fn number_of_level_values(&self) -> u16 {
-        self.template5.number_of_level_values
+    /// 一時ファイルまたは一時ディレクトリのパスを返す。
+    pub(crate) fn path(&self) -> &std::path::Path {
+        &self.0
     }
+}
 
-    /// データ代表値の尺度因子を返す。
-    pub fn decimal_scale_factor(&self) -> u8 {
-        self.template5.decimal_scale_factor
+#[cfg(test)]
+impl AsRef<std::path::Path> for TempPath {
+    fn as_ref(&self) -> &std::path::Path {
+        &self.0
     }
+}
 
-    /// レベルmに対応するデータ代表値を返す。
-    pub fn level_values(&self) -> &[u16] {
-        &self.template5.level_values
+#[cfg(test)]
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = match self.0.is_dir() {
+            true => std::fs::remove_dir_all(&self.0),
+            false => std::fs::remove_file(&self.0),
+        };
     }
 }
 
-impl Section5_200i16 {
-    /// 今回の圧縮に用いたレベルの最大値を返す。
-    pub fn max_level_value(&self) -> u16 {
-        self.template5.max_level_value
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use time::macros::{datetime, offset};
+    use time::{Duration, UtcOffset};
+
+    use super::{
+        fixed_surface_value, i16_from_sign_magnitude, i32_from_sign_magnitude,
+        i32_to_sign_magnitude, i64_from_sign_magnitude, i8_from_sign_magnitude, psw_tank,
+        read_datetime, read_i16, read_i32, read_i64, read_i8, section0_to_section3_bytes,
+        stat_proc_hours, time_range_duration, to_rfc3339, write_leading_sections_debug_info,
+        AnyTemplate4, DebugOptions, DebugTemplate, FromReader, LeadingSections, MessageBoundary,
+        Section0, Section1, Section3_0, Section3_40, Section4, Section4_0, Section4_50008,
+        Section4_50009, Section5, Section6, Section7_200, Template4_8, Template5_200, ToWriter,
+    };
+    use crate::reader::{
+        PrrReader, PswTank, ReaderError, ReferenceTimeSignificance, StatProc, TemplateRegistry,
+    };
+
+    /// 第0節と第1節のみを記録したGRIB2ファイルのバイト列を返す。
+    fn section0_and_section1_bytes(total_length: u64) -> Vec<u8> {
+        let mut bytes = b"GRIB".to_vec();
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x02]);
+        bytes.extend_from_slice(&total_length.to_be_bytes());
+        bytes.extend_from_slice(&21u32.to_be_bytes());
+        bytes.extend_from_slice(&[0x01, 0x00, 0x22, 0x00, 0x00, 0x02, 0x01, 0x00]);
+        bytes.extend_from_slice(&2024u16.to_be_bytes());
+        bytes.extend_from_slice(&[0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0xC8]);
+
+        bytes
+    }
+
+    #[test]
+    fn message_boundary_within_total_length_ok() {
+        let mut reader = Cursor::new(section0_and_section1_bytes(37));
+        let section0 = Section0::from_reader(&mut reader).unwrap();
+        let boundary = MessageBoundary::new(&mut reader, &section0).unwrap();
+        Section1::from_reader(&mut reader).unwrap();
+        assert!(boundary.validate(&mut reader, "第1節").is_ok());
+    }
+
+    #[test]
+    fn message_boundary_exceeding_total_length_err() {
+        let mut reader = Cursor::new(section0_and_section1_bytes(36));
+        let section0 = Section0::from_reader(&mut reader).unwrap();
+        let boundary = MessageBoundary::new(&mut reader, &section0).unwrap();
+        Section1::from_reader(&mut reader).unwrap();
+        assert!(matches!(
+            boundary.validate(&mut reader, "第1節"),
+            Err(ReaderError::Unexpected(_))
+        ));
     }
 
-    /// データの取り得るレベルの最大値を返す。
-    pub fn number_of_level_values(&self) -> u16 {
-        self.template5.number_of_level_values
+    #[test]
+    fn leading_sections_ok() {
+        let mut reader = Cursor::new(section0_to_section3_bytes(1_000));
+        let sections = LeadingSections::from_reader(&mut reader).unwrap();
+        assert_eq!(1_000, sections.section0.total_length());
+        assert_eq!(2_560, sections.section3.number_of_along_lat_points());
+        assert_eq!(3_360, sections.section3.number_of_along_lon_points());
+
+        let mut buf = Vec::new();
+        write_leading_sections_debug_info(
+            &mut buf,
+            &sections.section0,
+            &sections.section1,
+            &sections.section2,
+            &sections.section3,
+        )
+        .unwrap();
+        let info = String::from_utf8(buf).unwrap();
+        for section in ["第0節", "第1節", "第2節", "第3節"] {
+            assert!(info.contains(section));
+        }
     }
 
-    /// データ代表値の尺度因子を返す。
-    pub fn decimal_scale_factor(&self) -> u8 {
-        self.template5.decimal_scale_factor
+    #[test]
+    fn read_datetime_with_invalid_fields_err() {
+        // 2月30日、25時及び13月は日時に変換できない
+        let inputs = [[2, 30, 0, 0, 0], [1, 1, 25, 0, 0], [13, 1, 0, 0, 0]];
+        for parts in inputs {
+            let mut bytes = 2024u16.to_be_bytes().to_vec();
+            bytes.extend_from_slice(&parts);
+            match read_datetime(&mut Cursor::new(bytes), "参照時刻") {
+                Err(ReaderError::InvalidDateTime {
+                    name,
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minute,
+                    second,
+                }) => {
+                    assert_eq!("参照時刻", name);
+                    assert_eq!(2024, year);
+                    assert_eq!(parts, [month, day, hour, minute, second]);
+                }
+                _ => panic!("日時が不正なエラーにならなければなりません。"),
+            }
+        }
     }
 
-    /// レベルmに対応するデータ代表値を返す。
-    pub fn level_values(&self) -> &[i16] {
-        &self.template5.level_values
+    #[test]
+    fn leading_sections_exceeding_total_length_err() {
+        let mut reader = Cursor::new(section0_to_section3_bytes(40));
+        assert!(matches!(
+            LeadingSections::from_reader(&mut reader),
+            Err(ReaderError::Unexpected(_))
+        ));
     }
-}
 
-impl Section7_200 {
-    /// ランレングス圧縮符号列の開始位置を返す。
-    pub fn run_length_position(&self) -> usize {
-        self.template7.run_length_position
+    #[test]
+    fn reader_detects_corrupted_total_length_early() {
+        let reader = Cursor::new(section0_and_section1_bytes(20));
+        match PrrReader::from_reader(reader) {
+            Err(ReaderError::Unexpected(message)) => assert!(message.starts_with("第1節")),
+            _ => panic!("第1節を読み込んだ時点でエラーにならなければなりません。"),
+        }
     }
 
-    /// ランレングス圧縮符号のバイト数を返す。
-    pub fn run_length_bytes(&self) -> usize {
-        self.template7.run_length_bytes
+    /// 出力されたログのメッセージを記録するロガー
+    #[cfg(feature = "logging")]
+    struct RecordingLogger(std::sync::Mutex<Vec<String>>);
+
+    #[cfg(feature = "logging")]
+    impl log::Log for RecordingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn logging_does_not_affect_reading_ok() {
+        static LOGGER: RecordingLogger = RecordingLogger(std::sync::Mutex::new(Vec::new()));
+
+        let bytes = super::small_prr_bytes(36_004_167, [1, 2, 3, 0]);
+        let read = || {
+            let mut reader = PrrReader::from_reader(Cursor::new(bytes.clone())).unwrap();
+            let values = reader
+                .values()
+                .unwrap()
+                .map(|value| format!("{:?}", value))
+                .collect::<Vec<_>>();
+            (
+                reader.section4().product_definition_template_number(),
+                values,
+            )
+        };
+
+        // ログを出力しない場合
+        log::set_max_level(log::LevelFilter::Off);
+        let without_logging = read();
+
+        // ログを出力する場合
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+        let with_logging = read();
+        log::set_max_level(log::LevelFilter::Off);
+
+        assert_eq!(without_logging, with_logging);
+        let messages = LOGGER.0.lock().unwrap();
+        assert!(messages
+            .iter()
+            .any(|m| m == "第4節: プロダクト定義テンプレート番号=50008を検出"));
+        assert!(messages
+            .iter()
+            .any(|m| m.starts_with("第7節: ランレングス圧縮符号列 ")));
+    }
+
+    #[test]
+    fn stat_proc_hours_ok() {
+        assert_eq!(Some(1.0), stat_proc_hours(1, 1));
+        assert_eq!(Some(0.5), stat_proc_hours(0, 30));
+        assert_eq!(Some(72.0), stat_proc_hours(2, 3));
+        assert_eq!(Some(6.0), stat_proc_hours(11, 1));
+    }
+
+    #[test]
+    fn stat_proc_hours_with_unknown_unit_none() {
+        assert_eq!(None, stat_proc_hours(255, 1));
+        assert_eq!(None, stat_proc_hours(1, 0));
+    }
+
+    #[test]
+    fn time_range_duration_ok() {
+        assert_eq!(Some(Duration::minutes(30)), time_range_duration(0, 30));
+        assert_eq!(Some(Duration::hours(-2)), time_range_duration(1, -2));
+        assert_eq!(Some(Duration::hours(18)), time_range_duration(11, 3));
+        assert_eq!(None, time_range_duration(255, 1));
+    }
+
+    #[test]
+    fn section1_referenced_at_ok() {
+        let mut reader = Cursor::new(section0_to_section3_bytes(1_000));
+        Section0::from_reader(&mut reader).unwrap();
+        let section1 = Section1::from_reader(&mut reader).unwrap();
+        let referenced_at = section1.referenced_at();
+        assert_eq!(datetime!(2024-01-01 00:00:00 UTC), referenced_at);
+        assert_eq!(UtcOffset::UTC, referenced_at.offset());
+        let referenced_at_jst = section1.referenced_at_jst();
+        assert_eq!(referenced_at, referenced_at_jst);
+        assert_eq!(offset!(+9), referenced_at_jst.offset());
+        assert_eq!(9, referenced_at_jst.hour());
+        assert_eq!(
+            ReferenceTimeSignificance::Analysis,
+            section1.reference_time_significance().unwrap()
+        );
+    }
+
+    #[test]
+    fn to_rfc3339_ok() {
+        let datetime = datetime!(2023-09-10 12:00:00 UTC);
+        assert_eq!("2023-09-10T12:00:00Z", to_rfc3339(datetime));
+        let datetime = datetime!(2023-09-10 21:00:00 +09:00);
+        assert_eq!("2023-09-10T12:00:00Z", to_rfc3339(datetime));
+        let datetime = datetime!(2023-09-10 12:00:00 UTC) + Duration::hours(6);
+        assert_eq!("2023-09-10T18:00:00Z", to_rfc3339(datetime));
+    }
+
+    #[test]
+    fn fixed_surface_value_ok() {
+        assert_eq!(Some(850.0), fixed_surface_value(0, 850));
+        assert_eq!(Some(1.5), fixed_surface_value(1, 15));
+        assert_eq!(Some(2000.0), fixed_surface_value(0x83, 2));
+    }
+
+    #[test]
+    fn from_sign_magnitude_ok() {
+        assert_eq!(1, i8_from_sign_magnitude(0x01));
+        assert_eq!(-1, i8_from_sign_magnitude(0x81));
+        assert_eq!(i8::MAX, i8_from_sign_magnitude(0x7F));
+        assert_eq!(-i8::MAX, i8_from_sign_magnitude(0xFF));
+        assert_eq!(-300, i16_from_sign_magnitude(0x812C));
+        assert_eq!(-6, i32_from_sign_magnitude(0x8000_0006));
+        assert_eq!(i32::MAX, i32_from_sign_magnitude(0x7FFF_FFFF));
+        assert_eq!(-i64::MAX, i64_from_sign_magnitude(u64::MAX));
+    }
+
+    #[test]
+    fn from_sign_magnitude_with_negative_zero_returns_zero() {
+        // 符号ビットのみが1の値（負のゼロ）は0
+        assert_eq!(0, i8_from_sign_magnitude(0x80));
+        assert_eq!(0, i16_from_sign_magnitude(0x8000));
+        assert_eq!(0, i32_from_sign_magnitude(0x8000_0000));
+        assert_eq!(0, i64_from_sign_magnitude(0x8000_0000_0000_0000));
+    }
+
+    #[test]
+    fn read_signed_int_ok() {
+        let mut reader = Cursor::new(vec![
+            0x85, 0x80, 0x02, 0x80, 0x00, 0x00, 0x03, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x04,
+        ]);
+        assert_eq!(-5, read_i8(&mut reader, "i8").unwrap());
+        assert_eq!(-2, read_i16(&mut reader, "i16").unwrap());
+        assert_eq!(-3, read_i32(&mut reader, "i32").unwrap());
+        assert_eq!(-4, read_i64(&mut reader, "i64").unwrap());
+        assert!(read_i8(&mut reader, "i8").is_err());
+    }
+
+    #[test]
+    fn fixed_surface_value_with_missing_none() {
+        assert_eq!(None, fixed_surface_value(0xFF, 0));
+        assert_eq!(None, fixed_surface_value(0, 0xFFFF_FFFF));
+    }
+
+    /// 他機関の全球モデルが出力する6時間積算降水量を想定した、テンプレート4.8のバイト列を生成する。
+    fn template4_8_bytes() -> Vec<u8> {
+        vec![
+            // パラメータカテゴリー（降水）、パラメータ番号（総降水量）、作成処理の種類（予報）、
+            // 背景作成処理識別符、予報の作成処理識別符
+            0x01, 0x08, 0x02, 0x00,
+            0x60, // 観測資料の参照時刻からの締切時間（時、分）
+            0x00, 0x00, 0x00, // 期間の単位の指示符（時）、予報時間
+            0x01, 0x00, 0x00, 0x00, 0x00, // 第一固定面（地面）
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // 第二固定面（欠測）
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // 全時間間隔の終了時
+            0x07, 0xE8, 0x01, 0x01, 0x06, 0x00, 0x00,
+            // 期間の仕様の数、統計処理における欠測資料の総数
+            0x01, 0x00, 0x00, 0x00, 0x00,
+            // 統計処理の種類（積算）から連続的な資料場間の時間の増分まで
+            0x01, 0x02, 0x01, 0x00, 0x00, 0x00, 0x06, 0xFF, 0x00, 0x00, 0x00, 0x00,
+        ]
+    }
+
+    #[test]
+    fn section4_0_debug_info_with_fixed_surfaces_ok() {
+        // 850hPa面の気温の予報
+        let template4 = [
+            // パラメータカテゴリー（温度）、パラメータ番号（気温）、作成処理の種類（予報）、
+            // 背景作成処理識別符、予報の作成処理識別符
+            0x00, 0x00, 0x02, 0x00, 0x60,
+            // 観測資料の参照時刻からの締切時間（時、分）
+            0x00, 0x00, 0x00, // 期間の単位の指示符（時）、予報時間
+            0x01, 0x00, 0x00, 0x00, 0x06, // 第一固定面（等圧面、85000Pa）
+            0x64, 0x00, 0x00, 0x01, 0x4C, 0x08, // 第二固定面（欠測）
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ];
+        let bytes = section4_bytes(0, &template4);
+        let section4 = Section4_0::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(Some(85_000.0), section4.first_fixed_surface_value());
+
+        // 固定面の種類、尺度因子及び尺度付きの値を出力して、欠測はNoneと出力
+        let mut buf = vec![];
+        section4.debug_info(&mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        for line in [
+            "    第一固定面の種類: 100\n",
+            "    第一固定面の尺度因子: 0\n",
+            "    第一固定面の尺度付きの値: 85000\n",
+            "    第二固定面の種類: None\n",
+            "    第二固定面の尺度因子: None\n",
+            "    第二固定面の尺度付きの値: None\n",
+        ] {
+            assert!(text.contains(line), "{}", text);
+        }
+
+        // JSON形式では、欠測はnull
+        #[cfg(feature = "json")]
+        {
+            let json = section4.debug_info_json();
+            assert_eq!(100, json["template4"]["type_of_first_fixed_surface"]);
+            assert_eq!(
+                85_000,
+                json["template4"]["scaled_value_of_first_fixed_surface"]
+            );
+            assert!(json["template4"]["type_of_second_fixed_surface"].is_null());
+            assert!(json["template4"]["scaled_value_of_second_fixed_surface"].is_null());
+        }
+    }
+
+    #[test]
+    fn section4_8_ok() {
+        let bytes = section4_bytes(8, &template4_8_bytes());
+        let section4 = Section4::from_reader_dynamic(&mut Cursor::new(bytes)).unwrap();
+        let section4 = match section4.downcast::<Template4_8>() {
+            Some(section4) => section4,
+            None => panic!("テンプレート4.8を想定しています。"),
+        };
+        assert_eq!(0x3A, section4.section_bytes());
+        assert_eq!(1, section4.parameter_category());
+        assert_eq!(8, section4.parameter_number());
+        assert_eq!(96, section4.generating_process_identifier());
+        assert_eq!(Some(0.0), section4.first_fixed_surface_value());
+        assert_eq!(None, section4.second_fixed_surface_value());
+        assert_eq!(
+            datetime!(2024-01-01 06:00:00 UTC),
+            section4.end_of_all_time_intervals()
+        );
+        assert_eq!(StatProc::Accumulation, section4.stat_proc_kind().unwrap());
+        assert_eq!(Some(6.0), section4.stat_proc_hours());
+
+        // テンプレートの型を決めずに読み込んだ場合
+        let bytes = section4_bytes(8, &template4_8_bytes());
+        let section4 = Section4::from_reader_dynamic(&mut Cursor::new(bytes)).unwrap();
+        assert!(matches!(section4.template4(), AnyTemplate4::Template8(_)));
+        let definition = section4.template4().product_definition().unwrap();
+        assert_eq!(Some("総降水量"), definition.parameter_name());
+    }
+
+    /// プロダクト定義テンプレート番号とテンプレート4のバイト列から、第4節のバイト列を生成する。
+    fn section4_bytes(template_number: u16, template4: &[u8]) -> Vec<u8> {
+        let section_bytes = (4 + 1 + 2 + 2 + template4.len()) as u32;
+        let mut bytes = section_bytes.to_be_bytes().to_vec();
+        bytes.push(0x04);
+        bytes.extend_from_slice(&[0x00, 0x00]);
+        bytes.extend_from_slice(&template_number.to_be_bytes());
+        bytes.extend_from_slice(template4);
+
+        bytes
+    }
+
+    /// テンプレート4.50008のバイト列を生成する。
+    fn template4_50008_bytes() -> Vec<u8> {
+        let mut bytes = vec![];
+        // パラメータカテゴリーから第二固定面の尺度付きの値まで: 25バイト
+        bytes.extend_from_slice(&[0x00; 25]);
+        // 全時間間隔の終了時: 7バイト
+        bytes.extend_from_slice(&[0x07, 0xE5, 0x08, 0x11, 0x00, 0x00, 0x00]);
+        // 期間の仕様の数: 1、欠測資料の総数から連続的な資料場間の時間の増分まで: 16バイト
+        bytes.push(0x01);
+        bytes.extend_from_slice(&[0x00; 16]);
+        // レーダー等運用情報その1、その2、雨量計運用情報: 24バイト
+        bytes.extend_from_slice(&[0x00; 24]);
+
+        bytes
+    }
+
+    #[test]
+    fn section4_50008_section_bytes_ok() {
+        let bytes = section4_bytes(50008, &template4_50008_bytes());
+        let mut reader = Cursor::new(bytes.clone());
+        let section4 = Section4_50008::from_reader(&mut reader).unwrap();
+        assert_eq!(bytes.len(), section4.section_bytes());
+    }
+
+    #[test]
+    fn section4_50008_forecast_duration_ok() {
+        // 期間の単位の指示符: 分、予報時間: -60
+        let mut template4 = template4_50008_bytes();
+        template4[8] = 0;
+        template4[9..13].copy_from_slice(&[0x80, 0x00, 0x00, 0x3C]);
+        let bytes = section4_bytes(50008, &template4);
+        let section4 = Section4_50008::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(
+            Duration::minutes(-60),
+            section4.forecast_duration().unwrap()
+        );
+        assert_eq!(
+            datetime!(2021-08-17 08:00:00 UTC),
+            section4
+                .forecast_valid_time(datetime!(2021-08-17 09:00:00 UTC))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn section4_50008_stat_proc_kind_ok() {
+        // 統計処理の種類は、全時間間隔の終了時の後の期間の仕様の数と欠測資料の総数の次
+        let mut template4 = template4_50008_bytes();
+        template4[25 + 7 + 1 + 4] = 1;
+        let bytes = section4_bytes(50008, &template4);
+        let section4 = Section4_50008::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(StatProc::Accumulation, section4.stat_proc_kind().unwrap());
+        assert_eq!("積算", section4.stat_proc_kind().unwrap().to_string());
+
+        let mut template4 = template4_50008_bytes();
+        template4[25 + 7 + 1 + 4] = 200;
+        let bytes = section4_bytes(50008, &template4);
+        let section4 = Section4_50008::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert!(matches!(
+            section4.stat_proc_kind(),
+            Err(ReaderError::Unexpected(_))
+        ));
+    }
+
+    /// JSONの値に含まれる末端の項目の数を返す。配列は1つの項目として数える。
+    #[cfg(feature = "json")]
+    fn count_json_leaves(value: &serde_json::Value) -> usize {
+        match value {
+            serde_json::Value::Object(map) => map.values().map(count_json_leaves).sum(),
+            _ => 1,
+        }
+    }
+
+    /// テキスト形式のデバッグ情報の項目と、JSON形式のデバッグ情報の項目の数が一致することを確認する。
+    #[cfg(feature = "json")]
+    fn assert_debug_info_json_covers_text(text: &[u8], mut json: serde_json::Value) {
+        // 節番号と節の名前は、テキスト形式では見出しとして出力
+        let map = json.as_object_mut().unwrap();
+        assert!(map.remove("number").unwrap().is_u64());
+        assert!(map.remove("name").unwrap().is_string());
+        let text = String::from_utf8(text.to_vec()).unwrap();
+        let text_fields = text
+            .lines()
+            .filter(|line| line.starts_with("    ") && !line.starts_with("        "))
+            .count();
+        assert_eq!(text_fields, count_json_leaves(&json), "{}", text);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn debug_info_json_covers_debug_info() {
+        let mut reader = Cursor::new(section0_to_section3_bytes(1_000));
+        let section0 = Section0::from_reader(&mut reader).unwrap();
+        let section1 = Section1::from_reader(&mut reader).unwrap();
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+        let bytes = section4_bytes(50008, &template4_50008_bytes());
+        let section4 = Section4_50008::from_reader(&mut Cursor::new(bytes)).unwrap();
+
+        let mut text = vec![];
+        section0.debug_info(&mut text).unwrap();
+        assert_debug_info_json_covers_text(&text, section0.debug_info_json());
+        let mut text = vec![];
+        section1.debug_info(&mut text).unwrap();
+        assert_debug_info_json_covers_text(&text, section1.debug_info_json());
+        let mut text = vec![];
+        section3.debug_info(&mut text).unwrap();
+        assert_debug_info_json_covers_text(&text, section3.debug_info_json());
+        let mut text = vec![];
+        section4.debug_info(&mut text).unwrap();
+        assert_debug_info_json_covers_text(&text, section4.debug_info_json());
+
+        // 16進数で書式化する項目は文字列、それ以外の数値は数値
+        let json = section0.debug_info_json();
+        assert_eq!("0x000003E8", json["total_length"]);
+        assert_eq!(2, json["edition_number"]);
+        let json = section1.debug_info_json();
+        assert_eq!("2024-01-01T00:00:00Z", json["referenced_at"]);
+        let json = section3.debug_info_json();
+        assert_eq!(2_560, json["template3"]["number_of_along_lat_points"]);
+    }
+
+    #[test]
+    fn section4_50008_with_multiple_time_range_specs_ok() {
+        // 期間の仕様の数: 2、2つ目の仕様は統計処理の種類: 最大値、統計処理した時間の長さ: 3時間
+        let mut template4 = template4_50008_bytes();
+        template4[25 + 7] = 2;
+        template4[25 + 7 + 1 + 4] = 1;
+        let second = [
+            0x02, 0x02, 0x01, 0x00, 0x00, 0x00, 0x03, 0xFF, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let position = 25 + 7 + 1 + 4 + 12;
+        template4.splice(position..position, second);
+        let mut bytes = section4_bytes(50008, &template4);
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x06, 0x06, 0xFF]);
+
+        let mut reader = Cursor::new(bytes);
+        let section4 = Section4_50008::from_reader(&mut reader).unwrap();
+        assert_eq!(4 + 1 + 2 + 2 + 73 + 12, section4.section_bytes());
+        assert_eq!(2, section4.number_of_time_range_specs());
+        let specs = section4.time_range_specs();
+        assert_eq!(2, specs.len());
+        assert_eq!(1, specs[0].type_of_stat_proc());
+        assert_eq!(2, specs[1].type_of_stat_proc());
+        assert_eq!(1, specs[1].stat_proc_time_unit());
+        assert_eq!(3, specs[1].stat_proc_time_length());
+        // 単一の仕様を返すゲッターは、最初の仕様の値を返す
+        assert_eq!(StatProc::Accumulation, section4.stat_proc_kind().unwrap());
+        // 後続の節を読み込める
+        let section6 = Section6::from_reader(&mut reader).unwrap();
+        assert_eq!(255, section6.bitmap_indicator());
+
+        // 期間の仕様の数が0の場合はエラー
+        let mut template4 = template4_50008_bytes();
+        template4[25 + 7] = 0;
+        let bytes = section4_bytes(50008, &template4);
+        assert!(matches!(
+            Section4_50008::from_reader(&mut Cursor::new(bytes)),
+            Err(ReaderError::Unexpected(_))
+        ));
+    }
+
+    #[test]
+    fn section4_50008_forecast_duration_with_unsupported_unit_err() {
+        // 期間の単位の指示符: 月
+        let mut template4 = template4_50008_bytes();
+        template4[8] = 3;
+        template4[12] = 1;
+        let bytes = section4_bytes(50008, &template4);
+        let section4 = Section4_50008::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert!(matches!(
+            section4.forecast_duration(),
+            Err(ReaderError::Unexpected(_))
+        ));
+        assert!(section4
+            .forecast_valid_time(datetime!(2021-08-17 09:00:00 UTC))
+            .is_err());
+    }
+
+    #[test]
+    fn section4_from_reader_dynamic_ok() {
+        // パラメータカテゴリー: 1、パラメータ番号: 8
+        let mut template4 = template4_50008_bytes();
+        template4[0] = 1;
+        template4[1] = 8;
+        let bytes = section4_bytes(50008, &template4);
+        let section4 = Section4::from_reader_dynamic(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(50008, section4.product_definition_template_number());
+        assert!(matches!(
+            section4.template4(),
+            AnyTemplate4::Template50008(_)
+        ));
+        let definition = section4.template4().product_definition().unwrap();
+        assert_eq!(1, definition.parameter_category());
+        assert_eq!(8, definition.parameter_number());
+    }
+
+    #[test]
+    fn section4_from_reader_dynamic_with_unknown_template_ok() {
+        let mut bytes = section4_bytes(60000, &[0x01, 0x02, 0x03]);
+        bytes.extend_from_slice(b"7777");
+        let mut reader = Cursor::new(bytes);
+        let section4 = Section4::from_reader_dynamic(&mut reader).unwrap();
+        assert!(matches!(section4.template4(), AnyTemplate4::Unknown(60000)));
+        assert!(section4.template4().product_definition().is_none());
+        // テンプレートを読み飛ばして、節の末尾に位置する
+        assert_eq!(12, reader.position());
+        let mut buf = vec![];
+        section4.template4().debug_info(&mut buf).unwrap();
+        assert!(String::from_utf8(buf)
+            .unwrap()
+            .contains("テンプレート4.60000"));
+    }
+
+    #[test]
+    fn section4_from_reader_dynamic_with_registry_ok() {
+        // デコーダを登録していないテンプレートは読み飛ばす
+        let bytes = section4_bytes(8, &template4_8_bytes());
+        let mut reader = Cursor::new(bytes.clone());
+        let registry = TemplateRegistry::<AnyTemplate4>::default();
+        let section4 = Section4::from_reader_dynamic_with_registry(&mut reader, &registry).unwrap();
+        assert!(matches!(section4.template4(), AnyTemplate4::Unknown(8)));
+        assert_eq!(bytes.len() as u64, reader.position());
+        // 具体的なテンプレートに変換できない
+        assert!(section4.downcast::<Template4_8>().is_none());
+    }
+
+    #[test]
+    fn debug_info_with_options_ok() {
+        let bytes = section4_bytes(8, &template4_8_bytes());
+        let section4 = Section4::from_reader_dynamic(&mut Cursor::new(bytes)).unwrap();
+
+        // デフォルトの書式は、debug_infoと同じ出力
+        let mut expected = vec![];
+        section4.debug_info(&mut expected).unwrap();
+        let mut buf = vec![];
+        section4
+            .debug_info_with(&mut buf, &DebugOptions::default())
+            .unwrap();
+        assert_eq!(expected, buf);
+
+        let opts = DebugOptions::default()
+            .with_indent("\t")
+            .with_section_header("Section {number} ({name})")
+            .with_separator(" = ");
+        let mut buf = vec![];
+        section4.debug_info_with(&mut buf, &opts).unwrap();
+        let info = String::from_utf8(buf).unwrap();
+        let lines = info.lines().collect::<Vec<_>>();
+        assert_eq!("Section 4 (プロダクト定義節)", lines[0]);
+        assert_eq!("\t節の長さ = 58", lines[1]);
+        assert!(lines.contains(&"\tパラメータカテゴリー = 1"));
+        assert!(lines.contains(&"\t統計処理の時間範囲の仕様:"));
+        assert!(lines.contains(&"\t\t[1]"));
+        assert!(lines.contains(&"\t\t\t統計処理の種類 = 1"));
+    }
+
+    #[test]
+    fn section4_50009_with_combined_ratios_ok() {
+        let mut template4 = template4_50008_bytes();
+        // 計算領域数: 2、尺度因子: 1、各領域の結合比率: 5, 10
+        template4.extend_from_slice(&[0x00, 0x02, 0x01, 0x00, 0x05, 0x00, 0x0A]);
+        let bytes = section4_bytes(50009, &template4);
+        let mut reader = Cursor::new(bytes.clone());
+        let section4 = Section4_50009::from_reader(&mut reader).unwrap();
+        assert_eq!(bytes.len(), section4.section_bytes());
+        assert_eq!(2, section4.number_of_calculation_areas());
+        assert_eq!(1, section4.scale_factor_of_combined_ratio());
+        assert_eq!(&[5, 10], section4.combined_ratios_of_forecast_areas());
+    }
+
+    /// テンプレート5.200を記録した第5節のバイト列を返す。
+    ///
+    /// 今回の圧縮に用いたレベルの最大値は3、尺度因子は1とする。
+    fn section5_200_bytes(number_of_level_values: u16, level_value_bytes: &[u8]) -> Vec<u8> {
+        let section_bytes = 4 + 1 + 4 + 2 + 1 + 2 + 2 + 1 + level_value_bytes.len() as u32;
+        let mut bytes = section_bytes.to_be_bytes().to_vec();
+        bytes.push(5);
+        bytes.extend_from_slice(&6u32.to_be_bytes());
+        bytes.extend_from_slice(&200u16.to_be_bytes());
+        bytes.push(8);
+        bytes.extend_from_slice(&3u16.to_be_bytes());
+        bytes.extend_from_slice(&number_of_level_values.to_be_bytes());
+        bytes.push(1);
+        bytes.extend_from_slice(level_value_bytes);
+
+        bytes
+    }
+
+    #[test]
+    fn section5_200_with_level_value_types_ok() {
+        let bytes = section5_200_bytes(3, &[0x00, 0x0A, 0x01, 0x00, 0x80, 0x05]);
+        let section5 =
+            Section5::<Template5_200<u8>>::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(
+            &[0x00, 0x0A, 0x01, 0x00, 0x80, 0x05],
+            section5.level_values()
+        );
+        let section5 =
+            Section5::<Template5_200<u16>>::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(&[0x000A, 0x0100, 0x8005], section5.level_values());
+        let section5 =
+            Section5::<Template5_200<i16>>::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(&[10, 256, -5], section5.level_values());
+
+        let bytes = section5_200_bytes(2, &[0x00, 0x00, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x00]);
+        let section5 =
+            Section5::<Template5_200<u32>>::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(3, section5.max_level_value());
+        assert_eq!(1, section5.decimal_scale_factor());
+        assert_eq!(&[10, 65536], section5.level_values());
+    }
+
+    #[test]
+    fn section5_200_validate_levels_ok() {
+        let bytes = section5_200_bytes(4, &[0x00, 0x00, 0x00, 0x0A, 0x00, 0x0A, 0x00, 0x14]);
+        let section5 =
+            Section5::<Template5_200<u16>>::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        assert!(section5.validate_levels().is_ok());
+    }
+
+    #[test]
+    fn section5_200_validate_levels_with_reversed_levels_err() {
+        // レベル3の物理値がレベル2より小さいテーブル
+        let bytes = section5_200_bytes(4, &[0x00, 0x00, 0x00, 0x14, 0x00, 0x0A, 0x00, 0x1E]);
+        let section5 =
+            Section5::<Template5_200<u16>>::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        match section5.validate_levels() {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("レベル3の物理値(10)"));
+                assert!(message.contains("レベル2の物理値(20)"));
+            }
+            _ => panic!("レベル別物理値が減少しているエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn section5_200_validate_levels_with_mismatched_count_err() {
+        // データの取り得るレベルの最大値は3だが、レベル別物理値を2つしか記録していないテーブル
+        let bytes = section5_200_bytes(3, &[0x00, 0x0A, 0x00, 0x14]);
+        let section5 =
+            Section5::<Template5_200<u16>>::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(2, section5.level_values().len());
+        match section5.validate_levels() {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("レベル別物理値の数(2)"));
+                assert!(message.contains("レベルの最大値(3)"));
+            }
+            _ => panic!("レベル別物理値の数が一致しないエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn section5_with_too_short_section_bytes_err() {
+        // 節の長さが第5節の固定部分より短い第5節
+        let mut bytes = section5_200_bytes(0, &[]);
+        bytes[..4].copy_from_slice(&8u32.to_be_bytes());
+        assert!(Section5::<Template5_200<u16>>::from_reader(&mut Cursor::new(&bytes)).is_err());
+
+        // テンプレート5.200の固定部分より短い第5節
+        let mut bytes = section5_200_bytes(0, &[]);
+        bytes[..4].copy_from_slice(&14u32.to_be_bytes());
+        match Section5::<Template5_200<u16>>::from_reader(&mut Cursor::new(&bytes)) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("テンプレート5.200のバイト数(2)"));
+            }
+            _ => panic!("テンプレート5.200のバイト数が不正なエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn section7_with_too_short_section_bytes_err() {
+        // 節の長さが、節の長さと節番号のバイト数より短い第7節
+        let mut bytes = 3u32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[0x07, 0x01, 0x02]);
+        match Section7_200::from_reader(&mut Cursor::new(&bytes)) {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("第7節:節の長さ(3)が不正"));
+            }
+            _ => panic!("第7節の長さが不正なエラーを想定しています。"),
+        }
+    }
+
+    /// テンプレート3.40を記録した、経度方向に2格子点のガウス格子の第3節のバイト列を返す。
+    fn section3_40_bytes(nj: u32, lat_first: i32, lat_last: i32, n: u32) -> Vec<u8> {
+        let mut bytes = 72u32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[0x03, 0x00]);
+        bytes.extend_from_slice(&(2 * nj).to_be_bytes());
+        bytes.extend_from_slice(&[0x00, 0x00]);
+        bytes.extend_from_slice(&40u16.to_be_bytes());
+        bytes.extend_from_slice(&[0x06, 0x00]);
+        bytes.extend_from_slice(&[0x00; 4 + 1 + 4 + 1 + 4]);
+        for value in [2, nj, 0, 0] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        bytes.extend_from_slice(&i32_to_sign_magnitude(lat_first).to_be_bytes());
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+        bytes.push(0x30);
+        bytes.extend_from_slice(&i32_to_sign_magnitude(lat_last).to_be_bytes());
+        for value in [180_000_000u32, 180_000_000, n] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        bytes.push(0x00);
+
+        bytes
+    }
+
+    #[test]
+    fn section3_40_ok() {
+        // N=8の全球のガウス格子
+        let bytes = section3_40_bytes(16, 81_650_591, -81_650_591, 8);
+        let section3 = Section3_40::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(bytes.len(), section3.section_bytes());
+        assert_eq!(40, section3.grid_definition_template_number());
+        let template3 = section3.template3();
+        assert_eq!(81_650_591, template3.lat_of_first_grid_point());
+        assert_eq!(-81_650_591, template3.lat_of_last_grid_point());
+        assert_eq!(180_000_000, template3.i_direction_increment());
+        assert_eq!(8, template3.number_of_parallels_between_pole_and_equator());
+        let latitudes = template3.latitudes().unwrap();
+        assert_eq!(16, latitudes.len());
+        assert!((latitudes[0] - 81.650591).abs() < 1e-6);
+        assert!((latitudes[15] + 81.650591).abs() < 1e-6);
+
+        // 書き込んだバイト列は、読み込んだバイト列と一致する
+        let mut written = vec![];
+        section3.to_writer(&mut written).unwrap();
+        assert_eq!(bytes, written);
+    }
+
+    #[test]
+    fn section3_40_regional_latitudes_ok() {
+        // N=8のガウス格子から、北緯49.06度から北緯16.36度までの4行を切り出した格子
+        let bytes = section3_40_bytes(4, 49_060_719, 16_355_933, 8);
+        let section3 = Section3_40::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        let latitudes = section3.template3().latitudes().unwrap();
+        let expected = [49.060719, 38.161212, 27.259208, 16.355933];
+        assert_eq!(expected.len(), latitudes.len());
+        for (lat, expected) in latitudes.iter().zip(expected) {
+            assert!((lat - expected).abs() < 1e-6);
+        }
+
+        // 最初と最後の格子点の緯度が、ガウス緯度を1e-3度単位に丸めて記録されている格子
+        let bytes = section3_40_bytes(4, 49_061_000, 16_356_000, 8);
+        let section3 = Section3_40::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        let latitudes = section3.template3().latitudes().unwrap();
+        assert_eq!(expected.len(), latitudes.len());
+        for (lat, expected) in latitudes.iter().zip(expected) {
+            assert!((lat - expected).abs() < 1e-6);
+        }
+
+        // 経線に沿った格子点数が、最初と最後の格子点の間のガウス緯度の数と一致しない
+        let bytes = section3_40_bytes(3, 49_060_719, 16_355_933, 8);
+        let section3 = Section3_40::from_reader(&mut Cursor::new(&bytes)).unwrap();
+        match section3.template3().latitudes() {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("ガウス緯度の数(4)"));
+            }
+            _ => panic!("ガウス緯度の数が一致しないエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn section4_with_inconsistent_section_bytes_err() {
+        // 節の長さより計算領域数が多く、節の末尾を超えて読み込む第4節
+        let mut template4 = template4_50008_bytes();
+        template4.extend_from_slice(&[0x00, 0x03, 0x00, 0x00, 0x05, 0x00, 0x0A]);
+        let mut bytes = section4_bytes(50009, &template4);
+        bytes.extend_from_slice(&[0x00, 0x0F]);
+        let mut reader = Cursor::new(bytes);
+        assert!(matches!(
+            Section4_50009::from_reader(&mut reader),
+            Err(ReaderError::Unexpected(_))
+        ));
+    }
+
+    #[test]
+    fn sections_with_unknown_trailing_bytes_ok() {
+        // 第3節、第4節及び第5節の既知のフィールドの後に、未知のバイトが追加されたバイト列
+        let mut bytes = section0_to_section3_bytes(1_000);
+        let section3_start = 16 + 21;
+        bytes[section3_start..section3_start + 4].copy_from_slice(&(72u32 + 2).to_be_bytes());
+        bytes.extend_from_slice(&[0xFF; 2]);
+        let mut template4 = template4_50008_bytes();
+        template4.extend_from_slice(&[0xFF; 3]);
+        bytes.extend_from_slice(&section4_bytes(50008, &template4));
+        // レベル別物理値の2バイトに満たない末尾の1バイト
+        bytes.extend_from_slice(&section5_200_bytes(2, &[0x00, 0x0A, 0x00, 0x14, 0xFF]));
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x06, 0x06, 0xFF]);
+
+        let mut reader = Cursor::new(bytes);
+        Section0::from_reader(&mut reader).unwrap();
+        Section1::from_reader(&mut reader).unwrap();
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+        assert_eq!(74, section3.section_bytes());
+        assert_eq!(2_560, section3.number_of_along_lat_points());
+        let section4 = Section4_50008::from_reader(&mut reader).unwrap();
+        assert_eq!(4 + 1 + 2 + 2 + 73 + 3, section4.section_bytes());
+        let section5 = Section5::<Template5_200<u16>>::from_reader(&mut reader).unwrap();
+        assert_eq!(&[10, 20], section5.level_values());
+        let section6 = Section6::from_reader(&mut reader).unwrap();
+        assert_eq!(255, section6.bitmap_indicator());
+    }
+
+    #[test]
+    fn psw_tank_ok() {
+        assert_eq!(Some(PswTank::All), psw_tank(200, 0xFFFF_FFFF));
+        assert_eq!(Some(PswTank::First), psw_tank(201, 1));
+        assert_eq!(Some(PswTank::Second), psw_tank(201, 2));
+        assert_eq!(None, psw_tank(201, 3));
+        assert_eq!(None, psw_tank(1, 0));
+    }
+
+    #[test]
+    fn section6_with_bitmap_ok() {
+        let mut reader = Cursor::new(vec![
+            0x00,
+            0x00,
+            0x00,
+            0x08,
+            0x06,
+            0x00,
+            0b1010_0000,
+            0b0000_0001,
+        ]);
+        let section6 = Section6::from_reader(&mut reader).unwrap();
+        let bitmap = section6.bitmap().unwrap();
+        assert_eq!(16, bitmap.len());
+        assert_eq!(&[true, false, true, false], &bitmap[..4]);
+        assert!(bitmap[15]);
+    }
+
+    #[test]
+    fn section6_without_bitmap_ok() {
+        let mut reader = Cursor::new(vec![0x00, 0x00, 0x00, 0x06, 0x06, 0xFF]);
+        let section6 = Section6::from_reader(&mut reader).unwrap();
+        assert_eq!(255, section6.bitmap_indicator());
+        assert!(section6.bitmap().is_none());
+    }
+
+    #[test]
+    fn affine_transform_ok() {
+        let mut reader = Cursor::new(section0_to_section3_bytes(1_000));
+        Section0::from_reader(&mut reader).unwrap();
+        Section1::from_reader(&mut reader).unwrap();
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+        let expected = [118.0, 0.0125, 0.0, 47.9999995, 0.0, -0.008333];
+        let actual = section3.affine_transform();
+        for (e, a) in expected.iter().zip(actual.iter()) {
+            assert!((e - a).abs() < 1e-9, "expected: {}, actual: {}", e, a);
+        }
+    }
+
+    #[test]
+    fn validate_scanning_mode_ok() {
+        let mut reader = Cursor::new(section0_to_section3_bytes(1_000));
+        Section0::from_reader(&mut reader).unwrap();
+        Section1::from_reader(&mut reader).unwrap();
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+        assert!(section3.validate_scanning_mode().is_ok());
+    }
+
+    #[test]
+    fn validate_scanning_mode_with_inconsistent_points_err() {
+        // 最初の格子点が北西端にあるが、走査モードが東から西または南から北を示すファイル
+        for scanning_mode in [0x80, 0x40] {
+            let mut bytes = section0_to_section3_bytes(1_000);
+            *bytes.last_mut().unwrap() = scanning_mode;
+            let mut reader = Cursor::new(bytes);
+            Section0::from_reader(&mut reader).unwrap();
+            Section1::from_reader(&mut reader).unwrap();
+            let section3 = Section3_0::from_reader(&mut reader).unwrap();
+            assert!(matches!(
+                section3.validate_scanning_mode(),
+                Err(ReaderError::Unexpected(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn validate_scanning_mode_with_zero_increment_err() {
+        // i方向またはj方向の増分が0のファイル（増分は走査モードの直前に記録されている）
+        for (end, name) in [(5, "i方向の増分"), (1, "j方向の増分")] {
+            let mut bytes = section0_to_section3_bytes(1_000);
+            let end = bytes.len() - end;
+            bytes[end - 4..end].copy_from_slice(&0u32.to_be_bytes());
+            let mut reader = Cursor::new(bytes);
+            Section0::from_reader(&mut reader).unwrap();
+            Section1::from_reader(&mut reader).unwrap();
+            let section3 = Section3_0::from_reader(&mut reader).unwrap();
+            match section3.validate_scanning_mode() {
+                Err(ReaderError::Unexpected(message)) => assert!(message.contains(name)),
+                _ => panic!("増分が0の場合はエラーでなければなりません。"),
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_file_reports_offset() {
+        // 第1節の資料の参照時刻（年）の途中で切り詰めたファイル
+        let bytes = section0_to_section3_bytes(1_000);
+        let mut reader = Cursor::new(bytes[..16 + 13].to_vec());
+        Section0::from_reader(&mut reader).unwrap();
+        match Section1::from_reader(&mut reader) {
+            Err(ReaderError::ReadErrorAt { offset, .. }) => assert_eq!(16 + 12, offset),
+            _ => panic!("読み込みに失敗した位置を含むエラーでなければなりません。"),
+        }
     }
 }
@@ -0,0 +1,215 @@
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use super::{FileReader, ForecastHour6, GridDefinition, PrrReader, ReaderError, ReaderResult};
+
+/// 予報時間ごとに配信される複数のGRIB2ファイルをまとめて読み込み、同一の格子の時系列を取り出す。
+///
+/// 全てのファイルの格子系定義が一致していなければならない。
+pub struct Grib2TimeSeries<R = FileReader>
+where
+    R: Read + Seek,
+{
+    /// 予報時間とGRIB2ファイルのリーダーのコレクション
+    forecasts: Vec<(ForecastHour6, PrrReader<R>)>,
+}
+
+impl Grib2TimeSeries<FileReader> {
+    /// 予報時間とファイルパスのコレクションを受け取り、時系列リーダーを構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `files` - 予報時間とGRIB2形式のファイルのパスのコレクション
+    ///
+    /// # 戻り値
+    ///
+    /// 時系列リーダー
+    pub fn new<P: AsRef<Path>>(files: &[(ForecastHour6, P)]) -> ReaderResult<Self> {
+        let forecasts = files
+            .iter()
+            .map(|(hour, path)| Ok((*hour, PrrReader::new(path)?)))
+            .collect::<ReaderResult<Vec<_>>>()?;
+
+        Self::from_readers(forecasts)
+    }
+}
+
+impl<R> Grib2TimeSeries<R>
+where
+    R: Read + Seek,
+{
+    /// 予報時間とGRIB2ファイルのリーダーのコレクションを受け取り、時系列リーダーを構築する。
+    ///
+    /// 全てのリーダーの格子系定義（格子点数、最初及び最後の格子点、増分並びに走査モード）が、
+    /// 最初のリーダーの格子系定義と一致しない場合はエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `forecasts` - 予報時間とGRIB2ファイルのリーダーのコレクション
+    ///
+    /// # 戻り値
+    ///
+    /// 時系列リーダー
+    pub fn from_readers(forecasts: Vec<(ForecastHour6, PrrReader<R>)>) -> ReaderResult<Self> {
+        if let Some((first_hour, first)) = forecasts.first() {
+            let expected = GridDefinition::from(first.section3());
+            for (hour, reader) in forecasts.iter().skip(1) {
+                if GridDefinition::from(reader.section3()) != expected {
+                    return Err(ReaderError::Unexpected(
+                        format!(
+                            "{}のファイルの格子系定義が、{}のファイルの格子系定義と一致しません。",
+                            hour, first_hour
+                        )
+                        .into(),
+                    ));
+                }
+            }
+        }
+
+        Ok(Self { forecasts })
+    }
+
+    /// 予報時間のコレクションを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 構築時に受け取った順番の予報時間のコレクション
+    pub fn hours(&self) -> Vec<ForecastHour6> {
+        self.forecasts.iter().map(|(hour, _)| *hour).collect()
+    }
+
+    /// 指定した座標を含む格子の時系列を返す。
+    ///
+    /// 座標から緯度方向及び経度方向に増分の半分以内にある格子を、座標を含む格子とする。
+    /// 各ファイルのランレングス圧縮符号列を先頭から展開して格子を探すため、ファイルの数と
+    /// 格子数に比例した時間がかかる。
+    ///
+    /// # 引数
+    ///
+    /// * `lat` - 緯度（度単位）
+    /// * `lon` - 経度（度単位）
+    ///
+    /// # 戻り値
+    ///
+    /// 予報時間と格子の物理値（欠測値の場合は`None`）のコレクション。座標が格子の範囲外の場合は
+    /// エラーを返す。
+    pub fn series_at(
+        &mut self,
+        lat: f64,
+        lon: f64,
+    ) -> ReaderResult<Vec<(ForecastHour6, Option<u16>)>> {
+        let lat = (lat * 1_000_000.0).round() as i64;
+        let lon = (lon * 1_000_000.0).round() as i64;
+        let mut series = Vec::with_capacity(self.forecasts.len());
+        for (hour, reader) in self.forecasts.iter_mut() {
+            let section3 = reader.section3();
+            let lat_inc = section3.j_direction_increment() as i64;
+            let lon_inc = section3.i_direction_increment() as i64;
+            let mut found = None;
+            for value in reader.values_raw()? {
+                let value = value?;
+                if (value.lat_i32() as i64 - lat).abs() * 2 <= lat_inc
+                    && (value.lon_u32() as i64 - lon).abs() * 2 <= lon_inc
+                {
+                    found = Some(value.value());
+                    break;
+                }
+            }
+            let value = found.ok_or_else(|| {
+                ReaderError::Unexpected(
+                    format!(
+                        "座標(緯度: {:.6}, 経度: {:.6})が、{}のファイルの格子の範囲外です。",
+                        lat as f64 / 1_000_000.0,
+                        lon as f64 / 1_000_000.0,
+                        hour
+                    )
+                    .into(),
+                )
+            })?;
+            series.push((*hour, value));
+        }
+
+        Ok(series)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::Grib2TimeSeries;
+    use crate::reader::sections::small_prr_bytes;
+    use crate::reader::{FileReader, ForecastHour6, PrrReader, ReaderError};
+
+    const LAT_OF_FIRST_GRID_POINT: u32 = 36_004_167;
+
+    /// バイト列から読み込む1kmメッシュ解析雨量リーダー
+    type BytesReader = PrrReader<FileReader<Cursor<Vec<u8>>>>;
+
+    fn forecast_readers(lats: [u32; 6]) -> Vec<(ForecastHour6, BytesReader)> {
+        let levels = [
+            [1, 2, 3, 0],
+            [2, 3, 0, 1],
+            [3, 0, 1, 2],
+            [0, 1, 2, 3],
+            [1, 1, 1, 1],
+            [3, 3, 3, 3],
+        ];
+        (1..=6u8)
+            .zip(lats)
+            .zip(levels)
+            .map(|((hour, lat), levels)| {
+                let reader = Cursor::new(small_prr_bytes(lat, levels));
+                (
+                    ForecastHour6::try_from(hour).unwrap(),
+                    PrrReader::from_reader(reader).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn series_at_ok() {
+        let readers = forecast_readers([LAT_OF_FIRST_GRID_POINT; 6]);
+        let mut series = Grib2TimeSeries::from_readers(readers).unwrap();
+        assert_eq!(6, series.hours().len());
+
+        // 北西端の格子
+        let values = series.series_at(36.004167, 118.00625).unwrap();
+        let expected = vec![
+            (ForecastHour6::Hour1, Some(10)),
+            (ForecastHour6::Hour2, Some(20)),
+            (ForecastHour6::Hour3, Some(30)),
+            (ForecastHour6::Hour4, None),
+            (ForecastHour6::Hour5, Some(10)),
+            (ForecastHour6::Hour6, Some(30)),
+        ];
+        assert_eq!(expected, values);
+        // 南東端の格子（格子点から増分の半分未満だけずれた座標）
+        let values = series.series_at(35.9992, 118.0230).unwrap();
+        let values = values
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![None, Some(10), Some(20), Some(30), Some(10), Some(30)],
+            values
+        );
+        // 格子の範囲外
+        assert!(matches!(
+            series.series_at(35.0, 118.0),
+            Err(ReaderError::Unexpected(_))
+        ));
+    }
+
+    #[test]
+    fn from_readers_with_mismatched_grid_definitions_err() {
+        let mut lats = [LAT_OF_FIRST_GRID_POINT; 6];
+        lats[3] += 8_333;
+        let readers = forecast_readers(lats);
+        match Grib2TimeSeries::from_readers(readers) {
+            Err(ReaderError::Unexpected(message)) => assert!(message.contains("4時間後予報")),
+            _ => panic!("格子系定義が一致しないエラーを想定しています。"),
+        }
+    }
+}
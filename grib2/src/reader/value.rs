@@ -1,5 +1,11 @@
+use super::mesh::MeshLevel;
+use super::sections::decimal_scale;
+
 /// GRIB2値
+///
+/// feature`serde`が有効な場合は、シリアライズ及びデシリアライズできる。欠測値は`null`で表現する。
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Grib2Value<V> {
     /// 緯度（度単位）
     pub lat: f64,
@@ -10,4 +16,736 @@ pub struct Grib2Value<V> {
     /// 物理値
     /// Noneの場合は欠測値
     pub value: Option<V>,
+    /// データ代表値の尺度因子
+    ///
+    /// デシリアライズ時に存在しない場合は0とする。
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decimal_scale_factor: u8,
+    /// 欠測値の場合に`physical_value`が返すセンチネル値
+    ///
+    /// `None`の場合、`physical_value`は欠測値に対して`None`を返す。デシリアライズ時に存在しない
+    /// 場合は`None`とする。
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub missing_value: Option<f64>,
+}
+
+/// 座標を10e-6度単位の整数で保持するGRIB2値
+///
+/// GRIB2ファイルに記録されている座標をそのまま保持するため、浮動小数点数の誤差を気にせずに
+/// 座標をキーとしたメッシュコードのキャッシュなどに利用できる。度単位の座標が必要な場合は、
+/// `as_f64`で`Grib2Value`に変換する。
+///
+/// ```text
+/// let mut mesh_codes: HashMap<(i32, u32), Option<u64>> = HashMap::new();
+/// for value in reader.values_raw()? {
+///     let value = value?;
+///     mesh_codes
+///         .entry((value.lat_i32(), value.lon_u32()))
+///         .or_insert_with(|| value.mesh_code(MeshLevel::Third));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grib2ValueRaw<V> {
+    /// 緯度（10e-6度単位、南緯は負）
+    lat: i32,
+    /// 経度（10e-6度単位）
+    lon: u32,
+    /// レベル値
+    level: u16,
+    /// 物理値
+    /// Noneの場合は欠測値
+    value: Option<V>,
+    /// データ代表値の尺度因子
+    ///
+    /// デシリアライズ時に存在しない場合は0とする。
+    #[cfg_attr(feature = "serde", serde(default))]
+    decimal_scale_factor: u8,
+    /// 欠測値の場合に`physical_value`が返すセンチネル値
+    #[cfg_attr(feature = "serde", serde(default))]
+    missing_value: Option<f64>,
+}
+
+/// 降水量の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrecipitationUnit {
+    /// 降水強度（mm/h）
+    MillimetersPerHour,
+    /// 降水量（mm）
+    Millimeters,
+}
+
+impl std::fmt::Display for PrecipitationUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MillimetersPerHour => write!(f, "mm/h"),
+            Self::Millimeters => write!(f, "mm"),
+        }
+    }
+}
+
+/// 物理値を整数に丸める方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundMode {
+    /// 切り捨て（負の無限大方向に丸める）
+    Floor,
+    /// 四捨五入（端数が0.5の場合は0から遠い方向に丸める）
+    Round,
+    /// 切り上げ（正の無限大方向に丸める）
+    Ceil,
+}
+
+impl RoundMode {
+    /// 指定した方法で実数を整数に丸める。
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            Self::Floor => value.floor(),
+            Self::Round => value.round(),
+            Self::Ceil => value.ceil(),
+        }
+    }
+}
+
+/// 単位を添えたGRIB2値のイテレーター
+pub struct UnitValues<I> {
+    /// 物理値の単位
+    unit: PrecipitationUnit,
+    /// GRIB2値のイテレーター
+    inner: I,
+}
+
+impl<I> UnitValues<I> {
+    /// 単位を添えたGRIB2値のイテレーターを構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `unit` - 物理値の単位
+    /// * `inner` - GRIB2値のイテレーター
+    ///
+    /// # 戻り値
+    ///
+    /// 単位を添えたGRIB2値のイテレーター
+    pub(crate) fn new(unit: PrecipitationUnit, inner: I) -> Self {
+        Self { unit, inner }
+    }
+
+    /// 物理値の単位を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 物理値の単位
+    pub fn unit(&self) -> PrecipitationUnit {
+        self.unit
+    }
+}
+
+impl<I> Iterator for UnitValues<I>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<V> Grib2Value<V> {
+    /// 緯度でグループ化するときのキーとして、緯度を10e-6度単位の整数で返す。
+    ///
+    /// 格子の緯度は浮動小数点数で比較すると誤差が生じるため、第3節と同じ10e-6度単位に丸めた整数を
+    /// キーとする。同じ行の格子は同じキーを返すため、`itertools`の`chunk_by`などで緯度帯ごとに
+    /// グループ化できる。
+    ///
+    /// ```text
+    /// use itertools::Itertools;
+    ///
+    /// let values: Vec<_> = reader.values()?.collect::<ReaderResult<_>>()?;
+    /// for (lat_key, row) in &values.into_iter().chunk_by(|v| v.lat_key()) {
+    ///     println!("{}: {}", lat_key, row.count());
+    /// }
+    /// ```
+    ///
+    /// # 戻り値
+    ///
+    /// 10e-6度単位の緯度（南緯は負）
+    pub fn lat_key(&self) -> i32 {
+        (self.lat * 1_000_000.0).round() as i32
+    }
+
+    /// 座標が含まれる標準地域メッシュの第3次地域区画（1kmメッシュ）のメッシュコードを返す。
+    ///
+    /// メッシュの境界上にある座標は、境界の北側及び東側のメッシュに含まれるものとする。
+    ///
+    /// # 戻り値
+    ///
+    /// 8桁の第3次地域区画のメッシュコード。座標が標準地域メッシュの範囲外の場合は`None`
+    pub fn mesh_code(&self) -> Option<u64> {
+        self.mesh_code_of(MeshLevel::Third)
+    }
+
+    /// 座標が含まれる標準地域メッシュの第2次地域区画のメッシュコードを返す。
+    ///
+    /// メッシュの境界上にある座標は、境界の北側及び東側のメッシュに含まれるものとする。
+    ///
+    /// # 戻り値
+    ///
+    /// 6桁の第2次地域区画のメッシュコード。座標が標準地域メッシュの範囲外の場合は`None`
+    pub fn secondary_mesh_code(&self) -> Option<u64> {
+        self.mesh_code_of(MeshLevel::Second)
+    }
+
+    /// 座標が含まれる標準地域メッシュのメッシュコードを返す。
+    ///
+    /// 座標を10e-6度単位の整数に丸めて、`MeshLevel::mesh_code`で求める。
+    ///
+    /// # 引数
+    ///
+    /// * `level` - 標準地域メッシュの区画
+    ///
+    /// # 戻り値
+    ///
+    /// メッシュコード。座標が標準地域メッシュの範囲外の場合は`None`
+    pub(crate) fn mesh_code_of(&self, level: MeshLevel) -> Option<u64> {
+        // 南緯の座標は標準地域メッシュの範囲外
+        let lat = u32::try_from(self.lat_key()).ok()?;
+        // 負の経度は0に飽和するため、経度100度未満として範囲外になる
+        level.mesh_code(lat, (self.lon * 1_000_000.0).round() as u32)
+    }
+
+    /// 格子を面（セル）として扱うときの、セルの中心の座標を返す。
+    ///
+    /// GRIB2の緯度・経度格子は、格子点の値がその格子点を中心とするセルを代表するものと解釈する。
+    /// 例えば、1kmメッシュ解析雨量の最初の格子点（北緯47.995833度、東経118.00625度）は、
+    /// 第3次地域区画の南西端から緯度及び経度の増分の半分だけ北東にずれた、区画の中心に位置する。
+    /// したがって、セルの中心は格子点の座標と一致する。
+    ///
+    /// # 戻り値
+    ///
+    /// セルの中心の緯度と経度（度単位）
+    pub fn cell_center(&self) -> (f64, f64) {
+        (self.lat, self.lon)
+    }
+
+    /// 格子を面（セル）として扱うときの、セルの範囲を返す。
+    ///
+    /// 格子点をセルの中心とみなし（`cell_center`を参照）、格子点から緯度及び経度の増分の半分だけ
+    /// 離れた位置をセルの境界とする。隣接するセルは境界を共有する。
+    ///
+    /// # 引数
+    ///
+    /// * `lat_inc` - 緯度の増分（度単位、第3節のj方向の増分）
+    /// * `lon_inc` - 経度の増分（度単位、第3節のi方向の増分）
+    ///
+    /// # 戻り値
+    ///
+    /// セルの南西端の緯度、南西端の経度、北東端の緯度、北東端の経度（度単位）
+    pub fn cell_bounds(&self, lat_inc: f64, lon_inc: f64) -> (f64, f64, f64, f64) {
+        let half_lat = lat_inc / 2.0;
+        let half_lon = lon_inc / 2.0;
+
+        (
+            self.lat - half_lat,
+            self.lon - half_lon,
+            self.lat + half_lat,
+            self.lon + half_lon,
+        )
+    }
+}
+
+/// 格子の増分を添えたGRIB2値
+///
+/// `Grib2Value`は増分を保持しないため、格子を面（セル）として扱う場合は`Grib2ValueIter::cells`で
+/// このGRIB2値を取得する。
+#[derive(Debug, Clone, Copy)]
+pub struct Grib2Cell<V> {
+    /// GRIB2値
+    value: Grib2Value<V>,
+    /// 緯度の増分（度単位）
+    lat_inc: f64,
+    /// 経度の増分（度単位）
+    lon_inc: f64,
+}
+
+impl<V> Grib2Cell<V> {
+    /// 格子の増分を添えたGRIB2値を構築する。
+    pub(crate) fn new(value: Grib2Value<V>, lat_inc: f64, lon_inc: f64) -> Self {
+        Self {
+            value,
+            lat_inc,
+            lon_inc,
+        }
+    }
+
+    /// GRIB2値を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB2値
+    pub fn value(&self) -> &Grib2Value<V> {
+        &self.value
+    }
+
+    /// 緯度の増分を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 緯度の増分（度単位）
+    pub fn lat_inc(&self) -> f64 {
+        self.lat_inc
+    }
+
+    /// 経度の増分を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 経度の増分（度単位）
+    pub fn lon_inc(&self) -> f64 {
+        self.lon_inc
+    }
+
+    /// セルの中心の座標を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// セルの中心の緯度と経度（度単位）
+    pub fn center(&self) -> (f64, f64) {
+        self.value.cell_center()
+    }
+
+    /// セルの範囲を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// セルの南西端の緯度、南西端の経度、北東端の緯度、北東端の経度（度単位）
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        self.value.cell_bounds(self.lat_inc, self.lon_inc)
+    }
+
+    /// セルの四隅の座標を返す。
+    ///
+    /// ポリゴンとして出力できるように、南西端から反時計回りに並べる。
+    ///
+    /// # 戻り値
+    ///
+    /// 南西端、南東端、北東端、北西端の緯度と経度（度単位）
+    pub fn corners(&self) -> [(f64, f64); 4] {
+        let (south, west, north, east) = self.bounds();
+
+        [(south, west), (south, east), (north, east), (north, west)]
+    }
+}
+
+impl<V> Grib2Value<V>
+where
+    V: Copy,
+{
+    /// 尺度因子を適用する前の物理値を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 尺度因子を適用する前の物理値。欠測値の場合は`None`
+    pub fn value(&self) -> Option<V> {
+        self.value
+    }
+}
+
+impl<V> Grib2Value<V>
+where
+    V: Copy + Into<f64>,
+{
+    /// 尺度因子を適用した物理値（物理値 / 10^尺度因子）を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 尺度因子を適用した物理値。欠測値の場合はセンチネル値で、センチネル値を設定していない場合は
+    /// `None`
+    pub fn physical_value(&self) -> Option<f64> {
+        let scale = decimal_scale(self.decimal_scale_factor);
+        match self.value {
+            Some(v) => Some(v.into() / scale),
+            None => self.missing_value,
+        }
+    }
+
+    /// 尺度因子を適用した物理値を、指定した方法で整数に丸めて返す。
+    ///
+    /// # 引数
+    ///
+    /// * `mode` - 丸め方
+    ///
+    /// # 戻り値
+    ///
+    /// 丸めた物理値。欠測値の場合は`physical_value`と同様にセンチネル値または`None`
+    pub fn physical_value_rounded(&self, mode: RoundMode) -> Option<f64> {
+        self.physical_value().map(|v| mode.apply(v))
+    }
+
+    /// 尺度因子を適用した物理値を、指定した方法で整数に丸めて返す。
+    ///
+    /// # 引数
+    ///
+    /// * `mode` - 丸め方
+    ///
+    /// # 戻り値
+    ///
+    /// 丸めた物理値。欠測値の場合は`physical_value`と同様にセンチネル値または`None`
+    pub fn physical_value_int(&self, mode: RoundMode) -> Option<i64> {
+        self.physical_value_rounded(mode).map(|v| v as i64)
+    }
+}
+
+/// `(緯度, 経度) level=レベル値 value=物理値`の形式で、人間が読みやすいように出力する。
+///
+/// 緯度及び経度は小数点以下6桁で出力し、欠測値の物理値は`-`で出力する。構造体のフィールドを
+/// そのまま出力する場合は`Debug`を利用する。
+impl<V> std::fmt::Display for Grib2Value<V>
+where
+    V: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({:.6}, {:.6}) level={} value=",
+            self.lat, self.lon, self.level
+        )?;
+        match &self.value {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+impl<V> Grib2ValueRaw<V> {
+    /// 座標を10e-6度単位の整数で保持するGRIB2値を構築する。
+    pub(crate) fn new(
+        lat: i32,
+        lon: u32,
+        level: u16,
+        value: Option<V>,
+        decimal_scale_factor: u8,
+        missing_value: Option<f64>,
+    ) -> Self {
+        Self {
+            lat,
+            lon,
+            level,
+            value,
+            decimal_scale_factor,
+            missing_value,
+        }
+    }
+
+    /// 緯度を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 10e-6度単位の緯度（南緯は負）
+    pub fn lat_i32(&self) -> i32 {
+        self.lat
+    }
+
+    /// 経度を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 10e-6度単位の経度
+    pub fn lon_u32(&self) -> u32 {
+        self.lon
+    }
+
+    /// レベル値を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// レベル値
+    pub fn level(&self) -> u16 {
+        self.level
+    }
+
+    /// データ代表値の尺度因子を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// データ代表値の尺度因子
+    pub fn decimal_scale_factor(&self) -> u8 {
+        self.decimal_scale_factor
+    }
+
+    /// 座標が含まれる標準地域メッシュのメッシュコードを返す。
+    ///
+    /// 座標を整数のまま計算するため、度単位の座標から丸める必要がない。
+    ///
+    /// # 引数
+    ///
+    /// * `level` - 標準地域メッシュの区画
+    ///
+    /// # 戻り値
+    ///
+    /// メッシュコード。座標が標準地域メッシュの範囲外の場合は`None`
+    pub fn mesh_code(&self, level: MeshLevel) -> Option<u64> {
+        // 南緯の座標は標準地域メッシュの範囲外
+        level.mesh_code(u32::try_from(self.lat).ok()?, self.lon)
+    }
+}
+
+impl<V> Grib2ValueRaw<V>
+where
+    V: Copy,
+{
+    /// 尺度因子を適用する前の物理値を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 尺度因子を適用する前の物理値。欠測値の場合は`None`
+    pub fn value(&self) -> Option<V> {
+        self.value
+    }
+
+    /// 座標を度単位に変換したGRIB2値を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 座標を度単位で保持するGRIB2値
+    pub fn as_f64(&self) -> Grib2Value<V> {
+        Grib2Value {
+            lat: self.lat as f64 / 1_000_000.0,
+            lon: self.lon as f64 / 1_000_000.0,
+            level: self.level,
+            value: self.value,
+            decimal_scale_factor: self.decimal_scale_factor,
+            missing_value: self.missing_value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grib2Value, Grib2ValueRaw, RoundMode};
+    use crate::reader::MeshLevel;
+
+    fn value_at(lat: f64, lon: f64) -> Grib2Value<u16> {
+        Grib2Value {
+            lat,
+            lon,
+            level: 0,
+            value: None,
+            decimal_scale_factor: 0,
+            missing_value: None,
+        }
+    }
+
+    #[test]
+    fn display_ok() {
+        let value = Grib2Value {
+            lat: 35.681236,
+            lon: 139.7671254,
+            level: 3,
+            value: Some(120u16),
+            decimal_scale_factor: 1,
+            missing_value: None,
+        };
+        assert_eq!(
+            "(35.681236, 139.767125) level=3 value=120",
+            value.to_string()
+        );
+        // Debugは構造体のフィールドを出力する
+        assert_eq!(
+            "Grib2Value { lat: 35.681236, lon: 139.7671254, level: 3, value: Some(120), \
+            decimal_scale_factor: 1, missing_value: None }",
+            format!("{:?}", value)
+        );
+
+        let missing = value_at(36.0, 140.0);
+        assert_eq!(
+            "(36.000000, 140.000000) level=0 value=-",
+            missing.to_string()
+        );
+    }
+
+    #[test]
+    fn mesh_code_of_tokyo_station() {
+        let value = value_at(35.681236, 139.767125);
+        assert_eq!(Some(53394611), value.mesh_code());
+        assert_eq!(Some(533946), value.secondary_mesh_code());
+    }
+
+    #[test]
+    fn mesh_code_of_osaka_station() {
+        let value = value_at(34.702485, 135.495951);
+        assert_eq!(Some(52350349), value.mesh_code());
+        assert_eq!(Some(523503), value.secondary_mesh_code());
+    }
+
+    #[test]
+    fn mesh_code_on_boundary_belongs_to_north_east_mesh() {
+        // 第1次地域区画5339の南西端
+        let value = value_at(35.0 + 20.0 / 60.0, 139.0);
+        assert_eq!(Some(53390000), value.mesh_code());
+    }
+
+    #[test]
+    fn mesh_code_out_of_range_is_none() {
+        // 標準地域メッシュは経度100度以上、北緯の座標を対象とする
+        assert_eq!(None, value_at(35.0, 99.5).mesh_code());
+        assert_eq!(None, value_at(-35.0, 139.0).secondary_mesh_code());
+    }
+
+    #[test]
+    fn cell_bounds_ok() {
+        // 1kmメッシュ解析雨量の最初の格子点は、第3次地域区画の中心
+        let value = value_at(47.995833, 118.00625);
+        assert_eq!((47.995833, 118.00625), value.cell_center());
+        let (south, west, north, east) = value.cell_bounds(1.0 / 120.0, 0.0125);
+        assert!((south - (48.0 - 1.0 / 120.0)).abs() < 1e-6);
+        assert!((west - 118.0).abs() < 1e-9);
+        assert!((north - 48.0).abs() < 1e-6);
+        assert!((east - 118.0125).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_to_json() {
+        let value = Grib2Value {
+            lat: 35.5,
+            lon: 139.25,
+            level: 3,
+            value: Some(30u16),
+            decimal_scale_factor: 1,
+            missing_value: None,
+        };
+        assert_eq!(
+            r#"{"lat":35.5,"lon":139.25,"level":3,"value":30,"decimal_scale_factor":1,"missing_value":null}"#,
+            serde_json::to_string(&value).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_missing_value_as_null() {
+        let json = serde_json::to_string(&value_at(35.5, 139.25)).unwrap();
+        assert_eq!(
+            r#"{"lat":35.5,"lon":139.25,"level":0,"value":null,"decimal_scale_factor":0,"missing_value":null}"#,
+            json
+        );
+        let value: Grib2Value<u16> = serde_json::from_str(&json).unwrap();
+        assert_eq!(None, value.value);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_keeps_physical_value() {
+        // 尺度因子とセンチネル値を往復させても、物理値が変わらない
+        for value in [
+            value_of(Some(305), 1),
+            Grib2Value {
+                missing_value: Some(-1.0),
+                ..value_of(None, 1)
+            },
+        ] {
+            let json = serde_json::to_string(&value).unwrap();
+            let restored: Grib2Value<u16> = serde_json::from_str(&json).unwrap();
+            assert_eq!(value.physical_value(), restored.physical_value());
+            assert_eq!(value.decimal_scale_factor, restored.decimal_scale_factor);
+            assert_eq!(value.missing_value, restored.missing_value);
+        }
+
+        // 尺度因子とセンチネル値を含まないJSONは、尺度因子0、センチネル値なしとして読み込む
+        let json = r#"{"lat":35.5,"lon":139.25,"level":3,"value":30}"#;
+        let value: Grib2Value<u16> = serde_json::from_str(json).unwrap();
+        assert_eq!(0, value.decimal_scale_factor);
+        assert_eq!(None, value.missing_value);
+    }
+
+    #[test]
+    fn lat_key_ok() {
+        assert_eq!(35_000_000, value_at(35.0, 139.0).lat_key());
+        // 浮動小数点数の誤差を含む緯度は、同じキーに丸める
+        let lat = 36.0 - 0.008333 * 3.0;
+        assert_eq!(35_975_001, value_at(lat, 139.0).lat_key());
+        assert_eq!(
+            value_at(35.975001, 139.0).lat_key(),
+            value_at(lat, 140.0).lat_key()
+        );
+    }
+
+    fn value_of(value: Option<u16>, decimal_scale_factor: u8) -> Grib2Value<u16> {
+        Grib2Value {
+            lat: 35.0,
+            lon: 139.0,
+            level: 1,
+            value,
+            decimal_scale_factor,
+            missing_value: None,
+        }
+    }
+
+    #[test]
+    fn physical_value_ok() {
+        assert_eq!(Some(125.0), value_of(Some(125), 0).physical_value());
+        assert_eq!(Some(12.5), value_of(Some(125), 1).physical_value());
+        assert_eq!(Some(1.25), value_of(Some(125), 2).physical_value());
+        assert_eq!(None, value_of(None, 1).physical_value());
+        // センチネル値を設定した場合は、欠測値に対してセンチネル値を返す
+        let missing = Grib2Value {
+            missing_value: Some(-9999.0),
+            ..value_of(None, 1)
+        };
+        assert_eq!(Some(-9999.0), missing.physical_value());
+        let present = Grib2Value {
+            missing_value: Some(-9999.0),
+            ..value_of(Some(125), 1)
+        };
+        assert_eq!(Some(12.5), present.physical_value());
+        // 尺度因子を適用する前の物理値
+        assert_eq!(Some(125), value_of(Some(125), 2).value());
+    }
+
+    #[test]
+    fn physical_value_rounded_on_half_boundary() {
+        // 尺度因子1で0.5、1.5、2.5
+        for (value, floor, round, ceil) in
+            [(5, 0.0, 1.0, 1.0), (15, 1.0, 2.0, 2.0), (25, 2.0, 3.0, 3.0)]
+        {
+            let value = value_of(Some(value), 1);
+            assert_eq!(Some(floor), value.physical_value_rounded(RoundMode::Floor));
+            assert_eq!(Some(round), value.physical_value_rounded(RoundMode::Round));
+            assert_eq!(Some(ceil), value.physical_value_rounded(RoundMode::Ceil));
+        }
+    }
+
+    #[test]
+    fn physical_value_int_ok() {
+        let value = value_of(Some(24), 1);
+        assert_eq!(Some(2), value.physical_value_int(RoundMode::Floor));
+        assert_eq!(Some(2), value.physical_value_int(RoundMode::Round));
+        assert_eq!(Some(3), value.physical_value_int(RoundMode::Ceil));
+        // 整数になる物理値は、どの方法でも変わらない
+        let value = value_of(Some(30), 1);
+        assert_eq!(Some(3), value.physical_value_int(RoundMode::Floor));
+        assert_eq!(Some(3), value.physical_value_int(RoundMode::Ceil));
+        // 欠測値
+        assert_eq!(None, value_of(None, 0).physical_value_int(RoundMode::Round));
+    }
+
+    #[test]
+    fn grib2_value_raw_as_f64_ok() {
+        let raw = Grib2ValueRaw::new(35_681_236, 139_767_125, 3, Some(125u16), 1, None);
+        assert_eq!(Some(53394611), raw.mesh_code(MeshLevel::Third));
+        let value = raw.as_f64();
+        assert_eq!(35.681236, value.lat);
+        assert_eq!(139.767125, value.lon);
+        assert_eq!(raw.lat_i32(), value.lat_key());
+        assert_eq!(Some(12.5), value.physical_value());
+    }
+
+    #[test]
+    fn grib2_value_raw_with_southern_latitude_ok() {
+        let raw = Grib2ValueRaw::new(-35_681_236, 139_767_125, 3, Some(125u16), 1, None);
+        assert_eq!(-35_681_236, raw.lat_i32());
+        assert_eq!(None, raw.mesh_code(MeshLevel::Third));
+        let value = raw.as_f64();
+        assert_eq!(-35.681236, value.lat);
+        assert_eq!(raw.lat_i32(), value.lat_key());
+    }
 }
@@ -1,66 +1,128 @@
-use std::io::Read;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Arc;
 
 use num_format::{Locale, ToFormattedString};
 
-use super::value::Grib2Value;
-use super::{FileReader, ReaderError, ReaderResult};
+use super::color::{ColorMap, TRANSPARENT};
+use super::grid::{
+    cell_position, micro_degree_of_grid_point, GridDefinition, SCANNING_MODE_CONSECUTIVE_J,
+    SCANNING_MODE_POSITIVE_J,
+};
+use super::mask::GridMask;
+use super::mesh::{Aggregation, MeshAccumulator, MeshLevel};
+use super::sections::decimal_scale;
+use super::value::{Grib2Cell, Grib2Value, Grib2ValueRaw};
+use super::{ReaderError, ReaderResult};
+
+/// `Grib2ValueIter`が展開するランレングス圧縮符号列
+pub(crate) enum RunLength<'a> {
+    /// 読み込み位置が第7節ランレングス圧縮符号列の開始位置にあるリーダー
+    ///
+    /// 最初にランレングス圧縮符号を展開するときに、ランレングス圧縮符号列全体を読み込んで`Bytes`に
+    /// 置き換える。
+    Reader(Box<dyn Read + Send + 'a>),
+    /// メモリに読み込み済みのランレングス圧縮符号列
+    Bytes(Box<dyn AsRef<[u8]> + Send + 'a>),
+}
+
+impl<'a> RunLength<'a> {
+    /// ランレングス圧縮符号列を読み込むリーダーから構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 読み込み位置が第7節ランレングス圧縮符号列の開始位置にあるリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// `RunLength`
+    pub(crate) fn reader<R: Read + Send + 'a>(reader: R) -> Self {
+        Self::Reader(Box::new(reader))
+    }
+
+    /// メモリに読み込み済みのランレングス圧縮符号列から構築する。
+    ///
+    /// 複製せずに所有するため、`Vec<u8>`や`Arc<[u8]>`をそのまま渡せる。
+    ///
+    /// # 引数
+    ///
+    /// * `bytes` - ランレングス圧縮符号列
+    ///
+    /// # 戻り値
+    ///
+    /// `RunLength`
+    pub(crate) fn bytes<B: AsRef<[u8]> + Send + 'a>(bytes: B) -> Self {
+        Self::Bytes(Box::new(bytes))
+    }
+}
 
 pub struct Grib2ValueIter<'a, V> {
-    /// ファイルリーダー
-    reader: FileReader,
+    /// ランレングス圧縮符号列
+    run_length: RunLength<'a>,
     /// GRIB2ファイルに記録されている座標数
-    number_of_points: u32,
+    number_of_points: u64,
     /// ランレングス圧縮符号を記録しているバイト数
     total_bytes: usize,
-    /// 経度の最小値（10e-6度単位）
-    lon_min: u32,
-    /// 経度の最大値（10e-6度単位）
-    lon_max: u32,
-    /// 緯度の増分（10e-6度単位）
-    lat_inc: u32,
-    /// 経度の増分（10e-6度単位）
-    lon_inc: u32,
+    /// 走査モードに従って格子の座標を計算するための格子の定義
+    grid: ScanGrid,
     /// 今回のレベルの最大値
     maxv: u16,
+    /// データ代表値の尺度因子
+    decimal_scale_factor: u8,
+    /// 1格子点値当りのビット数
+    nbit: u16,
     /// LNGU進数
     lngu: u16,
     /// レベル別物理値
-    level_values: &'a [V],
+    ///
+    /// イテレーターをGRIB2ファイルのリーダーと独立して扱えるように、リーダーから複製して所有する。
+    level_values: Arc<[V]>,
     /// ランレングス圧縮符号を読み込んだバイト数
     read_bytes: usize,
-    /// 現在の緯度（10e-6度単位）
-    current_lat: u32,
-    /// 現在の経度（10e-6度単位）
-    current_lon: u32,
+    /// 読み込んだオクテットのうち、ランレングス圧縮符号として取り出していないビット
+    bit_buffer: u32,
+    /// `bit_buffer`に残っているビット数
+    bit_count: u32,
+    /// 最後に読み込んだランレングス圧縮符号が、最終オクテットの1オクテットに満たない残りのビットから
+    /// 取り出されたかどうか
+    last_code_in_tail: bool,
     /// 現在のレベル値
     current_level: u16,
     /// 現在の物理値
     current_value: Option<V>,
     /// 現在値を返却する回数
-    returning_times: u32,
+    ///
+    /// 1つのレベル値が長く連続する場合でも溢れないように`u64`で数える。
+    returning_times: u64,
     /// 読み込んだ座標数
-    number_of_reads: u32,
+    number_of_reads: u64,
     /// 最後に読み込んだランレングス圧縮符号
     last_run_length: Option<u16>,
+    /// ビットマップ（格子に資料値が存在する場合は`true`）
+    bitmap: Option<Arc<[bool]>>,
+    /// 欠測値の格子の物理値として返すセンチネル値
+    missing_value: Option<f64>,
+    /// エラーを返して終了したかどうか
+    finished: bool,
 }
 
 impl<'a, V> Grib2ValueIter<'a, V> {
     /// GRIB2値のイテレータを構築する。
     ///
-    /// 引数`reader`のファイルポインタは、第7節ランレングス圧縮符号列の開始位置にあることを想定している。
-    ///
     /// # 引数
     ///
-    /// * `reader` - ファイルのリーダー
+    /// * `run_length` - ランレングス圧縮符号列
     /// * `total_bytes` - ランレングス圧縮符号を記録しているバイト数
     /// * `number_of_points` - GRIB2ファイルに記録されている座標数
-    /// * `lat_max` - 緯度の最大値（10e-6度単位）
-    /// * `lon_min` - 経度の最小値（10e-6度単位）
-    /// * `lon_max` - 経度の最大値（10e-6度単位）
+    /// * `lat_first` - 最初の格子点の緯度（10e-6度単位）
+    /// * `lon_first` - 最初の格子点の経度（10e-6度単位）
+    /// * `lon_last` - 最後の格子点の経度（10e-6度単位）
     /// * `lat_inc` - 緯度の増分（10e-6度単位）
     /// * `lon_inc` - 経度の増分（10e-6度単位）
+    /// * `scan_mode` - 走査モード
     /// * `nbit` - 1格子点値当りのビット数
     /// * `maxv` - 今回の圧縮に用いたレベルの最大値
+    /// * `decimal_scale_factor` - データ代表値の尺度因子
     /// * `level_values` - レベル別物理値
     ///
     /// # 戻り値
@@ -68,57 +130,295 @@ impl<'a, V> Grib2ValueIter<'a, V> {
     /// `Grib2ValueIter`
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        reader: FileReader,
+        run_length: RunLength<'a>,
         total_bytes: usize,
         number_of_points: u32,
-        lat_max: u32,
-        lon_min: u32,
-        lon_max: u32,
+        lat_first: u32,
+        lon_first: u32,
+        lon_last: u32,
         lat_inc: u32,
         lon_inc: u32,
+        scan_mode: u8,
         nbit: u16,
         maxv: u16,
-        level_values: &'a [V],
-    ) -> Self {
+        decimal_scale_factor: u8,
+        level_values: &[V],
+    ) -> Self
+    where
+        V: Clone,
+    {
         Self {
-            reader,
+            run_length,
             total_bytes,
-            number_of_points,
-            lon_min,
-            lon_max,
-            lat_inc,
-            lon_inc,
+            number_of_points: number_of_points as u64,
+            grid: ScanGrid::new(
+                number_of_points,
+                lat_first,
+                lon_first,
+                lon_last,
+                lat_inc,
+                lon_inc,
+                scan_mode,
+            ),
             maxv,
-            lngu: 2u16.pow(nbit as u32) - 1 - maxv,
-            level_values,
+            decimal_scale_factor,
+            nbit,
+            // 2 ^ nbit - 1 - maxv（nbitが16を超える場合は16ビットとみなす）
+            lngu: u16::MAX
+                .checked_shr(16 - nbit.min(16) as u32)
+                .unwrap_or(0)
+                .saturating_sub(maxv),
+            level_values: Arc::from(level_values),
             read_bytes: 0,
-            current_lat: lat_max,
-            current_lon: lon_min,
+            bit_buffer: 0,
+            bit_count: 0,
+            last_code_in_tail: false,
             current_level: 0,
             current_value: None,
             returning_times: 0,
             number_of_reads: 0,
             last_run_length: None,
+            bitmap: None,
+            missing_value: None,
+            finished: false,
+        }
+    }
+
+    /// ビットマップを設定する。
+    ///
+    /// ビットマップを設定した場合、ビットが`false`の格子はランレングス圧縮符号を消費せずに
+    /// 欠測値として返す。
+    ///
+    /// # 引数
+    ///
+    /// * `bitmap` - 第6節に記録されているビットマップ
+    ///
+    /// # 戻り値
+    ///
+    /// `Grib2ValueIter`
+    pub(crate) fn with_bitmap(mut self, bitmap: Option<Arc<[bool]>>) -> Self {
+        self.bitmap = bitmap;
+
+        self
+    }
+
+    /// 格子の行ごとの緯度を設定する。
+    ///
+    /// ガウス格子のように緯度が等間隔でない格子で、最初の格子点の緯度と増分から緯度を計算する
+    /// 代わりに、行ごとの緯度を参照する。行ごとの緯度の数が行数より少ない場合、緯度を設定していない
+    /// 行は最初の格子点の緯度と増分から計算する。
+    ///
+    /// # 引数
+    ///
+    /// * `latitudes` - 走査モードによらず北の行から順に並べた行ごとの緯度（度単位）。
+    ///   `Template3_40::latitudes`の戻り値を想定している。
+    ///
+    /// # 戻り値
+    ///
+    /// `Grib2ValueIter`
+    pub fn with_latitudes(mut self, latitudes: &[f64]) -> Self {
+        self.grid.latitudes = Some(
+            latitudes
+                .iter()
+                .map(|lat| (lat * 1_000_000.0).round() as i64)
+                .collect(),
+        );
+
+        self
+    }
+
+    /// 欠測値の格子の物理値として返すセンチネル値を設定する。
+    ///
+    /// センチネル値を設定した場合、イテレーターが返すGRIB2値の`physical_value`は、欠測値の格子に
+    /// 対して`None`の代わりにセンチネル値を返す。`value`は欠測値の格子に対して`None`のままである。
+    /// 設定しない場合は`None`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `value` - 欠測値の格子の物理値として返すセンチネル値（例: `-9999.0`、`-1.0`）
+    ///
+    /// # 戻り値
+    ///
+    /// `Grib2ValueIter`
+    pub fn missing_as(mut self, value: f64) -> Self {
+        self.missing_value = Some(value);
+
+        self
+    }
+
+    /// 格子の読み込みの進捗を返す。
+    ///
+    /// イテレーション中に呼び出して、プログレスバーなどの進捗の表示に利用できる。
+    ///
+    /// # 戻り値
+    ///
+    /// 読み込んだ格子数と、GRIB2ファイルに記録されている格子数のタプル
+    pub fn progress(&self) -> (u64, u64) {
+        (self.number_of_reads, self.number_of_points)
+    }
+
+    /// ランレングス圧縮符号列の読み込みの進捗を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// ランレングス圧縮符号を読み込んだバイト数と、ランレングス圧縮符号を記録しているバイト数のタプル
+    pub fn bytes_progress(&self) -> (usize, usize) {
+        (self.read_bytes, self.total_bytes)
+    }
+
+    /// 現在の格子が、ビットマップにより資料値が存在しないとされているか確認する。
+    fn is_masked_by_bitmap(&self) -> bool {
+        match self.bitmap.as_deref() {
+            Some(bitmap) => {
+                self.number_of_reads < self.number_of_points
+                    && !bitmap
+                        .get(self.number_of_reads as usize)
+                        .copied()
+                        .unwrap_or(false)
+            }
+            None => false,
+        }
+    }
+
+    /// 次の格子に移動する。
+    fn move_to_next_point(&mut self) {
+        // 読み込んだ座標数をインクリメント
+        self.number_of_reads += 1;
+    }
+
+    /// 現在の格子のGRIB2値を返す。
+    fn current_point(&self, level: u16, value: Option<V>) -> Grib2ValueRaw<V> {
+        // 展開した格子が、第3節に記録されている資料点数を超えていないことを確認
+        debug_assert!(
+            self.number_of_reads < self.number_of_points,
+            "読み込んだ座標数({})が第3節に記録されている資料点数({})に達した後に格子を返そうとしました。",
+            self.number_of_reads,
+            self.number_of_points
+        );
+        // 読み込んだ座標数は資料点数未満であるため、格子のインデックスに変換できる
+        let (lat, lon) = self.grid.raw_coordinate(self.number_of_reads as usize);
+
+        Grib2ValueRaw::new(
+            lat,
+            lon,
+            level,
+            value,
+            self.decimal_scale_factor,
+            self.missing_value,
+        )
+    }
+
+    /// 格子の列数（緯線に沿った格子点数）を返す。
+    fn number_of_columns(&self) -> u32 {
+        self.grid.ni()
+    }
+
+    /// 格子の行数（経線に沿った格子点数）を返す。
+    fn number_of_rows(&self) -> u32 {
+        self.grid.nj()
+    }
+
+    /// ランレングス圧縮符号列をリーダーから読み込む場合は、ランレングス圧縮符号列全体を一括して
+    /// 読み込む。
+    ///
+    /// リーダーのデータがランレングス圧縮符号を記録しているバイト数より短い場合は、読み込めた
+    /// オクテットだけを保持して、それ以降のオクテットを読み込むときにエラーを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// `()`。リーダーからの読み込みに失敗した場合はエラー
+    fn prefetch(&mut self) -> ReaderResult<()> {
+        if let RunLength::Reader(reader) = &mut self.run_length {
+            let mut bytes = Vec::with_capacity(self.total_bytes);
+            let result = reader
+                .by_ref()
+                .take(self.total_bytes as u64)
+                .read_to_end(&mut bytes);
+            self.run_length = RunLength::bytes(bytes);
+            result.map_err(|e| {
+                ReaderError::ReadError(
+                    format!("ランレングス圧縮符号列の読み込みに失敗しました。{}", e).into(),
+                )
+            })?;
         }
+
+        Ok(())
     }
 
     fn read_u8(&mut self) -> ReaderResult<u8> {
-        let mut buf = [0; 1];
-        self.reader.read_exact(&mut buf).map_err(|_| {
+        let bytes = match &self.run_length {
+            RunLength::Bytes(bytes) => (**bytes).as_ref(),
+            RunLength::Reader(_) => &[],
+        };
+        let value = bytes.get(self.read_bytes).copied().ok_or_else(|| {
             ReaderError::ReadError("ランレングス圧縮オクテットの読み込みに失敗しました。".into())
         })?;
         self.read_bytes += 1;
 
-        Ok(u8::from_be_bytes(buf))
+        Ok(value)
+    }
+
+    /// ランレングス圧縮符号列の読み込んでいないビット数を返す。
+    fn remaining_bits(&self) -> usize {
+        (self.total_bytes - self.read_bytes) * 8 + self.bit_count as usize
+    }
+
+    /// ランレングス圧縮符号列に、読み込んでいないランレングス圧縮符号が残っているか確認する。
+    fn has_next_code(&self) -> bool {
+        self.nbit as usize <= self.remaining_bits()
+    }
+
+    /// ランレングス圧縮符号列から、`nbit`ビットのランレングス圧縮符号を1つ読み込む。
+    ///
+    /// ランレングス圧縮符号はオクテットの境界に関係なく、上位ビットから詰めて記録されている。
+    fn read_code(&mut self) -> ReaderResult<u16> {
+        if !(1..=16).contains(&self.nbit) {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "1データのビット数({})は1から16の範囲でなければなりません。",
+                    self.nbit
+                )
+                .into(),
+            ));
+        }
+        let nbit = self.nbit as u32;
+        self.last_code_in_tail = self.remaining_bits() < 8;
+        while self.bit_count < nbit {
+            self.bit_buffer = (self.bit_buffer << 8) | self.read_u8()? as u32;
+            self.bit_count += 8;
+        }
+        self.bit_count -= nbit;
+        let code = self.bit_buffer >> self.bit_count;
+        self.bit_buffer &= (1 << self.bit_count) - 1;
+
+        Ok(code as u16)
+    }
+
+    /// ランレングス圧縮符号列の残りが、最終オクテットを埋めるための0のビットだけか確認する。
+    ///
+    /// `nbit`が8の倍数でない場合、ランレングス圧縮符号列の最終オクテットの残りのビットは0で埋められる。
+    /// このビットはレベル値0と区別できないため、全ての格子を展開し終えた場合に限り、読み込んだ
+    /// レベル値0を含めて埋め草とみなす。
+    fn only_padding_remains(&self) -> bool {
+        let last_is_padding = match self.last_run_length {
+            None => true,
+            Some(0) => self.last_code_in_tail,
+            Some(_) => false,
+        };
+        let rest_is_padding = !self.has_next_code()
+            || (self.total_bytes <= self.read_bytes && self.bit_count < 8 && self.bit_buffer == 0);
+
+        last_is_padding && rest_is_padding
     }
 
     fn retrieve_run_length(&mut self) -> ReaderResult<Vec<u16>> {
+        self.prefetch()?;
         let mut run_length: Vec<u16> = vec![];
-        if self.last_run_length.is_some() {
-            run_length.push(self.last_run_length.unwrap());
+        if let Some(last_run_length) = self.last_run_length.take() {
+            run_length.push(last_run_length);
         }
-        while self.read_bytes < self.total_bytes {
-            let value = self.read_u8()? as u16;
+        while self.has_next_code() {
+            let value = self.read_code()?;
             if value <= self.maxv && !run_length.is_empty() {
                 self.last_run_length = Some(value);
                 break;
@@ -131,182 +431,1362 @@ impl<'a, V> Grib2ValueIter<'a, V> {
     }
 }
 
-impl<'a, V> Iterator for Grib2ValueIter<'a, V>
+impl<'a, V> Grib2ValueIter<'a, V>
 where
-    V: Copy,
+    V: Copy + Into<f64>,
 {
-    type Item = ReaderResult<Grib2Value<V>>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        // 現在値返却回数が0かつ、読み込んだバイト数がランレングス圧縮符号列を記録しているバイト数に達している場合は終了
-        if self.returning_times == 0 && self.total_bytes <= self.read_bytes {
-            if self.number_of_reads == self.number_of_points {
-                return None;
-            } else {
-                return Some(Err(ReaderError::Unexpected(
+    /// 流域ごとに、流域に含まれる格子の物理値の平均を返す。
+    ///
+    /// 流域に含まれる格子のうち、欠測値の格子は平均の計算から除外する。
+    /// 流域に含まれる格子がすべて欠測値の場合、その流域の平均は`None`とする。
+    ///
+    /// # 引数
+    ///
+    /// * `basins` - 流域を表現する格子マスクのスライス
+    ///
+    /// # 戻り値
+    ///
+    /// 引数`basins`と同じ順番で格納した流域ごとの物理値の平均
+    pub fn basin_means(self, basins: &[GridMask]) -> ReaderResult<Vec<Option<f64>>> {
+        // 格子マスクの寸法が格子と一致するか確認
+        let nrows = self.number_of_rows();
+        let ncols = self.number_of_columns();
+        for (i, basin) in basins.iter().enumerate() {
+            if basin.nrows() != nrows || basin.ncols() != ncols {
+                return Err(ReaderError::Unexpected(
                     format!(
-                        "読み込んだ座標数({})が第3節に記録されている資料点数({})と一致しません。\
-                        ファイルが壊れている、またはクレートにバグがある可能性があります。",
-                        self.number_of_reads.to_formatted_string(&Locale::ja),
-                        self.number_of_points.to_formatted_string(&Locale::ja),
+                        "{}番目の格子マスクの寸法({}行{}列)が格子の寸法({}行{}列)と一致しません。",
+                        i + 1,
+                        basin.nrows(),
+                        basin.ncols(),
+                        nrows,
+                        ncols,
                     )
                     .into(),
-                )));
+                ));
             }
         }
 
-        // 現在値返却回数が0の場合は、ランレングス圧縮符号を展開して現在値を更新
-        if self.returning_times == 0 {
-            // ランレングス圧縮符号を取得
-            let run_length = self.retrieve_run_length();
-            if run_length.is_err() {
-                return Some(Err(run_length.err().unwrap()));
+        // 流域ごとに物理値の合計と有効な格子の数を集計
+        let mut sums = vec![0.0; basins.len()];
+        let mut counts = vec![0_u32; basins.len()];
+        for (index, value) in self.enumerate() {
+            let value = value?;
+            if let Some(physical_value) = value.value {
+                for (i, basin) in basins.iter().enumerate() {
+                    if basin.contains(index) {
+                        sums[i] += physical_value.into();
+                        counts[i] += 1;
+                    }
+                }
             }
-            // ランレングス圧縮符号を展開
-            let (level, times) = expand_run_length(&run_length.unwrap(), self.maxv, self.lngu);
-            // 現在のレベル値、物理値及び返却回数を更新
-            self.current_level = level;
-            self.current_value = if 0 < level {
-                Some(self.level_values[level as usize - 1])
-            } else {
-                None
-            };
-            self.returning_times = times;
-        }
-
-        // 結果を生成
-        let result = Some(Ok(Grib2Value {
-            lat: self.current_lat as f64 / 1_000_000.0,
-            lon: self.current_lon as f64 / 1_000_000.0,
-            level: self.current_level,
-            value: self.current_value,
-        }));
-        // 現在値を返す回数を減らす
-        self.returning_times -= 1;
-        // 格子を移動
-        self.current_lon += self.lon_inc;
-        if self.lon_max < self.current_lon {
-            self.current_lat -= self.lat_inc;
-            self.current_lon = self.lon_min;
         }
-        // 読み込んだ座標数をインクリメント
-        self.number_of_reads += 1;
 
-        result
+        Ok(sums
+            .into_iter()
+            .zip(counts)
+            .map(|(sum, count)| match count {
+                0 => None,
+                _ => Some(sum / count as f64),
+            })
+            .collect())
     }
-}
 
-/// 1セットのランレングス圧縮符号を展開する。
-///
-/// 引数valuesの最初の要素はレベル値で、それ以降はランレングス値である。
-/// これを1セットのランレングス圧縮符号とする。
-/// ランレングス値を含まない場合のvaluesの要素数は1で、ランレングス値を含む場合のvaluesの要素数
-/// は2以上である。
-///
-/// この関数が展開する、GRIB2資料テンプレート7.200（気象庁定義資料テンプレート）で利用されている
-/// ランレングス圧縮符号を以下に示す。
-///
-/// * 格子点値が取りうるレベル値
-///   * レベル値は2次元矩形領域の格子点上に存在し、0以上maxv以下の整数を取る。
-///   * ここでmaxvは、GRIB資料表現テンプレート5.200（気象庁定義資料表現テンプレート）
-///     第5節13-14オクテットで示される「今回の圧縮に用いたレベルの最大値」である。
-///     * 第5節15-16オクテットの「レベルの最大値」ではないことに注意すること。
-/// * 2次元データの1次元化
-///   * 主走査方向を2次元矩形領域の左から右（通常西から東）、副走査方向を上から下（通常北から南）と
-///     して、2次元データを1次元化する。
-///     * データは最も左上の格子点の値から始まり、東方向に向かって格子点のレベル値を記録する。
-///     * その緯度の最東端に達したら、下の最西端の格子点に移動して、上記同様に格子点のレベル値を記録
-///       する。
-///   * 最初のデータは最も左上の格子点の値であり、最後のデータは最も右下の格子点の値である。
-/// * ランレングス符号化後の1格子点値当りのビット数（nbit）
-///   * nbitは、ランレングス符号化されたデータ列の中で、レベル値及びランレングス値を表現するビット数
-///     である。
-///   * nbitは、GRIB2資料表現テンプレート5.200第5節12オクテットで示される「1データのビット数」
-///     である。
-/// * 1セット内のレベル値とランレングス値の配置
-///   * ランレングス符号化されたデータ列の中で0以上maxv以下の値は各格子点のレベル値で、maxvよりも
-///     大きな値はランレングス値である。
-///   * 1セットは、最初にレベル値を配置し、もしその値が連続するのであれば後ろにランレングス値を付加
-///     して作成される。
-///   * maxvよりも大きな値が続く場合、それらすべては当該セットのランレングス値である。
-///   * データに、maxv以下の値が現れた時点で当該セットが終了し、このmaxv以下の値は次のセットの
-///     レベル値となる。
-///   * なお、同じレベル値が連続しない場合はランレングスは付加されず、次のセットに移る。
-/// * ランレングス符号化方法
-///   * (2 ^ nbit - maxv)よりも大きなランレングスが必要となった場合、1データでは表現すること
-///     ができない。
-///   * これに対応するために、2つ以上のランレングス値を連続させてランレングスを表現するが、連続した
-///      データの単純な総和をランレングスとしても圧縮効率があがらない。
-///   * よって、lngu(=2 ^ nbit - 1 - maxv)進数を用いてランレングスを表現する。
-///   * レベル値のすぐ後に続く最初のランレングス値(data1)をlngu進数の1桁目
-///     RL1={lngu ^ (1 - 1) * (data1 - (maxv + 1))}とする。
-///   * それ以降n番目のランレングス値(dataN)は、lngu進数のn桁目
-///     RLn={lngu ^ (n - 1) * (dataN - (maxv + 1))}とする。
-///   * 最終的なランレングスは、それらの「総和 + 1(RL = ΣRLi + 1)」となる。
-/// * ランレングス符号化例
-///   * nbit = 4、maxv = 10とした場合、lngu = 2 ^ 4 - 1 - 10 = 16 - 1 - 10 = 5となる。
-///   * ランレングス符号化列 = {3, 9, 12, 6, 4, 15, 2, 1, 0, 13, 12, 2, 3}は、以下の通り
-///     展開される。
-///   * {3, 9, 9, 6, 4, 4, 4, 4, 4, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 2, 3}
-///   * 最初の3
-///     * 最初の3はレベル値である。
-///     * 3の次の9はmaxv以下であるため、9はレベル値である。
-///     * よって、最初の3は1つだけで、繰り返されない。
-///   * レベル値とランレングス値のセット{9, 12}
-///     * 9がレベル値で12がランレングス値である。
-///     * 12の次は6であり、10以下であるため6はレベル値である。
-///     * RL1 = 5 ^ (1 - 1) * (12 - (10 + 1)) = 1 * 1 = 1
-///     * RL = 1 + 1 = 2
-///     * よって、9が２つ連続する。
-///   * レベル値とランレングス値のセット{0, 13, 12}
-///     * 0がレベル値で13と12がランレングス値である。
-///     * RL1 = 5 ^ (1 - 1) * (13 - (10 + 1)) = 1 * 2 = 2
-///     * RL2 = 5 ^ (2 - 1) * (12 - (10 + 1)) = 5 * 1 = 5
-///     * RL = 2 + 5 + 1 = 8
-///     * よって、0が8連続する。
-///
-/// # 引数
-///
-/// * `values` - 1セットのランレングス圧縮データ。
-/// * `maxv` - 今回の圧縮に用いたレベルの最大値（第5節 13-14オクテット）。
-/// * `lngu` - レベル値またはランレングス値のビット数をnbitとしたときの、2 ^ nbit -1 - maxvの値。
-///
-/// # 戻り値
-///
-/// レベル値とそのレベル値を繰り返す数を格納したタプル。
-fn expand_run_length(values: &[u16], maxv: u16, lngu: u16) -> (u16, u32) {
-    assert!(values[0] <= maxv, "values[0]={}, maxv={}", values[0], maxv);
+    /// 標準地域メッシュごとに、メッシュに含まれる格子の尺度因子を適用した物理値を集約する。
+    ///
+    /// 各格子の座標からメッシュコードを求め、メッシュコードをキーとした`HashMap`に集計するため、
+    /// 同じメッシュの集計値を繰り返し計算しない。欠測値の格子及び標準地域メッシュの範囲外にある
+    /// 格子は集約から除外し、含まれる格子がすべて欠測値のメッシュは結果に含めない。
+    ///
+    /// # 引数
+    ///
+    /// * `level` - 集約するメッシュの区画
+    /// * `aggregation` - 同じメッシュに含まれる複数の格子の物理値を集約する方法
+    ///
+    /// # 戻り値
+    ///
+    /// メッシュコードをキー、集約した物理値を値とした`HashMap`
+    pub fn aggregate_by_mesh(
+        self,
+        level: MeshLevel,
+        aggregation: Aggregation,
+    ) -> ReaderResult<HashMap<u64, f64>> {
+        let mut accumulators: HashMap<u64, MeshAccumulator> = HashMap::new();
+        for value in self {
+            let value = value?;
+            if let (Some(physical_value), Some(code)) =
+                (value.physical_value(), value.mesh_code_of(level))
+            {
+                accumulators.entry(code).or_default().push(physical_value);
+            }
+        }
 
-    // ランレングス圧縮されていない場合
-    if values.len() == 1 {
-        return (values[0], 1);
+        Ok(accumulators
+            .into_iter()
+            .map(|(code, accumulator)| (code, accumulator.result(aggregation)))
+            .collect())
     }
 
-    // ランレングス圧縮を展開
-    let values: Vec<u32> = values.iter().map(|v| *v as u32).collect();
-    let lngu = lngu as u32;
-    let maxv = maxv as u32;
-    let times: u32 = values[1..]
-        .iter()
-        .enumerate()
-        .map(|(i, &v)| lngu.pow(i as u32) * (v - (maxv + 1)))
-        .sum();
+    /// 尺度因子を適用した物理値に係数を乗じたGRIB2値を返すイテレーターを返す。
+    ///
+    /// 返すGRIB2値は尺度因子を適用済みであるため、GRIB2値の尺度因子は0とする。
+    ///
+    /// # 引数
+    ///
+    /// * `factor` - 尺度因子を適用した物理値に乗じる係数
+    ///
+    /// # 戻り値
+    ///
+    /// 物理値を実数で保持するGRIB2値のイテレーター
+    pub fn scaled(self, factor: f64) -> impl Iterator<Item = ReaderResult<Grib2Value<f64>>> + 'a
+    where
+        V: 'a,
+    {
+        let scale = decimal_scale(self.decimal_scale_factor);
+        self.map(move |value| {
+            value.map(|value| Grib2Value {
+                lat: value.lat,
+                lon: value.lon,
+                level: value.level,
+                value: value.value.map(|v| v.into() / scale * factor),
+                decimal_scale_factor: 0,
+                missing_value: value.missing_value,
+            })
+        })
+    }
 
-    (values[0] as u16, times + 1)
-}
+    /// 尺度因子を適用した物理値が閾値以上の格子を1、それ以外の格子を0とした、1格子1ビットの
+    /// マスクを返す。
+    ///
+    /// ビットは走査順に、1バイトの最上位ビットから格納する。欠測値の格子は0とする。
+    ///
+    /// # 引数
+    ///
+    /// * `threshold` - 閾値
+    ///
+    /// # 戻り値
+    ///
+    /// 資料点数と同じビット長のマスク
+    #[cfg(feature = "bitvec")]
+    pub fn to_bitvec_mask(
+        &mut self,
+        threshold: f64,
+    ) -> ReaderResult<bitvec::vec::BitVec<u8, bitvec::order::Msb0>> {
+        let scale = decimal_scale(self.decimal_scale_factor);
+        let mut mask = bitvec::vec::BitVec::with_capacity(self.number_of_points as usize);
+        for value in self.by_ref() {
+            let value = value?;
+            mask.push(matches!(value.value, Some(v) if threshold <= v.into() / scale));
+        }
+        if mask.len() != self.number_of_points as usize {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "マスクのビット長({})が第3節に記録されている資料点数({})と一致しません。",
+                    mask.len().to_formatted_string(&Locale::ja),
+                    self.number_of_points.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::expand_run_length;
+        Ok(mask)
+    }
 
-    #[test]
+    /// 尺度因子を適用した物理値を、走査順に格納した実数の配列として返す。
+    ///
+    /// 欠測値の格子には、引数`missing`の値を格納する。
+    ///
+    /// # 引数
+    ///
+    /// * `missing` - 欠測値の格子に格納する値（例: `f32::NAN`、`-1.0`）
+    ///
+    /// # 戻り値
+    ///
+    /// 資料点数と同じ長さの物理値の配列
+    pub fn to_flat_f32(&mut self, missing: f32) -> ReaderResult<Vec<f32>> {
+        let scale = decimal_scale(self.decimal_scale_factor);
+        let mut values = Vec::with_capacity(self.number_of_points as usize);
+        for value in self.by_ref() {
+            let value = value?;
+            values.push(match value.value {
+                Some(physical_value) => (physical_value.into() / scale) as f32,
+                None => missing,
+            });
+        }
+        if values.len() != self.number_of_points as usize {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "展開した物理値の数({})が第3節に記録されている資料点数({})と一致しません。",
+                    values.len().to_formatted_string(&Locale::ja),
+                    self.number_of_points.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        Ok(values)
+    }
+
+    /// 全ての格子に対する欠測値の格子の比率を返す。
+    ///
+    /// ビットマップにより資料値が存在しない格子は、欠測値の格子として数える。
+    ///
+    /// # 戻り値
+    ///
+    /// 欠測値の格子の比率（0.0以上1.0以下）。資料点数が0の場合は0.0
+    pub fn missing_ratio(&mut self) -> ReaderResult<f64> {
+        let mut number_of_values = 0_u64;
+        let mut number_of_missing = 0_u64;
+        for value in self.by_ref() {
+            if value?.value.is_none() {
+                number_of_missing += 1;
+            }
+            number_of_values += 1;
+        }
+        if number_of_values != self.number_of_points {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "展開した物理値の数({})が第3節に記録されている資料点数({})と一致しません。",
+                    number_of_values.to_formatted_string(&Locale::ja),
+                    self.number_of_points.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+        if number_of_values == 0 {
+            return Ok(0.0);
+        }
+
+        Ok(number_of_missing as f64 / number_of_values as f64)
+    }
+
+    /// レベル値ごとに、そのレベル値の格子の数を集計する。
+    ///
+    /// ビットマップにより資料値が存在しない格子は、レベル値0（欠測値）として数える。
+    ///
+    /// # 戻り値
+    ///
+    /// インデックスをレベル値、要素を格子の数としたベクタ。長さはレベル別物理値の数に1を
+    /// 加えた値で、インデックス0は欠測値の格子の数
+    pub fn level_histogram(&mut self) -> ReaderResult<Vec<u32>> {
+        let mut histogram = vec![0_u32; self.level_values.len() + 1];
+        for value in self.by_ref() {
+            histogram[value?.level as usize] += 1;
+        }
+        let number_of_values: u64 = histogram.iter().map(|&count| count as u64).sum();
+        if number_of_values != self.number_of_points {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "展開した物理値の数({})が第3節に記録されている資料点数({})と一致しません。",
+                    number_of_values.to_formatted_string(&Locale::ja),
+                    self.number_of_points.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        Ok(histogram)
+    }
+}
+
+impl<'a, V> Grib2ValueIter<'a, V>
+where
+    V: Copy + Send + Sync,
+{
+    /// 全ての格子のGRIB2値を、走査順に格納したベクタとして返す。
+    ///
+    /// ランレングス圧縮符号列を全て`(レベル値, 繰り返し回数)`のセットに展開した後、各格子の座標と
+    /// 物理値を計算する。feature`rayon`が有効な場合は、座標と物理値を並列に計算する。
+    /// 結果の順番は、イテレーターで走査した場合と一致する。
+    ///
+    /// # 戻り値
+    ///
+    /// 走査順に格納したGRIB2値
+    pub fn collect_parallel(mut self) -> ReaderResult<Vec<Grib2Value<V>>> {
+        // ランレングス圧縮符号列のデータ長が0の場合は、全格子を欠測値として返す
+        if self.total_bytes == 0 {
+            return self.collect();
+        }
+
+        // ビットマップを適用する場合は、格子ごとに資料値のインデックスを記録
+        let number_of_points = self.number_of_points as usize;
+        let value_indices: Option<Vec<Option<usize>>> = self.bitmap.as_deref().map(|bitmap| {
+            let mut count = 0;
+            (0..number_of_points)
+                .map(|index| match bitmap.get(index).copied().unwrap_or(false) {
+                    true => {
+                        count += 1;
+                        Some(count - 1)
+                    }
+                    false => None,
+                })
+                .collect()
+        });
+        let expected = match &value_indices {
+            Some(indices) => indices.iter().flatten().count() as u64,
+            None => self.number_of_points,
+        };
+
+        // ランレングス圧縮符号列を(レベル値, 繰り返し回数)のセットに展開して、各セットの開始位置を記録
+        let mut sets = vec![];
+        let mut offsets = vec![];
+        let mut number_of_values = 0_u64;
+        while self.has_next_code() || self.last_run_length.is_some() {
+            // 全ての資料値を展開した後に、最終オクテットの埋め草だけが残っている場合は終了
+            if number_of_values == expected && self.only_padding_remains() {
+                break;
+            }
+            let run_length = self.retrieve_run_length()?;
+            let (level, times) = expand_run_length(&run_length, self.maxv, self.lngu)?;
+            sets.push((level, level_value(&self.level_values, level)?));
+            offsets.push(number_of_values as usize);
+            number_of_values = number_of_values.saturating_add(times);
+            // 資料値の数を超えた場合は、格子のインデックスに変換できない可能性があるため終了
+            if expected < number_of_values {
+                break;
+            }
+        }
+
+        if number_of_values != expected {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "展開した資料値の数({})が第3節に記録されている資料点数から求めた資料値の数({})と一致しません。\
+                    ファイルが壊れている、またはクレートにバグがある可能性があります。",
+                    number_of_values.to_formatted_string(&Locale::ja),
+                    expected.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        // 格子ごとに座標と物理値を計算
+        let grid = &self.grid;
+        let decimal_scale_factor = self.decimal_scale_factor;
+        let missing_value = self.missing_value;
+        let point = |index: usize| {
+            let value_index = match &value_indices {
+                Some(indices) => indices[index],
+                None => Some(index),
+            };
+            let (level, value) = match value_index {
+                Some(value_index) => sets[offsets.partition_point(|&o| o <= value_index) - 1],
+                None => (0, None),
+            };
+            let (lat, lon) = grid.coordinate(index);
+            Grib2Value {
+                lat,
+                lon,
+                level,
+                value,
+                decimal_scale_factor,
+                missing_value,
+            }
+        };
+        #[cfg(feature = "rayon")]
+        let values = {
+            use rayon::prelude::*;
+            (0..number_of_points).into_par_iter().map(point).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let values = (0..number_of_points).map(point).collect();
+
+        Ok(values)
+    }
+}
+
+impl<'a, V> Grib2ValueIter<'a, V>
+where
+    V: Copy + std::fmt::Display,
+{
+    /// GRIB2値を、`lat,lon,level,value`のヘッダを持つCSV形式で出力する。
+    ///
+    /// 1行に1格子の緯度、経度、レベル値及び物理値を出力する。緯度と経度は小数点以下6桁で出力し、
+    /// 欠測値の物理値は空欄で出力する。GRIB2値は1格子ずつ出力するため、全ての格子をメモリに
+    /// 保持しない。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先
+    pub fn write_csv<W: Write>(&mut self, writer: &mut W) -> ReaderResult<()> {
+        let to_error = |e: std::io::Error| {
+            ReaderError::Unexpected(format!("CSV形式の出力に失敗しました: {}", e).into())
+        };
+        writeln!(writer, "lat,lon,level,value").map_err(to_error)?;
+        for value in self.by_ref() {
+            let value = value?;
+            match value.value {
+                Some(physical_value) => writeln!(
+                    writer,
+                    "{:.6},{:.6},{},{}",
+                    value.lat, value.lon, value.level, physical_value
+                ),
+                None => writeln!(writer, "{:.6},{:.6},{},", value.lat, value.lon, value.level),
+            }
+            .map_err(to_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// GRIB2値を、PostgreSQLの`COPY ... FROM STDIN`のテキスト形式で出力する。
+    ///
+    /// 1行に1格子の緯度、経度及び物理値をタブ区切りで出力する。欠測値は`\N`（NULL）で出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - 出力先
+    pub fn write_pg_copy<W: Write>(&mut self, writer: &mut W) -> ReaderResult<()> {
+        let to_error = |e: std::io::Error| {
+            ReaderError::Unexpected(format!("COPY形式の出力に失敗しました: {}", e).into())
+        };
+        for value in self.by_ref() {
+            let value = value?;
+            match value.value {
+                Some(physical_value) => writeln!(
+                    writer,
+                    "{:.6}\t{:.6}\t{}",
+                    value.lat, value.lon, physical_value
+                ),
+                None => writeln!(writer, "{:.6}\t{:.6}\t\\N", value.lat, value.lon),
+            }
+            .map_err(to_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, V> Iterator for Grib2ValueIter<'a, V>
+where
+    V: Copy,
+{
+    type Item = ReaderResult<Grib2Value<V>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_raw()
+            .map(|result| result.map(|value| value.as_f64()))
+    }
+
+    /// 返す要素数の範囲を返す。
+    ///
+    /// 欠測値やビットマップにより資料値が存在しない格子も1つのGRIB2値として返すため、ファイルが
+    /// 正常であれば、第3節に記録されている資料点数から読み込んだ座標数を引いた数のGRIB2値を返す。
+    /// ランレングス圧縮符号列から展開した格子数が資料点数を超える場合は、最後にエラーを1つ返すため、
+    /// 上限は残りの格子数に1を加えた数とする。ランレングス圧縮符号列が壊れている場合は、最初の
+    /// エラーを返した時点で終了するため、下限は残りの格子がある場合に1とする。
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+        let remaining = self.number_of_points.saturating_sub(self.number_of_reads) as usize;
+
+        (remaining.min(1), Some(remaining + 1))
+    }
+}
+
+impl<'a, V> Grib2ValueIter<'a, V>
+where
+    V: Copy,
+{
+    /// 座標を10e-6度単位の整数で保持するGRIB2値を返すイテレーターに変換する。
+    ///
+    /// 座標を度単位に変換しないため、座標を整数のまま扱う場合に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// 座標を10e-6度単位の整数で保持するGRIB2値を返すイテレーター
+    pub fn into_raw(self) -> Grib2RawValueIter<'a, V> {
+        Grib2RawValueIter { inner: self }
+    }
+
+    /// エラーが発生するまでに展開できたGRIB2値と、発生したエラーを返す。
+    ///
+    /// 配信途中で切れたファイルなどで、ランレングス圧縮符号列の途中でエラーが発生した場合でも、
+    /// それまでに展開できたGRIB2値を取り出すために使用する。
+    ///
+    /// # 戻り値
+    ///
+    /// エラーが発生するまでに展開できたGRIB2値と、発生したエラー（エラーが発生しなかった場合は`None`）
+    pub fn into_partial(mut self) -> (Vec<Grib2Value<V>>, Option<ReaderError>) {
+        let mut values = Vec::with_capacity(self.number_of_points as usize);
+        for value in self.by_ref() {
+            match value {
+                Ok(value) => values.push(value),
+                Err(e) => return (values, Some(e)),
+            }
+        }
+
+        (values, None)
+    }
+
+    /// エラーを返さずに、全ての格子のGRIB2値を返すイテレーターに変換する。
+    ///
+    /// ランレングス圧縮符号列の途中でエラーが発生した場合は、エラーが発生した格子から最後の格子
+    /// までを欠測値として返す。したがって、イテレーターは常に第3節に記録されている資料点数の
+    /// GRIB2値を返す。発生したエラーは`Grib2LenientIter::error`で確認できる。
+    ///
+    /// # 戻り値
+    ///
+    /// 全ての格子のGRIB2値を返すイテレーター
+    pub fn lenient(self) -> Grib2LenientIter<'a, V> {
+        Grib2LenientIter {
+            inner: self,
+            error: None,
+        }
+    }
+
+    /// 次の格子の座標を10e-6度単位の整数で保持するGRIB2値を返す。
+    fn next_raw(&mut self) -> Option<ReaderResult<Grib2ValueRaw<V>>> {
+        if self.finished {
+            return None;
+        }
+        let result = self.read_next();
+        // エラーを返した後は、不整合な状態から格子を生成しないように終了
+        if matches!(result, Some(Err(_))) {
+            self.finished = true;
+        }
+
+        result
+    }
+
+    /// 次の格子のGRIB2値を読み込む。
+    fn read_next(&mut self) -> Option<ReaderResult<Grib2ValueRaw<V>>> {
+        // ビットマップにより資料値が存在しない格子は、ランレングス圧縮符号を消費せずに欠測値を返す
+        if self.is_masked_by_bitmap() {
+            let result = Some(Ok(self.current_point(0, None)));
+            self.move_to_next_point();
+            return result;
+        }
+
+        // ランレングス圧縮符号列のデータ長が0の場合は、全格子が欠測しているとみなして欠測値を返す
+        if self.total_bytes == 0 {
+            if self.number_of_points <= self.number_of_reads {
+                return None;
+            }
+            let result = Some(Ok(self.current_point(0, None)));
+            self.move_to_next_point();
+            return result;
+        }
+
+        // 現在値返却回数が0かつ、全ての格子を展開して、最終オクテットの埋め草だけが残っている場合は終了
+        if self.returning_times == 0
+            && self.number_of_reads == self.number_of_points
+            && self.only_padding_remains()
+        {
+            return None;
+        }
+
+        // 現在値返却回数が0かつ、ランレングス圧縮符号を全て読み込んでおり、次のセットのレベル値を
+        // 読み込んでいない場合は、格子数が不足している
+        if self.returning_times == 0 && !self.has_next_code() && self.last_run_length.is_none() {
+            return Some(Err(ReaderError::Unexpected(
+                format!(
+                    "読み込んだ座標数({})が第3節に記録されている資料点数({})と一致しません。\
+                    ファイルが壊れている、またはクレートにバグがある可能性があります。",
+                    self.number_of_reads.to_formatted_string(&Locale::ja),
+                    self.number_of_points.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            )));
+        }
+
+        // ランレングス圧縮符号列から展開した格子が、第3節に記録されている資料点数を超えていないことを確認
+        if self.number_of_points <= self.number_of_reads {
+            return Some(Err(ReaderError::Unexpected(
+                format!(
+                    "ランレングス圧縮符号列から展開した格子数が、第3節に記録されている資料点数({})を超えています。\
+                    ファイルが壊れている、またはクレートにバグがある可能性があります。",
+                    self.number_of_points.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            )));
+        }
+
+        // 現在値返却回数が0の場合は、ランレングス圧縮符号を展開して現在値を更新
+        if self.returning_times == 0 {
+            // ランレングス圧縮符号を取得
+            let run_length = self.retrieve_run_length();
+            if run_length.is_err() {
+                return Some(Err(run_length.err().unwrap()));
+            }
+            // ランレングス圧縮符号を展開
+            let (level, times) = match expand_run_length(&run_length.unwrap(), self.maxv, self.lngu)
+            {
+                Ok(expanded) => expanded,
+                Err(e) => return Some(Err(e)),
+            };
+            // レベル値に対応するレベル別物理値を取得
+            let value = match level_value(&self.level_values, level) {
+                Ok(value) => value,
+                Err(e) => return Some(Err(e)),
+            };
+            // 現在のレベル値、物理値及び返却回数を更新
+            self.current_level = level;
+            self.current_value = value;
+            self.returning_times = times;
+        }
+
+        // 結果を生成
+        let result = Some(Ok(
+            self.current_point(self.current_level, self.current_value)
+        ));
+        // 現在値を返す回数を減らす
+        self.returning_times -= 1;
+        // 格子を移動
+        self.move_to_next_point();
+
+        result
+    }
+}
+
+impl<'a, V> Grib2ValueIter<'a, V>
+where
+    V: Copy,
+{
+    /// レベル値をカラーマップで色に変換して、RGBAの画素バッファに描画する。
+    ///
+    /// 画素バッファは、北を上、西を左とした行優先で、1画素4バイトのRGBAを格納する。
+    /// 各格子の画素の位置は走査モードに従って計算し、GRIB2値を1格子ずつ描画するため、全ての格子を
+    /// メモリに保持しない。欠測値の格子は透明にする。
+    ///
+    /// # 引数
+    ///
+    /// * `buf` - 画素バッファ（長さは`width * height * 4`）
+    /// * `width` - 画像の幅（緯線に沿った格子点数）
+    /// * `colormap` - カラーマップ
+    pub fn render_into(
+        &mut self,
+        buf: &mut [u8],
+        width: u32,
+        colormap: &ColorMap,
+    ) -> ReaderResult<()> {
+        let (nrows, ncols) = (self.number_of_rows(), self.number_of_columns());
+        if width != ncols {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "画像の幅({})が緯線に沿った格子点数({})と一致しません。",
+                    width.to_formatted_string(&Locale::ja),
+                    ncols.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+        let number_of_pixels = nrows as usize * ncols as usize;
+        if buf.len() != number_of_pixels * 4 {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "画素バッファの長さ({})が画像の幅と高さと4の積({})と一致しません。",
+                    buf.len().to_formatted_string(&Locale::ja),
+                    (number_of_pixels * 4).to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        let scan_mode = self.grid.scan_mode();
+        let mut count = 0_usize;
+        for value in self.by_ref() {
+            let value = value?;
+            if number_of_pixels <= count {
+                break;
+            }
+            let (row, col) = cell_position(count, nrows as usize, ncols as usize, scan_mode);
+            let offset = (row * ncols as usize + col) * 4;
+            buf[offset..offset + 4].copy_from_slice(&match value.value {
+                Some(_) => colormap.color(value.level),
+                None => TRANSPARENT,
+            });
+            count += 1;
+        }
+        if count != number_of_pixels {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "描画した格子の数({})が画像の画素数({})と一致しません。",
+                    count.to_formatted_string(&Locale::ja),
+                    number_of_pixels.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 矩形領域に含まれるGRIB2値のみを返すイテレーターを返す。
+    ///
+    /// 矩形領域の境界に一致する格子は、矩形領域に含まれるものとする。
+    /// 格子が北から南に行単位で走査される場合は、最小緯度より南の格子に達した時点で走査を終了する。
+    ///
+    /// # 引数
+    ///
+    /// * `min_lat` - 矩形領域の最小緯度（度単位）
+    /// * `min_lon` - 矩形領域の最小経度（度単位）
+    /// * `max_lat` - 矩形領域の最大緯度（度単位）
+    /// * `max_lon` - 矩形領域の最大経度（度単位）
+    ///
+    /// # 戻り値
+    ///
+    /// 矩形領域に含まれるGRIB2値を返すイテレーター
+    pub fn within(
+        self,
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    ) -> Grib2WithinIter<'a, V> {
+        let stops_at_min_lat =
+            self.grid.scan_mode() & (SCANNING_MODE_POSITIVE_J | SCANNING_MODE_CONSECUTIVE_J) == 0;
+        Grib2WithinIter {
+            inner: self,
+            stops_at_min_lat,
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon,
+            finished: false,
+        }
+    }
+
+    /// 格子の増分を添えたGRIB2値を返すイテレーターを返す。
+    ///
+    /// 格子を面（セル）として扱い、ラスタ化やポリゴンの出力などでセルの範囲が必要な場合に使用する。
+    ///
+    /// # 戻り値
+    ///
+    /// 格子の増分を添えたGRIB2値を返すイテレーター
+    pub fn cells(self) -> Grib2CellIter<'a, V> {
+        let lat_inc = self.grid.definition.j_direction_increment() as f64 / 1_000_000.0;
+        let lon_inc = self.grid.definition.i_direction_increment() as f64 / 1_000_000.0;
+        Grib2CellIter {
+            inner: self,
+            lat_inc,
+            lon_inc,
+        }
+    }
+}
+
+/// 座標を10e-6度単位の整数で保持するGRIB2値を返すイテレーター
+pub struct Grib2RawValueIter<'a, V> {
+    /// GRIB2値イテレーター
+    inner: Grib2ValueIter<'a, V>,
+}
+
+impl<'a, V> Iterator for Grib2RawValueIter<'a, V>
+where
+    V: Copy,
+{
+    type Item = ReaderResult<Grib2ValueRaw<V>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_raw()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// エラーが発生した後の格子を欠測値として返すGRIB2値イテレーター
+pub struct Grib2LenientIter<'a, V> {
+    /// GRIB2値イテレーター
+    inner: Grib2ValueIter<'a, V>,
+    /// GRIB2値イテレーターが返したエラー
+    error: Option<ReaderError>,
+}
+
+impl<'a, V> Grib2LenientIter<'a, V> {
+    /// GRIB2値イテレーターが返したエラーを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB2値イテレーターが返したエラー。エラーが発生していない場合は`None`
+    pub fn error(&self) -> Option<&ReaderError> {
+        self.error.as_ref()
+    }
+}
+
+impl<'a, V> Iterator for Grib2LenientIter<'a, V>
+where
+    V: Copy,
+{
+    type Item = Grib2Value<V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.is_none() {
+            match self.inner.next_raw() {
+                Some(Ok(value)) => return Some(value.as_f64()),
+                Some(Err(e)) => self.error = Some(e),
+                None => return None,
+            }
+        }
+        // エラーが発生した後は、残りの格子を欠測値として返す
+        let inner = &mut self.inner;
+        if inner.number_of_points <= inner.number_of_reads {
+            return None;
+        }
+        let value = inner.current_point(0, None).as_f64();
+        inner.move_to_next_point();
+
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // エラーが発生しても残りの格子を欠測値として返すため、残りの格子数は正確である
+        let inner = &self.inner;
+        let remaining = inner.number_of_points.saturating_sub(inner.number_of_reads) as usize;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, V> ExactSizeIterator for Grib2LenientIter<'a, V> where V: Copy {}
+
+/// 格子の増分を添えたGRIB2値を返すイテレーター
+pub struct Grib2CellIter<'a, V> {
+    /// GRIB2値イテレーター
+    inner: Grib2ValueIter<'a, V>,
+    /// 緯度の増分（度単位）
+    lat_inc: f64,
+    /// 経度の増分（度単位）
+    lon_inc: f64,
+}
+
+impl<'a, V> Iterator for Grib2CellIter<'a, V>
+where
+    V: Copy,
+{
+    type Item = ReaderResult<Grib2Cell<V>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|value| value.map(|value| Grib2Cell::new(value, self.lat_inc, self.lon_inc)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// 矩形領域に含まれるGRIB2値のみを返すイテレーター
+pub struct Grib2WithinIter<'a, V> {
+    /// GRIB2値イテレーター
+    inner: Grib2ValueIter<'a, V>,
+    /// 最小緯度より南の格子に達した時点で走査を終了するかを示すフラグ
+    stops_at_min_lat: bool,
+    /// 矩形領域の最小緯度（度単位）
+    min_lat: f64,
+    /// 矩形領域の最小経度（度単位）
+    min_lon: f64,
+    /// 矩形領域の最大緯度（度単位）
+    max_lat: f64,
+    /// 矩形領域の最大経度（度単位）
+    max_lon: f64,
+    /// 走査を終了したかを示すフラグ
+    finished: bool,
+}
+
+impl<'a, V> Iterator for Grib2WithinIter<'a, V>
+where
+    V: Copy,
+{
+    type Item = ReaderResult<Grib2Value<V>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        for value in self.inner.by_ref() {
+            let value = match value {
+                Ok(value) => value,
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            };
+            // 最小緯度より南の格子に達した場合は、以降の格子はすべて矩形領域外であるため終了
+            if self.stops_at_min_lat && value.lat < self.min_lat {
+                self.finished = true;
+                return None;
+            }
+            if self.min_lat <= value.lat
+                && value.lat <= self.max_lat
+                && self.min_lon <= value.lon
+                && value.lon <= self.max_lon
+            {
+                return Some(Ok(value));
+            }
+        }
+        self.finished = true;
+
+        None
+    }
+}
+
+/// 走査モードに従って格子の座標を計算するための格子の定義
+#[derive(Debug, Clone)]
+struct ScanGrid {
+    /// 格子系定義
+    definition: GridDefinition,
+    /// 北の行から順に並べた行ごとの緯度（10e-6度単位）
+    ///
+    /// ガウス格子のように緯度が等間隔でない格子で、最初の格子点の緯度と増分から計算する代わりに
+    /// 参照する。
+    latitudes: Option<Arc<[i64]>>,
+}
+
+impl ScanGrid {
+    /// 格子の定義を構築する。
+    ///
+    /// 緯線に沿った格子点数は最初と最後の格子点の経度から求め、経線に沿った格子点数は
+    /// 資料点数を緯線に沿った格子点数で除して求める。
+    fn new(
+        number_of_points: u32,
+        lat_first: u32,
+        lon_first: u32,
+        lon_last: u32,
+        lat_inc: u32,
+        lon_inc: u32,
+        scan_mode: u8,
+    ) -> Self {
+        let ni = lon_first.abs_diff(lon_last) / lon_inc + 1;
+
+        Self {
+            definition: GridDefinition::from_first_grid_point(
+                number_of_points / ni,
+                ni,
+                lat_first,
+                lon_first,
+                lat_inc,
+                lon_inc,
+                scan_mode,
+            ),
+            latitudes: None,
+        }
+    }
+
+    /// 緯線に沿った格子点数を返す。
+    fn ni(&self) -> u32 {
+        self.definition.number_of_along_lat_points()
+    }
+
+    /// 経線に沿った格子点数を返す。
+    fn nj(&self) -> u32 {
+        self.definition.number_of_along_lon_points()
+    }
+
+    /// 走査モードを返す。
+    fn scan_mode(&self) -> u8 {
+        self.definition.scanning_mode()
+    }
+
+    /// 走査順のインデックスの格子の座標を返す。
+    ///
+    /// 座標はインデックスから10e-6度単位の整数で計算して、最後に度単位へ変換する。このため、
+    /// 格子を進めても浮動小数点の誤差は累積しない。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - 格子の走査順のインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// 格子の緯度と経度（度単位）を格納したタプル
+    fn coordinate(&self, index: usize) -> (f64, f64) {
+        let (lat, lon) = self.micro_degree_coordinate(index);
+
+        (lat as f64 / 1_000_000.0, lon as f64 / 1_000_000.0)
+    }
+
+    /// 格子の座標を10e-6度単位の整数で返す。
+    ///
+    /// 緯度は-90度から90度、経度は0度から360度の範囲であるため、緯度は符号付き、経度は符号なしの
+    /// 整数で表現できる。
+    fn raw_coordinate(&self, index: usize) -> (i32, u32) {
+        let (lat, lon) = self.micro_degree_coordinate(index);
+
+        (lat as i32, lon as u32)
+    }
+
+    /// 格子の座標を10e-6度単位で返す。
+    ///
+    /// 行ごとの緯度を設定している場合は、緯度を行ごとの緯度から取得する。
+    fn micro_degree_coordinate(&self, index: usize) -> (i64, i64) {
+        let (row, col) = cell_position(
+            index,
+            self.nj() as usize,
+            self.ni() as usize,
+            self.scan_mode(),
+        );
+        let (lat, lon) = micro_degree_of_grid_point(row as u32, col as u32, &self.definition);
+        let lat = self
+            .latitudes
+            .as_deref()
+            .and_then(|latitudes| latitudes.get(row).copied())
+            .unwrap_or(lat);
+
+        (lat, lon)
+    }
+}
+
+/// 1セットのランレングス圧縮符号を展開する。
+///
+/// 引数valuesの最初の要素はレベル値で、それ以降はランレングス値である。
+/// これを1セットのランレングス圧縮符号とする。
+/// ランレングス値を含まない場合のvaluesの要素数は1で、ランレングス値を含む場合のvaluesの要素数
+/// は2以上である。
+///
+/// この関数が展開する、GRIB2資料テンプレート7.200（気象庁定義資料テンプレート）で利用されている
+/// ランレングス圧縮符号を以下に示す。
+///
+/// * 格子点値が取りうるレベル値
+///   * レベル値は2次元矩形領域の格子点上に存在し、0以上maxv以下の整数を取る。
+///   * ここでmaxvは、GRIB資料表現テンプレート5.200（気象庁定義資料表現テンプレート）
+///     第5節13-14オクテットで示される「今回の圧縮に用いたレベルの最大値」である。
+///     * 第5節15-16オクテットの「レベルの最大値」ではないことに注意すること。
+/// * 2次元データの1次元化
+///   * 主走査方向を2次元矩形領域の左から右（通常西から東）、副走査方向を上から下（通常北から南）と
+///     して、2次元データを1次元化する。
+///     * データは最も左上の格子点の値から始まり、東方向に向かって格子点のレベル値を記録する。
+///     * その緯度の最東端に達したら、下の最西端の格子点に移動して、上記同様に格子点のレベル値を記録
+///       する。
+///   * 最初のデータは最も左上の格子点の値であり、最後のデータは最も右下の格子点の値である。
+/// * ランレングス符号化後の1格子点値当りのビット数（nbit）
+///   * nbitは、ランレングス符号化されたデータ列の中で、レベル値及びランレングス値を表現するビット数
+///     である。
+///   * nbitは、GRIB2資料表現テンプレート5.200第5節12オクテットで示される「1データのビット数」
+///     である。
+/// * 1セット内のレベル値とランレングス値の配置
+///   * ランレングス符号化されたデータ列の中で0以上maxv以下の値は各格子点のレベル値で、maxvよりも
+///     大きな値はランレングス値である。
+///   * 1セットは、最初にレベル値を配置し、もしその値が連続するのであれば後ろにランレングス値を付加
+///     して作成される。
+///   * maxvよりも大きな値が続く場合、それらすべては当該セットのランレングス値である。
+///   * データに、maxv以下の値が現れた時点で当該セットが終了し、このmaxv以下の値は次のセットの
+///     レベル値となる。
+///   * なお、同じレベル値が連続しない場合はランレングスは付加されず、次のセットに移る。
+/// * ランレングス符号化方法
+///   * (2 ^ nbit - maxv)よりも大きなランレングスが必要となった場合、1データでは表現すること
+///     ができない。
+///   * これに対応するために、2つ以上のランレングス値を連続させてランレングスを表現するが、連続した
+///     データの単純な総和をランレングスとしても圧縮効率があがらない。
+///   * よって、lngu(=2 ^ nbit - 1 - maxv)進数を用いてランレングスを表現する。
+///   * レベル値のすぐ後に続く最初のランレングス値(data1)をlngu進数の1桁目
+///     RL1={lngu ^ (1 - 1) * (data1 - (maxv + 1))}とする。
+///   * それ以降n番目のランレングス値(dataN)は、lngu進数のn桁目
+///     RLn={lngu ^ (n - 1) * (dataN - (maxv + 1))}とする。
+///   * 最終的なランレングスは、それらの「総和 + 1(RL = ΣRLi + 1)」となる。
+/// * ランレングス符号化例
+///   * nbit = 4、maxv = 10とした場合、lngu = 2 ^ 4 - 1 - 10 = 16 - 1 - 10 = 5となる。
+///   * ランレングス符号化列 = {3, 9, 12, 6, 4, 15, 2, 1, 0, 13, 12, 2, 3}は、以下の通り
+///     展開される。
+///   * {3, 9, 9, 6, 4, 4, 4, 4, 4, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 2, 3}
+///   * 最初の3
+///     * 最初の3はレベル値である。
+///     * 3の次の9はmaxv以下であるため、9はレベル値である。
+///     * よって、最初の3は1つだけで、繰り返されない。
+///   * レベル値とランレングス値のセット{9, 12}
+///     * 9がレベル値で12がランレングス値である。
+///     * 12の次は6であり、10以下であるため6はレベル値である。
+///     * RL1 = 5 ^ (1 - 1) * (12 - (10 + 1)) = 1 * 1 = 1
+///     * RL = 1 + 1 = 2
+///     * よって、9が２つ連続する。
+///   * レベル値とランレングス値のセット{0, 13, 12}
+///     * 0がレベル値で13と12がランレングス値である。
+///     * RL1 = 5 ^ (1 - 1) * (13 - (10 + 1)) = 1 * 2 = 2
+///     * RL2 = 5 ^ (2 - 1) * (12 - (10 + 1)) = 5 * 1 = 5
+///     * RL = 2 + 5 + 1 = 8
+///     * よって、0が8連続する。
+///
+/// # 引数
+///
+/// * `values` - 1セットのランレングス圧縮データ。
+/// * `maxv` - 今回の圧縮に用いたレベルの最大値（第5節 13-14オクテット）。
+/// * `lngu` - レベル値またはランレングス値のビット数をnbitとしたときの、2 ^ nbit -1 - maxvの値。
+///
+/// # 戻り値
+///
+/// レベル値とそのレベル値を繰り返す数を格納したタプル。セットの先頭がレベル値でない場合、または
+/// ランレングスが`u64`で表現できない場合はエラーを返す。
+fn expand_run_length(values: &[u16], maxv: u16, lngu: u16) -> ReaderResult<(u16, u64)> {
+    // セットの先頭はmaxv以下のレベル値
+    match values.first() {
+        Some(&level) if level <= maxv => {}
+        first => {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "ランレングス圧縮符号のセットの先頭({:?})が、今回の圧縮に用いたレベルの最大値({})以下のレベル値ではありません。ファイルが壊れている可能性があります。",
+                    first, maxv
+                )
+                .into(),
+            ));
+        }
+    }
+
+    // ランレングス圧縮されていない場合
+    if values.len() == 1 {
+        return Ok((values[0], 1));
+    }
+
+    // ランレングス圧縮を展開
+    let lngu = lngu as u64;
+    let maxv = maxv as u64;
+    let times = values[1..]
+        .iter()
+        .enumerate()
+        .try_fold(0u64, |sum, (i, &v)| {
+            lngu.checked_pow(i as u32)
+                .and_then(|digit| digit.checked_mul(v as u64 - (maxv + 1)))
+                .and_then(|rl| sum.checked_add(rl))
+        })
+        .and_then(|times| times.checked_add(1))
+        .ok_or_else(|| {
+            ReaderError::Unexpected(
+                format!(
+                    "ランレングス({}桁)を展開した繰り返し数が大きすぎます。ファイルが壊れている可能性があります。",
+                    values.len() - 1
+                )
+                .into(),
+            )
+        })?;
+
+    Ok((values[0], times))
+}
+
+/// レベル値に対応するレベル別物理値を返す。
+///
+/// # 引数
+///
+/// * `level_values` - レベル別物理値
+/// * `level` - レベル値
+///
+/// # 戻り値
+///
+/// レベル値が0（欠測値）の場合は`None`、それ以外はレベル値に対応するレベル別物理値。レベル値が
+/// レベル別物理値の数を超える場合はエラー
+fn level_value<V: Copy>(level_values: &[V], level: u16) -> ReaderResult<Option<V>> {
+    if level == 0 {
+        return Ok(None);
+    }
+    match level_values.get(level as usize - 1) {
+        Some(&value) => Ok(Some(value)),
+        None => Err(ReaderError::Unexpected(
+            format!(
+                "レベル値({})がレベル別物理値の数({})を超えています。ファイルが壊れている可能性があります。",
+                level,
+                level_values.len()
+            )
+            .into(),
+        )),
+    }
+}
+
+/// レベル値の列をランレングス圧縮する。
+///
+/// 同じレベル値が連続する場合は、レベル値の直後に`maxv`より大きい値でランレングスを記録する。
+/// ランレングスは`繰り返し数 - 1`を`lngu`進数で表現し、下位の桁から順に各桁の値に`maxv + 1`を
+/// 加えて記録する。`lngu`が0の場合はランレングスを表現できないため、レベル値を繰り返して記録する。
+/// 各符号は`nbit`ビットで上位ビットから詰めて記録し、最終オクテットの残りのビットは0で埋める。
+///
+/// # 引数
+///
+/// * `levels` - 走査順に並べたレベル値
+/// * `maxv` - 今回の圧縮に用いたレベルの最大値
+/// * `nbit` - 1格子点値当りのビット数（1から16）
+///
+/// # 戻り値
+///
+/// ランレングス圧縮符号列
+pub(crate) fn encode_run_length(levels: &[u16], maxv: u16, nbit: u16) -> ReaderResult<Vec<u8>> {
+    if !(1..=16).contains(&nbit) {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "1データのビット数({})は1から16の範囲でなければなりません。",
+                nbit
+            )
+            .into(),
+        ));
+    }
+    // 2 ^ nbit - 1
+    let max_code = u16::MAX as u32 >> (16 - nbit);
+    if max_code < maxv as u32 {
+        return Err(ReaderError::Unexpected(
+            format!(
+                "レベルの最大値({})を1データのビット数({})で表現できません。",
+                maxv, nbit
+            )
+            .into(),
+        ));
+    }
+    let lngu = max_code - maxv as u32;
+
+    let mut codes = vec![];
+    let mut start = 0;
+    while start < levels.len() {
+        let level = levels[start];
+        if maxv < level {
+            return Err(ReaderError::Unexpected(
+                format!(
+                    "レベル値({})が今回の圧縮に用いたレベルの最大値({})を超えています。",
+                    level, maxv
+                )
+                .into(),
+            ));
+        }
+        let times = levels[start..]
+            .iter()
+            .take_while(|&&value| value == level)
+            .count();
+        start += times;
+        if lngu == 0 {
+            codes.extend(std::iter::repeat_n(level, times));
+            continue;
+        }
+        codes.push(level);
+        let mut rest = times as u32 - 1;
+        while 0 < rest {
+            codes.push((rest % lngu + maxv as u32 + 1) as u16);
+            rest /= lngu;
+        }
+    }
+
+    Ok(pack_codes(&codes, nbit))
+}
+
+/// 符号を`nbit`ビットずつ上位ビットから詰めて、オクテット列に変換する。
+///
+/// # 引数
+///
+/// * `codes` - 符号
+/// * `nbit` - 1符号当りのビット数（1から16）
+///
+/// # 戻り値
+///
+/// 最終オクテットの残りのビットを0で埋めたオクテット列
+fn pack_codes(codes: &[u16], nbit: u16) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity((codes.len() * nbit as usize).div_ceil(8));
+    let mut buffer = 0u32;
+    let mut count = 0;
+    for &code in codes {
+        buffer = (buffer << nbit) | code as u32;
+        count += nbit as u32;
+        while 8 <= count {
+            count -= 8;
+            bytes.push((buffer >> count) as u8);
+        }
+        buffer &= (1 << count) - 1;
+    }
+    if 0 < count {
+        bytes.push((buffer << (8 - count)) as u8);
+    }
+
+    bytes
+}
+
+/// ランレングス圧縮符号列から、GRIB2値のイテレーターを構築する。
+///
+/// 格子は北西端を北緯36度、東経140度とし、緯度及び経度の増分を1度とする。
+///
+/// # 引数
+///
+/// * `run_length` - ランレングス圧縮符号列
+/// * `nrows` - 格子の行数
+/// * `ncols` - 格子の列数
+/// * `nbit` - 1格子点値当りのビット数
+/// * `maxv` - 今回の圧縮に用いたレベルの最大値
+/// * `level_values` - レベル別物理値
+///
+/// # 戻り値
+///
+/// `Grib2ValueIter`
+#[cfg(test)]
+pub(crate) fn iter_from_run_length<V: Clone>(
+    run_length: &[u8],
+    nrows: u32,
+    ncols: u32,
+    nbit: u16,
+    maxv: u16,
+    level_values: &[V],
+) -> Grib2ValueIter<'static, V> {
+    Grib2ValueIter::new(
+        RunLength::bytes(run_length.to_vec()),
+        run_length.len(),
+        nrows * ncols,
+        36_000_000,
+        140_000_000,
+        140_000_000 + (ncols - 1) * 1_000_000,
+        1_000_000,
+        1_000_000,
+        0,
+        nbit,
+        maxv,
+        0,
+        level_values,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    use super::{
+        encode_run_length, expand_run_length, iter_from_run_length, pack_codes, Grib2ValueIter,
+        RunLength, ScanGrid,
+    };
+    use crate::reader::{
+        gaussian_latitudes, Aggregation, ColorMap, GridMask, MeshLevel, ReaderError, ReaderResult,
+    };
+
+    #[test]
+    fn into_raw_matches_values() {
+        let level_values: Vec<u16> = vec![10, 20, 30];
+        let values = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let raws = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values)
+            .into_raw()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(values.len(), raws.len());
+        for (value, raw) in values.iter().zip(raws.iter()) {
+            assert_eq!(value.lat_key(), raw.lat_i32());
+            assert_eq!((value.lon * 1_000_000.0).round() as u32, raw.lon_u32());
+            assert_eq!(value.level, raw.level());
+            assert_eq!(value.value, raw.value());
+        }
+        assert_eq!(
+            (36_000_000, 141_000_000),
+            (raws[1].lat_i32(), raws[1].lon_u32())
+        );
+    }
+
+    #[test]
+    fn progress_during_iteration_ok() {
+        let level_values: Vec<u16> = vec![10, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        assert_eq!((0, 4), iter.progress());
+        assert_eq!((0, 4), iter.bytes_progress());
+        let mut previous = iter.progress();
+        while let Some(value) = iter.next() {
+            value.unwrap();
+            let progress = iter.progress();
+            assert_eq!(previous.0 + 1, progress.0);
+            assert!(iter.bytes_progress().0 <= iter.bytes_progress().1);
+            previous = progress;
+        }
+        assert_eq!((4, 4), iter.progress());
+        assert_eq!((4, 4), iter.bytes_progress());
+    }
+
+    #[test]
     fn expand_run_length0_ok() {
         let nbit = 4;
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![3u16];
-        let expected = (3u16, 1u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (3u16, 1u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
     }
 
     #[test]
@@ -315,8 +1795,8 @@ mod tests {
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![9u16, 12];
-        let expected = (9u16, 2u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (9u16, 2u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
     }
 
     #[test]
@@ -325,8 +1805,8 @@ mod tests {
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![4u16, 15];
-        let expected = (4u16, 5u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (4u16, 5u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
     }
 
     #[test]
@@ -335,7 +1815,993 @@ mod tests {
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![0u16, 13, 12];
-        let expected = (0u16, 8u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (0u16, 8u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
+    }
+
+    #[test]
+    fn expand_run_length_near_u32_max_ok() {
+        // nbit = 16、maxv = 0の場合、lngu = 65535で2桁のランレングスはu32で表現できる
+        let values = vec![0u16, 65535, 65535];
+        let expected = (0u16, 65535u64 * 65535);
+        assert_eq!(expected, expand_run_length(&values, 0, 65535).unwrap());
+    }
+
+    #[test]
+    fn expand_run_length_beyond_u32_ok() {
+        // 3桁目の途中でu32の範囲を超えるが、u64で計算するため溢れない
+        let values = vec![0u16, 65535, 65535, 65535];
+        let expected = (0u16, 65535u64.pow(3));
+        assert_eq!(expected, expand_run_length(&values, 0, 65535).unwrap());
+        // 桁数が多く、lnguのべき乗がu32の範囲を超える
+        let values = vec![0u16, 11, 11, 11, 11, 11, 11, 255];
+        let expected = (0u16, 244 * 245u64.pow(6) + 1);
+        assert_eq!(expected, expand_run_length(&values, 10, 245).unwrap());
+    }
+
+    #[test]
+    fn encode_run_length_ok() {
+        // 展開の例と同じレベル値の列を圧縮
+        let mut levels = vec![3, 9, 9, 6, 4, 4, 4, 4, 4, 2, 1];
+        levels.extend([0; 8]);
+        levels.extend([2, 3]);
+        let bytes = encode_run_length(&levels, 10, 8).unwrap();
+        assert_eq!(vec![3, 9, 12, 6, 4, 15, 2, 1, 0, 18, 2, 3], bytes);
+
+        // 圧縮した符号列を展開すると元のレベル値の列に戻る
+        let level_values: Vec<u16> = (1..=10).collect();
+        let iter = iter_from_run_length(&bytes, 3, 7, 8, 10, &level_values);
+        let expanded = iter.map(|value| value.unwrap().level).collect::<Vec<_>>();
+        assert_eq!(levels, expanded);
+
+        // lnguを超える繰り返し数は複数の桁で表現する
+        let levels = vec![1; 300];
+        let bytes = encode_run_length(&levels, 10, 8).unwrap();
+        let iter = iter_from_run_length(&bytes, 1, 300, 8, 10, &level_values);
+        assert_eq!(
+            300,
+            iter.map(|value| value.unwrap().level)
+                .filter(|&l| l == 1)
+                .count()
+        );
+    }
+
+    #[test]
+    fn encode_run_length_err() {
+        assert!(encode_run_length(&[1, 2], 10, 0).is_err());
+        assert!(encode_run_length(&[1, 2], 10, 17).is_err());
+        assert!(encode_run_length(&[1, 2], 16, 4).is_err());
+        assert!(encode_run_length(&[1, 11], 10, 8).is_err());
+    }
+
+    #[test]
+    fn next_with_4bit_run_length_ok() {
+        // 展開の例の符号化列{3, 9, 12, 6, 4, 15, 2, 1, 0, 13, 12, 2, 3}を4ビットずつ詰めて、
+        // 最終オクテットの残りの4ビットを0で埋めた符号列
+        let bytes = [0x39, 0xC6, 0x4F, 0x21, 0x0D, 0xC2, 0x30];
+        let mut levels = vec![3, 9, 9, 6, 4, 4, 4, 4, 4, 2, 1];
+        levels.extend([0; 8]);
+        levels.extend([2, 3]);
+        assert_eq!(bytes.to_vec(), encode_run_length(&levels, 10, 4).unwrap());
+
+        let level_values: Vec<u16> = (1..=10).collect();
+        let iter = iter_from_run_length(&bytes, 3, 7, 4, 10, &level_values);
+        assert_eq!(5, iter.lngu);
+        let expanded = iter.map(|value| value.unwrap().level).collect::<Vec<_>>();
+        assert_eq!(levels, expanded);
+
+        let iter = iter_from_run_length(&bytes, 3, 7, 4, 10, &level_values);
+        let expanded = iter
+            .collect_parallel()
+            .unwrap()
+            .iter()
+            .map(|value| value.level)
+            .collect::<Vec<_>>();
+        assert_eq!(levels, expanded);
+    }
+
+    #[test]
+    fn next_with_4bit_trailing_zero_level_ok() {
+        // 最後のレベル値0と、最終オクテットを埋める0を区別する
+        let bytes = [0x12, 0x00];
+        let level_values = vec![10u16, 20];
+        let iter = iter_from_run_length(&bytes, 1, 3, 4, 2, &level_values);
+        let expanded = iter.map(|value| value.unwrap().level).collect::<Vec<_>>();
+        assert_eq!(vec![1, 2, 0], expanded);
+
+        // 最終オクテットを埋める0を除いても格子数に満たない場合はエラー
+        let iter = iter_from_run_length(&bytes, 1, 5, 4, 2, &level_values);
+        assert!(iter.collect::<ReaderResult<Vec<_>>>().is_err());
+    }
+
+    #[test]
+    fn next_with_14bit_run_length_ok() {
+        // nbit = 14、maxv = 16,000の場合、lngu = 2 ^ 14 - 1 - 16,000 = 383
+        // 5が1,000回連続する場合、1,000 - 1 = 233 + 2 * 383であるため、
+        // ランレングス値は233 + 16,001と2 + 16,001となる
+        let mut levels = vec![5; 1_000];
+        levels.push(7);
+        levels.extend([0; 3]);
+        let bytes = pack_codes(&[5, 16_234, 16_003, 7, 0, 16_003], 14);
+        assert_eq!(11, bytes.len());
+        assert_eq!(bytes, encode_run_length(&levels, 16_000, 14).unwrap());
+
+        let level_values: Vec<u16> = (1..=16_000).collect();
+        let iter = iter_from_run_length(&bytes, 4, 251, 14, 16_000, &level_values);
+        assert_eq!(383, iter.lngu);
+        let expanded = iter.map(|value| value.unwrap().level).collect::<Vec<_>>();
+        assert_eq!(levels, expanded);
+    }
+
+    #[test]
+    fn next_with_invalid_nbit_err() {
+        let level_values = vec![10u16];
+        for nbit in [0, 17] {
+            let mut iter = iter_from_run_length(&[1], 1, 1, nbit, 1, &level_values);
+            assert!(matches!(iter.next(), Some(Err(ReaderError::Unexpected(_)))));
+        }
+    }
+
+    #[test]
+    fn expand_run_length_overflow_err() {
+        // 5桁目でu64の範囲を超える
+        let values = vec![0u16, 65535, 65535, 65535, 65535, 65535];
+        assert!(expand_run_length(&values, 0, 65535).is_err());
+        // 桁数が多く、lnguのべき乗がu64の範囲を超える
+        let mut values = vec![0u16];
+        values.extend([11; 8]);
+        values.push(255);
+        assert!(expand_run_length(&values, 10, 245).is_err());
+    }
+
+    #[test]
+    fn values_with_overflowed_run_length_err() {
+        // u64の範囲を超えるランレングス
+        let level_values = vec![1u16];
+        let mut iter = iter_from_run_length(
+            &[1, 255, 255, 255, 255, 255, 255, 255, 255, 255],
+            1,
+            4,
+            8,
+            1,
+            &level_values,
+        );
+        assert!(iter.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn values_with_run_length_beyond_u32_err() {
+        // 繰り返し数がu32の範囲を超えるランレングスでも、途中で溢れずに資料点数を超えたエラーを返す
+        let level_values = vec![1u16];
+        let run_length = [1, 255, 255, 255, 255, 255, 255];
+        let mut iter = iter_from_run_length(&run_length, 1, 4, 8, 1, &level_values);
+        for _ in 0..4 {
+            assert_eq!(1, iter.next().unwrap().unwrap().level);
+        }
+        assert_eq!((4, 4), iter.progress());
+        match iter.next() {
+            Some(Err(ReaderError::Unexpected(message))) => {
+                assert!(message.contains("資料点数(4)を超えています"));
+            }
+            _ => panic!("資料点数を超えたエラーを想定しています。"),
+        }
+
+        let iter = iter_from_run_length(&run_length, 1, 4, 8, 1, &level_values);
+        match iter.collect_parallel() {
+            Err(ReaderError::Unexpected(message)) => {
+                // 254の6乗
+                assert!(message.contains("268,535,866,540,096"));
+            }
+            _ => panic!("展開した資料値の数が一致しないエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn with_latitudes_ok() {
+        // N=2のガウス格子で、4行2列の格子
+        let latitudes = gaussian_latitudes(2);
+        let level_values = [10u16];
+        let run_length = [1; 8];
+        let iter =
+            iter_from_run_length(&run_length, 4, 2, 8, 1, &level_values).with_latitudes(&latitudes);
+        let values = iter.map(|value| value.unwrap()).collect::<Vec<_>>();
+        assert_eq!(8, values.len());
+        for (index, value) in values.iter().enumerate() {
+            assert!((latitudes[index / 2] - value.lat).abs() < 1e-6);
+            assert_eq!(140.0 + (index % 2) as f64, value.lon);
+        }
+        // 南半球の緯度は負になる
+        assert!(values[7].lat < -59.0);
+        let raws = iter_from_run_length(&run_length, 4, 2, 8, 1, &level_values)
+            .with_latitudes(&latitudes)
+            .into_raw()
+            .map(|raw| raw.unwrap())
+            .collect::<Vec<_>>();
+        for (value, raw) in values.iter().zip(&raws) {
+            assert_eq!(value.lat_key(), raw.lat_i32());
+            assert_eq!(value.lat, raw.as_f64().lat);
+        }
+        assert!(raws[7].lat_i32() < -59_000_000);
+
+        // 並列に展開した場合も同様
+        let iter =
+            iter_from_run_length(&run_length, 4, 2, 8, 1, &level_values).with_latitudes(&latitudes);
+        let lats = iter
+            .collect_parallel()
+            .unwrap()
+            .iter()
+            .map(|value| value.lat)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values.iter().map(|value| value.lat).collect::<Vec<_>>(),
+            lats
+        );
+    }
+
+    #[test]
+    fn missing_as_ok() {
+        // 2行3列の格子で、レベル値は北西から1, 0, 2, 0, 0, 1
+        let level_values = [10u16, 20];
+        let run_length = [1, 0, 2, 0, 0, 1];
+        let expected = [Some(10.0), None, Some(20.0), None, None, Some(10.0)];
+
+        // センチネル値を設定しない場合は、欠測値の格子に対してNoneを返す
+        let iter = iter_from_run_length(&run_length, 2, 3, 8, 2, &level_values);
+        let values = iter
+            .map(|value| value.unwrap().physical_value())
+            .collect::<Vec<_>>();
+        assert_eq!(expected.to_vec(), values);
+
+        // センチネル値を設定した場合は、欠測値の格子が全てセンチネル値になる
+        let sentinel = expected.map(|v| v.or(Some(-9999.0)));
+        let iter = iter_from_run_length(&run_length, 2, 3, 8, 2, &level_values).missing_as(-9999.0);
+        let values = iter.map(|value| value.unwrap()).collect::<Vec<_>>();
+        assert!(values
+            .iter()
+            .filter(|value| value.level == 0)
+            .all(|value| value.value.is_none()));
+        let values = values
+            .iter()
+            .map(|value| value.physical_value())
+            .collect::<Vec<_>>();
+        assert_eq!(sentinel.to_vec(), values);
+
+        // 並列に展開した場合も同様
+        let iter = iter_from_run_length(&run_length, 2, 3, 8, 2, &level_values).missing_as(-9999.0);
+        let values = iter
+            .collect_parallel()
+            .unwrap()
+            .iter()
+            .map(|value| value.physical_value())
+            .collect::<Vec<_>>();
+        assert_eq!(sentinel.to_vec(), values);
+    }
+
+    #[test]
+    fn basin_means_ok() {
+        // 2行2列の格子で、レベル値は北西から1, 2, 3, 0（欠測）
+        let level_values = [10u16, 20, 30];
+        let iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let north = GridMask::new(2, 2, vec![true, true, false, false]).unwrap();
+        let south = GridMask::new(2, 2, vec![false, false, true, true]).unwrap();
+        let missing = GridMask::new(2, 2, vec![false, false, false, true]).unwrap();
+        let means = iter.basin_means(&[north, south, missing]).unwrap();
+        assert_eq!(vec![Some(15.0), Some(30.0), None], means);
+    }
+
+    #[test]
+    fn basin_means_with_mismatched_mask_err() {
+        let level_values = [10u16, 20, 30];
+        let iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let basin = GridMask::new(1, 4, vec![true; 4]).unwrap();
+        assert!(iter.basin_means(&[basin]).is_err());
+    }
+
+    /// 1回の読み込みで1オクテットだけを返すリーダー
+    struct OneByteReader(Cursor<Vec<u8>>);
+
+    impl std::io::Read for OneByteReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn prefetch_with_short_reads_ok() {
+        // 1オクテットずつしか読み込めないリーダーでも、ランレングス圧縮符号列全体を読み込む
+        let level_values = [10u16, 20, 30];
+        let run_length = vec![1, 5, 2, 3, 5, 0];
+        let iter_of = |bytes: Vec<u8>, total_bytes| {
+            Grib2ValueIter::new(
+                RunLength::reader(OneByteReader(Cursor::new(bytes))),
+                total_bytes,
+                6,
+                36_000_000,
+                140_000_000,
+                142_000_000,
+                1_000_000,
+                1_000_000,
+                0,
+                8,
+                3,
+                0,
+                &level_values,
+            )
+        };
+        let levels = iter_of(run_length.clone(), 6)
+            .map(|value| value.unwrap().level)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1, 1, 2, 3, 3, 0], levels);
+
+        // ランレングス圧縮符号列を記録しているバイト数より、リーダーのデータが短い場合は、
+        // 読み込めたランレングス圧縮符号を展開した後にエラーを返す
+        let results = iter_of(run_length[..3].to_vec(), 6).collect::<Vec<_>>();
+        assert_eq!(3, results.len());
+        assert!(results[..2].iter().all(|r| r.is_ok()));
+        assert!(matches!(results[2], Err(ReaderError::ReadError(_))));
+    }
+
+    /// 1オクテットを返した後に、読み込みに失敗するリーダー
+    struct FailingReader(bool);
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if std::mem::replace(&mut self.0, true) {
+                return Err(std::io::Error::other("読み込みに失敗"));
+            }
+            buf[0] = 1;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn prefetch_with_read_error_err() {
+        // リーダーの読み込みエラーを、最初の格子を返す前にエラーとして返して終了する
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[], 1, 2, 8, 3, &level_values);
+        iter.run_length = RunLength::reader(FailingReader(false));
+        iter.total_bytes = 2;
+        match iter.next() {
+            Some(Err(ReaderError::ReadError(message))) => {
+                assert!(message.contains("読み込みに失敗"))
+            }
+            _ => panic!("ランレングス圧縮符号列の読み込みエラーを想定しています。"),
+        }
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn strict_and_lenient_with_truncated_run_length() {
+        // 2行2列の格子だが、ランレングス圧縮符号列は2格子分しか記録されていない
+        let level_values = [10u16, 20, 30];
+        let iter = iter_from_run_length(&[1, 2], 2, 2, 8, 3, &level_values);
+        let results = iter.collect::<Vec<_>>();
+        // 厳格版: 展開できた2格子を返した後にエラーを返して終了
+        assert_eq!(3, results.len());
+        assert!(results[..2].iter().all(|r| r.is_ok()));
+        assert!(matches!(results[2], Err(ReaderError::Unexpected(_))));
+
+        // 展開できた格子のみを取り出す
+        let iter = iter_from_run_length(&[1, 2], 2, 2, 8, 3, &level_values);
+        let (values, error) = iter.into_partial();
+        let values = values.iter().map(|v| v.value()).collect::<Vec<_>>();
+        assert_eq!(vec![Some(10), Some(20)], values);
+        assert!(matches!(error, Some(ReaderError::Unexpected(_))));
+
+        // lenient版: エラーが発生した格子以降を欠測値として、全ての格子を返す
+        let iter = iter_from_run_length(&[1, 2], 2, 2, 8, 3, &level_values);
+        let mut iter = iter.lenient();
+        let values = iter.by_ref().collect::<Vec<_>>();
+        let expected = vec![
+            (36.0, 140.0, Some(10)),
+            (36.0, 141.0, Some(20)),
+            (35.0, 140.0, None),
+            (35.0, 141.0, None),
+        ];
+        let values = values
+            .iter()
+            .map(|v| (v.lat, v.lon, v.value()))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, values);
+        assert!(matches!(iter.error(), Some(ReaderError::Unexpected(_))));
+    }
+
+    #[test]
+    fn lenient_without_error_ok() {
+        let level_values = [10u16, 20, 30];
+        let iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let (values, error) = iter.into_partial();
+        assert_eq!(4, values.len());
+        assert!(error.is_none());
+
+        let iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let mut iter = iter.lenient();
+        assert_eq!(4, iter.by_ref().count());
+        assert!(iter.error().is_none());
+    }
+
+    #[test]
+    fn cells_ok() {
+        let level_values = [10u16, 20, 30];
+        let iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let cells = iter.cells().map(|c| c.unwrap()).collect::<Vec<_>>();
+        assert_eq!(4, cells.len());
+        // 北西端の格子（北緯36度、東経140度）
+        let first = &cells[0];
+        assert_eq!(1.0, first.lat_inc());
+        assert_eq!(1.0, first.lon_inc());
+        assert_eq!(Some(10), first.value().value());
+        assert_eq!((36.0, 140.0), first.center());
+        assert_eq!((35.5, 139.5, 36.5, 140.5), first.bounds());
+        assert_eq!(
+            [(35.5, 139.5), (35.5, 140.5), (36.5, 140.5), (36.5, 139.5)],
+            first.corners()
+        );
+        // 隣接する格子は境界を共有する
+        assert_eq!(first.bounds().3, cells[1].bounds().1);
+        assert_eq!(first.bounds().0, cells[2].bounds().2);
+    }
+
+    #[test]
+    fn within_includes_boundaries() {
+        // 3行3列の格子で、北西から順にレベル値1から9
+        let level_values: Vec<u16> = (1..=9).collect();
+        let iter = iter_from_run_length(&[1, 2, 3, 4, 5, 6, 7, 8, 9], 3, 3, 8, 9, &level_values);
+        let values: Vec<(f64, f64, u16)> = iter
+            .within(35.0, 141.0, 36.0, 142.0)
+            .map(|v| v.unwrap())
+            .map(|v| (v.lat, v.lon, v.value.unwrap()))
+            .collect();
+        let expected = vec![
+            (36.0, 141.0, 2),
+            (36.0, 142.0, 3),
+            (35.0, 141.0, 5),
+            (35.0, 142.0, 6),
+        ];
+        assert_eq!(expected, values);
+    }
+
+    #[test]
+    fn within_stops_after_min_lat() {
+        // 3行3列の格子だが、ランレングス圧縮符号列は7格子分しか記録されていない
+        let level_values: Vec<u16> = (1..=9).collect();
+        let iter = iter_from_run_length(&[1, 2, 3, 4, 5, 6, 7], 3, 3, 8, 9, &level_values);
+        let values: Vec<u16> = iter
+            .within(35.0, 140.0, 36.0, 142.0)
+            .map(|v| v.unwrap().value.unwrap())
+            .collect();
+        assert_eq!(vec![1, 2, 3, 4, 5, 6], values);
+    }
+
+    #[test]
+    fn to_flat_f32_ok() {
+        // 2行2列の格子で、レベル値は北西から1, 2, 3, 0（欠測）
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        iter.decimal_scale_factor = 1;
+        let values = iter.to_flat_f32(-1.0).unwrap();
+        assert_eq!(vec![1.0, 2.0, 3.0, -1.0], values);
+    }
+
+    #[test]
+    fn to_flat_f32_with_nan_ok() {
+        // 2行2列の格子で、北西から3格子が欠測、最後の格子がレベル値3
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[0, 6, 3], 2, 2, 8, 3, &level_values);
+        let values = iter.to_flat_f32(f32::NAN).unwrap();
+        assert_eq!(4, values.len());
+        assert!(values[..3].iter().all(|v| v.is_nan()));
+        assert_eq!(30.0, values[3]);
+    }
+
+    #[test]
+    fn missing_ratio_ok() {
+        // 2行2列の格子で、レベル値は北西から1, 2, 3, 0（欠測）
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        assert_eq!(0.25, iter.missing_ratio().unwrap());
+    }
+
+    #[test]
+    fn missing_ratio_of_extreme_grids_ok() {
+        let level_values = [10u16, 20, 30];
+        // 全ての格子が欠測
+        let mut iter = iter_from_run_length(&[0, 0, 0, 0], 2, 2, 8, 3, &level_values);
+        assert_eq!(1.0, iter.missing_ratio().unwrap());
+        // 全ての格子が有効
+        let mut iter = iter_from_run_length(&[3, 3, 3, 3], 2, 2, 8, 3, &level_values);
+        assert_eq!(0.0, iter.missing_ratio().unwrap());
+    }
+
+    #[test]
+    fn missing_ratio_with_bitmap_ok() {
+        // ビットマップにより資料値が存在しない2格子を、欠測値の格子として数える
+        let level_values = [10u16, 20, 30];
+        let bitmap = [true, false, false, true];
+        let mut iter = iter_from_run_length(&[1, 3], 2, 2, 8, 3, &level_values)
+            .with_bitmap(Some(bitmap.into()));
+        assert_eq!(0.5, iter.missing_ratio().unwrap());
+    }
+
+    #[test]
+    fn level_histogram_ok() {
+        // 3行3列の格子で、レベル値は北西から1, 1, 1, 0（欠測）, 3, 3, 3, 3, 0（欠測）
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 6, 0, 3, 7, 0], 3, 3, 8, 3, &level_values);
+        let histogram = iter.level_histogram().unwrap();
+        assert_eq!(vec![2, 3, 0, 4], histogram);
+        assert_eq!(9, histogram.iter().sum::<u32>());
+    }
+
+    #[test]
+    fn aggregate_by_mesh_ok() {
+        // 北西端が北緯36.15度、東経140.05度で、格子の間隔が0.1度の2行2列の格子
+        // レベル値は北西から1, 2, 3, 0（欠測）で、全ての格子が第1次地域区画5440に含まれる
+        let level_values = [10u16, 20, 30];
+        let iter_of = || {
+            Grib2ValueIter::new(
+                RunLength::bytes(vec![1, 2, 3, 0]),
+                4,
+                4,
+                36_150_000,
+                140_050_000,
+                140_150_000,
+                100_000,
+                100_000,
+                0,
+                8,
+                3,
+                1,
+                &level_values,
+            )
+        };
+
+        let means = iter_of()
+            .aggregate_by_mesh(MeshLevel::First, Aggregation::Mean)
+            .unwrap();
+        assert_eq!(HashMap::from([(5440, 2.0)]), means);
+        let maxes = iter_of()
+            .aggregate_by_mesh(MeshLevel::First, Aggregation::Max)
+            .unwrap();
+        assert_eq!(HashMap::from([(5440, 3.0)]), maxes);
+        // 第2次地域区画では格子ごとに異なるメッシュに含まれ、欠測値の格子のメッシュは含まない
+        let maxes = iter_of()
+            .aggregate_by_mesh(MeshLevel::Second, Aggregation::Max)
+            .unwrap();
+        assert_eq!(
+            HashMap::from([(544010, 1.0), (544011, 2.0), (544000, 3.0)]),
+            maxes
+        );
+    }
+
+    #[test]
+    fn render_into_ok() {
+        // 2行2列の格子で、レベル値は北西から1, 2, 3, 0（欠測）
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let (red, blue) = ([255, 0, 0, 255], [0, 0, 255, 255]);
+        let colormap = ColorMap::new(vec![(1, red), (3, blue)]).unwrap();
+        let mut buf = [0xFF; 2 * 2 * 4];
+        iter.render_into(&mut buf, 2, &colormap).unwrap();
+        assert_eq!(red, buf[0..4]);
+        assert_eq!(red, buf[4..8]);
+        assert_eq!(blue, buf[8..12]);
+        assert_eq!([0, 0, 0, 0], buf[12..16]);
+    }
+
+    #[test]
+    fn render_into_with_invalid_buffer_err() {
+        let level_values = [10u16, 20, 30];
+        let colormap = ColorMap::new(vec![(1, [255, 0, 0, 255])]).unwrap();
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        assert!(iter.render_into(&mut [0; 15], 2, &colormap).is_err());
+        assert!(iter.render_into(&mut [0; 24], 3, &colormap).is_err());
+    }
+
+    #[test]
+    fn size_hint_ok() {
+        // 2行3列の格子
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 1, 2, 3], 2, 3, 8, 3, &level_values);
+        assert_eq!((1, Some(7)), iter.size_hint());
+        iter.next();
+        assert_eq!((1, Some(6)), iter.size_hint());
+        iter.by_ref().for_each(drop);
+        assert_eq!((0, Some(1)), iter.size_hint());
+
+        // 実際に返される要素数は、下限と上限の範囲に収まる
+        let iter = iter_from_run_length(&[1, 2, 3, 1, 2, 3], 2, 3, 8, 3, &level_values);
+        let (lower, upper) = iter.size_hint();
+        let values = iter.collect::<Vec<_>>();
+        assert!(lower <= values.len() && values.len() <= upper.unwrap());
+
+        // ランレングス圧縮符号列が途中で切れている場合も、下限以上の要素を返してから終了する
+        let mut iter = iter_from_run_length(&[1, 2], 2, 3, 8, 3, &level_values);
+        let (lower, upper) = iter.size_hint();
+        let results = iter.by_ref().collect::<Vec<_>>();
+        assert!(lower <= results.len() && results.len() <= upper.unwrap());
+        assert!(results.last().unwrap().is_err());
+        assert_eq!((0, Some(0)), iter.size_hint());
+
+        // lenient版は、エラーが発生しても正確な残りの格子数を返す
+        let mut iter = iter_from_run_length(&[1, 2], 2, 3, 8, 3, &level_values).lenient();
+        assert_eq!(6, iter.len());
+        iter.nth(2);
+        assert_eq!(3, iter.len());
+    }
+
+    #[test]
+    fn next_with_empty_run_length_ok() {
+        // データ長0のランレングス圧縮符号列は、全格子を欠測値として返す
+        let level_values = [10u16, 20, 30];
+        let iter = iter_from_run_length(&[], 2, 3, 8, 3, &level_values);
+        let values = iter.collect::<ReaderResult<Vec<_>>>().unwrap();
+        assert_eq!(6, values.len());
+        assert!(values.iter().all(|v| v.level == 0 && v.value().is_none()));
+
+        let iter = iter_from_run_length(&[], 2, 3, 8, 3, &level_values);
+        let values = iter.collect_parallel().unwrap();
+        assert_eq!(6, values.len());
+        assert!(values.iter().all(|v| v.value().is_none()));
+    }
+
+    #[test]
+    fn next_with_too_many_points_err() {
+        // 2行2列の格子に対して、5格子分のランレングス圧縮符号を記録した破損入力
+        let level_values = [10u16, 20, 30];
+        let iter = iter_from_run_length(&[1, 2, 3, 1, 2], 2, 2, 8, 3, &level_values);
+        // エラーを返した後は終了する
+        let results: Vec<_> = iter.collect();
+        assert_eq!(5, results.len());
+        assert!(results[..4].iter().all(|result| result.is_ok()));
+        match &results[4] {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("第3節に記録されている資料点数(4)を超えています。"))
+            }
+            _ => panic!("格子数が資料点数を超えたエラーを想定しています。"),
+        }
+
+        // 2行2列の格子に対して、繰り返しで7格子に展開されるランレングス圧縮符号を記録した破損入力
+        let iter = iter_from_run_length(&[1, 10], 2, 2, 8, 3, &level_values);
+        let results: Vec<_> = iter.collect();
+        assert_eq!(5, results.len());
+        assert!(results[4].is_err());
+    }
+
+    #[test]
+    fn next_with_level_out_of_level_values_err() {
+        // レベルの最大値が3であるのに、レベル別物理値が2つしかない破損入力
+        let level_values = [10u16, 20];
+        let iter = iter_from_run_length(&[1, 2, 3, 1], 2, 2, 8, 3, &level_values);
+        // エラーを返した後は終了する
+        let results: Vec<_> = iter.collect();
+        assert_eq!(3, results.len());
+        assert!(results[..2].iter().all(|result| result.is_ok()));
+        match &results[2] {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("レベル値(3)がレベル別物理値の数(2)を超えています。"))
+            }
+            _ => panic!("レベル値がレベル別物理値の数を超えたエラーを想定しています。"),
+        }
+
+        // 全ての格子をベクタに格納する場合も、panicせずにエラーを返す
+        let iter = iter_from_run_length(&[1, 2, 3, 1], 2, 2, 8, 3, &level_values);
+        match iter.collect_parallel() {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("レベル値(3)がレベル別物理値の数(2)を超えています。"))
+            }
+            _ => panic!("レベル値がレベル別物理値の数を超えたエラーを想定しています。"),
+        }
+    }
+
+    #[test]
+    fn next_with_run_length_before_level_err() {
+        // ランレングス圧縮符号列が、レベル値ではなくランレングス値(4 > maxv)から始まる破損入力
+        let level_values = [10u16, 20, 30];
+        let results: Vec<_> = iter_from_run_length(&[4, 1], 1, 2, 8, 3, &level_values).collect();
+        assert_eq!(1, results.len());
+        match &results[0] {
+            Err(ReaderError::Unexpected(message)) => {
+                assert!(message.contains("セットの先頭(Some(4))"))
+            }
+            _ => panic!("セットの先頭がレベル値でないエラーを想定しています。"),
+        }
+        let iter = iter_from_run_length(&[4, 1], 1, 2, 8, 3, &level_values);
+        assert!(matches!(
+            iter.collect_parallel(),
+            Err(ReaderError::Unexpected(_))
+        ));
+    }
+
+    #[test]
+    fn physical_value_ok() {
+        // 尺度因子が0、1、2及び-1（符号と絶対値で0x81）のそれぞれで、尺度因子を適用した物理値を返す
+        let level_values = [125u16];
+        for (decimal_scale_factor, expected) in [(0, 125.0), (1, 12.5), (2, 1.25), (0x81, 1250.0)] {
+            let mut iter = iter_from_run_length(&[1], 1, 1, 8, 1, &level_values);
+            iter.decimal_scale_factor = decimal_scale_factor;
+            let value = iter.next().unwrap().unwrap();
+            assert_eq!(decimal_scale_factor, value.decimal_scale_factor);
+            assert_eq!(Some(125), value.value());
+            assert_eq!(Some(expected), value.physical_value());
+        }
+    }
+
+    #[test]
+    fn scaled_ok() {
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        iter.decimal_scale_factor = 1;
+        let values: Vec<Option<f64>> = iter.scaled(3.0).map(|v| v.unwrap().value).collect();
+        assert_eq!(vec![Some(3.0), Some(6.0), Some(9.0), None], values);
+    }
+
+    #[test]
+    fn values_with_bitmap_ok() {
+        // 2行2列の格子で、ビットマップにより2番目と3番目の格子は資料値が存在しない
+        let level_values = [10u16, 20, 30];
+        let bitmap = [true, false, false, true];
+        let iter = iter_from_run_length(&[1, 3], 2, 2, 8, 3, &level_values)
+            .with_bitmap(Some(bitmap.into()));
+        let values: Vec<Option<u16>> = iter.map(|v| v.unwrap().value).collect();
+        assert_eq!(vec![Some(10), None, None, Some(30)], values);
+    }
+
+    #[test]
+    fn values_with_trailing_bitmap_off_ok() {
+        let level_values = [10u16, 20, 30];
+        let bitmap = [true, true, false, false];
+        let iter = iter_from_run_length(&[2, 5], 2, 2, 8, 3, &level_values)
+            .with_bitmap(Some(bitmap.into()));
+        let values: Vec<Option<u16>> = iter.map(|v| v.unwrap().value).collect();
+        assert_eq!(vec![Some(20), Some(20), None, None], values);
+    }
+
+    #[test]
+    fn write_pg_copy_ok() {
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let mut buf = vec![];
+        iter.write_pg_copy(&mut buf).unwrap();
+        let copy = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = copy.lines().collect();
+        assert_eq!(4, lines.len());
+        assert_eq!("36.000000\t140.000000\t10", lines[0]);
+        assert_eq!("35.000000\t141.000000\t\\N", lines[3]);
+    }
+
+    #[test]
+    fn write_csv_ok() {
+        let level_values = [10u16, 20, 30];
+        let mut iter = iter_from_run_length(&[1, 2, 3, 0], 2, 2, 8, 3, &level_values);
+        let mut buf = vec![];
+        iter.write_csv(&mut buf).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(5, lines.len());
+        assert_eq!("lat,lon,level,value", lines[0]);
+        assert_eq!("36.000000,140.000000,1,10", lines[1]);
+        assert_eq!("35.000000,141.000000,0,", lines[4]);
+    }
+
+    #[test]
+    fn collect_parallel_matches_sequential() {
+        // 3行3列の格子で、同じレベル値の連続と欠測値を含む
+        let level_values = [10u16, 20, 30];
+        let run_length = [1, 6, 0, 2, 5, 3, 6];
+        let sequential: Vec<_> = iter_from_run_length(&run_length, 3, 3, 8, 3, &level_values)
+            .map(|v| v.unwrap())
+            .map(|v| (v.lat, v.lon, v.level, v.value))
+            .collect();
+        let parallel: Vec<_> = iter_from_run_length(&run_length, 3, 3, 8, 3, &level_values)
+            .collect_parallel()
+            .unwrap()
+            .into_iter()
+            .map(|v| (v.lat, v.lon, v.level, v.value))
+            .collect();
+        assert_eq!(9, parallel.len());
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn collect_parallel_with_bitmap_matches_sequential() {
+        let level_values = [10u16, 20, 30];
+        let bitmap = [true, false, false, true];
+        let sequential: Vec<_> = iter_from_run_length(&[1, 3], 2, 2, 8, 3, &level_values)
+            .with_bitmap(Some(bitmap.into()))
+            .map(|v| v.unwrap().value)
+            .collect();
+        let parallel: Vec<_> = iter_from_run_length(&[1, 3], 2, 2, 8, 3, &level_values)
+            .with_bitmap(Some(bitmap.into()))
+            .collect_parallel()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.value)
+            .collect();
+        assert_eq!(sequential, parallel);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn to_bitvec_mask_ok() {
+        // 3行3列の格子で、北西から順にレベル値1から8と欠測値
+        let level_values: Vec<u16> = (1..=8).map(|v| v * 10).collect();
+        let mut iter =
+            iter_from_run_length(&[1, 2, 3, 4, 5, 6, 7, 8, 0], 3, 3, 8, 8, &level_values);
+        iter.decimal_scale_factor = 1;
+        let mask = iter.to_bitvec_mask(5.0).unwrap();
+        assert_eq!(9, mask.len());
+        assert_eq!(2, mask.as_raw_slice().len());
+        assert_eq!(0b0000_1111, mask.as_raw_slice()[0]);
+        assert!(!mask[8]);
+    }
+
+    /// 2行3列の格子の座標を走査順に返す。
+    fn scanned_coordinates(lat_first: u32, lon_first: u32, scan_mode: u8) -> Vec<(f64, f64)> {
+        let lon_last = match scan_mode & 0x80 {
+            0 => lon_first + 2_000_000,
+            _ => lon_first - 2_000_000,
+        };
+        let grid = ScanGrid::new(
+            6, lat_first, lon_first, lon_last, 1_000_000, 1_000_000, scan_mode,
+        );
+        (0..6).map(|index| grid.coordinate(index)).collect()
+    }
+
+    #[test]
+    fn last_grid_point_coordinate_exact_ok() {
+        // 1kmメッシュ解析雨量の格子
+        let (ni, nj) = (2_560, 3_360);
+        let (lat_first, lon_first, lon_last) = (47_995_833, 118_006_250, 149_993_750);
+        let (lat_inc, lon_inc) = (8_333, 12_500);
+        let grid = ScanGrid::new(ni * nj, lat_first, lon_first, lon_last, lat_inc, lon_inc, 0);
+
+        // 座標は10e-6度単位の整数で計算してから度単位に変換するため、格子を進めても誤差が
+        // 累積せず、最後の格子点の経度は第3節の最後の格子点の経度と完全に一致する
+        let (lat, lon) = grid.coordinate((ni * nj - 1) as usize);
+        assert_eq!(lon_last as f64 / 1_000_000.0, lon);
+        assert_eq!((lat_first - (nj - 1) * lat_inc) as f64 / 1_000_000.0, lat);
+
+        // 行の先頭に戻った格子点の経度も、最初の格子点の経度と完全に一致する
+        let (_, lon) = grid.coordinate(((nj - 1) * ni) as usize);
+        assert_eq!(lon_first as f64 / 1_000_000.0, lon);
+    }
+
+    #[test]
+    fn scan_mode_default_ok() {
+        let expected = vec![
+            (36.0, 140.0),
+            (36.0, 141.0),
+            (36.0, 142.0),
+            (35.0, 140.0),
+            (35.0, 141.0),
+            (35.0, 142.0),
+        ];
+        assert_eq!(expected, scanned_coordinates(36_000_000, 140_000_000, 0x00));
+    }
+
+    #[test]
+    fn scan_mode_i_negative_ok() {
+        let expected = vec![
+            (36.0, 142.0),
+            (36.0, 141.0),
+            (36.0, 140.0),
+            (35.0, 142.0),
+            (35.0, 141.0),
+            (35.0, 140.0),
+        ];
+        assert_eq!(expected, scanned_coordinates(36_000_000, 142_000_000, 0x80));
+    }
+
+    #[test]
+    fn scan_mode_j_positive_ok() {
+        let expected = vec![
+            (35.0, 140.0),
+            (35.0, 141.0),
+            (35.0, 142.0),
+            (36.0, 140.0),
+            (36.0, 141.0),
+            (36.0, 142.0),
+        ];
+        assert_eq!(expected, scanned_coordinates(35_000_000, 140_000_000, 0x40));
+    }
+
+    #[test]
+    fn scan_mode_j_consecutive_ok() {
+        let expected = vec![
+            (36.0, 140.0),
+            (35.0, 140.0),
+            (36.0, 141.0),
+            (35.0, 141.0),
+            (36.0, 142.0),
+            (35.0, 142.0),
+        ];
+        assert_eq!(expected, scanned_coordinates(36_000_000, 140_000_000, 0x20));
+    }
+
+    #[test]
+    fn scan_mode_boustrophedon_ok() {
+        let expected = vec![
+            (36.0, 140.0),
+            (36.0, 141.0),
+            (36.0, 142.0),
+            (35.0, 142.0),
+            (35.0, 141.0),
+            (35.0, 140.0),
+        ];
+        assert_eq!(expected, scanned_coordinates(36_000_000, 140_000_000, 0x10));
+    }
+
+    #[test]
+    fn scan_mode_j_consecutive_boustrophedon_ok() {
+        let expected = vec![
+            (36.0, 140.0),
+            (35.0, 140.0),
+            (35.0, 141.0),
+            (36.0, 141.0),
+            (36.0, 142.0),
+            (35.0, 142.0),
+        ];
+        assert_eq!(expected, scanned_coordinates(36_000_000, 140_000_000, 0x30));
+    }
+
+    #[test]
+    fn values_with_scan_mode_ok() {
+        // 2行3列の格子を牛耕式に走査し、レベル値は走査順に1から6
+        let level_values: Vec<u16> = (1..=6).collect();
+        let mut iter = iter_from_run_length(&[1, 2, 3, 4, 5, 6], 2, 3, 8, 6, &level_values);
+        iter.grid = ScanGrid::new(
+            6,
+            36_000_000,
+            140_000_000,
+            142_000_000,
+            1_000_000,
+            1_000_000,
+            0x10,
+        );
+        let values: Vec<(f64, f64, u16)> = iter
+            .map(|v| v.unwrap())
+            .map(|v| (v.lat, v.lon, v.value.unwrap()))
+            .collect();
+        let expected = vec![
+            (36.0, 140.0, 1),
+            (36.0, 141.0, 2),
+            (36.0, 142.0, 3),
+            (35.0, 142.0, 4),
+            (35.0, 141.0, 5),
+            (35.0, 140.0, 6),
+        ];
+        assert_eq!(expected, values);
+    }
+
+    #[test]
+    fn within_with_j_positive_scan_mode_ok() {
+        // 3行3列の格子を南から北に走査するため、最小緯度より南の格子で走査を終了しない
+        let level_values: Vec<u16> = (1..=9).collect();
+        let mut iter =
+            iter_from_run_length(&[1, 2, 3, 4, 5, 6, 7, 8, 9], 3, 3, 8, 9, &level_values);
+        iter.grid = ScanGrid::new(
+            9,
+            34_000_000,
+            140_000_000,
+            142_000_000,
+            1_000_000,
+            1_000_000,
+            0x40,
+        );
+        let values: Vec<u16> = iter
+            .within(35.0, 140.0, 36.0, 140.0)
+            .map(|v| v.unwrap().value.unwrap())
+            .collect();
+        assert_eq!(vec![4, 7], values);
+    }
+
+    #[test]
+    fn iter_with_16bit_lngu_ok() {
+        // nbit = 16の場合に、LNGU進数の計算でオーバーフローしない
+        let level_values = vec![1u16];
+        let iter = iter_from_run_length(&[1], 1, 1, 16, 1, &level_values);
+        assert_eq!(65534, iter.lngu);
     }
 }
@@ -0,0 +1,54 @@
+use std::io::{Cursor, Read, Seek};
+
+use grib2::reader::helpers::{read_i16, read_u8};
+use grib2::reader::{FromReader, ReaderError, ReaderResult, Section4, TemplateFromReader};
+
+/// 利用者が独自に定義したプロダクト定義テンプレート
+struct DummyTemplate4 {
+    parameter_category: u8,
+    parameter_number: u8,
+    offset: i16,
+}
+
+impl TemplateFromReader<u16> for DummyTemplate4 {
+    fn from_reader<R: Read + Seek>(reader: &mut R, template_number: u16) -> ReaderResult<Self> {
+        if template_number != 65_000 {
+            return Err(ReaderError::Unexpected(
+                format!("テンプレート番号({})に対応していません。", template_number).into(),
+            ));
+        }
+
+        Ok(Self {
+            parameter_category: read_u8(reader, "第4節:パラメータカテゴリー")?,
+            parameter_number: read_u8(reader, "第4節:パラメータ番号")?,
+            offset: read_i16(reader, "第4節:オフセット")?,
+        })
+    }
+}
+
+/// 独自のテンプレートを記録した第4節のバイト列を返す。
+fn section4_bytes(template_number: u16) -> Vec<u8> {
+    let mut bytes = 13u32.to_be_bytes().to_vec();
+    bytes.push(4);
+    bytes.extend_from_slice(&0u16.to_be_bytes());
+    bytes.extend_from_slice(&template_number.to_be_bytes());
+    bytes.extend_from_slice(&[1, 8]);
+    // 符号と絶対値で表現した-3
+    bytes.extend_from_slice(&0x8003u16.to_be_bytes());
+
+    bytes
+}
+
+#[test]
+fn test_section4_with_custom_template() {
+    let mut reader = Cursor::new(section4_bytes(65_000));
+    let section4 = Section4::<DummyTemplate4>::from_reader(&mut reader).unwrap();
+    assert_eq!(65_000, section4.product_definition_template_number());
+    let template4 = section4.template4();
+    assert_eq!(1, template4.parameter_category);
+    assert_eq!(8, template4.parameter_number);
+    assert_eq!(-3, template4.offset);
+
+    let mut reader = Cursor::new(section4_bytes(65_001));
+    assert!(Section4::<DummyTemplate4>::from_reader(&mut reader).is_err());
+}
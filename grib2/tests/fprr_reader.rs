@@ -40,13 +40,11 @@ fn write_values(
     let mut number_of_reads = 0_u32;
     for value in value_iter {
         let value = value.unwrap();
-        if value.value.is_some() {
+        if let Some(physical_value) = value.value {
             writeln!(
                 writer,
                 "{:.6},{:.6},{}",
-                value.lon,
-                value.lat,
-                value.value.unwrap()
+                value.lon, value.lat, physical_value
             )
             .unwrap();
         }
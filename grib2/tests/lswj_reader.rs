@@ -26,13 +26,11 @@ fn test_lswj_reader() {
     let mut number_of_read = 0_u32;
     for value in reader.values(LswjHour::Actual).unwrap() {
         let value = value.unwrap();
-        if value.value.is_some() {
+        if let Some(physical_value) = value.value {
             writeln!(
                 writer,
                 "{:.6},{:.6},{}",
-                value.lon,
-                value.lat,
-                value.value.unwrap()
+                value.lon, value.lat, physical_value
             )
             .unwrap();
         }
@@ -0,0 +1,26 @@
+use grib2::reader::{open_auto, Grib2Product};
+
+#[test]
+#[ignore]
+fn test_open_auto() {
+    assert!(matches!(
+        open_auto("../resources/prr.bin").unwrap(),
+        Grib2Product::Prr(_)
+    ));
+    assert!(matches!(
+        open_auto("../resources/fprr.bin").unwrap(),
+        Grib2Product::Fprr(_)
+    ));
+    assert!(matches!(
+        open_auto("../resources/psw.bin").unwrap(),
+        Grib2Product::Psw(_)
+    ));
+    assert!(matches!(
+        open_auto("../resources/fpsw6.bin").unwrap(),
+        Grib2Product::FPsw(_)
+    ));
+    assert!(matches!(
+        open_auto("../resources/lswj.bin").unwrap(),
+        Grib2Product::Lswj(_)
+    ));
+}
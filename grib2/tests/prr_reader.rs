@@ -1,7 +1,11 @@
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
-use std::io::{stdout, BufRead, BufReader, BufWriter, Write};
+use std::io::{stdout, BufRead, BufReader, BufWriter, Cursor, Write};
 
-use grib2::reader::PrrReader;
+use grib2::reader::{
+    audit_directory, Aggregation, ColorMap, Grib2Sections, MeshLevel, PrecipitationUnit, PrrReader,
+    Section5Variant, Template4_50008,
+};
 
 #[test]
 #[ignore]
@@ -26,13 +30,11 @@ fn test_prr_reader() {
     let mut number_of_points = 0_u64;
     for value in reader.values().unwrap() {
         let value = value.unwrap();
-        if value.value.is_some() {
+        if let Some(physical_value) = value.value {
             writeln!(
                 writer,
                 "{:.6},{:.6},{}",
-                value.lon,
-                value.lat,
-                value.value.unwrap()
+                value.lon, value.lat, physical_value
             )
             .unwrap();
         }
@@ -80,3 +82,420 @@ fn test_prr_reader() {
     // 出力したファイルと、予期したファイルの内容が完全に一致
     println!("the output file is completely same as the expected file");
 }
+
+#[test]
+#[ignore]
+fn test_prr_reader_to_tensor() {
+    let inputs = ["../resources/prr.bin", "../resources/prr.bin"];
+    let (tensor, shape) = PrrReader::to_tensor(&inputs, f32::NAN).unwrap();
+    assert_eq!((2, 3360, 2560), shape);
+    assert_eq!(shape.0 * shape.1 * shape.2, tensor.len());
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_to_accumulated_mm() {
+    // 解析雨量は1時間の統計処理であるため、降水量は尺度因子を適用した解析雨量と一致
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    assert_eq!(Some(1.0), reader.section4().stat_proc_hours());
+    assert_eq!(Some("1時間降水量"), reader.parameter_name());
+    let run_length = reader.run_length_bytes_raw().unwrap();
+    assert_eq!(reader.section7().run_length_bytes(), run_length.len());
+    let missing_ratio = reader.missing_ratio().unwrap();
+    assert!((0.0..1.0).contains(&missing_ratio));
+    // 最後の格子の経度は、第3節の最後の格子点の経度と一致
+    // j方向の増分は1/120度を10e-6度単位に丸めた値であるため、最後の格子の緯度は第3節の最後の
+    // 格子点の緯度と増分の半分未満の範囲で一致
+    let section3 = *reader.section3();
+    let last = reader.values_raw().unwrap().last().unwrap().unwrap();
+    assert_eq!(section3.lon_of_last_grid_point(), last.lon_u32());
+    assert!(
+        (section3.lat_of_last_grid_point() as i32).abs_diff(last.lat_i32()) * 2
+            < section3.j_direction_increment()
+    );
+    // 度単位の座標も、第3節の最後の格子点の経度を度単位に変換した値と完全に一致
+    let last = reader.values().unwrap().last().unwrap().unwrap();
+    assert_eq!(
+        section3.lon_of_last_grid_point() as f64 / 1_000_000.0,
+        last.lon
+    );
+    let histogram = reader.level_histogram().unwrap();
+    assert_eq!(
+        reader.section3().number_of_data_points(),
+        histogram.iter().sum::<u32>()
+    );
+    let intensities = reader.to_flat_f32(-1.0).unwrap();
+    let accumulated = reader.to_accumulated_mm().unwrap();
+    assert_eq!(PrecipitationUnit::Millimeters, accumulated.unit());
+    for (value, intensity) in accumulated.zip(intensities) {
+        match value.unwrap().value {
+            Some(value) => assert_eq!(intensity, value as f32),
+            None => assert_eq!(-1.0, intensity),
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_messages() {
+    let input = "../resources/prr.bin";
+    assert!(PrrReader::validate(input).unwrap().is_valid());
+    let mut reader = PrrReader::new(input).unwrap();
+    let total_length = reader.section0().total_length() as u64;
+    let messages: Vec<_> = reader.messages().unwrap().map(|m| m.unwrap()).collect();
+    assert_eq!(1, messages.len());
+    assert_eq!(0, messages[0].offset());
+    assert_eq!(total_length, messages[0].total_length());
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_from_reader() {
+    // ファイルの内容をメモリに読み込み、ファイルパスを使用せずに読み込む
+    let input = "../resources/prr.bin";
+    let bytes = std::fs::read(input).unwrap();
+    let mut reader = PrrReader::from_reader(Cursor::new(bytes)).unwrap();
+    let mut expected = PrrReader::new(input).unwrap();
+    assert_eq!(1, reader.messages().unwrap().count());
+
+    // GRIB報を走査した後でも、ランレングス圧縮符号列の開始位置にシークして展開できる
+    let mut number_of_points = 0_u32;
+    for (value, expected) in reader.values().unwrap().zip(expected.values().unwrap()) {
+        let (value, expected) = (value.unwrap(), expected.unwrap());
+        assert_eq!(
+            (expected.lat, expected.lon, expected.value),
+            (value.lat, value.lon, value.value)
+        );
+        number_of_points += 1;
+    }
+    assert_eq!(reader.section3().number_of_data_points(), number_of_points);
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_render_into() {
+    // 格子をベクタに格納せずに画素バッファへ描画
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    let width = reader.section3().number_of_along_lat_points();
+    let height = reader.section3().number_of_along_lon_points();
+    let colormap = ColorMap::new(vec![(1, [0, 0, 255, 255])]).unwrap();
+    let mut buf = vec![0u8; width as usize * height as usize * 4];
+    reader
+        .values()
+        .unwrap()
+        .render_into(&mut buf, width, &colormap)
+        .unwrap();
+
+    // 不透明な画素の数は、欠測値でない格子の数と一致
+    let opaque = buf.chunks_exact(4).filter(|p| p[3] != 0).count();
+    let expected = reader
+        .values()
+        .unwrap()
+        .filter(|v| v.as_ref().unwrap().value.is_some())
+        .count();
+    assert_eq!(expected, opaque);
+}
+
+#[cfg(feature = "memmap2")]
+#[test]
+#[ignore]
+fn test_prr_reader_from_mmap() {
+    // メモリマップして読み込んだ結果が、ファイルを読み込んだ結果と一致することを確認
+    let input = "../resources/prr.bin";
+    // SAFETY: テスト中にリソースのファイルを変更しない
+    let mut reader = unsafe { PrrReader::from_mmap(input) }.unwrap();
+    let mut expected = PrrReader::new(input).unwrap();
+    assert_eq!(
+        expected.section7().run_length_bytes(),
+        reader.run_length_bytes().len()
+    );
+    let mut number_of_points = 0_u32;
+    for (value, expected) in reader.values().unwrap().zip(expected.values().unwrap()) {
+        let (value, expected) = (value.unwrap(), expected.unwrap());
+        assert_eq!(
+            (expected.lat, expected.lon, expected.value),
+            (value.lat, value.lon, value.value)
+        );
+        number_of_points += 1;
+    }
+    assert_eq!(reader.section3().number_of_data_points(), number_of_points);
+}
+
+#[cfg(feature = "json")]
+#[test]
+#[ignore]
+fn test_prr_reader_write_sections_json() {
+    // JSON形式で出力した節の項目が、テキスト形式で出力した項目と一致することを確認
+    let input = "../resources/prr.bin";
+    let reader = PrrReader::new(input).unwrap();
+    let mut text = vec![];
+    reader.debug_info(&mut text).unwrap();
+    let mut json = vec![];
+    reader.write_sections_json(&mut json).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+
+    fn count_leaves(value: &serde_json::Value) -> usize {
+        match value {
+            serde_json::Value::Object(map) => map.values().map(count_leaves).sum(),
+            _ => 1,
+        }
+    }
+    let sections = json.as_object().unwrap();
+    assert_eq!(9, sections.len());
+    // 節番号と節の名前は、テキスト形式では見出しとして出力
+    let json_fields = sections.values().map(count_leaves).sum::<usize>() - 2 * sections.len();
+    let text = String::from_utf8(text).unwrap();
+    let text_fields = text
+        .lines()
+        .filter(|line| line.starts_with("    ") && !line.starts_with("        "))
+        .count();
+    assert_eq!(text_fields, json_fields);
+    assert!(json["section0"]["total_length"].is_string());
+    assert!(json["section3"]["template3"]["number_of_along_lat_points"].is_u64());
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_aggregate_by_mesh() {
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    // 1kmメッシュ解析雨量の格子は第3次地域区画と一致するため、格子の値がそのまま集約値となる
+    let means = reader
+        .aggregate_by_mesh(MeshLevel::Third, Aggregation::Mean)
+        .unwrap();
+    assert_eq!(Some(&4.0), means.get(&52350349));
+    // 第2次地域区画には100個の第3次地域区画が含まれる
+    let maxes = reader
+        .aggregate_by_mesh(MeshLevel::Second, Aggregation::Max)
+        .unwrap();
+    assert_eq!(Some(&100.0), maxes.get(&412743));
+    let means = reader
+        .aggregate_by_mesh(MeshLevel::Second, Aggregation::Mean)
+        .unwrap();
+    assert!((means[&412743] - 52.42).abs() < 1e-9);
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_values_raw() {
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    // 整数の座標をキーとしてメッシュコードを蓄積
+    let mut mesh_codes: HashMap<(i32, u32), Option<u64>> = HashMap::new();
+    for value in reader.values_raw().unwrap() {
+        let value = value.unwrap();
+        mesh_codes
+            .entry((value.lat_i32(), value.lon_u32()))
+            .or_insert_with(|| value.mesh_code(MeshLevel::Third));
+    }
+    assert_eq!(
+        reader.section3().number_of_data_points() as usize,
+        mesh_codes.len()
+    );
+    // 度単位の座標に変換したGRIB2値と一致する
+    let values = reader
+        .values()
+        .unwrap()
+        .take(10_000)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let raws = reader.values_raw().unwrap().take(10_000);
+    for (value, raw) in values.iter().zip(raws) {
+        let converted = raw.unwrap().as_f64();
+        assert_eq!((value.lat, value.lon), (converted.lat, converted.lon));
+        assert_eq!(value.value, converted.value);
+    }
+}
+
+#[test]
+#[ignore]
+fn test_audit_directory() {
+    // 正常なファイルと壊れたファイルが混在するディレクトリを監査
+    let dir =
+        std::env::temp_dir().join(format!("grib2_{}_test_audit_directory", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy("../resources/prr.bin", dir.join("prr.bin")).unwrap();
+    let bytes = std::fs::read("../resources/prr.bin").unwrap();
+    std::fs::write(dir.join("truncated.bin"), &bytes[..bytes.len() / 2]).unwrap();
+
+    let results = audit_directory(&dir);
+    assert_eq!(2, results.len());
+    assert!(results[0].is_valid());
+    assert_eq!(1, results[0].number_of_messages());
+    assert_eq!(
+        3_360,
+        results[0].section3().unwrap().number_of_along_lon_points()
+    );
+    assert!(!results[1].is_valid());
+    assert_eq!(0, results[1].number_of_messages());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_rfc3339() {
+    // 解析雨量の予報時間は-60分であるため、予報の対象時刻は参照時刻の1時間前
+    let input = "../resources/prr.bin";
+    let reader = PrrReader::new(input).unwrap();
+    assert_eq!("2021-08-17T09:00:00Z", reader.referenced_at_rfc3339());
+    assert_eq!(
+        "2021-08-17T08:00:00Z",
+        reader.forecast_valid_time_rfc3339().unwrap()
+    );
+    assert_eq!(
+        time::Duration::minutes(-60),
+        reader.forecast_duration().unwrap()
+    );
+    assert_eq!(
+        reader.referenced_at_jst() - time::Duration::hours(1),
+        reader.forecast_valid_at().unwrap()
+    );
+    // 日本標準時では参照時刻は18時
+    let referenced_at_jst = reader.referenced_at_jst();
+    assert_eq!(9, referenced_at_jst.offset().whole_hours());
+    assert_eq!(18, referenced_at_jst.hour());
+}
+
+#[test]
+#[ignore]
+fn test_grib2_sections() {
+    let input = "../resources/prr.bin";
+    let mut reader = BufReader::new(File::open(input).unwrap());
+    let sections = Grib2Sections::from_reader(&mut reader).unwrap();
+    assert_eq!(3_360, sections.section3().number_of_along_lon_points());
+    let section4 = match sections.section4().clone().downcast::<Template4_50008>() {
+        Some(section4) => section4,
+        None => panic!("テンプレート4.50008を想定しています。"),
+    };
+    assert_eq!(Some(1.0), section4.stat_proc_hours());
+    let Section5Variant::Template200(section5) = sections.section5();
+    assert_eq!(
+        sections.section3().number_of_data_points(),
+        section5.number_of_values()
+    );
+}
+
+#[test]
+#[ignore]
+fn test_prr_message_write_to() {
+    let input = "../resources/prr.bin";
+    let (message, errors) = PrrReader::new_partial(input);
+    assert!(errors.is_empty());
+    let mut written = vec![];
+    message.unwrap().write_to(&mut written).unwrap();
+    assert_eq!(std::fs::read(input).unwrap(), written);
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_subset() {
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    let (min_lat, min_lon, max_lat, max_lon) = (35.0, 139.0, 36.0, 140.5);
+    let expected = reader
+        .within(min_lat, min_lon, max_lat, max_lon)
+        .unwrap()
+        .map(|v| v.unwrap())
+        .map(|v| (v.lat, v.lon, v.value))
+        .collect::<Vec<_>>();
+    let message = reader.subset(min_lat, min_lon, max_lat, max_lon).unwrap();
+    let mut written = vec![];
+    message.write_to(&mut written).unwrap();
+
+    let mut subset = PrrReader::from_reader(Cursor::new(written)).unwrap();
+    assert_eq!(
+        expected.len() as u32,
+        subset.section3().number_of_data_points()
+    );
+    let values = subset
+        .values()
+        .unwrap()
+        .map(|v| v.unwrap())
+        .map(|v| (v.lat, v.lon, v.value))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, values);
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_with_level_values_checked() {
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    let original = reader.level_values().to_vec();
+
+    // 単位が一致しない、または尺度因子が異なるレベル別物理値は差し替えない
+    assert!(reader
+        .with_level_values_checked(original.clone(), "mm")
+        .is_err());
+    let scaled: Vec<u16> = original.iter().map(|v| v / 10).collect();
+    assert!(reader.with_level_values_checked(scaled, "mm/h").is_err());
+    assert_eq!(original, reader.level_values());
+
+    // 整合するレベル別物理値は差し替える
+    let mut replaced = original.clone();
+    replaced[0] += 1;
+    reader
+        .with_level_values_checked(replaced.clone(), "mm/h")
+        .unwrap();
+    assert_eq!(replaced, reader.level_values());
+    let level = reader
+        .values()
+        .unwrap()
+        .map(|v| v.unwrap())
+        .find(|v| v.level == 1)
+        .unwrap();
+    assert_eq!(Some(replaced[0]), level.value);
+}
+
+#[cfg(feature = "tiff")]
+#[test]
+#[ignore]
+fn test_prr_reader_write_cog() {
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    let output = "../resources/prr.tif";
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(output)
+        .unwrap();
+    reader.write_cog(&mut file).unwrap();
+    drop(file);
+
+    // リトルエンディアンのTIFFで、最初のIFDがヘッダの直後に配置されていることを確認
+    let bytes = std::fs::read(output).unwrap();
+    assert_eq!(&[b'I', b'I', 42, 0, 8, 0, 0, 0], &bytes[..8]);
+}
+
+#[test]
+#[ignore]
+fn test_prr_reader_cache_run_length() {
+    // 格子数、レベル値及び物理値を集計する（走査順の違いも検出できるように、レベル値は格子の
+    // 位置で重み付けする）
+    fn summarize(reader: &mut PrrReader) -> (u64, u64, u64) {
+        reader
+            .values()
+            .unwrap()
+            .fold((0, 0, 0), |(count, levels, values), value| {
+                let value = value.unwrap();
+                (
+                    count + 1,
+                    levels + value.level as u64 * (count % 7 + 1),
+                    values + value.value.unwrap_or(0) as u64,
+                )
+            })
+    }
+
+    let input = "../resources/prr.bin";
+    let mut reader = PrrReader::new(input).unwrap();
+    let expected = summarize(&mut reader);
+    assert_eq!(reader.section3().number_of_data_points() as u64, expected.0);
+
+    // キャッシュしてから2回走査した結果が、キャッシュせずに走査した結果と一致する
+    reader.cache_run_length().unwrap();
+    assert_eq!(expected, summarize(&mut reader));
+    assert_eq!(expected, summarize(&mut reader));
+}
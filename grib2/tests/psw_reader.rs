@@ -1,7 +1,7 @@
 use std::fs::{File, OpenOptions};
 use std::io::{stdout, BufWriter, Write};
 
-use grib2::reader::{Grib2ValueIter, PswReader};
+use grib2::reader::{Grib2ValueIter, PswReader, PswTank};
 
 #[test]
 #[ignore]
@@ -15,6 +15,20 @@ fn test_psw_reader() {
     writer.flush().unwrap();
     let number_of_points = reader.section3().number_of_data_points();
 
+    // 第一固定面からタンクを判定
+    assert_eq!(
+        Some(PswTank::All),
+        reader.all_tanks_sections().section4().tank()
+    );
+    assert_eq!(
+        Some(PswTank::First),
+        reader.first_tank_sections().section4().tank()
+    );
+    assert_eq!(
+        Some(PswTank::Second),
+        reader.second_tank_sections().section4().tank()
+    );
+
     // 土壌雨量指数を出力
     let output = "../resources/psw_0.csv";
     let file = OpenOptions::new()
@@ -72,13 +86,11 @@ fn write_values(
     let mut number_of_reads = 0_u32;
     for value in value_iter {
         let value = value.unwrap();
-        if value.value.is_some() {
+        if let Some(physical_value) = value.value {
             writeln!(
                 writer,
                 "{:.6},{:.6},{}",
-                value.lon,
-                value.lat,
-                value.value.unwrap()
+                value.lon, value.lat, physical_value
             )
             .unwrap();
         }
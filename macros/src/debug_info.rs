@@ -1,10 +1,14 @@
 use proc_macro2::{Ident, Literal, TokenStream as TokenStream2};
 use quote::quote;
 use syn::{
-    punctuated::Punctuated, Attribute, DeriveInput, Expr, Field, ImplGenerics, Lit, TypeGenerics,
-    WhereClause,
+    parse_quote, punctuated::Punctuated, Attribute, DeriveInput, Expr, Field, ImplGenerics, Lit,
+    TypeGenerics, WhereClause,
 };
 
+#[cfg(feature = "json")]
+use crate::debug_info_json::{
+    derive_section_debug_info_json_impl, derive_template_debug_info_json_impl,
+};
 use crate::utils::{
     expr_to_string, expr_to_u8, is_unit_struct, retrieve_fields_by_names, retrieve_struct_fields,
     retrieve_value_from_name_value, CommaPunctuatedNameValues,
@@ -24,6 +28,13 @@ pub(crate) fn derive_section_debug_info_impl(input: DeriveInput) -> syn::Result<
     let section_number = Literal::u8_unsuffixed(section_attr_values.number);
     let section_name = Literal::string(&section_attr_values.name);
 
+    // jsonフィーチャーが有効な場合は、JSON形式のデバッグ情報を出力するメソッドも導出
+    #[cfg(feature = "json")]
+    let json_token_stream =
+        derive_section_debug_info_json_impl(&input, &section_number, &section_name)?;
+    #[cfg(not(feature = "json"))]
+    let json_token_stream = TokenStream2::new();
+
     let token_stream = match is_unit_struct(&input.data) {
         true => derive_section_unit_struct_impl(input.ident, section_number, section_name),
         false => derive_section_struct_impl(
@@ -36,7 +47,10 @@ pub(crate) fn derive_section_debug_info_impl(input: DeriveInput) -> syn::Result<
         )?,
     };
 
-    Ok(token_stream)
+    Ok(quote! {
+        #token_stream
+        #json_token_stream
+    })
 }
 
 fn derive_section_unit_struct_impl(
@@ -50,7 +64,14 @@ fn derive_section_unit_struct_impl(
             where
                 W: std::io::Write,
             {
-                writeln!(writer, "第{}節:{}", #section_number, #section_name)?;
+                self.debug_info_with(writer, &DebugOptions::default())
+            }
+
+            pub fn debug_info_with<W>(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
+            where
+                W: std::io::Write,
+            {
+                writeln!(writer, "{}", opts.section_header(#section_number, #section_name))?;
 
                 Ok(())
             }
@@ -93,7 +114,15 @@ fn derive_section_struct_impl(
                     T: DebugTemplate<W>,
                     W: std::io::Write,
                 {
-                    writeln!(writer, "第{}節:{}", #section_number, #section_name)?;
+                    self.debug_info_with(writer, &DebugOptions::default())
+                }
+
+                pub fn debug_info_with<W>(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
+                where
+                    T: DebugTemplate<W>,
+                    W: std::io::Write,
+                {
+                    writeln!(writer, "{}", opts.section_header(#section_number, #section_name))?;
                     #(
                         #debug_infos
                     )*
@@ -109,7 +138,14 @@ fn derive_section_struct_impl(
                 where
                     W: std::io::Write,
                 {
-                    writeln!(writer, "第{}節:{}", #section_number, #section_name)?;
+                    self.debug_info_with(writer, &DebugOptions::default())
+                }
+
+                pub fn debug_info_with<W>(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
+                where
+                    W: std::io::Write,
+                {
+                    writeln!(writer, "{}", opts.section_header(#section_number, #section_name))?;
                     #(
                         #debug_infos
                     )*
@@ -139,18 +175,32 @@ fn derive_debug_info_statement_impl(field: &Field) -> syn::Result<TokenStream2>
         match retrieve_value_from_name_value(&field.attrs, "debug_info", "fmt") {
             Some(fmt) => {
                 quote! {
-                    writeln!(writer, "    {}: {}", #name, format!(#fmt, self.#field_ident))?;
+                    writeln!(
+                        writer,
+                        "{}{}{}{}",
+                        opts.indent(),
+                        #name,
+                        opts.separator(),
+                        format!(#fmt, self.#field_ident)
+                    )?;
                 }
             }
             None => {
                 quote! {
-                    writeln!(writer, "    {}: {}", #name, self.#field_ident)?;
+                    writeln!(
+                        writer,
+                        "{}{}{}{}",
+                        opts.indent(),
+                        #name,
+                        opts.separator(),
+                        self.#field_ident
+                    )?;
                 }
             }
         }
     } else {
         quote! {
-            self.#field_ident.debug_info(writer)?;
+            self.#field_ident.debug_info_with(writer, opts)?;
         }
     };
 
@@ -205,17 +255,46 @@ pub(crate) fn derive_template_debug_info_impl(input: DeriveInput) -> syn::Result
     let ident = &input.ident;
     // 構造体のフィールドを取得
     let fields = retrieve_struct_fields(&input)?;
-    // debug_info属性が付与されたフィールドを取得
-    let fields = retrieve_fields_by_names(&fields, &["debug_info"]);
+    // debug_infoまたはdebug_template属性が付与されたフィールドを取得
+    let fields = retrieve_fields_by_names(&fields, &["debug_info", "debug_template"]);
     // フィールドごとにデバッグ情報を取得する文を生成
     let mut debug_infos = vec![];
     for field in fields.iter() {
         debug_infos.push(derive_template_debug_statement_impl(field)?);
     }
 
+    // 構造体のジェネリックパラメーターの前に出力先のジェネリックパラメーターを追加
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, parse_quote! { W });
+    // 埋め込まれたテンプレートは、バイト列に出力したデバッグ情報を字下げして出力するため、
+    // バイト列に出力できることを境界に追加
+    let nested_types = fields
+        .iter()
+        .filter(|field| is_debug_template_field(field))
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+    if !nested_types.is_empty() {
+        let where_clause = generics.make_where_clause();
+        for ty in nested_types {
+            where_clause
+                .predicates
+                .push(parse_quote! { #ty: DebugTemplate<Vec<u8>> });
+        }
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    // jsonフィーチャーが有効な場合は、JSON形式のデバッグ情報を出力するトレイトも実装
+    #[cfg(feature = "json")]
+    let json_token_stream = derive_template_debug_info_json_impl(&input)?;
+    #[cfg(not(feature = "json"))]
+    let json_token_stream = TokenStream2::new();
+
     Ok(quote! {
-        impl<W> DebugTemplate<W> for #ident {
-            fn debug_info(&self, writer: &mut W) -> std::io::Result<()>
+        #json_token_stream
+
+        impl #impl_generics DebugTemplate<W> for #ident #ty_generics #where_clause {
+            fn debug_info_with(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
             where
                 W: std::io::Write,
             {
@@ -229,7 +308,18 @@ pub(crate) fn derive_template_debug_info_impl(input: DeriveInput) -> syn::Result
     })
 }
 
+fn is_debug_template_field(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("debug_template"))
+}
+
 fn derive_template_debug_statement_impl(field: &Field) -> syn::Result<TokenStream2> {
+    // debug_template属性が付与されたフィールドは、埋め込まれたテンプレートのデバッグ情報を字下げして出力
+    if is_debug_template_field(field) {
+        return Ok(derive_template_debug_info_nested_statement_impl(field));
+    }
     // debug_info属性のdata_type属性を取得
     let data_type = retrieve_value_from_name_value(&field.attrs, "debug_info", "data_type");
     if data_type.is_some() {
@@ -237,6 +327,8 @@ fn derive_template_debug_statement_impl(field: &Field) -> syn::Result<TokenStrea
         let data_type = expr_to_string(data_type).unwrap();
         if data_type == "serial" {
             derive_template_debug_info_serial_statement_impl(field)
+        } else if data_type == "missing" {
+            derive_template_debug_info_missing_statement_impl(field)
         } else {
             derive_template_debug_info_normal_statement_impl(field)
         }
@@ -246,6 +338,25 @@ fn derive_template_debug_statement_impl(field: &Field) -> syn::Result<TokenStrea
     }
 }
 
+fn derive_template_debug_info_nested_statement_impl(field: &Field) -> TokenStream2 {
+    // フィールドの識別子を取得
+    let field_ident = field.ident.as_ref().unwrap();
+    // debug_template属性にname属性が指定されている場合は見出しを出力
+    let header = match retrieve_value_from_name_value(&field.attrs, "debug_template", "name") {
+        Some(name) => quote! { writeln!(writer, "{}{}:", opts.indent(), #name)?; },
+        None => quote! {},
+    };
+    // 埋め込まれたテンプレートが出力した各行を、さらに字下げして出力
+    quote! {
+        #header
+        let mut nested = Vec::<u8>::new();
+        DebugTemplate::<Vec<u8>>::debug_info_with(&self.#field_ident, &mut nested, opts)?;
+        for line in String::from_utf8_lossy(&nested).lines() {
+            writeln!(writer, "{}{}", opts.indent(), line)?;
+        }
+    }
+}
+
 fn derive_template_debug_info_normal_statement_impl(field: &Field) -> syn::Result<TokenStream2> {
     // フィールドの識別子を取得
     let field_ident = field.ident.as_ref().unwrap();
@@ -259,18 +370,63 @@ fn derive_template_debug_info_normal_statement_impl(field: &Field) -> syn::Resul
         Some(fmt) => {
             // fmt属性が存在する場合
             Ok(quote! {
-                writeln!(writer, "    {}: {}", #name, format!(#fmt, self.#field_ident))?;
+                writeln!(
+                    writer,
+                    "{}{}{}{}",
+                    opts.indent(),
+                    #name,
+                    opts.separator(),
+                    format!(#fmt, self.#field_ident)
+                )?;
             })
         }
         None => {
             // fmt属性が存在しない場合
             Ok(quote! {
-                writeln!(writer, "    {}: {}", #name, self.#field_ident)?;
+                writeln!(
+                    writer,
+                    "{}{}{}{}",
+                    opts.indent(),
+                    #name,
+                    opts.separator(),
+                    self.#field_ident
+                )?;
             })
         }
     }
 }
 
+/// 全ビットが1の値を欠測として`None`と出力する文を生成する。
+fn derive_template_debug_info_missing_statement_impl(field: &Field) -> syn::Result<TokenStream2> {
+    // フィールドの識別子と型を取得
+    let field_ident = field.ident.as_ref().unwrap();
+    let ty = &field.ty;
+    // debug_info属性のname属性を取得
+    let name =
+        retrieve_value_from_name_value(&field.attrs, "debug_info", "name").ok_or_else(|| {
+            syn::Error::new_spanned(field, "name attribute not found in debug_info attribute")
+        })?;
+    // debug_info属性のfmt属性を取得
+    let value = match retrieve_value_from_name_value(&field.attrs, "debug_info", "fmt") {
+        Some(fmt) => quote! { format!(#fmt, self.#field_ident) },
+        None => quote! { self.#field_ident.to_string() },
+    };
+
+    Ok(quote! {
+        writeln!(
+            writer,
+            "{}{}{}{}",
+            opts.indent(),
+            #name,
+            opts.separator(),
+            match self.#field_ident == <#ty>::MAX {
+                true => String::from("None"),
+                false => #value,
+            }
+        )?;
+    })
+}
+
 fn derive_template_debug_info_serial_statement_impl(field: &Field) -> syn::Result<TokenStream2> {
     // フィールドの識別子を取得
     let field_ident = field.ident.as_ref().unwrap();
@@ -308,18 +464,34 @@ fn derive_template_debug_info_serial_statement_impl(field: &Field) -> syn::Resul
         Some(fmt) => {
             // fmt属性が存在する場合
             Ok(quote! {
-                writeln!(writer, "    {}:", #name)?;
+                writeln!(writer, "{}{}:", opts.indent(), #name)?;
                 for (i, value) in self.#field_ident.iter().enumerate() {
-                    writeln!(writer, "        {}: {}", format!(#header, i as i32 + #start), format!(#fmt, value))?;
+                    writeln!(
+                        writer,
+                        "{}{}{}{}{}",
+                        opts.indent(),
+                        opts.indent(),
+                        format!(#header, i as i32 + #start),
+                        opts.separator(),
+                        format!(#fmt, value)
+                    )?;
                 }
             })
         }
         None => {
             // fmt属性が存在しない場合
             Ok(quote! {
-                writeln!(writer, "    {}:", #name)?;
+                writeln!(writer, "{}{}:", opts.indent(), #name)?;
                 for (i, value) in self.#field_ident.iter().enumerate() {
-                    writeln!(writer, "        {}: {}", format!(#header, i as i32 + #start),  value)?;
+                    writeln!(
+                        writer,
+                        "{}{}{}{}{}",
+                        opts.indent(),
+                        opts.indent(),
+                        format!(#header, i as i32 + #start),
+                        opts.separator(),
+                        value
+                    )?;
                 }
             })
         }
@@ -0,0 +1,177 @@
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_quote, DeriveInput, Field};
+
+use crate::utils::{
+    expr_to_string, is_unit_struct, retrieve_fields_by_names, retrieve_struct_fields,
+    retrieve_value_from_name_value,
+};
+
+pub(crate) fn derive_section_debug_info_json_impl(
+    input: &DeriveInput,
+    section_number: &Literal,
+    section_name: &Literal,
+) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // 節番号と節の名前を格納する文
+    let header = quote! {
+        let mut map = serde_json::Map::new();
+        map.insert("number".to_string(), serde_json::Value::from(#section_number));
+        map.insert("name".to_string(), serde_json::Value::from(#section_name));
+    };
+    if is_unit_struct(&input.data) {
+        return Ok(quote! {
+            impl #ident {
+                pub fn debug_info_json(&self) -> serde_json::Value {
+                    #header
+
+                    serde_json::Value::Object(map)
+                }
+            }
+        });
+    }
+
+    // debug_infoまたはdebug_template属性が付与されたフィールドを取得
+    let fields = retrieve_struct_fields(input)?;
+    let fields = retrieve_fields_by_names(&fields, &["debug_info", "debug_template"]);
+    let mut exists_debug_template = false;
+    let mut inserts = vec![];
+    for field in fields.iter() {
+        let field_ident = field.ident.as_ref().unwrap();
+        let key = Literal::string(&field_ident.to_string());
+        let value = if is_debug_template(field) {
+            exists_debug_template = true;
+            quote! { self.#field_ident.debug_info_json() }
+        } else {
+            derive_json_value_expr(field)
+        };
+        inserts.push(quote! {
+            map.insert(#key.to_string(), #value);
+        });
+    }
+    // テンプレートを持つ節は、テンプレートがJSONを出力できる場合に実装
+    let template_bound = match exists_debug_template {
+        true => quote! { where T: DebugTemplateJson },
+        false => quote! {},
+    };
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn debug_info_json(&self) -> serde_json::Value
+            #template_bound
+            {
+                #header
+                #(
+                    #inserts
+                )*
+
+                serde_json::Value::Object(map)
+            }
+        }
+    })
+}
+
+pub(crate) fn derive_template_debug_info_json_impl(
+    input: &DeriveInput,
+) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    // debug_infoまたはdebug_template属性が付与されたフィールドを取得
+    let fields = retrieve_struct_fields(input)?;
+    let fields = retrieve_fields_by_names(&fields, &["debug_info", "debug_template"]);
+    // ジェネリックな構造体は、フィールドの型がJSONの値に変換できる場合に実装
+    // 埋め込まれたテンプレートは、JSONの値を出力できる場合に実装
+    let mut generics = input.generics.clone();
+    for field in fields.iter() {
+        let ty = &field.ty;
+        if is_debug_template(field) {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { #ty: DebugTemplateJson });
+        } else if !input.generics.params.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { #ty: ToJsonValue });
+        }
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut inserts = vec![];
+    for field in fields.iter() {
+        let field_ident = field.ident.as_ref().unwrap();
+        let key = Literal::string(&field_ident.to_string());
+        let value = if is_debug_template(field) {
+            quote! { self.#field_ident.debug_info_json() }
+        } else {
+            derive_json_value_expr(field)
+        };
+        inserts.push(quote! {
+            map.insert(#key.to_string(), #value);
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics DebugTemplateJson for #ident #ty_generics #where_clause {
+            fn debug_info_json(&self) -> serde_json::Value {
+                let mut map = serde_json::Map::new();
+                #(
+                    #inserts
+                )*
+
+                serde_json::Value::Object(map)
+            }
+        }
+    })
+}
+
+fn is_debug_template(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("debug_template"))
+}
+
+/// フィールドの値をJSONの値に変換する式を生成する。
+///
+/// `fmt`属性に`{}`以外の書式（`0x{:04X}`など）が指定されている場合は、書式化した文字列とし、
+/// それ以外は`ToJsonValue`で数値などに変換する。`data_type = "serial"`のフィールドは配列とし、
+/// `data_type = "missing"`のフィールドは、全ビットが1の場合に`null`とする。
+fn derive_json_value_expr(field: &Field) -> TokenStream2 {
+    let field_ident = field.ident.as_ref().unwrap();
+    let is_missing = retrieve_value_from_name_value(&field.attrs, "debug_info", "data_type")
+        .is_some_and(|data_type| expr_to_string(Some(data_type)).as_deref() == Some("missing"));
+    if is_missing {
+        let ty = &field.ty;
+        return quote! {
+            match self.#field_ident == <#ty>::MAX {
+                true => serde_json::Value::Null,
+                false => ToJsonValue::to_json_value(&self.#field_ident),
+            }
+        };
+    }
+    let fmt = retrieve_value_from_name_value(&field.attrs, "debug_info", "fmt");
+    let is_formatted = fmt
+        .clone()
+        .is_some_and(|fmt| expr_to_string(Some(fmt)).as_deref() != Some("{}"));
+    let is_serial = retrieve_value_from_name_value(&field.attrs, "debug_info", "data_type")
+        .is_some_and(|data_type| expr_to_string(Some(data_type)).as_deref() == Some("serial"));
+
+    match (is_formatted, is_serial) {
+        (true, true) => quote! {
+            serde_json::Value::Array(
+                self.#field_ident
+                    .iter()
+                    .map(|value| serde_json::Value::from(format!(#fmt, value)))
+                    .collect(),
+            )
+        },
+        (true, false) => quote! {
+            serde_json::Value::from(format!(#fmt, self.#field_ident))
+        },
+        (false, _) => quote! {
+            ToJsonValue::to_json_value(&self.#field_ident)
+        },
+    }
+}
@@ -2,7 +2,9 @@ use std::str::FromStr;
 
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{punctuated::Punctuated, DeriveInput, Expr, Visibility};
+use syn::{
+    punctuated::Punctuated, DeriveInput, Expr, GenericArgument, PathArguments, Type, Visibility,
+};
 
 use crate::utils::{
     expr_to_string, retrieve_field_attrs_by_names, retrieve_struct_fields,
@@ -16,7 +18,7 @@ pub(crate) fn derive_getter_impl(input: DeriveInput) -> syn::Result<TokenStream2
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // 構造体のフィールドのゲッターメソッドを導出
-    let getter_methods = derive_getter_methods(&input)?;
+    let getter_methods = derive_getter_methods(&input, &quote! { self })?;
 
     // ゲッターメソッドを構造体に実装する構文木を生成
     Ok(quote! {
@@ -26,7 +28,14 @@ pub(crate) fn derive_getter_impl(input: DeriveInput) -> syn::Result<TokenStream2
     })
 }
 
-fn derive_getter_methods(input: &DeriveInput) -> syn::Result<TokenStream2> {
+/// getter属性が付与されたフィールドのゲッターメソッドを導出する。
+///
+/// `receiver`は、フィールドを保持する式で、構造体自身の場合は`self`、節が保持するテンプレートの
+/// 場合は`self.template5`などを指定する。
+pub(crate) fn derive_getter_methods(
+    input: &DeriveInput,
+    receiver: &TokenStream2,
+) -> syn::Result<TokenStream2> {
     // 構造体の可視性を取得
     let vis = &input.vis;
     // 構造体のフィールドを取得
@@ -36,7 +45,7 @@ fn derive_getter_methods(input: &DeriveInput) -> syn::Result<TokenStream2> {
     // 構造体のフィールドのゲッターメソッドの構文木を生成
     let mut getter_methods: Vec<TokenStream2> = vec![];
     for field_attr in field_attrs.iter() {
-        getter_methods.push(derive_getter_method(vis, field_attr)?);
+        getter_methods.push(derive_getter_method(vis, field_attr, receiver)?);
     }
 
     // 構造体の各フィールドのゲッターメソッドの構文木を結合
@@ -47,7 +56,11 @@ fn derive_getter_methods(input: &DeriveInput) -> syn::Result<TokenStream2> {
     })
 }
 
-fn derive_getter_method(vis: &Visibility, field_attr: &FieldAttrPair) -> syn::Result<TokenStream2> {
+fn derive_getter_method(
+    vis: &Visibility,
+    field_attr: &FieldAttrPair,
+    receiver: &TokenStream2,
+) -> syn::Result<TokenStream2> {
     // フィールドの識別子を取得
     let field_ty = &field_attr.field.ty;
     let field_ident = field_attr.field.ident.as_ref().unwrap();
@@ -57,20 +70,41 @@ fn derive_getter_method(vis: &Visibility, field_attr: &FieldAttrPair) -> syn::Re
     let token_stream = if values.ret == "val" {
         quote! {
             #vis fn #field_ident(&self) -> #field_ty {
-                self.#field_ident
+                #receiver.#field_ident
+            }
+        }
+    } else if values.ret == "unwrap" {
+        // rty属性が指定されていない場合は、Option<T>のTを返す
+        let rty = match values.rty {
+            Some(rty) => TokenStream2::from_str(&rty).unwrap(),
+            None => {
+                let inner_ty = option_inner_type(field_ty).ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        field_attr.field,
+                        "getter attribute `ret` argument `unwrap` requires `Option<T>` field or `rty` argument",
+                    )
+                })?;
+                quote! { #inner_ty }
+            }
+        };
+        // アンラップに失敗した場合のpanicメッセージにフィールド名を含める
+        let message = format!("field `{}` has not been set", field_ident);
+        quote! {
+            #vis fn #field_ident(&self) -> #rty {
+                #receiver.#field_ident.expect(#message)
             }
         }
     } else if values.ret == "ref" && values.rty.is_none() {
         quote! {
             #vis fn #field_ident(&self) -> &#field_ty {
-                &self.#field_ident
+                &#receiver.#field_ident
             }
         }
     } else {
         let rty = TokenStream2::from_str(&values.rty.unwrap()).unwrap();
         quote! {
             #vis fn #field_ident(&self) -> #rty {
-                &self.#field_ident
+                &#receiver.#field_ident
             }
         }
     };
@@ -78,6 +112,24 @@ fn derive_getter_method(vis: &Visibility, field_attr: &FieldAttrPair) -> syn::Re
     Ok(token_stream)
 }
 
+// Option<T>型のTを取得
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
 struct GetterAttrValues {
     /// getter属性のret属性の値
     pub ret: String,
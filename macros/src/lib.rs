@@ -2,11 +2,15 @@ use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
 mod debug_info;
+#[cfg(feature = "json")]
+mod debug_info_json;
 mod getter;
+mod template_getter;
 mod utils;
 
 use debug_info::{derive_section_debug_info_impl, derive_template_debug_info_impl};
 use getter::derive_getter_impl;
+use template_getter::derive_template_getter_impl;
 
 /// ゲッター導出マクロ
 ///
@@ -17,8 +21,10 @@ use getter::derive_getter_impl;
 ///     a: i32,
 ///     #[getter(ret="ref")]
 ///     b: PathBuf,
-///     #[getter(ret="ref", ty="&str")]
+///     #[getter(ret="ref", rty="&str")]
 ///     c: String,
+///     #[getter(ret="unwrap")]
+///     d: Option<u8>,
 /// }
 /// ```
 ///
@@ -35,8 +41,14 @@ use getter::derive_getter_impl;
 ///     pub fn c(&self) -> &str {
 ///        &self.c
 ///     }
+///     pub fn d(&self) -> u8 {
+///        self.d.expect("field `d` has not been set")
+///     }
 /// }
 /// ```
+///
+/// `ret="unwrap"`は`Option<T>`型のフィールドをアンラップした値を返し、`rty`で戻り値の型を指定
+/// できる。フィールドが`None`の場合は、フィールド名を含むメッセージでpanicする。
 #[proc_macro_derive(Getter, attributes(getter))]
 pub fn derive_getter(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -47,6 +59,47 @@ pub fn derive_getter(input: TokenStream) -> TokenStream {
     }
 }
 
+/// テンプレートゲッター導出マクロ
+///
+/// テンプレートの`getter`属性が付与されたフィールドのゲッターメソッドを、テンプレートを保持する
+/// 節に導出する。`getter`属性の指定方法は`Getter`マクロと同じである。
+///
+/// ```text
+/// #[derive(TemplateGetter)]
+/// #[template_getter(section = "Section5", member = "template5", generics = "V: Copy")]
+/// pub struct Template5_200<V> {
+///     #[getter(ret = "val")]
+///     decimal_scale_factor: u8,
+///     #[getter(ret = "ref", rty = "&[V]")]
+///     level_values: Vec<V>,
+/// }
+/// ```
+///
+/// 上記構造体から次を導出する。
+///
+/// ```text
+/// impl<V: Copy> Section5<Template5_200<V>> {
+///     pub fn decimal_scale_factor(&self) -> u8 {
+///         self.template5.decimal_scale_factor
+///     }
+///     pub fn level_values(&self) -> &[V] {
+///         &self.template5.level_values
+///     }
+/// }
+/// ```
+///
+/// `generics`には、節に実装するときのジェネリックパラメーターと境界を指定する。`generics`を
+/// 指定しない場合は、テンプレートのジェネリックパラメーターと境界で実装する。
+#[proc_macro_derive(TemplateGetter, attributes(template_getter, getter))]
+pub fn derive_template_getter(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match derive_template_getter_impl(input) {
+        Ok(token_stream) => TokenStream::from(token_stream),
+        Err(err) => TokenStream::from(err.into_compile_error()),
+    }
+}
+
 /// 節デバッグ情報出力導出マクロ
 ///
 /// ```text
@@ -100,6 +153,13 @@ pub fn derive_getter(input: TokenStream) -> TokenStream {
 ///    Ok(())
 /// }
 /// ```
+///
+/// 実際には、`debug_info`はデフォルトの`DebugOptions`で`debug_info_with`を呼び出し、`debug_info_with`が
+/// `DebugOptions`の字下げ、節の見出しの書式及び項目名と値の区切りに従って出力する。
+///
+/// フィーチャー`json`が有効な場合は、節のデバッグ情報を`serde_json::Value`で返す`debug_info_json`
+/// メソッドも導出する。`fmt`属性で書式を指定したフィールドは文字列、それ以外のフィールドは
+/// `ToJsonValue`トレイトで変換した値になる。
 #[proc_macro_derive(SectionDebugInfo, attributes(section, debug_info, debug_template))]
 pub fn derive_section_debug_info(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -135,7 +195,21 @@ pub fn derive_section_debug_info(input: TokenStream) -> TokenStream {
 ///     }
 /// }
 /// ```
-#[proc_macro_derive(TemplateDebugInfo, attributes(debug_info))]
+///
+/// 実際には、`DebugTemplate`トレイトの`debug_info_with`メソッドを実装し、字下げ及び項目名と値の区切りに
+/// 引数`opts`の`DebugOptions`を使用する。`debug_info`メソッドは、デフォルトの`DebugOptions`で
+/// `debug_info_with`を呼び出す。
+///
+/// `#[debug_info(name = "...", data_type = "missing")]`のように`data_type`に`missing`を指定した
+/// フィールドは、全ビットが1の値を欠測として`None`と出力する。
+///
+/// `#[debug_template]`属性を付与したフィールドは、テンプレートに埋め込まれたテンプレートとして
+/// `DebugTemplate`トレイトでデバッグ情報を出力し、各行をさらに4文字字下げする。
+/// `#[debug_template(name = "...")]`のように`name`を指定した場合は、見出しを出力してから
+/// 埋め込まれたテンプレートを出力する。
+///
+/// フィーチャー`json`が有効な場合は、`DebugTemplateJson`トレイトも実装する。
+#[proc_macro_derive(TemplateDebugInfo, attributes(debug_info, debug_template))]
 pub fn derive_template_debug_info(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{punctuated::Punctuated, Attribute, DeriveInput, Expr, Generics};
+
+use crate::getter::derive_getter_methods;
+use crate::utils::{expr_to_string, CommaPunctuatedNameValues};
+
+pub(crate) fn derive_template_getter_impl(input: DeriveInput) -> syn::Result<TokenStream2> {
+    // 構造体の識別子を取得
+    let ident = &input.ident;
+    // 構造体に付与されたtemplate_getter属性を取得
+    let attr = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("template_getter"))
+        .ok_or_else(|| syn::Error::new_spanned(&input, "template_getter attribute not found"))?;
+    let values = retrieve_template_getter_attr_values(attr)?;
+    let section = TokenStream2::from_str(&values.section)
+        .map_err(|_| syn::Error::new_spanned(attr, "failed to parse `section` argument"))?;
+    let member = TokenStream2::from_str(&values.member)
+        .map_err(|_| syn::Error::new_spanned(attr, "failed to parse `member` argument"))?;
+
+    // 構造体のジェネリックスを取得
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    // generics属性が指定された場合は、指定されたジェネリックパラメーターと境界で実装する
+    let generics = match values.generics {
+        Some(generics) => {
+            syn::parse_str::<Generics>(&format!("<{}>", generics)).map_err(|err| {
+                syn::Error::new_spanned(
+                    attr,
+                    format!("failed to parse `generics` argument: {}", err),
+                )
+            })?
+        }
+        None => input.generics.clone(),
+    };
+    let (impl_generics, _, _) = generics.split_for_impl();
+
+    // テンプレートのフィールドのゲッターメソッドを導出
+    let getter_methods = derive_getter_methods(&input, &quote! { self.#member })?;
+
+    // ゲッターメソッドを節に実装する構文木を生成
+    Ok(quote! {
+        impl #impl_generics #section<#ident #ty_generics> #where_clause {
+            #getter_methods
+        }
+    })
+}
+
+struct TemplateGetterAttrValues {
+    /// テンプレートを保持する節の型
+    section: String,
+    /// 節がテンプレートを保持するフィールド名
+    member: String,
+    /// 節に実装するときのジェネリックパラメーターと境界
+    generics: Option<String>,
+}
+
+// template_getter属性のカンマで区切られた属性を取得
+// template_getter(section = "Section5", member = "template5", generics = "V: Copy")
+//                 ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^  <- この部分を取得
+fn retrieve_template_getter_attr_values(attr: &Attribute) -> syn::Result<TemplateGetterAttrValues> {
+    let name_values: CommaPunctuatedNameValues = attr
+        .parse_args_with(Punctuated::parse_terminated)
+        .map_err(|err| {
+            syn::Error::new_spanned(
+                attr,
+                format!("failed to parse template_getter attribute: {}", err),
+            )
+        })?;
+
+    let mut section: Option<Expr> = None;
+    let mut member: Option<Expr> = None;
+    let mut generics: Option<Expr> = None;
+    for nv in name_values {
+        let att_ident = nv.path.get_ident().unwrap();
+        if *att_ident == "section" {
+            section = Some(nv.value);
+        } else if *att_ident == "member" {
+            member = Some(nv.value);
+        } else if *att_ident == "generics" {
+            generics = Some(nv.value);
+        }
+    }
+
+    // section属性とmember属性が指定されていない場合はエラー
+    let section = expr_to_string(section).ok_or_else(|| {
+        syn::Error::new_spanned(
+            attr,
+            "template_getter attribute must have `section` argument",
+        )
+    })?;
+    let member = expr_to_string(member).ok_or_else(|| {
+        syn::Error::new_spanned(
+            attr,
+            "template_getter attribute must have `member` argument",
+        )
+    })?;
+
+    Ok(TemplateGetterAttrValues {
+        section,
+        member,
+        generics: expr_to_string(generics),
+    })
+}
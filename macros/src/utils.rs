@@ -117,8 +117,8 @@ pub(crate) fn retrieve_value_from_name_value(
         if attr.path().is_ident(path) {
             let name_values: syn::Result<CommaPunctuatedNameValues> =
                 attr.parse_args_with(Punctuated::parse_terminated);
-            if name_values.is_ok() {
-                for nv in name_values.unwrap() {
+            if let Ok(name_values) = name_values {
+                for nv in name_values {
                     let att_ident = nv.path.get_ident().unwrap();
                     if *att_ident == key {
                         return Some(nv.value);
@@ -18,10 +18,88 @@ pub trait DebugTemplate<W> {
     ///
     /// * `writer` - 出力先
     fn debug_info(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.debug_info_with(writer, &DebugOptions::default())
+    }
+
+    /// 字下げなどの書式を指定して、テンプレートのデバッグ情報を出力する。
+    fn debug_info_with(&self, writer: &mut W, opts: &DebugOptions) -> std::io::Result<()>
     where
         W: std::io::Write;
 }
 
+pub struct DebugOptions {
+    indent: String,
+    section_header: String,
+    separator: String,
+}
+
+impl Default for DebugOptions {
+    fn default() -> Self {
+        Self {
+            indent: String::from("    "),
+            section_header: String::from("第{number}節:{name}"),
+            separator: String::from(": "),
+        }
+    }
+}
+
+impl DebugOptions {
+    pub fn indent(&self) -> &str {
+        &self.indent
+    }
+
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    pub fn section_header(&self, number: u8, name: &str) -> String {
+        self.section_header
+            .replace("{number}", &number.to_string())
+            .replace("{name}", name)
+    }
+}
+
+#[cfg(feature = "json")]
+pub trait DebugTemplateJson {
+    fn debug_info_json(&self) -> serde_json::Value;
+}
+
+#[cfg(feature = "json")]
+pub trait ToJsonValue {
+    fn to_json_value(&self) -> serde_json::Value;
+}
+
+#[cfg(feature = "json")]
+impl ToJsonValue for u8 {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(*self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJsonValue for u16 {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(*self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJsonValue for usize {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(*self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: ToJsonValue> ToJsonValue for Vec<T> {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::Array(self.iter().map(ToJsonValue::to_json_value).collect())
+    }
+}
+
 #[derive(SectionDebugInfo)]
 #[section(number = 0, name = "地域使用節")]
 pub struct Section2;
@@ -52,3 +130,159 @@ pub struct Template5_200 {
     )]
     level_values: Vec<u16>,
 }
+
+#[derive(TemplateDebugInfo)]
+pub struct Template5_200Generic<V: std::fmt::Display> {
+    #[debug_info(name = "今回の圧縮に用いたレベルの最大値")]
+    max_level_value: u16,
+    #[debug_info(
+        data_type = "serial",
+        name = "レベルmに対応するデータ代表値",
+        header = "レベル{}",
+        start = 1,
+        fmt = "{}"
+    )]
+    level_values: Vec<V>,
+}
+
+#[derive(TemplateDebugInfo)]
+pub struct TimeRange {
+    #[debug_info(name = "統計処理の種類")]
+    type_of_stat_proc: u8,
+}
+
+#[derive(TemplateDebugInfo)]
+pub struct TimeRangeSpec {
+    #[debug_info(name = "期間の仕様の数")]
+    number_of_time_range_specs: u8,
+    #[debug_template]
+    time_range: TimeRange,
+}
+
+#[derive(TemplateDebugInfo)]
+pub struct Template4_8 {
+    #[debug_info(name = "パラメータカテゴリー")]
+    parameter_category: u8,
+    #[debug_template(name = "統計処理の時間範囲の仕様")]
+    time_range_spec: TimeRangeSpec,
+    #[debug_info(name = "パラメータ番号")]
+    parameter_number: u8,
+}
+
+#[test]
+fn nested_template_debug_info_is_indented() {
+    let template = Template4_8 {
+        parameter_category: 1,
+        time_range_spec: TimeRangeSpec {
+            number_of_time_range_specs: 2,
+            time_range: TimeRange {
+                type_of_stat_proc: 3,
+            },
+        },
+        parameter_number: 4,
+    };
+    let mut writer = vec![];
+    template.debug_info(&mut writer).unwrap();
+    assert_eq!(
+        concat!(
+            "    パラメータカテゴリー: 1\n",
+            "    統計処理の時間範囲の仕様:\n",
+            "        期間の仕様の数: 2\n",
+            "            統計処理の種類: 3\n",
+            "    パラメータ番号: 4\n",
+        ),
+        String::from_utf8(writer).unwrap()
+    );
+}
+
+#[test]
+fn debug_info_with_options() {
+    let section = Section3 {
+        section_bytes: 72,
+        source_of_grid_definition: 0,
+        template3: Template5_200 {
+            max_level_value: 3,
+            number_of_level_values: 3,
+            decimal_scale_factor: 1,
+            level_values: vec![10, 20],
+        },
+    };
+    let mut writer = vec![];
+    section.debug_info(&mut writer).unwrap();
+    assert_eq!(
+        concat!(
+            "第3節:格子系定義節\n",
+            "    節の長さ: 0x0048\n",
+            "    格子系定義の出典: 0\n",
+            "    今回の圧縮に用いたレベルの最大値: 3\n",
+            "    データの取り得るレベルの最大値: 3\n",
+            "    データ代表値の尺度因子: 1\n",
+            "    レベルmに対応するデータ代表値:\n",
+            "        レベル1: 10\n",
+            "        レベル2: 20\n",
+        ),
+        String::from_utf8(writer).unwrap()
+    );
+
+    let opts = DebugOptions {
+        indent: String::from("\t"),
+        section_header: String::from("[Section {number}] {name}"),
+        separator: String::from(" = "),
+    };
+    let mut writer = vec![];
+    section.debug_info_with(&mut writer, &opts).unwrap();
+    assert_eq!(
+        concat!(
+            "[Section 3] 格子系定義節\n",
+            "\t節の長さ = 0x0048\n",
+            "\t格子系定義の出典 = 0\n",
+            "\t今回の圧縮に用いたレベルの最大値 = 3\n",
+            "\tデータの取り得るレベルの最大値 = 3\n",
+            "\tデータ代表値の尺度因子 = 1\n",
+            "\tレベルmに対応するデータ代表値:\n",
+            "\t\tレベル1 = 10\n",
+            "\t\tレベル2 = 20\n",
+        ),
+        String::from_utf8(writer).unwrap()
+    );
+}
+
+#[derive(TemplateDebugInfo)]
+pub struct FixedSurface {
+    #[debug_info(name = "固定面の種類", data_type = "missing")]
+    type_of_fixed_surface: u8,
+    #[debug_info(name = "固定面の尺度付きの値", data_type = "missing", fmt = "0x{:04X}")]
+    scaled_value_of_fixed_surface: u16,
+}
+
+#[test]
+fn missing_debug_info_is_none() {
+    let template = FixedSurface {
+        type_of_fixed_surface: 100,
+        scaled_value_of_fixed_surface: 850,
+    };
+    let mut writer = vec![];
+    template.debug_info(&mut writer).unwrap();
+    assert_eq!(
+        concat!(
+            "    固定面の種類: 100\n",
+            "    固定面の尺度付きの値: 0x0352\n",
+        ),
+        String::from_utf8(writer).unwrap()
+    );
+
+    // 全ビットが1の値は欠測
+    let template = FixedSurface {
+        type_of_fixed_surface: u8::MAX,
+        scaled_value_of_fixed_surface: u16::MAX,
+    };
+    let mut writer = vec![];
+    template.debug_info(&mut writer).unwrap();
+    assert_eq!(
+        concat!(
+            "    固定面の種類: None\n",
+            "    固定面の尺度付きの値: None\n",
+        ),
+        String::from_utf8(writer).unwrap()
+    );
+}
@@ -8,4 +8,34 @@ pub struct Foo {
     b: std::path::PathBuf,
     #[getter(ret = "ref", rty = "&str")]
     c: String,
+    #[getter(ret = "unwrap")]
+    d: Option<u8>,
+    #[getter(ret = "unwrap", rty = "u16")]
+    e: Option<u16>,
+}
+
+fn foo(d: Option<u8>) -> Foo {
+    Foo {
+        a: 1,
+        b: std::path::PathBuf::from("b"),
+        c: String::from("c"),
+        d,
+        e: Some(5),
+    }
+}
+
+#[test]
+fn getter_ok() {
+    let foo = foo(Some(4));
+    assert_eq!(1, foo.a());
+    assert_eq!(std::path::Path::new("b"), foo.b());
+    assert_eq!("c", foo.c());
+    assert_eq!(4, foo.d());
+    assert_eq!(5, foo.e());
+}
+
+#[test]
+#[should_panic(expected = "field `d` has not been set")]
+fn unwrap_getter_panics_with_field_name() {
+    foo(None).d();
 }
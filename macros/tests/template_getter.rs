@@ -0,0 +1,59 @@
+use macros::TemplateGetter;
+
+pub struct Section5<T> {
+    template5: T,
+}
+
+#[derive(TemplateGetter)]
+#[template_getter(section = "Section5", member = "template5")]
+pub struct Template5_200u16 {
+    #[getter(ret = "val")]
+    decimal_scale_factor: u8,
+    #[getter(ret = "ref", rty = "&[u16]")]
+    level_values: Vec<u16>,
+    #[getter(ret = "unwrap")]
+    max_level_value: Option<u16>,
+}
+
+#[derive(TemplateGetter)]
+#[template_getter(section = "Section5", member = "template5", generics = "V: Copy")]
+pub struct Template5_200<V> {
+    #[getter(ret = "val")]
+    decimal_scale_factor: u8,
+    #[getter(ret = "ref", rty = "&[V]")]
+    level_values: Vec<V>,
+}
+
+#[test]
+fn template_getter_ok() {
+    let section5 = Section5 {
+        template5: Template5_200u16 {
+            decimal_scale_factor: 1,
+            level_values: vec![10, 20],
+            max_level_value: Some(2),
+        },
+    };
+    assert_eq!(1, section5.decimal_scale_factor());
+    assert_eq!(&[10, 20], section5.level_values());
+    assert_eq!(2, section5.max_level_value());
+}
+
+#[test]
+fn template_getter_with_generics_ok() {
+    let section5 = Section5 {
+        template5: Template5_200 {
+            decimal_scale_factor: 2,
+            level_values: vec![-1i16, 1],
+        },
+    };
+    assert_eq!(2, section5.decimal_scale_factor());
+    assert_eq!(&[-1, 1], section5.level_values());
+
+    let section5 = Section5 {
+        template5: Template5_200 {
+            decimal_scale_factor: 0,
+            level_values: vec![100u32, 200],
+        },
+    };
+    assert_eq!(&[100, 200], section5.level_values());
+}